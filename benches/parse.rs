@@ -0,0 +1,48 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+fn source(count: usize) -> String {
+    let mut source = String::new();
+    for index in 0..count {
+        source.push_str(&format!(
+            "BEGIN:VCARD\r\n\
+            VERSION:4.0\r\n\
+            FN:Contact {index}\r\n\
+            N:Contact;{index};;;\r\n\
+            EMAIL;TYPE=work:contact{index}@example.com\r\n\
+            ORG:Example\\, Inc.;Sales\r\n\
+            CATEGORIES:work,friends\r\n\
+            NOTE:Imported contact number {index}\r\n\
+            END:VCARD\r\n"
+        ));
+    }
+    source
+}
+
+fn parse(c: &mut Criterion) {
+    let source = source(1000);
+    c.bench_function("parse", |b| {
+        b.iter(|| vcard4::parse(black_box(&source)).unwrap())
+    });
+    c.bench_function("parse_borrowed", |b| {
+        b.iter(|| vcard4::parse_borrowed(black_box(&source)).unwrap())
+    });
+    c.bench_function("verify", |b| {
+        b.iter(|| vcard4::verify(black_box(&source), true))
+    });
+}
+
+fn to_string(c: &mut Criterion) {
+    let cards = vcard4::parse(source(1000)).unwrap();
+    c.bench_function("to_string", |b| {
+        b.iter(|| {
+            black_box(&cards)
+                .iter()
+                .map(|card| card.to_string())
+                .collect::<Vec<_>>()
+        })
+    });
+}
+
+criterion_group!(benches, parse, to_string);
+criterion_main!(benches);
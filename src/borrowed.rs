@@ -0,0 +1,452 @@
+//! vCards that borrow their text from the source.
+//!
+//! Parsing with [parse_borrowed()](crate::parse_borrowed) only
+//! decodes the FN, N, NOTE, EMAIL, ORG and CATEGORIES properties;
+//! values without escape sequences or folds are borrowed from the
+//! source rather than copied, even when other lines are folded. Use [BorrowedVcard::into_owned] to
+//! convert to a [Vcard] when the source is no longer available.
+use logos::{Lexer, Logos};
+use std::borrow::Cow;
+
+use crate::{
+    error::snippet,
+    escape::{split_unescaped, unescape_component},
+    name::*,
+    parameter::Parameters,
+    parser::{assert_token, iana_name_len, skip_line, Token, VcardParser},
+    property::{
+        NameProperty, StructuredName, TextListDelimiter, TextListProperty,
+        TextProperty,
+    },
+    unfold::Unfolded,
+    Error, ParseOptions, Result, Vcard,
+};
+
+/// Text property that borrows from the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BorrowedText<'s> {
+    /// Group for this property.
+    pub group: Option<Cow<'s, str>>,
+    /// The value with escape sequences removed.
+    pub value: Cow<'s, str>,
+    /// The property parameters.
+    pub parameters: Option<Box<Parameters>>,
+}
+
+impl<'a> BorrowedText<'a> {
+    /// Convert to an owned text property.
+    pub fn into_owned(self) -> TextProperty {
+        TextProperty {
+            group: self.group.map(Cow::into_owned),
+            value: self.value.into_owned(),
+            parameters: self.parameters,
+        }
+    }
+
+    fn rebind<'b>(self, rebind: &Rebind<'b, '_>) -> BorrowedText<'b> {
+        BorrowedText {
+            group: self.group.map(|group| rebind.value(group)),
+            value: rebind.value(self.value),
+            parameters: self.parameters,
+        }
+    }
+}
+
+/// Text list property (ORG or CATEGORIES) that borrows
+/// from the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BorrowedTextList<'s> {
+    /// Group for this property.
+    pub group: Option<Cow<'s, str>>,
+    /// The values with escape sequences removed.
+    pub value: Vec<Cow<'s, str>>,
+    /// The property parameters.
+    pub parameters: Option<Box<Parameters>>,
+    /// The value delimiter.
+    pub delimiter: TextListDelimiter,
+}
+
+impl<'a> BorrowedTextList<'a> {
+    /// Convert to an owned text list property.
+    pub fn into_owned(self) -> TextListProperty {
+        TextListProperty {
+            group: self.group.map(Cow::into_owned),
+            value: self.value.into_iter().map(Cow::into_owned).collect(),
+            parameters: self.parameters,
+            delimiter: self.delimiter,
+        }
+    }
+
+    fn rebind<'b>(self, rebind: &Rebind<'b, '_>) -> BorrowedTextList<'b> {
+        BorrowedTextList {
+            group: self.group.map(|group| rebind.value(group)),
+            value: rebind.values(self.value),
+            parameters: self.parameters,
+            delimiter: self.delimiter,
+        }
+    }
+}
+
+/// N property that borrows from the source.
+///
+/// Each component is a list of values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BorrowedName<'s> {
+    /// Group for this property.
+    pub group: Option<Cow<'s, str>>,
+    /// The family names, given names, additional names,
+    /// honorific prefixes and honorific suffixes.
+    pub value: [Vec<Cow<'s, str>>; 5],
+    /// The property parameters.
+    pub parameters: Option<Box<Parameters>>,
+}
+
+impl<'a> BorrowedName<'a> {
+    /// Convert to an owned name property.
+    pub fn into_owned(self) -> NameProperty {
+        let [family, given, additional, prefixes, suffixes] =
+            self.value.map(|component| {
+                component.into_iter().map(Cow::into_owned).collect()
+            });
+        NameProperty {
+            group: self.group.map(Cow::into_owned),
+            value: StructuredName {
+                family_names: family,
+                given_names: given,
+                additional_names: additional,
+                honorific_prefixes: prefixes,
+                honorific_suffixes: suffixes,
+            },
+            parameters: self.parameters,
+        }
+    }
+
+    fn rebind<'b>(self, rebind: &Rebind<'b, '_>) -> BorrowedName<'b> {
+        BorrowedName {
+            group: self.group.map(|group| rebind.value(group)),
+            value: self.value.map(|values| rebind.values(values)),
+            parameters: self.parameters,
+        }
+    }
+}
+
+/// vCard with the text of common properties borrowed
+/// from the source.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BorrowedVcard<'s> {
+    /// Value of the FN property.
+    pub formatted_name: Vec<BorrowedText<'s>>,
+    /// Value of the N property.
+    pub name: Option<BorrowedName<'s>>,
+    /// Value of the EMAIL property.
+    pub email: Vec<BorrowedText<'s>>,
+    /// Value of the ORG property.
+    pub org: Vec<BorrowedTextList<'s>>,
+    /// Value of the CATEGORIES property.
+    pub categories: Vec<BorrowedTextList<'s>>,
+    /// Value of the NOTE property.
+    pub note: Vec<BorrowedText<'s>>,
+}
+
+impl<'a> BorrowedVcard<'a> {
+    /// Convert to an owned vCard.
+    pub fn into_owned(self) -> Vcard {
+        let mut card = Vcard::default();
+        card.formatted_name.extend(
+            self.formatted_name
+                .into_iter()
+                .map(BorrowedText::into_owned),
+        );
+        card.name = self.name.map(BorrowedName::into_owned);
+        card.email
+            .extend(self.email.into_iter().map(BorrowedText::into_owned));
+        card.org
+            .extend(self.org.into_iter().map(BorrowedTextList::into_owned));
+        card.categories.extend(
+            self.categories
+                .into_iter()
+                .map(BorrowedTextList::into_owned),
+        );
+        card.note
+            .extend(self.note.into_iter().map(BorrowedText::into_owned));
+        card
+    }
+
+    fn rebind<'b>(self, rebind: &Rebind<'b, '_>) -> BorrowedVcard<'b> {
+        BorrowedVcard {
+            formatted_name: self
+                .formatted_name
+                .into_iter()
+                .map(|prop| prop.rebind(rebind))
+                .collect(),
+            name: self.name.map(|prop| prop.rebind(rebind)),
+            email: self
+                .email
+                .into_iter()
+                .map(|prop| prop.rebind(rebind))
+                .collect(),
+            org: self
+                .org
+                .into_iter()
+                .map(|prop| prop.rebind(rebind))
+                .collect(),
+            categories: self
+                .categories
+                .into_iter()
+                .map(|prop| prop.rebind(rebind))
+                .collect(),
+            note: self
+                .note
+                .into_iter()
+                .map(|prop| prop.rebind(rebind))
+                .collect(),
+        }
+    }
+}
+
+/// Moves values borrowed from the unfolded text back to
+/// the source.
+struct Rebind<'s, 't> {
+    source: &'s str,
+    unfolded: &'t Unfolded<'s>,
+}
+
+impl<'s> Rebind<'s, '_> {
+    /// Borrow a value from the source when no fold was
+    /// removed from it, otherwise copy it.
+    fn value(&self, value: Cow<'_, str>) -> Cow<'s, str> {
+        match value {
+            Cow::Borrowed(value) => {
+                let text = self.unfolded.text.as_ref();
+                let offset = value.as_ptr() as usize - text.as_ptr() as usize;
+                let range =
+                    self.unfolded.source_range(offset..offset + value.len());
+                if range.len() == value.len() {
+                    Cow::Borrowed(&self.source[range])
+                } else {
+                    Cow::Owned(value.to_owned())
+                }
+            }
+            Cow::Owned(value) => Cow::Owned(value),
+        }
+    }
+
+    fn values(&self, values: Vec<Cow<'_, str>>) -> Vec<Cow<'s, str>> {
+        values.into_iter().map(|value| self.value(value)).collect()
+    }
+}
+
+/// Parse vCards borrowing from the source.
+pub(crate) fn parse(source: &str) -> Result<Vec<BorrowedVcard<'_>>> {
+    let parser = VcardParser::new(source, ParseOptions::new());
+    match &parser.unfolded.text {
+        Cow::Borrowed(text) => parse_cards(&parser, text),
+        // Values in the unfolded text cannot outlive the parser
+        // so values without folds are borrowed from the source
+        Cow::Owned(text) => {
+            let rebind = Rebind {
+                source,
+                unfolded: &parser.unfolded,
+            };
+            Ok(parse_cards(&parser, text)?
+                .into_iter()
+                .map(|card| card.rebind(&rebind))
+                .collect())
+        }
+    }
+}
+
+fn parse_cards<'a>(
+    parser: &VcardParser<'_>,
+    text: &'a str,
+) -> Result<Vec<BorrowedVcard<'a>>> {
+    let mut cards = Vec::new();
+    // A trailing carriage return terminates the last line
+    let mut lex = Token::lexer(text.trim_end_matches('\r'));
+
+    while let Some(first) = lex.next() {
+        // Allow leading newlines and newlines between
+        // vCard definitions
+        if first == Ok(Token::NewLine) {
+            continue;
+        }
+
+        let start = lex.span().start;
+        assert_token(Some(&first), &[Token::Begin])?;
+        let card = parse_card(parser, &mut lex)
+            .map_err(|e| parser.located(e, lex.span().start))?;
+        if card.formatted_name.is_empty() {
+            return Err(parser.located(Error::NoFormattedName, start));
+        }
+        cards.push(card);
+    }
+
+    if cards.is_empty() {
+        return Err(Error::TokenExpected);
+    }
+
+    Ok(cards)
+}
+
+fn parse_card<'a>(
+    parser: &VcardParser<'_>,
+    lex: &mut Lexer<'a, Token>,
+) -> Result<BorrowedVcard<'a>> {
+    assert_token(lex.next().as_ref(), &[Token::NewLine])?;
+    assert_token(lex.next().as_ref(), &[Token::Version])?;
+    assert_token(lex.next().as_ref(), &[Token::NewLine])?;
+
+    let mut card = BorrowedVcard::default();
+    while let Some(first) = lex.next() {
+        if first == Ok(Token::End) {
+            break;
+        }
+        if let Ok(Token::Version) = first {
+            return Err(Error::VersionMisplaced);
+        }
+
+        // Names not known to the lexer may be IANA tokens
+        if let Some(len) = iana_name_len(lex, &first) {
+            lex.bump(len - lex.slice().len());
+        } else {
+            assert_token(
+                Some(&first),
+                &[
+                    Token::PropertyName,
+                    Token::ExtensionName,
+                    Token::TimeZone,
+                    Token::Geo,
+                ],
+            )?;
+        }
+
+        let (group, name) = match lex.slice().split_once('.') {
+            Some((group, name)) => (Some(Cow::Borrowed(group)), name),
+            None => (None, lex.slice()),
+        };
+        let upper_name = name.to_uppercase();
+        if !matches!(
+            &upper_name[..],
+            FN | N | EMAIL | ORG | CATEGORIES | NOTE
+        ) {
+            skip_line(lex);
+            continue;
+        }
+
+        let delimiter = lex.next();
+        let parameters = if delimiter == Some(Ok(Token::ParameterDelimiter)) {
+            // Parameters may all be empty
+            Some(parser.parse_parameters(lex, Some(name))?)
+                .filter(|params| params != &Parameters::default())
+                .map(Box::new)
+        } else if delimiter == Some(Ok(Token::PropertyDelimiter)) {
+            None
+        } else if delimiter.is_some() {
+            return Err(Error::DelimiterExpected);
+        } else {
+            return Err(Error::TokenExpected);
+        };
+
+        // Structured values are split before escape
+        // sequences are removed
+        let unescape = !matches!(&upper_name[..], N | ORG | CATEGORIES);
+        let value = parser.parse_property_value(lex, false, unescape)?;
+
+        match &upper_name[..] {
+            FN | EMAIL | NOTE => {
+                let prop = BorrowedText {
+                    group,
+                    value,
+                    parameters,
+                };
+                match &upper_name[..] {
+                    FN => card.formatted_name.push(prop),
+                    EMAIL => card.email.push(prop),
+                    _ => card.note.push(prop),
+                }
+            }
+            N => {
+                if card.name.is_some() {
+                    return Err(Error::OnlyOnce(upper_name));
+                }
+                card.name = Some(BorrowedName {
+                    group,
+                    value: parse_name(value)?,
+                    parameters,
+                });
+            }
+            _ => {
+                let (delimiter, separator) = if upper_name == ORG {
+                    (TextListDelimiter::SemiColon, ';')
+                } else {
+                    (TextListDelimiter::Comma, ',')
+                };
+                let prop = BorrowedTextList {
+                    group,
+                    value: split_values(value, separator),
+                    parameters,
+                    delimiter,
+                };
+                if upper_name == ORG {
+                    card.org.push(prop);
+                } else {
+                    card.categories.push(prop);
+                }
+            }
+        }
+    }
+    Ok(card)
+}
+
+/// Parse the components of an escaped N value.
+fn parse_name(value: Cow<'_, str>) -> Result<[Vec<Cow<'_, str>>; 5]> {
+    Ok(match &value {
+        Cow::Borrowed(value) => split_name(value)?,
+        Cow::Owned(value) => split_name(value)?.map(into_owned_values),
+    })
+}
+
+fn split_name(value: &str) -> Result<[Vec<Cow<'_, str>>; 5]> {
+    let components = split_unescaped(value, ';');
+    if components.len() > 5 {
+        return Err(Error::InvalidName(snippet(value)));
+    }
+    let mut name: [Vec<Cow<'_, str>>; 5] = Default::default();
+    for (index, component) in components.into_iter().enumerate() {
+        if !component.is_empty() {
+            name[index] = split_list(component, ',');
+        }
+    }
+    Ok(name)
+}
+
+/// Split an escaped value on a delimiter removing
+/// escape sequences from each part.
+fn split_values(value: Cow<'_, str>, delimiter: char) -> Vec<Cow<'_, str>> {
+    match value {
+        Cow::Borrowed(value) => split_list(value, delimiter),
+        Cow::Owned(value) => into_owned_values(split_list(&value, delimiter)),
+    }
+}
+
+/// Split an escaped value; parts without escape
+/// sequences are borrowed.
+fn split_list(value: &str, delimiter: char) -> Vec<Cow<'_, str>> {
+    split_unescaped(value, delimiter)
+        .into_iter()
+        .map(|part| {
+            if part.contains('\\') {
+                Cow::Owned(unescape_component(part))
+            } else {
+                Cow::Borrowed(part)
+            }
+        })
+        .collect()
+}
+
+fn into_owned_values<'a>(values: Vec<Cow<'_, str>>) -> Vec<Cow<'a, str>> {
+    values
+        .into_iter()
+        .map(|value| Cow::Owned(value.into_owned()))
+        .collect()
+}
@@ -1,8 +1,14 @@
 //! Builder for creating vCards.
 //!
 use crate::{
-    property::{DeliveryAddress, Gender, Kind, TextListProperty},
-    Date, DateTime, Uri, Vcard,
+    geo::GeoUri,
+    parameter::Parameters,
+    property::{
+        AddressProperty, AnyProperty, ClientPidMap, ClientPidMapProperty,
+        DeliveryAddress, ExtensionProperty, Gender, Kind, StructuredName,
+        TextListProperty, TextOrUriProperty, TextProperty, UriProperty,
+    },
+    Date, DateTime, PropertyKind, Uri, Vcard,
 };
 
 #[cfg(feature = "language-tags")]
@@ -11,17 +17,24 @@ use language_tags::LanguageTag;
 /// Build vCard instances.
 ///
 /// This is a high-level interface for creating vCards programatically;
-/// if you need to assign parameters or use a group then either use
-/// [Vcard](Vcard) directly or update properties after finishing a builder.
+/// parameters can be assigned to the common properties using the
+/// `*_with` functions, otherwise if you need to assign parameters or
+/// use a group then either use [Vcard](Vcard) directly or update
+/// properties after finishing a builder.
+///
+/// Custom labels in the style of Apple Contacts can be added using
+/// the `*_with_label` functions which put the property and an
+/// `X-ABLabel` extension in a fresh group (`item1`, `item2`, etc).
 ///
 /// The card is not validated so it is possible to create
 /// invalid vCards using the builder. To ensure you have a valid vCard call
 /// [validate](Vcard::validate) afterwards.
 ///
-/// The builder does not support the CLIENTPIDMAP property, if you need to
-/// use a CLIENTPIDMAP use [Vcard](Vcard).
+/// PID parameters can be assigned using the `*_with` functions and the
+/// source identifiers mapped with [client_pid_map](Self::client_pid_map).
 pub struct VcardBuilder {
     card: Vcard,
+    next_group: usize,
 }
 
 impl VcardBuilder {
@@ -29,9 +42,58 @@ impl VcardBuilder {
     pub fn new(formatted_name: String) -> Self {
         Self {
             card: Vcard::new(formatted_name),
+            next_group: 1,
+        }
+    }
+
+    /// Next group name that is not used by any property.
+    fn group_name(&mut self) -> String {
+        loop {
+            let group = format!("item{}", self.next_group);
+            self.next_group += 1;
+            let used = self.card.properties().any(|prop| {
+                prop.property()
+                    .group()
+                    .map(|g| g.eq_ignore_ascii_case(&group))
+                    .unwrap_or(false)
+            });
+            if !used {
+                return group;
+            }
         }
     }
 
+    /// Add a label extension in a fresh group and return the group
+    /// name to assign to the labeled property.
+    ///
+    /// The source order is updated so the property and the
+    /// label are adjacent when iterating
+    /// [in source order](Vcard::properties_in_source_order).
+    ///
+    /// Writing a vCard keeps the properties of a group together
+    /// so the label is also written after the property unless the
+    /// [group_properties](crate::WriteOptions::group_properties)
+    /// option is disabled.
+    fn push_label(&mut self, kind: PropertyKind, label: &str) -> String {
+        let mut order: Vec<PropertyKind> = self
+            .card
+            .properties_in_source_order()
+            .map(|prop| prop.kind())
+            .collect();
+        order.push(kind);
+        order.push(PropertyKind::Extension);
+        self.card.order.0 = order;
+
+        let group = self.group_name();
+        self.card.extensions.push(ExtensionProperty {
+            name: crate::name::X_ABLABEL.to_owned(),
+            group: Some(group.clone()),
+            value: AnyProperty::Text(label.to_owned()),
+            parameters: None,
+        });
+        group
+    }
+
     // General
 
     /// Set the kind of vCard.
@@ -65,8 +127,14 @@ impl VcardBuilder {
     /// Should be family name, given name, additional names, honorific
     /// prefixes followed by honorific suffixes.
     pub fn name(mut self, value: [String; 5]) -> Self {
-        self.card.name =
-            Some(TextListProperty::new_semi_colon(value.to_vec()));
+        self.card.name = Some(StructuredName::from(value).into());
+        self
+    }
+
+    /// Set the name for the vCard from components that
+    /// may have multiple values.
+    pub fn name_components(mut self, value: StructuredName) -> Self {
+        self.card.name = Some(value.into());
         self
     }
 
@@ -97,23 +165,107 @@ impl VcardBuilder {
         self
     }
 
+    /// Set a birthday for the vCard from a chrono date.
+    ///
+    /// Dates outside the years -9999 to 9999 are not supported
+    /// and the birthday is not set.
+    #[cfg(feature = "chrono")]
+    pub fn birthday_chrono(self, value: chrono::NaiveDate) -> Self {
+        match Date::try_from(value) {
+            Ok(value) => self.birthday(value),
+            Err(_) => self,
+        }
+    }
+
+    /// Set an anniversary for the vCard from a chrono date.
+    ///
+    /// Dates outside the years -9999 to 9999 are not supported
+    /// and the anniversary is not set.
+    #[cfg(feature = "chrono")]
+    pub fn anniversary_chrono(self, value: chrono::NaiveDate) -> Self {
+        match Date::try_from(value) {
+            Ok(value) => self.anniversary(value),
+            Err(_) => self,
+        }
+    }
+
+    /// Set the place of birth for the vCard.
+    pub fn birthplace(mut self, value: String) -> Self {
+        self.card.birthplace = Some(value.into());
+        self
+    }
+
+    /// Set the place of death for the vCard.
+    pub fn deathplace(mut self, value: String) -> Self {
+        self.card.deathplace = Some(value.into());
+        self
+    }
+
+    /// Set the date of death for the vCard.
+    pub fn deathdate(mut self, value: Date) -> Self {
+        self.card.deathdate = Some(value.into());
+        self
+    }
+
     /// Set the gender for the vCard.
     ///
     /// If the value cannot be parsed in to a gender according to
     /// RFC6350 then the gender will not be set.
     pub fn gender(mut self, value: &str) -> Self {
         if let Ok(gender) = value.parse::<Gender>() {
-            self.card.gender = Some(gender.into());
+            self.card.gender = vec![gender.into()];
         }
         self
     }
 
+    /// Add pronouns to the vCard.
+    pub fn pronouns(mut self, value: String) -> Self {
+        self.card.pronouns.push(value.into());
+        self
+    }
+
     /// Add an address to the vCard.
     pub fn address(mut self, value: DeliveryAddress) -> Self {
         self.card.address.push(value.into());
         self
     }
 
+    /// Add an address with parameters to the vCard.
+    ///
+    /// ```
+    /// use vcard4::{parameter::Parameters, property::DeliveryAddress, VcardBuilder};
+    /// let mut address = DeliveryAddress::default();
+    /// address.street_address.push("123 Main St".to_owned());
+    /// let card = VcardBuilder::new("Jane Doe".to_owned())
+    ///     .address_with(
+    ///         address,
+    ///         Parameters::new().with_country_code("US".to_owned()),
+    ///     )
+    ///     .finish();
+    /// assert!(card.to_string().contains("\r\nADR;CC=US:;;123 Main St;;;;\r\n"));
+    /// ```
+    pub fn address_with(
+        mut self,
+        value: DeliveryAddress,
+        parameters: Parameters,
+    ) -> Self {
+        let prop = AddressProperty::from(value).with_parameters(parameters);
+        self.card.address.push(prop);
+        self
+    }
+
+    /// Add an address with a custom label to the vCard.
+    pub fn address_with_label(
+        mut self,
+        value: DeliveryAddress,
+        label: &str,
+    ) -> Self {
+        let mut prop: AddressProperty = value.into();
+        prop.group = Some(self.push_label(PropertyKind::Address, label));
+        self.card.address.push(prop);
+        self
+    }
+
     // Communications
 
     /// Add a telephone number to the vCard.
@@ -122,18 +274,80 @@ impl VcardBuilder {
         self
     }
 
+    /// Add a telephone number with parameters to the vCard.
+    pub fn telephone_with(
+        mut self,
+        value: String,
+        parameters: Parameters,
+    ) -> Self {
+        let prop = TextProperty::from(value).with_parameters(parameters);
+        self.card.tel.push(TextOrUriProperty::Text(prop));
+        self
+    }
+
+    /// Add a telephone number with a custom label to the vCard.
+    pub fn telephone_with_label(
+        mut self,
+        value: String,
+        label: &str,
+    ) -> Self {
+        let mut prop: TextProperty = value.into();
+        prop.group = Some(self.push_label(PropertyKind::Tel, label));
+        self.card.tel.push(TextOrUriProperty::Text(prop));
+        self
+    }
+
     /// Add an email address to the vCard.
     pub fn email(mut self, value: String) -> Self {
         self.card.email.push(value.into());
         self
     }
 
+    /// Add an email address with parameters to the vCard.
+    pub fn email_with(
+        mut self,
+        value: String,
+        parameters: Parameters,
+    ) -> Self {
+        let prop = TextProperty::from(value).with_parameters(parameters);
+        self.card.email.push(prop);
+        self
+    }
+
+    /// Add an email address with a custom label to the vCard.
+    pub fn email_with_label(mut self, value: String, label: &str) -> Self {
+        let mut prop: TextProperty = value.into();
+        prop.group = Some(self.push_label(PropertyKind::Email, label));
+        self.card.email.push(prop);
+        self
+    }
+
     /// Add an instant messaging URI to the vCard.
     pub fn impp(mut self, value: Uri) -> Self {
         self.card.impp.push(value.into());
         self
     }
 
+    /// Add a social media profile to the vCard.
+    pub fn social_profile(mut self, value: Uri) -> Self {
+        self.card.social_profile.push(value.into());
+        self
+    }
+
+    /// Add a social media profile with a service type
+    /// (for example `Mastodon`) to the vCard.
+    pub fn social_profile_with_service(
+        mut self,
+        value: Uri,
+        service_type: String,
+    ) -> Self {
+        let prop = UriProperty::from(value).with_parameters(
+            Parameters::new().with_service_type(service_type),
+        );
+        self.card.social_profile.push(TextOrUriProperty::Uri(prop));
+        self
+    }
+
     #[cfg(feature = "language-tags")]
     /// Add a preferred language to the vCard.
     pub fn lang(mut self, value: LanguageTag) -> Self {
@@ -148,6 +362,20 @@ impl VcardBuilder {
         self
     }
 
+    #[cfg(feature = "language-tags")]
+    /// Set the default language of the vCard.
+    pub fn language(mut self, value: LanguageTag) -> Self {
+        self.card.language = Some(value.into());
+        self
+    }
+
+    #[cfg(not(feature = "language-tags"))]
+    /// Set the default language of the vCard.
+    pub fn language(mut self, value: String) -> Self {
+        self.card.language = Some(value.into());
+        self
+    }
+
     // Geographical
 
     /// Add a timezone to the vCard.
@@ -162,6 +390,18 @@ impl VcardBuilder {
         self
     }
 
+    /// Add a geographic position from WGS-84 coordinates
+    /// to the vCard.
+    ///
+    /// Coordinates that are out of range are ignored.
+    pub fn geo_coords(mut self, latitude: f64, longitude: f64) -> Self {
+        let uri = GeoUri::new(latitude, longitude).and_then(Uri::try_from);
+        if let Ok(uri) = uri {
+            self.card.geo.push(uri.into());
+        }
+        self
+    }
+
     // Organizational
 
     /// Add a title to the vCard.
@@ -202,6 +442,30 @@ impl VcardBuilder {
         self
     }
 
+    /// Add an area of expertise to the vCard.
+    pub fn expertise(mut self, value: String) -> Self {
+        self.card.expertise.push(value.into());
+        self
+    }
+
+    /// Add a hobby to the vCard.
+    pub fn hobby(mut self, value: String) -> Self {
+        self.card.hobby.push(value.into());
+        self
+    }
+
+    /// Add an interest to the vCard.
+    pub fn interest(mut self, value: String) -> Self {
+        self.card.interest.push(value.into());
+        self
+    }
+
+    /// Add an organization directory to the vCard.
+    pub fn org_directory(mut self, value: Uri) -> Self {
+        self.card.org_directory.push(value.into());
+        self
+    }
+
     // Explanatory
 
     /// Add categories to the vCard.
@@ -230,12 +494,32 @@ impl VcardBuilder {
         self
     }
 
+    /// Set the creation timestamp for the vCard.
+    pub fn created(mut self, value: DateTime) -> Self {
+        self.card.created = Some(value.into());
+        self
+    }
+
     /// Add a sound to the vCard.
     pub fn sound(mut self, value: Uri) -> Self {
         self.card.sound.push(value.into());
         self
     }
 
+    /// Add a client PID map to the vCard.
+    ///
+    /// The source identifier is referenced by the PID
+    /// parameters of properties added with the `*_with`
+    /// functions.
+    pub fn client_pid_map(mut self, source: u64, uri: Uri) -> Self {
+        self.card.client_pid_map.push(ClientPidMapProperty {
+            value: ClientPidMap { source, uri },
+            group: None,
+            parameters: None,
+        });
+        self
+    }
+
     /// Set the UID for the vCard.
     pub fn uid(mut self, value: Uri) -> Self {
         self.card.uid = Some(value.into());
@@ -248,6 +532,21 @@ impl VcardBuilder {
         self
     }
 
+    /// Add a URL with parameters to the vCard.
+    pub fn url_with(mut self, value: Uri, parameters: Parameters) -> Self {
+        let prop = UriProperty::from(value).with_parameters(parameters);
+        self.card.url.push(prop);
+        self
+    }
+
+    /// Add a URL with a custom label to the vCard.
+    pub fn url_with_label(mut self, value: Uri, label: &str) -> Self {
+        let mut prop: UriProperty = value.into();
+        prop.group = Some(self.push_label(PropertyKind::Url, label));
+        self.card.url.push(prop);
+        self
+    }
+
     // Security
 
     /// Add a key to the vCard.
@@ -276,16 +575,62 @@ impl VcardBuilder {
         self
     }
 
+    // Extensions
+
+    /// Add an extension property to the vCard.
+    ///
+    /// The name must be an x-name (`X-` followed by letters, digits
+    /// and dashes); otherwise the extension is not added.
+    pub fn extension(mut self, name: &str, value: AnyProperty) -> Self {
+        if is_x_name(name) {
+            self.card.extensions.push(ExtensionProperty {
+                name: name.to_owned(),
+                group: None,
+                value,
+                parameters: None,
+            });
+        }
+        self
+    }
+
+    /// Add an extension property with a text value to the vCard.
+    ///
+    /// The name must be an x-name; otherwise the extension
+    /// is not added.
+    pub fn extension_text(self, name: &str, value: String) -> Self {
+        self.extension(name, AnyProperty::Text(value))
+    }
+
     /// Finish building the vCard.
     pub fn finish(self) -> Vcard {
         self.card
     }
 }
 
+/// Determine if a name is an x-name.
+fn is_x_name(name: &str) -> bool {
+    match name.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("X-") => {
+            name.len() > 2
+                && name[2..]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        }
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::VcardBuilder;
-    use crate::property::{DeliveryAddress, Kind, LanguageProperty};
+    use crate::{
+        parameter::{Parameters, TelephoneType, TypeParameter},
+        property::{
+            AnyProperty, DeliveryAddress, Kind, LanguageProperty,
+            StructuredName,
+        },
+        Error,
+    };
     use time::{Date, Month, OffsetDateTime, Time};
 
     #[test]
@@ -325,13 +670,13 @@ mod tests {
             )
             .gender("F")
             .address(DeliveryAddress {
-                po_box: None,
-                extended_address: None,
-                street_address: Some("123 Main Street".to_owned()),
-                locality: Some("Mock City".to_owned()),
-                region: Some("Mock State".to_owned()),
-                country_name: Some("Mock Country".to_owned()),
-                postal_code: Some("123".to_owned()),
+                po_box: Vec::new(),
+                extended_address: Vec::new(),
+                street_address: vec!["123 Main Street".to_owned()],
+                locality: vec!["Mock City".to_owned()],
+                region: vec!["Mock State".to_owned()],
+                country_name: vec!["Mock Country".to_owned()],
+                postal_code: vec!["123".to_owned()],
             })
             // Communication
             .telephone("+10987654321".to_owned())
@@ -374,6 +719,43 @@ mod tests {
         assert_eq!(expected, &vcard);
     }
 
+    #[test]
+    fn builder_parameters() {
+        let work = Parameters::new()
+            .with_type(TypeParameter::Work)
+            .with_pref(1);
+        let home_cell = Parameters::new()
+            .with_type(TypeParameter::Home)
+            .with_type(TypeParameter::Telephone(TelephoneType::Cell));
+        let card = VcardBuilder::new("Jane Doe".to_owned())
+            .email_with("jane@example.com".to_owned(), work)
+            .email("jane@example.org".to_owned())
+            .telephone_with("+1-555-555-5555".to_owned(), home_cell)
+            .finish();
+
+        let encoded = card.to_string();
+        assert!(
+            encoded.contains("EMAIL;PREF=1;TYPE=work:jane@example.com\r\n")
+        );
+        assert!(encoded.contains("EMAIL:jane@example.org\r\n"));
+        assert!(encoded.contains("TEL;TYPE=home,cell:+1-555-555-5555\r\n"));
+    }
+
+    #[test]
+    fn builder_name_components() {
+        let mut name = StructuredName::default();
+        name.family_names.push("Public".to_owned());
+        name.given_names.push("John".to_owned());
+        name.additional_names.push("Quinlan".to_owned());
+        name.additional_names.push("Jake".to_owned());
+        let card = VcardBuilder::new("John Public".to_owned())
+            .name_components(name)
+            .finish();
+        assert!(card
+            .to_string()
+            .contains("N:Public;John;Quinlan,Jake;;\r\n"));
+    }
+
     #[test]
     fn builder_member_group() {
         let card = VcardBuilder::new("Mock Company".to_owned())
@@ -392,6 +774,11 @@ mod tests {
             .finish();
         assert_eq!(1, card.member.len());
         assert!(card.validate().is_err());
+        assert!(!card.is_valid());
+
+        let issues = card.validation_issues();
+        assert_eq!(1, issues.len());
+        assert!(matches!(issues[0].error, Error::MemberRequiresGroup));
     }
 
     #[cfg(not(feature = "language-tags"))]
@@ -402,7 +789,7 @@ mod tests {
             .lang("fr".to_owned())
             .finish();
         assert_eq!(
-            card.lang.get(0).unwrap(),
+            card.lang.first().unwrap(),
             &LanguageProperty {
                 value: "en".to_owned(),
                 group: None,
@@ -428,7 +815,7 @@ mod tests {
             .lang("fr".parse::<LanguageTag>().unwrap())
             .finish();
         assert_eq!(
-            card.lang.get(0).unwrap(),
+            card.lang.first().unwrap(),
             &LanguageProperty {
                 value: "en".parse::<LanguageTag>().unwrap(),
                 group: None,
@@ -444,4 +831,49 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn builder_extension() {
+        let card = VcardBuilder::new("Mock Company".to_owned())
+            .extension_text("X-ABShowAs", "COMPANY".to_owned())
+            .extension(
+                "X-SOCIALPROFILE",
+                AnyProperty::Uri("https://example.com/mock".parse().unwrap()),
+            )
+            .extension_text("NOT-EXTENSION", "ignored".to_owned())
+            .extension_text("X-", "ignored".to_owned())
+            .extension_text("é", "ignored".to_owned())
+            .extension_text("X-BAD NAME", "ignored".to_owned())
+            .finish();
+        assert_eq!(2, card.extensions.len());
+        assert_eq!("X-ABShowAs", card.extensions[0].name);
+        assert_eq!(
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Mock Company\r\n\
+             X-ABShowAs:COMPANY\r\n\
+             X-SOCIALPROFILE;VALUE=uri:https://example.com/mock\r\n\
+             END:VCARD\r\n",
+            card.to_string()
+        );
+    }
+
+    #[test]
+    fn builder_pid() {
+        let mut params = Parameters::new();
+        params.add_pid("1.1".parse().unwrap());
+        params.add_pid("2.1".parse().unwrap());
+        params.add_pid("1.1".parse().unwrap());
+        let card = VcardBuilder::new("Jane Doe".to_owned())
+            .email_with("jane@example.com".to_owned(), params)
+            .client_pid_map(1, "urn:uuid:1".parse().unwrap())
+            .client_pid_map(2, "urn:uuid:2".parse().unwrap())
+            .finish();
+        assert!(card.validate().is_ok());
+        assert_eq!(
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\n\
+             EMAIL;PID=1.1,2.1:jane@example.com\r\n\
+             CLIENTPIDMAP:1;urn:uuid:1\r\nCLIENTPIDMAP:2;urn:uuid:2\r\n\
+             END:VCARD\r\n",
+            card.to_string()
+        );
+    }
 }
@@ -0,0 +1,239 @@
+//! Canonical form of a vCard.
+//!
+//! Producers may encode the same vCard with different letter
+//! case, parameter order and property order; the canonical form
+//! removes these differences so the encoding can be hashed.
+use crate::{
+    data::is_data_uri,
+    merge::ParametersMut,
+    parameter::{Parameters, TypeParameter},
+    property::{Property, TextOrUriProperty},
+    Uri, Vcard,
+};
+
+/// Convert a vCard to the canonical form.
+pub(crate) fn canonicalize(card: &mut Vcard) {
+    macro_rules! canonical_single {
+        ($($field:ident),+) => {
+            $(
+                if let Some(prop) = card.$field.as_mut() {
+                    canonical_parameters(prop.parameters_mut());
+                }
+            )+
+        };
+    }
+
+    macro_rules! canonical_multiple {
+        ($($field:ident),+) => {
+            $(
+                for prop in card.$field.iter_mut() {
+                    canonical_parameters(prop.parameters_mut());
+                }
+                // Equal preferences are ordered by the other data
+                // before they are renumbered
+                card.$field.sort_by_cached_key(|prop| tie_key(prop));
+                compact_pref(
+                    card.$field.iter_mut().map(|prop| prop.parameters_mut()),
+                );
+                card.$field.sort_by_cached_key(|prop| sort_key(prop));
+            )+
+        };
+    }
+
+    for prop in card.extensions.iter_mut() {
+        prop.name = prop.name.to_uppercase();
+    }
+
+    // Percent-encoded octets in data URIs use uppercase hex digits
+    for prop in card.photo.iter_mut().chain(card.key.iter_mut()) {
+        if let TextOrUriProperty::Uri(prop) = prop {
+            canonical_data_uri(&mut prop.value);
+        }
+    }
+    for prop in card.logo.iter_mut().chain(card.sound.iter_mut()) {
+        canonical_data_uri(&mut prop.value);
+    }
+
+    canonical_single!(
+        kind,
+        name,
+        bday,
+        anniversary,
+        birthplace,
+        deathplace,
+        deathdate,
+        prod_id,
+        rev,
+        created,
+        language,
+        uid
+    );
+    canonical_multiple!(
+        source,
+        xml,
+        formatted_name,
+        nickname,
+        photo,
+        gender,
+        pronouns,
+        url,
+        address,
+        tel,
+        email,
+        impp,
+        social_profile,
+        lang,
+        title,
+        role,
+        logo,
+        org,
+        member,
+        related,
+        expertise,
+        hobby,
+        interest,
+        org_directory,
+        timezone,
+        geo,
+        categories,
+        note,
+        sound,
+        client_pid_map,
+        key,
+        fburl,
+        cal_adr_uri,
+        cal_uri
+    );
+
+    // Extensions are ordered by name first
+    for prop in card.extensions.iter_mut() {
+        canonical_parameters(prop.parameters_mut());
+    }
+    let mut names: Vec<String> = card
+        .extensions
+        .iter()
+        .map(|prop| prop.name.clone())
+        .collect();
+    names.sort();
+    names.dedup();
+    card.extensions
+        .sort_by_cached_key(|prop| (prop.name.clone(), tie_key(prop)));
+    for name in names {
+        compact_pref(
+            card.extensions
+                .iter_mut()
+                .filter(|prop| prop.name == name)
+                .map(|prop| prop.parameters_mut()),
+        );
+    }
+    card.extensions
+        .sort_by_cached_key(|prop| (prop.name.clone(), sort_key(prop)));
+
+    // Properties are now in the order they are encoded
+    card.order = Default::default();
+}
+
+/// Key for sorting properties of the same kind.
+fn sort_key(prop: &dyn Property) -> String {
+    format!(
+        "{}{}:{}",
+        prop.group().map(String::as_str).unwrap_or_default(),
+        prop.parameters().map(|p| p.to_string()).unwrap_or_default(),
+        prop,
+    )
+}
+
+/// Key for ordering properties of the same kind that have
+/// equal preferences; the PREF parameter is ignored.
+fn tie_key(prop: &dyn Property) -> String {
+    let parameters = prop.parameters().map(|p| {
+        let mut params = p.clone();
+        params.pref = None;
+        params.to_string()
+    });
+    format!(
+        "{}{}:{}",
+        prop.group().map(String::as_str).unwrap_or_default(),
+        parameters.unwrap_or_default(),
+        prop,
+    )
+}
+
+/// Convert the hex digits of percent-encoded octets in a data
+/// URI to uppercase.
+fn canonical_data_uri(uri: &mut Uri) {
+    let value = uri.to_string();
+    if !is_data_uri(&value) || !value.contains('%') {
+        return;
+    }
+    let mut canonical = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        canonical.push(c);
+        if c == '%' {
+            for c in chars.by_ref().take(2) {
+                canonical.push(c.to_ascii_uppercase());
+            }
+        }
+    }
+    if let Ok(value) = canonical.parse() {
+        *uri = value;
+    }
+}
+
+/// Convert parameters to the canonical form.
+///
+/// TYPE values are converted to lowercase, sorted and
+/// duplicates removed. Extension parameter names are converted
+/// to uppercase and sorted. Empty lists are removed.
+fn canonical_parameters(parameters: &mut Option<Box<Parameters>>) {
+    let Some(params) = parameters.as_mut() else {
+        return;
+    };
+
+    if let Some(types) = params.types.as_mut() {
+        for kind in types.iter_mut() {
+            if let TypeParameter::Extension(value) = kind {
+                *value = value.to_lowercase();
+            }
+        }
+        types.sort_by_cached_key(|kind| kind.to_string().to_lowercase());
+        types.dedup();
+    }
+    if let Some(extensions) = params.extensions.as_mut() {
+        for (name, _) in extensions.iter_mut() {
+            *name = name.to_uppercase();
+        }
+        extensions.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    if params.types.as_ref().is_some_and(|t| t.is_empty()) {
+        params.types = None;
+    }
+    if params.extensions.as_ref().is_some_and(|e| e.is_empty()) {
+        params.extensions = None;
+    }
+    if params.pid.as_ref().is_some_and(|pid| pid.is_empty()) {
+        params.pid = None;
+    }
+    if params.sort_as.as_ref().is_some_and(|s| s.is_empty()) {
+        params.sort_as = None;
+    }
+    if **params == Parameters::default() {
+        *parameters = None;
+    }
+}
+
+/// Renumber PREF values from one keeping their relative order.
+fn compact_pref<'a>(
+    parameters: impl Iterator<Item = &'a mut Option<Box<Parameters>>>,
+) {
+    let mut prefs: Vec<&mut u8> = parameters
+        .filter_map(|params| params.as_mut()?.pref.as_mut())
+        .collect();
+    // Stable so that equal preferences keep their order
+    prefs.sort_by_key(|pref| **pref);
+    for (pref, value) in prefs.into_iter().zip(1..=u8::MAX) {
+        *pref = value;
+    }
+}
@@ -0,0 +1,219 @@
+//! Conversions between date and time values and the
+//! [chrono](https://docs.rs/chrono) types.
+//!
+//! Offsets are preserved exactly; a conversion fails when a value
+//! is outside the range of the target type, for example chrono
+//! does not support offsets of a day or more and dates are limited
+//! to the years -9999 to 9999.
+use ::chrono::{
+    Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+    Timelike,
+};
+use time::{Month, OffsetDateTime, Time, UtcOffset};
+
+use crate::{
+    error::snippet,
+    property::{DateAndOrTime, DateAndOrTimeProperty, DateTimeProperty},
+    Date, DateTime, Error, PartialDate, Result,
+};
+
+fn naive_date(date: time::Date) -> Result<NaiveDate> {
+    NaiveDate::from_ymd_opt(
+        date.year(),
+        date.month() as u32,
+        date.day() as u32,
+    )
+    .ok_or_else(|| Error::InvalidDate(snippet(date.to_string())))
+}
+
+fn naive_time(time: Time) -> Result<NaiveTime> {
+    NaiveTime::from_hms_nano_opt(
+        time.hour() as u32,
+        time.minute() as u32,
+        time.second() as u32,
+        time.nanosecond(),
+    )
+    .ok_or_else(|| Error::InvalidTime(snippet(time.to_string())))
+}
+
+fn fixed_offset(offset: UtcOffset) -> Result<FixedOffset> {
+    FixedOffset::east_opt(offset.whole_seconds())
+        .ok_or_else(|| Error::InvalidTime(snippet(offset.to_string())))
+}
+
+fn from_naive_date(date: NaiveDate) -> Result<time::Date> {
+    let invalid = || Error::InvalidDate(snippet(date.to_string()));
+    let month = Month::try_from(date.month() as u8).map_err(|_| invalid())?;
+    time::Date::from_calendar_date(date.year(), month, date.day() as u8)
+        .map_err(|_| invalid())
+}
+
+fn from_naive_time(time: NaiveTime) -> Result<Time> {
+    // Leap seconds are represented by chrono as a nanosecond
+    // value past one second so clamp to the end of the second
+    let nanosecond = time.nanosecond().min(999_999_999);
+    Ok(Time::from_hms_nano(
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+        nanosecond,
+    )?)
+}
+
+fn from_fixed_offset(offset: FixedOffset) -> Result<UtcOffset> {
+    Ok(UtcOffset::from_whole_seconds(offset.local_minus_utc())?)
+}
+
+impl DateTime {
+    /// Convert to a chrono date and time with the same offset.
+    pub fn to_chrono(&self) -> Result<::chrono::DateTime<FixedOffset>> {
+        let value: &OffsetDateTime = self.as_ref();
+        let offset = fixed_offset(value.offset())?;
+        let local = NaiveDateTime::new(
+            naive_date(value.date())?,
+            naive_time(value.time())?,
+        );
+        offset
+            .from_local_datetime(&local)
+            .single()
+            .ok_or_else(|| Error::InvalidDateTime(snippet(self.to_string())))
+    }
+}
+
+impl<Tz: TimeZone> TryFrom<::chrono::DateTime<Tz>> for DateTime {
+    type Error = Error;
+
+    fn try_from(value: ::chrono::DateTime<Tz>) -> Result<Self> {
+        let value = value.fixed_offset();
+        let local = value.naive_local();
+        let date = from_naive_date(local.date())?;
+        let time = from_naive_time(local.time())?;
+        let offset = from_fixed_offset(*value.offset())?;
+        Ok(OffsetDateTime::new_in_offset(date, time, offset).into())
+    }
+}
+
+impl TryFrom<&DateTime> for ::chrono::DateTime<FixedOffset> {
+    type Error = Error;
+
+    fn try_from(value: &DateTime) -> Result<Self> {
+        value.to_chrono()
+    }
+}
+
+impl TryFrom<Date> for NaiveDate {
+    type Error = Error;
+
+    fn try_from(value: Date) -> Result<Self> {
+        naive_date(*value.as_ref())
+    }
+}
+
+impl TryFrom<NaiveDate> for Date {
+    type Error = Error;
+
+    fn try_from(value: NaiveDate) -> Result<Self> {
+        Ok(from_naive_date(value)?.into())
+    }
+}
+
+impl PartialDate {
+    /// Convert to a chrono date.
+    ///
+    /// A year is required; a missing month or day is the first
+    /// month or day so `1985-04` is April 1, 1985.
+    pub fn to_chrono_naive(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(
+            self.year?,
+            self.month.unwrap_or(1) as u32,
+            self.day.unwrap_or(1) as u32,
+        )
+    }
+}
+
+impl DateTimeProperty {
+    /// Convert the value to a chrono date and time with the
+    /// same offset.
+    pub fn to_chrono(&self) -> Result<::chrono::DateTime<FixedOffset>> {
+        self.value.to_chrono()
+    }
+}
+
+impl<Tz: TimeZone> TryFrom<::chrono::DateTime<Tz>> for DateTimeProperty {
+    type Error = Error;
+
+    fn try_from(value: ::chrono::DateTime<Tz>) -> Result<Self> {
+        Ok(DateTime::try_from(value)?.into())
+    }
+}
+
+impl DateAndOrTime {
+    /// Convert to a chrono date.
+    ///
+    /// A date and time is the date in its own offset; partial
+    /// dates follow [PartialDate::to_chrono_naive] and a time
+    /// is `None`.
+    pub fn to_chrono_naive_date(&self) -> Option<NaiveDate> {
+        match self {
+            Self::Date(value) => naive_date(*value.as_ref()).ok(),
+            Self::DateTime(value) => naive_date(value.as_ref().date()).ok(),
+            Self::PartialDate(value) => value.to_chrono_naive(),
+            Self::Time(_) => None,
+        }
+    }
+
+    /// Convert to a chrono time.
+    ///
+    /// Times are local to their offset which is discarded; a date
+    /// is `None`.
+    pub fn to_chrono_naive_time(&self) -> Option<NaiveTime> {
+        match self {
+            Self::DateTime(value) => naive_time(value.as_ref().time()).ok(),
+            Self::Time((value, _)) => naive_time(*value).ok(),
+            Self::Date(_) | Self::PartialDate(_) => None,
+        }
+    }
+
+    /// Convert to a chrono date and time.
+    ///
+    /// A date and time is local to its offset which is discarded;
+    /// dates are at midnight and a time is `None`.
+    pub fn to_chrono_naive_date_time(&self) -> Option<NaiveDateTime> {
+        let date = self.to_chrono_naive_date()?;
+        let time = self.to_chrono_naive_time().unwrap_or(NaiveTime::MIN);
+        Some(NaiveDateTime::new(date, time))
+    }
+}
+
+impl TryFrom<NaiveDate> for DateAndOrTime {
+    type Error = Error;
+
+    fn try_from(value: NaiveDate) -> Result<Self> {
+        Ok(Date::try_from(value)?.into())
+    }
+}
+
+impl<Tz: TimeZone> TryFrom<::chrono::DateTime<Tz>> for DateAndOrTime {
+    type Error = Error;
+
+    fn try_from(value: ::chrono::DateTime<Tz>) -> Result<Self> {
+        Ok(DateTime::try_from(value)?.into())
+    }
+}
+
+impl TryFrom<(NaiveTime, FixedOffset)> for DateAndOrTime {
+    type Error = Error;
+
+    fn try_from(value: (NaiveTime, FixedOffset)) -> Result<Self> {
+        let (time, offset) = value;
+        Ok((from_naive_time(time)?, from_fixed_offset(offset)?).into())
+    }
+}
+
+impl TryFrom<NaiveDate> for DateAndOrTimeProperty {
+    type Error = Error;
+
+    fn try_from(value: NaiveDate) -> Result<Self> {
+        Ok(Date::try_from(value)?.into())
+    }
+}
@@ -0,0 +1,92 @@
+//! Compare vCards ignoring the order of properties.
+use crate::{parameter::Parameters, CompareOptions, PropertyKind, Vcard};
+
+/// Get the sorted keys for the properties of a vCard.
+///
+/// Each key is the content line for a property with the
+/// parameters in a canonical form so that equal keys
+/// represent equivalent properties.
+pub(crate) fn property_keys(
+    card: &Vcard,
+    options: &CompareOptions,
+) -> Vec<String> {
+    let mut keys: Vec<String> = card
+        .properties()
+        .filter(|prop| match prop.kind() {
+            PropertyKind::Rev => !options.ignore_rev,
+            PropertyKind::ProdId => !options.ignore_prod_id,
+            _ => true,
+        })
+        .map(|prop| {
+            let property = prop.property();
+            let group = property
+                .group()
+                .map(|group| format!("{}.", group.to_uppercase()))
+                .unwrap_or_default();
+            format!(
+                "{}{}{}:{}",
+                group,
+                prop.name().to_uppercase(),
+                parameters_key(property.parameters()),
+                property,
+            )
+        })
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// Stable 64-bit FNV-1a hash of the property keys.
+pub(crate) fn content_hash(keys: &[String]) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    let mut hash = OFFSET;
+    for key in keys {
+        // Terminate each key so that keys can not run together
+        for byte in key.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+/// Encode parameters in a canonical form.
+///
+/// TYPE values are compared case-insensitively and in any order,
+/// extension parameters are compared in any order and empty
+/// lists are the same as missing parameters.
+fn parameters_key(parameters: Option<&Parameters>) -> String {
+    let Some(parameters) = parameters else {
+        return String::new();
+    };
+    let mut parameters = parameters.clone();
+
+    let mut types: Vec<String> = parameters
+        .types
+        .take()
+        .unwrap_or_default()
+        .iter()
+        .map(|value| value.to_string().to_lowercase())
+        .collect();
+    types.sort();
+    types.dedup();
+
+    let mut extensions = parameters.extensions.take().unwrap_or_default();
+    extensions.sort_by_key(|(name, _)| name.to_uppercase());
+    if !extensions.is_empty() {
+        parameters.extensions = Some(extensions);
+    }
+    if parameters.pid.as_ref().is_some_and(|pid| pid.is_empty()) {
+        parameters.pid = None;
+    }
+    if parameters.sort_as.as_ref().is_some_and(|s| s.is_empty()) {
+        parameters.sort_as = None;
+    }
+
+    if types.is_empty() {
+        parameters.to_string()
+    } else {
+        format!("{};TYPE={}", parameters, types.join(","))
+    }
+}
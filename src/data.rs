@@ -0,0 +1,172 @@
+//! Data URIs.
+//!
+//! The `data:` scheme is defined in
+//! [RFC2397](https://www.rfc-editor.org/rfc/rfc2397) and is commonly
+//! used to embed media such as a PHOTO or LOGO in a vCard.
+//!
+//! ```
+//! use vcard4::data::DataUri;
+//! let data: DataUri = "data:image/png;base64,iVBORw0KGgo=".parse().unwrap();
+//! assert_eq!("image/png", data.media_type);
+//! assert!(data.is_base64);
+//! assert_eq!(b"\x89PNG\r\n\x1a\n", &data.data[..]);
+//! ```
+use base64::{
+    alphabet,
+    engine::{general_purpose, DecodePaddingMode, GeneralPurposeConfig},
+    Engine,
+};
+use std::{fmt, str::FromStr};
+
+use crate::{error::snippet, Error, Result, Uri};
+
+const SCHEME: &str = "data:";
+const BASE64: &str = "base64";
+const DEFAULT_MEDIA_TYPE: &str = "text/plain;charset=US-ASCII";
+
+/// Producers do not agree on padding so it is optional when decoding.
+const DECODER: general_purpose::GeneralPurpose =
+    general_purpose::GeneralPurpose::new(
+        &alphabet::STANDARD,
+        GeneralPurposeConfig::new()
+            .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    );
+
+/// Data URI with the decoded data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataUri {
+    /// The media type including any parameters; when the URI does
+    /// not specify a media type it is `text/plain;charset=US-ASCII`.
+    pub media_type: String,
+    /// Whether the data is base64 encoded in the URI.
+    pub is_base64: bool,
+    /// The decoded data.
+    pub data: Vec<u8>,
+}
+
+impl DataUri {
+    /// Create a base64 encoded data URI.
+    pub fn new(media_type: String, data: Vec<u8>) -> Self {
+        Self {
+            media_type,
+            is_base64: true,
+            data,
+        }
+    }
+}
+
+impl fmt::Display for DataUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", SCHEME, self.media_type)?;
+        if self.is_base64 {
+            write!(
+                f,
+                ";{},{}",
+                BASE64,
+                general_purpose::STANDARD.encode(&self.data)
+            )
+        } else {
+            write!(f, ",")?;
+            for byte in &self.data {
+                if byte.is_ascii_alphanumeric() || b"-._~".contains(byte) {
+                    write!(f, "{}", *byte as char)?;
+                } else {
+                    write!(f, "%{:02X}", byte)?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+impl FromStr for DataUri {
+    type Err = Error;
+
+    /// Parse a data URI.
+    ///
+    /// Some producers escape the comma that separates the data
+    /// from the media type as `\,` which is accepted.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidDataUri(snippet(s));
+        if !is_data_uri(s) {
+            return Err(invalid());
+        }
+
+        let (header, payload) =
+            s[SCHEME.len()..].split_once(',').ok_or_else(invalid)?;
+        let header = header.strip_suffix('\\').unwrap_or(header);
+
+        let (media_type, is_base64) = match header.rsplit_once(';') {
+            Some((media_type, encoding))
+                if encoding.eq_ignore_ascii_case(BASE64) =>
+            {
+                (media_type, true)
+            }
+            _ if header.eq_ignore_ascii_case(BASE64) => ("", true),
+            _ => (header, false),
+        };
+        let media_type = if media_type.is_empty() {
+            DEFAULT_MEDIA_TYPE.to_owned()
+        } else {
+            media_type.to_owned()
+        };
+
+        let data = if is_base64 {
+            let payload: String =
+                payload.chars().filter(|c| !c.is_whitespace()).collect();
+            DECODER.decode(payload)?
+        } else {
+            percent_decode(payload).ok_or_else(invalid)?
+        };
+
+        Ok(Self {
+            media_type,
+            is_base64,
+            data,
+        })
+    }
+}
+
+impl TryFrom<&Uri> for DataUri {
+    type Error = Error;
+
+    fn try_from(value: &Uri) -> Result<Self> {
+        value.to_string().parse()
+    }
+}
+
+impl TryFrom<DataUri> for Uri {
+    type Error = Error;
+
+    fn try_from(value: DataUri) -> Result<Self> {
+        value.to_string().parse()
+    }
+}
+
+/// Determine if a value uses the `data:` scheme.
+pub(crate) fn is_data_uri(value: &str) -> bool {
+    value
+        .get(..SCHEME.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(SCHEME))
+}
+
+/// Decode percent-encoded octets.
+fn percent_decode(value: &str) -> Option<Vec<u8>> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = value.get(index + 1..index + 3)?;
+            if !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    Some(decoded)
+}
@@ -1,6 +1,9 @@
-use crate::Error;
+use crate::{error::snippet, Error};
 use std::{fmt, str::FromStr};
-use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use time::{
+    format_description::well_known::{Iso8601, Rfc3339},
+    OffsetDateTime,
+};
 
 #[cfg(feature = "serde")]
 use serde_with::{serde_as, DeserializeFromStr, SerializeDisplay};
@@ -38,11 +41,7 @@ impl AsRef<OffsetDateTime> for DateTime {
 
 impl fmt::Display for DateTime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.0.format(&Rfc3339).map_err(|_| fmt::Error::default())?
-        )
+        write!(f, "{}", self.0.format(&Rfc3339).map_err(|_| fmt::Error)?)
     }
 }
 
@@ -54,6 +53,26 @@ impl FromStr for DateTime {
     }
 }
 
+/// Source of the current time used when updating the REV property.
+pub trait Clock {
+    /// Get the current date and time.
+    fn now(&self) -> DateTime;
+}
+
+/// Clock that uses the system time in UTC.
+///
+/// The time is truncated to whole seconds as that is the
+/// precision of the timestamp encoding.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime {
+        let now = OffsetDateTime::now_utc();
+        now.replace_nanosecond(0).unwrap_or(now).into()
+    }
+}
+
 /// Date that serializes to and from RFC3339.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", cfg_eval::cfg_eval, serde_as)]
@@ -80,14 +99,154 @@ impl AsRef<time::Date> for Date {
 
 impl fmt::Display for Date {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0.to_string(),)
+        write!(f, "{}", self.0)
     }
 }
 
 impl FromStr for Date {
     type Err = Error;
 
+    /// Parse a date in the `YYYY-MM-DD` form used by the
+    /// [Display](fmt::Display) implementation; the date of an
+    /// RFC3339 date and time is also accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match OffsetDateTime::parse(s, &Rfc3339) {
+            Ok(date_time) => Ok(Self(date_time.date())),
+            Err(_) => Ok(Self(time::Date::parse(s, &Iso8601::DATE)?)),
+        }
+    }
+}
+
+/// Date with reduced accuracy or a truncated representation.
+///
+/// Represents the forms of the date value type from
+/// [RFC6350](https://www.rfc-editor.org/rfc/rfc6350#section-4.3.1)
+/// that omit components, for example a birthday without
+/// a year (`--0203`).
+///
+/// ```
+/// use vcard4::PartialDate;
+/// let date: PartialDate = "--0203".parse().unwrap();
+/// assert_eq!(None, date.year);
+/// assert_eq!(Some(2), date.month);
+/// assert_eq!(Some(3), date.day);
+/// assert_eq!("--0203", date.to_string());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(DeserializeFromStr, SerializeDisplay))]
+pub struct PartialDate {
+    /// The year.
+    pub year: Option<i32>,
+    /// The month.
+    pub month: Option<u8>,
+    /// The day of the month.
+    pub day: Option<u8>,
+}
+
+impl PartialDate {
+    /// Create a partial date.
+    ///
+    /// Only the truncated forms are accepted: a year, a year and
+    /// month, a month, a month and day or a day.
+    pub fn new(
+        year: Option<i32>,
+        month: Option<u8>,
+        day: Option<u8>,
+    ) -> crate::Result<Self> {
+        let date = Self { year, month, day };
+        date.validate()?;
+        Ok(date)
+    }
+
+    fn validate(&self) -> crate::Result<()> {
+        let invalid = || Error::InvalidDate(snippet(self.to_string()));
+        match (self.year, self.month, self.day) {
+            (Some(year), None, None) if (0..=9999).contains(&year) => {}
+            (Some(year), Some(_), None) if (0..=9999).contains(&year) => {}
+            (None, Some(_), _) | (None, None, Some(_)) => {}
+            _ => return Err(invalid()),
+        }
+        if let Some(month) = self.month {
+            let month =
+                time::Month::try_from(month).map_err(|_| invalid())?;
+            if let Some(day) = self.day {
+                // Without a year February 29 is allowed
+                let max = month.length(2000);
+                if day == 0 || day > max {
+                    return Err(invalid());
+                }
+            }
+        } else if let Some(day) = self.day {
+            if day == 0 || day > 31 {
+                return Err(invalid());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PartialDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.year, self.month, self.day) {
+            (Some(year), Some(month), None) => {
+                write!(f, "{:04}-{:02}", year, month)
+            }
+            (Some(year), _, _) => write!(f, "{:04}", year),
+            (None, Some(month), Some(day)) => {
+                write!(f, "--{:02}{:02}", month, day)
+            }
+            (None, Some(month), None) => write!(f, "--{:02}", month),
+            (None, None, Some(day)) => write!(f, "---{:02}", day),
+            (None, None, None) => Ok(()),
+        }
+    }
+}
+
+impl FromStr for PartialDate {
+    type Err = Error;
+
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(OffsetDateTime::parse(s, &Rfc3339)?.date()))
+        let invalid = || Error::InvalidDate(snippet(s));
+        let digits = |value: &str| -> Result<u8, Error> {
+            if value.len() == 2 && value.bytes().all(|b| b.is_ascii_digit()) {
+                value.parse().map_err(|_| invalid())
+            } else {
+                Err(invalid())
+            }
+        };
+
+        let date = if let Some(day) = s.strip_prefix("---") {
+            Self {
+                year: None,
+                month: None,
+                day: Some(digits(day)?),
+            }
+        } else if let Some(value) = s.strip_prefix("--") {
+            let (month, day) = match value.split_at_checked(2) {
+                Some((month, "")) => (month, None),
+                Some((month, day)) => (month, Some(digits(day)?)),
+                None => return Err(invalid()),
+            };
+            Self {
+                year: None,
+                month: Some(digits(month)?),
+                day,
+            }
+        } else {
+            let (year, month) = match s.split_once('-') {
+                Some((year, month)) => (year, Some(digits(month)?)),
+                None => (s, None),
+            };
+            if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            Self {
+                year: Some(year.parse().map_err(|_| invalid())?),
+                month,
+                day: None,
+            }
+        };
+        date.validate().map_err(|_| invalid())?;
+        Ok(date)
     }
 }
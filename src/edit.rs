@@ -0,0 +1,439 @@
+//! Edit the properties of a vCard using property handles.
+//!
+//! A [PropertyHandle](crate::PropertyHandle) identifies a property
+//! by kind and index and is obtained from a
+//! [PropertyRef](crate::PropertyRef) when iterating the properties
+//! of a vCard.
+use crate::{
+    error::snippet,
+    merge::{GroupMut, ParametersMut},
+    parameter::TYPE_PROPERTIES,
+    parser::VcardParser,
+    property::{ExtensionProperty, ParsedProperty, PropertyGroup},
+    property_ref::{PropertyHandle, PropertyKind},
+    Error, ParseOptions, Result, Vcard,
+};
+
+/// Remove a property by index; single-valued properties
+/// only have an index of zero.
+macro_rules! remove_match {
+    ($card:expr, $kind:expr, $index:expr,
+        [$($single_kind:ident => $single:ident),+],
+        [$($multiple_kind:ident => $multiple:ident),+]) => {
+        match $kind {
+            $(
+                PropertyKind::$single_kind => {
+                    $index == 0 && $card.$single.take().is_some()
+                }
+            )+
+            $(
+                PropertyKind::$multiple_kind => {
+                    if $index < $card.$multiple.len() {
+                        $card.$multiple.remove($index);
+                        true
+                    } else {
+                        false
+                    }
+                }
+            )+
+        }
+    };
+}
+
+/// Replace a property by index with the first property
+/// of the same kind from another vCard.
+macro_rules! replace_match {
+    ($card:expr, $other:expr, $kind:expr, $index:expr,
+        [$($single_kind:ident => $single:ident),+],
+        [$($multiple_kind:ident => $multiple:ident),+]) => {
+        match $kind {
+            $(
+                PropertyKind::$single_kind => {
+                    match ($card.$single.as_mut(), $other.$single.take()) {
+                        (Some(prop), Some(value)) if $index == 0 => {
+                            *prop = value;
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+            )+
+            $(
+                PropertyKind::$multiple_kind => {
+                    match $card.$multiple.get_mut($index) {
+                        Some(prop) if !$other.$multiple.is_empty() => {
+                            *prop = $other.$multiple.remove(0);
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+            )+
+        }
+    };
+}
+
+/// Take the first property of a kind.
+macro_rules! take_match {
+    ($card:expr, $kind:expr,
+        [$($single_kind:ident => $single:ident),+],
+        [$($multiple_kind:ident => $multiple:ident),+]) => {
+        match $kind {
+            $(
+                PropertyKind::$single_kind => {
+                    $card.$single.take().map(ParsedProperty::from)
+                }
+            )+
+            $(
+                PropertyKind::$multiple_kind => {
+                    if $card.$multiple.is_empty() {
+                        None
+                    } else {
+                        Some(ParsedProperty::from($card.$multiple.remove(0)))
+                    }
+                }
+            )+
+        }
+    };
+}
+
+/// Mutable parameters of a property by index.
+macro_rules! parameters_match {
+    ($card:expr, $kind:expr, $index:expr,
+        [$($single_kind:ident => $single:ident),+],
+        [$($multiple_kind:ident => $multiple:ident),+]) => {
+        match $kind {
+            $(
+                PropertyKind::$single_kind => $card
+                    .$single
+                    .as_mut()
+                    .filter(|_| $index == 0)
+                    .map(|prop| prop.parameters_mut()),
+            )+
+            $(
+                PropertyKind::$multiple_kind => $card
+                    .$multiple
+                    .get_mut($index)
+                    .map(|prop| prop.parameters_mut()),
+            )+
+        }
+    };
+}
+
+/// Mutable group of a property by index.
+macro_rules! group_match {
+    ($card:expr, $kind:expr, $index:expr,
+        [$($single_kind:ident => $single:ident),+],
+        [$($multiple_kind:ident => $multiple:ident),+]) => {
+        match $kind {
+            $(
+                PropertyKind::$single_kind => $card
+                    .$single
+                    .as_mut()
+                    .filter(|_| $index == 0)
+                    .map(|prop| prop.group_mut()),
+            )+
+            $(
+                PropertyKind::$multiple_kind => $card
+                    .$multiple
+                    .get_mut($index)
+                    .map(|prop| prop.group_mut()),
+            )+
+        }
+    };
+}
+
+/// Invoke a match macro with the fields for each kind of property.
+macro_rules! for_each_kind {
+    ($macro:ident, $($args:expr),+) => {
+        $macro!(
+            $($args),+,
+            [
+                Kind => kind,
+                Name => name,
+                Bday => bday,
+                Anniversary => anniversary,
+                Birthplace => birthplace,
+                Deathplace => deathplace,
+                Deathdate => deathdate,
+                ProdId => prod_id,
+                Rev => rev,
+                Created => created,
+                Language => language,
+                Uid => uid
+            ],
+            [
+                Source => source,
+                Xml => xml,
+                FormattedName => formatted_name,
+                Nickname => nickname,
+                Photo => photo,
+                Gender => gender,
+                Pronouns => pronouns,
+                Url => url,
+                Address => address,
+                Title => title,
+                Role => role,
+                Logo => logo,
+                Org => org,
+                Member => member,
+                Related => related,
+                Expertise => expertise,
+                Hobby => hobby,
+                Interest => interest,
+                OrgDirectory => org_directory,
+                Tel => tel,
+                Email => email,
+                Impp => impp,
+                SocialProfile => social_profile,
+                Lang => lang,
+                TimeZone => timezone,
+                Geo => geo,
+                Categories => categories,
+                Note => note,
+                Sound => sound,
+                ClientPidMap => client_pid_map,
+                Key => key,
+                FbUrl => fburl,
+                CalAdrUri => cal_adr_uri,
+                CalUri => cal_uri,
+                Extension => extensions
+            ]
+        )
+    };
+}
+
+/// Remove the property for a handle.
+pub(crate) fn remove(card: &mut Vcard, handle: PropertyHandle) -> bool {
+    let PropertyHandle { kind, index } = handle;
+    let removed = for_each_kind!(remove_match, card, kind, index);
+    if removed {
+        // Keep the source order for the remaining properties
+        if let Some(position) = card
+            .order
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, k)| **k == kind)
+            .nth(index)
+            .map(|(position, _)| position)
+        {
+            card.order.0.remove(position);
+        }
+    }
+    removed
+}
+
+/// Replace the property for a handle with a property
+/// parsed from a content line.
+pub(crate) fn set(
+    card: &mut Vcard,
+    handle: PropertyHandle,
+    line: &str,
+) -> Result<()> {
+    let PropertyHandle { kind, index } = handle;
+    if card.property_at(kind, index).is_none() {
+        return Err(Error::PropertyNotFound(kind_name(kind), index));
+    }
+
+    let mismatch =
+        || Error::PropertyKindMismatch(kind_name(kind), snippet(line));
+
+    let parser = VcardParser::new(line, ParseOptions::new());
+    let (mut other, other_kind) = parser.parse_content_line()?;
+    if other_kind != kind {
+        return Err(mismatch());
+    }
+
+    if for_each_kind!(replace_match, card, other, kind, index) {
+        Ok(())
+    } else {
+        Err(Error::PropertyKindMismatch(kind_name(kind), snippet(line)))
+    }
+}
+
+/// Parse a single content line.
+pub(crate) fn parse_property(
+    line: &str,
+) -> Result<(String, Option<String>, ParsedProperty)> {
+    let parser = VcardParser::new(line, ParseOptions::new());
+    let (mut card, kind) = parser.parse_content_line()?;
+    let prop = card.property_at(kind, 0).ok_or(Error::TokenExpected)?;
+    let name = prop.name().to_owned();
+    let group = prop.property().group().cloned();
+    let prop =
+        for_each_kind!(take_match, card, kind).ok_or(Error::TokenExpected)?;
+    Ok((name, group, prop))
+}
+
+/// Make the property for a handle the most preferred
+/// property of its kind.
+pub(crate) fn set_preferred(
+    card: &mut Vcard,
+    handle: PropertyHandle,
+) -> bool {
+    let PropertyHandle { kind, index } = handle;
+    if card.property_at(kind, index).is_none() {
+        return false;
+    }
+
+    // Other ranked properties are renumbered below the target
+    // in their current order so ties cannot survive the move
+    let mut ranked = (0..card.property_count(kind))
+        .filter(|other| *other != index)
+        .filter_map(|other| {
+            card.property_at(kind, other)
+                .and_then(|prop| prop.property().parameters())
+                .and_then(|params| params.pref)
+                .map(|pref| (pref, other))
+        })
+        .collect::<Vec<_>>();
+    ranked.sort();
+
+    for (rank, (_, other)) in ranked.into_iter().enumerate() {
+        let params = for_each_kind!(parameters_match, card, kind, other);
+        let pref = params
+            .and_then(|params| params.as_mut())
+            .and_then(|params| params.pref.as_mut());
+        if let Some(pref) = pref {
+            *pref = (rank + 2).min(100) as u8;
+        }
+    }
+
+    if let Some(params) = for_each_kind!(parameters_match, card, kind, index)
+    {
+        params.get_or_insert_with(Box::default).pref = Some(1);
+    }
+    true
+}
+
+/// Set or clear the group of the property for a handle.
+pub(crate) fn set_group(
+    card: &mut Vcard,
+    handle: PropertyHandle,
+    group: Option<PropertyGroup>,
+) -> bool {
+    let PropertyHandle { kind, index } = handle;
+    match for_each_kind!(group_match, card, kind, index) {
+        Some(value) => {
+            *value = group.map(String::from);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Move extensions into the typed properties of a kind.
+macro_rules! promote_match {
+    ($card:expr, $props:expr, $kind:expr,
+        [$($single_kind:ident => $single:ident),+],
+        [$($multiple_kind:ident => $multiple:ident),+]) => {
+        match $kind {
+            $(
+                PropertyKind::$single_kind => {
+                    if $props.len() > 1
+                        || (!$props.is_empty() && $card.$single.is_some())
+                    {
+                        return Err(Error::OnlyOnce(kind_name($kind)));
+                    }
+                    if let Some(prop) = $props.pop() {
+                        $card.$single = Some(prop.try_into()?);
+                    }
+                }
+            )+
+            $(
+                PropertyKind::$multiple_kind => {
+                    let props = $props
+                        .into_iter()
+                        .map(TryFrom::try_from)
+                        .collect::<Result<Vec<_>>>()?;
+                    $card.$multiple.extend(props);
+                }
+            )+
+            _ => return Err(Error::PromoteNotSupported(kind_name($kind))),
+        }
+    };
+}
+
+/// Move the extensions with a name into the typed
+/// properties of a kind.
+pub(crate) fn promote(
+    card: &mut Vcard,
+    name: &str,
+    kind: PropertyKind,
+) -> Result<usize> {
+    let indices: Vec<usize> = card
+        .extensions
+        .iter()
+        .enumerate()
+        .filter(|(_, prop)| prop.name.eq_ignore_ascii_case(name))
+        .map(|(index, _)| index)
+        .collect();
+    let mut props: Vec<ExtensionProperty> = indices
+        .iter()
+        .map(|index| card.extensions[*index].clone())
+        .collect();
+
+    let has_types = props.iter().any(|prop| {
+        prop.parameters.as_ref().is_some_and(|p| p.types.is_some())
+    });
+    if has_types && !TYPE_PROPERTIES.contains(&&kind_name(kind)[..]) {
+        return Err(Error::TypeParameter(kind_name(kind)));
+    }
+
+    // Extensions are only removed when every value converts
+    promote_match!(
+        card,
+        props,
+        kind,
+        [
+            Kind => kind,
+            Bday => bday,
+            Anniversary => anniversary,
+            Birthplace => birthplace,
+            Deathplace => deathplace,
+            Deathdate => deathdate,
+            ProdId => prod_id,
+            Uid => uid
+        ],
+        [
+            Source => source,
+            Xml => xml,
+            FormattedName => formatted_name,
+            Nickname => nickname,
+            Photo => photo,
+            Gender => gender,
+            Pronouns => pronouns,
+            Url => url,
+            Title => title,
+            Role => role,
+            Logo => logo,
+            Member => member,
+            Related => related,
+            Expertise => expertise,
+            Hobby => hobby,
+            Interest => interest,
+            OrgDirectory => org_directory,
+            Tel => tel,
+            Email => email,
+            Impp => impp,
+            SocialProfile => social_profile,
+            Geo => geo,
+            Note => note,
+            Sound => sound,
+            Key => key,
+            FbUrl => fburl,
+            CalAdrUri => cal_adr_uri,
+            CalUri => cal_uri
+        ]
+    );
+
+    for index in indices.iter().rev() {
+        remove(card, PropertyHandle::new(PropertyKind::Extension, *index));
+    }
+    Ok(indices.len())
+}
+
+fn kind_name(kind: PropertyKind) -> String {
+    kind.name().unwrap_or("X-").to_owned()
+}
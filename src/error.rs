@@ -1,5 +1,8 @@
+use std::ops::Range;
 use thiserror::Error;
 
+use crate::Limit;
+
 /// Error lexing a vcard string.
 #[derive(Debug, Error, PartialEq, Clone, Default)]
 #[doc(hidden)]
@@ -10,7 +13,42 @@ pub enum LexError {
     Other,
 }
 
+/// Error for a property that was skipped in loose mode.
+///
+/// See [parse_collect](crate::parse_collect).
+#[derive(Debug)]
+pub struct PropertyError {
+    /// The property name without the group.
+    pub name: String,
+    /// The source text for the property excluding the line break.
+    pub raw: String,
+    /// The byte range of the property in the source.
+    pub span: Range<usize>,
+    /// The error generated parsing the property.
+    pub error: Error,
+}
+
+/// Maximum number of characters of source text kept in an error.
+const SNIPPET_LEN: usize = 64;
+
+/// Bound source text embedded in an error.
+///
+/// Text longer than [SNIPPET_LEN] characters is truncated on
+/// a character boundary and an ellipsis is appended so that
+/// large values (such as inline photos) are not copied into
+/// errors and logs.
+pub(crate) fn snippet(value: impl AsRef<str>) -> String {
+    let value = value.as_ref();
+    match value.char_indices().nth(SNIPPET_LEN) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value.to_owned(),
+    }
+}
+
 /// Errors generated by the vCard library.
+///
+/// Source text included in an error is truncated to a
+/// short prefix.
 #[derive(Debug, Error)]
 pub enum Error {
     /// Error generated when a token was expected but no more tokens
@@ -35,6 +73,21 @@ pub enum Error {
     #[error("unknown parameter '{0}'")]
     UnknownParameter(String),
 
+    /// Error with the location in the source that caused it.
+    ///
+    /// Only generated when the `locations` parse option is enabled;
+    /// the line and column are one-based and the column is
+    /// a count of characters.
+    #[error("line {line}, column {column}: {source}")]
+    Parse {
+        /// Line number.
+        line: usize,
+        /// Column number.
+        column: usize,
+        /// The error at this location.
+        source: Box<Error>,
+    },
+
     /// Error generated when an unknown property name is encountered.
     #[error("property name '{0}' is not supported")]
     UnknownPropertyName(String),
@@ -43,6 +96,21 @@ pub enum Error {
     #[error("property value is invalid")]
     InvalidPropertyValue,
 
+    /// Error generated when a property handle does not
+    /// identify a property.
+    #[error("property {0} at index {1} does not exist")]
+    PropertyNotFound(String, usize),
+
+    /// Error generated when a content line does not contain
+    /// a single property of the expected kind.
+    #[error("content line '{1}' is not a {0} property")]
+    PropertyKindMismatch(String, String),
+
+    /// Error generated when extensions can not be promoted
+    /// to a kind of property.
+    #[error("extensions cannot be promoted to {0}")]
+    PromoteNotSupported(String),
+
     /// Error generated when a time is invalid.
     #[error("time '{0}' is invalid")]
     InvalidTime(String),
@@ -55,15 +123,45 @@ pub enum Error {
     #[error("delivery address '{0}' is invalid")]
     InvalidAddress(String),
 
+    /// Error generated when a name (`N`) is invalid.
+    #[error("name '{0}' is invalid")]
+    InvalidName(String),
+
     /// Error generated when a LABEL parameter is specified on a property
     /// other than ADR.
     #[error("parameter LABEL can only be applied to ADR but used on '{0}'")]
     InvalidLabel(String),
 
+    /// Error generated when the LEVEL parameter is used on a property
+    /// other than EXPERTISE, HOBBY or INTEREST.
+    #[error("parameter LEVEL can only be applied to EXPERTISE, HOBBY or INTEREST but used on '{0}'")]
+    InvalidLevel(String),
+
+    /// Error generated when a LEVEL is not allowed for a property.
+    #[error("level '{0}' is not valid for '{1}'")]
+    UnknownLevel(String, String),
+
+    /// Error generated when a CC parameter is specified on a property
+    /// other than ADR.
+    #[error("parameter CC can only be applied to ADR but used on '{0}'")]
+    InvalidCountryCode(String),
+
+    /// Error generated when a CC is not a two letter country code.
+    #[error("country code '{0}' is invalid, must be two letters")]
+    UnknownCountryCode(String),
+
+    /// Error generated when an INDEX is not a positive integer.
+    #[error("index '{0}' is invalid, must be a positive integer")]
+    InvalidIndex(String),
+
     /// Error generated when a boolean is invalid.
     #[error("value '{0}' is not a valid boolean")]
     InvalidBoolean(String),
 
+    /// Error generated when a LANG property has multiple values.
+    #[error("LANG must be a single language tag, got '{0}'")]
+    MultipleLanguages(String),
+
     /// Error generated when a CLIENTPIDMAP value could not be parsed.
     #[error("client PID map '{0}' is not valid")]
     InvalidClientPidMap(String),
@@ -125,20 +223,77 @@ pub enum Error {
     #[error("pid '{0}' is invalid")]
     InvalidPid(String),
 
+    /// Error generated when a part of a PID has more than two digits.
+    #[error(
+        "pid '{0}' is out of range, each part must be one or two digits"
+    )]
+    PidOutOfRange(String),
+
     /// Error generated when an unquoted value was encountered when it must
     /// be quoted; eg: the GEO parameter URI.
-    #[error("'{0}' must be enclosed in quotes")]
+    ///
+    /// The value is the name of the parameter.
+    #[error("{0} parameter value must be enclosed in quotes")]
     NotQuoted(String),
 
     /// Error generated when MEMBER is specified but the kind is not group.
     #[error("member property is only allowed when the kind is group")]
     MemberRequiresGroup,
 
+    /// Error generated when a property group name contains
+    /// characters other than letters, digits and dashes.
+    #[error("property group '{0}' is invalid")]
+    InvalidGroup(String),
+
+    /// Error generated when a PID source identifier does not
+    /// have a matching CLIENTPIDMAP.
+    #[error("pid source '{0}' does not have a CLIENTPIDMAP")]
+    UnknownPidSource(u64),
+
     /// Error generated when the PID parameter is used on the
     /// CLIENTPIDMAP property.
     #[error("PID parameter not allowed for CLIENTPIDMAP")]
     ClientPidMapPidNotAllowed,
 
+    /// Error generated when merging vCards needs a new
+    /// CLIENTPIDMAP but all source identifiers from 1 to 99
+    /// are in use.
+    #[error("no free CLIENTPIDMAP source identifier for a merge")]
+    ClientPidMapExhausted,
+
+    /// Error generated when a QR code payload exceeds the byte budget
+    /// after dropping all the optional properties.
+    #[error("QR payload is {0} bytes which exceeds the limit of {1} bytes")]
+    QrPayloadTooLarge(usize, usize),
+
+    /// Error generated when a `tel:` URI is invalid.
+    #[error("telephone URI '{0}' is invalid")]
+    InvalidTelUri(String),
+
+    /// Error generated when a `geo:` URI is invalid.
+    #[error("geographic URI '{0}' is invalid")]
+    InvalidGeoUri(String),
+
+    /// Error generated when a `data:` URI is invalid.
+    #[error("data URI '{0}' is invalid")]
+    InvalidDataUri(String),
+
+    /// Error generated when a jCard is invalid.
+    #[error("jCard is invalid: {0}")]
+    InvalidJcard(String),
+
+    /// Error generated when an xCard is invalid.
+    #[error("xCard is invalid: {0}")]
+    InvalidXcard(String),
+
+    /// Error generated when a MECARD is invalid.
+    #[error("MECARD '{0}' is invalid")]
+    InvalidMeCard(String),
+
+    /// Error generated when merging vCards that have different UIDs.
+    #[error("cannot merge vCards with UID '{0}' and UID '{1}'")]
+    UidConflict(String, String),
+
     /// Errors generated by the language tags library.
     #[cfg(feature = "language-tags")]
     #[error(transparent)]
@@ -177,6 +332,29 @@ pub enum Error {
     #[error(transparent)]
     Mime(#[from] mime::FromStrError),
 
+    /// Error generated parsing JSON.
+    #[cfg(feature = "jcard")]
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    /// Error generated parsing XML.
+    #[cfg(feature = "xml")]
+    #[error(transparent)]
+    Xml(#[from] roxmltree::Error),
+
+    /// Error generated when the value of an XML property is
+    /// in the vCard namespace.
+    #[cfg(feature = "xml")]
+    #[error(
+        "XML property at {row}:{col} must not be in the vCard namespace"
+    )]
+    XmlVcardNamespace {
+        /// Row of the root element.
+        row: u32,
+        /// Column of the root element.
+        col: u32,
+    },
+
     /// Error generated decoding from base64.
     #[error(transparent)]
     Base64(#[from] base64::DecodeError),
@@ -185,7 +363,37 @@ pub enum Error {
     #[error(transparent)]
     LexError(#[from] LexError),
 
+    /// Error generated when input bytes use an encoding other
+    /// than UTF-8.
+    #[error("input is {0} encoded, expected UTF-8")]
+    Encoding(&'static str),
+
+    /// Error generated when input bytes are not valid UTF-8.
+    #[error(transparent)]
+    Utf8(#[from] std::str::Utf8Error),
+
     /// Error generated when a CHARSET other than UTF-8 is specified.
     #[error("CHARSET='{0}' is invalid, expected UTF-8")]
     CharsetParameter(String),
+
+    /// Error generated when a quoted-printable value has an
+    /// invalid escape sequence or does not decode to valid text.
+    #[error("quoted-printable value '{0}' is invalid")]
+    QuotedPrintable(String),
+
+    /// Error for content that is not a vCard and was skipped
+    /// with [Recovery::Report](crate::Recovery::Report); the range
+    /// is the byte range of the content in the source.
+    #[error("content at {}..{} is not a vCard and was skipped", .0.start, .0.end)]
+    SkippedContent(Range<usize>),
+
+    /// Error generated when a limit set in the
+    /// [ParseOptions](crate::ParseOptions) is exceeded.
+    #[error("{which} limit of {limit} exceeded")]
+    LimitExceeded {
+        /// The limit that was exceeded.
+        which: Limit,
+        /// The value of the limit.
+        limit: usize,
+    },
 }
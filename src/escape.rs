@@ -0,0 +1,365 @@
+//! Escape and unescape property values.
+//!
+//! Parameter values use the circumflex escaping defined by
+//! [RFC6868](https://www.rfc-editor.org/rfc/rfc6868).
+//!
+//! When the `aho-corasick` feature is disabled a simple scanning
+//! implementation is used which produces identical output.
+
+#[cfg(feature = "aho-corasick")]
+use aho_corasick::AhoCorasick;
+#[cfg(feature = "aho-corasick")]
+use std::sync::LazyLock;
+
+const ESCAPE_PATTERNS: [&str; 4] = ["\\", "\n", ",", ";"];
+const ESCAPE_REPLACE: [&str; 4] = ["\\\\", "\\n", "\\,", "\\;"];
+
+const FOLD_PATTERNS: [&str; 3] = ["\r", "\n ", "\n\t"];
+const FOLD_REPLACE: [&str; 3] = ["", "", ""];
+
+const PARAMETER_PATTERNS: [&str; 3] = ["^", "\n", "\""];
+const PARAMETER_REPLACE: [&str; 3] = ["^^", "^n", "^'"];
+
+/// Patterns and their replacements.
+///
+/// The automaton is built the first time the patterns are used.
+struct Replacements {
+    patterns: &'static [&'static str],
+    replace_with: &'static [&'static str],
+    #[cfg(feature = "aho-corasick")]
+    automaton: LazyLock<AhoCorasick, fn() -> AhoCorasick>,
+}
+
+macro_rules! replacements {
+    ($patterns:expr, $replace_with:expr) => {
+        Replacements {
+            patterns: &$patterns,
+            replace_with: &$replace_with,
+            #[cfg(feature = "aho-corasick")]
+            automaton: LazyLock::new(|| AhoCorasick::new($patterns).unwrap()),
+        }
+    };
+}
+
+// Semi-colon is the last escape pattern
+static ESCAPE: Replacements = replacements!(ESCAPE_PATTERNS, ESCAPE_REPLACE);
+static ESCAPE_TEXT: Replacements = replacements!(
+    [ESCAPE_PATTERNS[0], ESCAPE_PATTERNS[1], ESCAPE_PATTERNS[2]],
+    [ESCAPE_REPLACE[0], ESCAPE_REPLACE[1], ESCAPE_REPLACE[2]]
+);
+static FOLD: Replacements = replacements!(FOLD_PATTERNS, FOLD_REPLACE);
+static PARAMETER: Replacements =
+    replacements!(PARAMETER_PATTERNS, PARAMETER_REPLACE);
+
+/// Helper for escaping values.
+pub(crate) fn escape_value(value: &str, semi_colons: bool) -> String {
+    if semi_colons {
+        replace_all(value, &ESCAPE)
+    } else {
+        replace_all(value, &ESCAPE_TEXT)
+    }
+}
+
+/// Escape a parameter value using circumflex escaping.
+pub(crate) fn escape_parameter(value: &str) -> String {
+    replace_all(value, &PARAMETER)
+}
+
+/// Remove the circumflex escape sequences from a parameter value.
+///
+/// Unknown escape sequences are preserved.
+pub(crate) fn unescape_parameter(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '^' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some('\'') => unescaped.push('"'),
+            Some('^') => unescaped.push('^'),
+            _ => {
+                unescaped.push('^');
+                continue;
+            }
+        }
+        chars.next();
+    }
+    unescaped
+}
+
+/// Remove folded line breaks from a value.
+///
+/// Must be called on the raw source before escape sequences
+/// are decoded so a decoded newline followed by whitespace is
+/// not mistaken for a fold.
+pub(crate) fn remove_folds(value: &str) -> String {
+    replace_all(value, &FOLD)
+}
+
+/// Split a parameter value into the values of a list.
+///
+/// Values may be quoted individually or the entire list may be
+/// quoted; commas escaped with a backslash do not separate
+/// values. The quotes and escape sequences are removed from
+/// the returned values; `\n` is accepted for a newline as used
+/// by the LABEL example in RFC6350.
+pub(crate) fn split_parameter_values(value: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => in_quotes = false,
+            '"' if current.is_empty() => in_quotes = true,
+            ',' => {
+                values.push(unescape_parameter(&current));
+                current.clear();
+            }
+            '\\' => match chars.peek() {
+                Some('n') | Some('N') => {
+                    current.push('\n');
+                    chars.next();
+                }
+                Some(&next @ (',' | '\\')) => {
+                    current.push(next);
+                    chars.next();
+                }
+                _ => current.push(c),
+            },
+            _ => current.push(c),
+        }
+    }
+    values.push(unescape_parameter(&current));
+    values
+}
+
+/// Format a single parameter value.
+///
+/// Backslashes are escaped with a backslash, other characters
+/// use circumflex escaping and the value is quoted only when it
+/// contains a colon, semi-colon or comma.
+pub(crate) fn format_parameter_value(value: &str) -> String {
+    quote_parameter_value(escape_parameter(&value.replace('\\', "\\\\")))
+}
+
+/// Format a list of parameter values.
+///
+/// Commas and backslashes in a value are escaped with a
+/// backslash so they do not separate values when parsed, other
+/// characters use circumflex escaping and each value is quoted
+/// in the same way as [format_parameter_value].
+pub(crate) fn format_parameter_values<T: AsRef<str>>(
+    values: impl IntoIterator<Item = T>,
+) -> String {
+    let values: Vec<String> = values
+        .into_iter()
+        .map(|value| {
+            let value = value.as_ref().replace('\\', "\\\\");
+            quote_parameter_value(escape_parameter(
+                &value.replace(',', "\\,"),
+            ))
+        })
+        .collect();
+    values.join(",")
+}
+
+/// Quote an escaped parameter value when it contains
+/// a colon, semi-colon or comma.
+fn quote_parameter_value(value: String) -> String {
+    if value.contains([':', ';', ',']) {
+        format!("\"{}\"", value)
+    } else {
+        value
+    }
+}
+
+/// Split a value on a delimiter that is not escaped.
+///
+/// Escape sequences are left in the returned slices.
+pub(crate) fn split_unescaped(value: &str, delimiter: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == delimiter {
+            parts.push(&value[start..index]);
+            start = index + c.len_utf8();
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// Remove the escape sequences from a component of
+/// a structured value.
+///
+/// Unknown escape sequences are preserved.
+pub(crate) fn unescape_component(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(c @ ('\\' | ',' | ';')) => unescaped.push(c),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(feature = "aho-corasick")]
+fn replace_all(value: &str, replacements: &Replacements) -> String {
+    if !has_pattern_start(value, replacements.patterns) {
+        return value.to_owned();
+    }
+    replacements
+        .automaton
+        .replace_all(value, replacements.replace_with)
+}
+
+#[cfg(not(feature = "aho-corasick"))]
+fn replace_all(value: &str, replacements: &Replacements) -> String {
+    scan_replace_all(value, replacements.patterns, replacements.replace_with)
+}
+
+/// Determine if a value contains the first character of a pattern.
+#[cfg(feature = "aho-corasick")]
+fn has_pattern_start(value: &str, patterns: &[&str]) -> bool {
+    value.contains(|c: char| patterns.iter().any(|p| p.starts_with(c)))
+}
+
+/// Replace patterns by scanning the value.
+///
+/// No pattern is a substring of another pattern in the same set
+/// so matching the first pattern at each position gives the same
+/// result as the automaton. Text between the characters that
+/// start a pattern is copied in one step.
+#[cfg(any(test, not(feature = "aho-corasick")))]
+fn scan_replace_all(
+    value: &str,
+    patterns: &[&str],
+    replace_with: &[&str],
+) -> String {
+    let is_start = |c: char| patterns.iter().any(|p| p.starts_with(c));
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    'scan: while let Some(index) = rest.find(is_start) {
+        result.push_str(&rest[..index]);
+        rest = &rest[index..];
+        for (pattern, replacement) in patterns.iter().zip(replace_with) {
+            if rest.starts_with(pattern) {
+                result.push_str(replacement);
+                rest = &rest[pattern.len()..];
+                continue 'scan;
+            }
+        }
+        // Starts a pattern that does not match, eg: a newline
+        // that is not followed by whitespace
+        if let Some(c) = rest.chars().next() {
+            result.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUES: [&str; 10] = [
+        "",
+        "plain text",
+        "a,b;c\\d",
+        "line\nbreak",
+        "folded\r\n line\r\n\tagain",
+        "\\n\\N\\,\\;",
+        "\\\\n",
+        "trailing\\",
+        "caf\u{e9}, \u{1F600};\n",
+        "\r\r\n\n \n\t\\",
+    ];
+
+    #[test]
+    fn escape_parameter_round_trip() {
+        for value in VALUES.iter().copied().chain(["^'\"^^n^", "^x^"]) {
+            assert_eq!(value, unescape_parameter(&escape_parameter(value)));
+        }
+        assert_eq!("a\n\"b\"^c^x", unescape_parameter("a^n^'b^'^^c^x"));
+    }
+
+    #[test]
+    fn parameter_values_round_trip() {
+        let lists: [&[&str]; 6] = [
+            &[""],
+            &["Doe", "Jane"],
+            &["Harten, Rene van", "Rene"],
+            &["say \"hi\"", "a;b:c", "^", "back\\slash"],
+            &["", "line\nbreak", ""],
+            &["C:\\new", "trailing\\", "\\,"],
+        ];
+        for values in lists {
+            let formatted = format_parameter_values(values);
+            assert_eq!(values, split_parameter_values(&formatted));
+        }
+        for value in ["C:\\new", "a\\,b", "\\"] {
+            let formatted = format_parameter_value(value);
+            assert_eq!(value, split_parameter_values(&formatted).join(","));
+        }
+        assert_eq!(
+            vec!["Harten, Rene van", "Rene"],
+            split_parameter_values("Harten\\, Rene van,Rene")
+        );
+        assert_eq!(
+            vec!["voice", "home"],
+            split_parameter_values("\"voice,home\"")
+        );
+        assert_eq!(vec!["a;b", "c"], split_parameter_values("\"a;b\",c"));
+    }
+
+    #[cfg(feature = "aho-corasick")]
+    #[test]
+    fn escape_scan_equivalent() {
+        for value in VALUES {
+            for replacements in [&ESCAPE_TEXT, &ESCAPE] {
+                assert_eq!(
+                    replace_all(value, replacements),
+                    scan_replace_all(
+                        value,
+                        replacements.patterns,
+                        replacements.replace_with
+                    ),
+                );
+            }
+            assert_eq!(
+                replace_all(value, &PARAMETER),
+                scan_replace_all(
+                    value,
+                    &PARAMETER_PATTERNS,
+                    &PARAMETER_REPLACE
+                ),
+            );
+            assert_eq!(
+                replace_all(value, &FOLD),
+                scan_replace_all(value, &FOLD_PATTERNS, &FOLD_REPLACE),
+            );
+        }
+    }
+}
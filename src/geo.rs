@@ -0,0 +1,219 @@
+//! Geographic location URIs.
+//!
+//! The `geo:` scheme is defined in
+//! [RFC5870](https://www.rfc-editor.org/rfc/rfc5870) and is the
+//! recommended value type for the GEO property and parameter.
+//!
+//! ```
+//! use vcard4::geo::GeoUri;
+//! let geo: GeoUri = "geo:37.386013,-122.082932;u=35".parse().unwrap();
+//! assert_eq!(37.386013, geo.latitude);
+//! assert_eq!(-122.082932, geo.longitude);
+//! assert_eq!(Some(35.0), geo.uncertainty);
+//! ```
+use std::{fmt, str::FromStr};
+
+use crate::{error::snippet, Error, Result, Uri};
+
+const SCHEME: &str = "geo:";
+const CRS: &str = "crs";
+const UNCERTAINTY: &str = "u";
+const WGS84: &str = "wgs84";
+
+/// Geographic location URI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoUri {
+    /// The latitude in decimal degrees.
+    pub latitude: f64,
+    /// The longitude in decimal degrees.
+    pub longitude: f64,
+    /// The altitude in meters.
+    pub altitude: Option<f64>,
+    /// The coordinate reference system (`crs` parameter);
+    /// when missing the system is WGS-84.
+    pub crs: Option<String>,
+    /// The uncertainty of the location in meters
+    /// (`u` parameter).
+    pub uncertainty: Option<f64>,
+    /// Other parameters in the order they appeared.
+    pub parameters: Vec<(String, Option<String>)>,
+}
+
+impl GeoUri {
+    /// Create a location URI for WGS-84 coordinates.
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self> {
+        let geo = Self {
+            latitude,
+            longitude,
+            altitude: None,
+            crs: None,
+            uncertainty: None,
+            parameters: Vec::new(),
+        };
+        geo.validate()?;
+        Ok(geo)
+    }
+
+    /// Determine if the coordinates use the WGS-84 reference system.
+    pub fn is_wgs84(&self) -> bool {
+        self.crs
+            .as_ref()
+            .map(|crs| crs.eq_ignore_ascii_case(WGS84))
+            .unwrap_or(true)
+    }
+
+    /// Validate the coordinates and parameters.
+    ///
+    /// The range of the coordinates is only checked for
+    /// the WGS-84 reference system.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = || Error::InvalidGeoUri(snippet(self.to_string()));
+        let finite = self.latitude.is_finite()
+            && self.longitude.is_finite()
+            && self.altitude.map(f64::is_finite).unwrap_or(true);
+        if !finite {
+            return Err(invalid());
+        }
+        if self.is_wgs84()
+            && (!(-90.0..=90.0).contains(&self.latitude)
+                || !(-180.0..=180.0).contains(&self.longitude))
+        {
+            return Err(invalid());
+        }
+        if let Some(uncertainty) = self.uncertainty {
+            if !uncertainty.is_finite() || uncertainty < 0.0 {
+                return Err(invalid());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for GeoUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{},{}", SCHEME, self.latitude, self.longitude)?;
+        if let Some(altitude) = self.altitude {
+            write!(f, ",{}", altitude)?;
+        }
+        if let Some(crs) = &self.crs {
+            write!(f, ";{}={}", CRS, crs)?;
+        }
+        if let Some(uncertainty) = self.uncertainty {
+            write!(f, ";{}={}", UNCERTAINTY, uncertainty)?;
+        }
+        for (name, value) in &self.parameters {
+            if let Some(value) = value {
+                write!(f, ";{}={}", name, value)?;
+            } else {
+                write!(f, ";{}", name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GeoUri {
+    type Err = Error;
+
+    /// Parse a location URI.
+    ///
+    /// Commas escaped as `\,` are accepted as some producers
+    /// escape the coordinates.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidGeoUri(snippet(s));
+        let rest = match s.get(..SCHEME.len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(SCHEME) => {
+                &s[SCHEME.len()..]
+            }
+            _ => return Err(invalid()),
+        };
+
+        let value = rest.replace("\\,", ",");
+        let mut parts = value.split(';');
+        let coordinates = parts
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .map(parse_number)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        let (latitude, longitude, altitude) = match coordinates[..] {
+            [latitude, longitude] => (latitude, longitude, None),
+            [latitude, longitude, altitude] => {
+                (latitude, longitude, Some(altitude))
+            }
+            _ => return Err(invalid()),
+        };
+
+        let mut geo = Self {
+            latitude,
+            longitude,
+            altitude,
+            crs: None,
+            uncertainty: None,
+            parameters: Vec::new(),
+        };
+
+        for part in parts {
+            let (name, value) = match part.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_owned())),
+                None => (part, None),
+            };
+            if name.eq_ignore_ascii_case(CRS) {
+                if geo.crs.is_some() || value.is_none() {
+                    return Err(invalid());
+                }
+                geo.crs = value;
+            } else if name.eq_ignore_ascii_case(UNCERTAINTY) {
+                if geo.uncertainty.is_some() {
+                    return Err(invalid());
+                }
+                geo.uncertainty = Some(
+                    value
+                        .as_deref()
+                        .and_then(parse_number)
+                        .ok_or_else(invalid)?,
+                );
+            } else {
+                geo.parameters.push((name.to_owned(), value));
+            }
+        }
+
+        geo.validate().map_err(|_| invalid())?;
+        Ok(geo)
+    }
+}
+
+impl TryFrom<&Uri> for GeoUri {
+    type Error = Error;
+
+    fn try_from(value: &Uri) -> Result<Self> {
+        value.to_string().parse()
+    }
+}
+
+impl TryFrom<GeoUri> for Uri {
+    type Error = Error;
+
+    fn try_from(value: GeoUri) -> Result<Self> {
+        value.to_string().parse()
+    }
+}
+
+/// Parse a decimal number with an optional sign.
+///
+/// Exponents and special values such as `inf`
+/// are not allowed.
+fn parse_number(value: &str) -> Option<f64> {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (digits, None),
+    };
+    let is_digits =
+        |s: &str| !s.is_empty() && s.bytes().all(|c| c.is_ascii_digit());
+    if !is_digits(whole) || !fraction.map(is_digits).unwrap_or(true) {
+        return None;
+    }
+    value.parse().ok()
+}
@@ -5,7 +5,9 @@ use time::{
     Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset,
 };
 
-use crate::{property::DateAndOrTime, DateTime, Error, Result};
+use crate::{
+    error::snippet, property::DateAndOrTime, DateTime, Error, Result,
+};
 
 // UTC OFFSET
 
@@ -17,12 +19,13 @@ pub fn parse_utc_offset(value: &str) -> Result<UtcOffset> {
 
     //println!("Parsing value {}", value);
 
-    let offset_format = format_description::parse(
+    let offset_format = format_description::parse_borrowed::<2>(
         "[offset_hour sign:mandatory][offset_minute]",
     )?;
 
-    let offset_hours =
-        format_description::parse("[offset_hour sign:mandatory]")?;
+    let offset_hours = format_description::parse_borrowed::<2>(
+        "[offset_hour sign:mandatory]",
+    )?;
 
     if let Ok(result) = UtcOffset::parse(value, &offset_format) {
         Ok(result)
@@ -35,7 +38,7 @@ pub(crate) fn format_utc_offset(
     f: &mut fmt::Formatter<'_>,
     val: &UtcOffset,
 ) -> fmt::Result {
-    let offset = format_description::parse(
+    let offset = format_description::parse_borrowed::<2>(
         "[offset_hour sign:mandatory][offset_minute]",
     )
     .map_err(|_| fmt::Error)?;
@@ -59,17 +62,17 @@ pub fn parse_time(value: &str) -> Result<(Time, UtcOffset)> {
         let mut parts = value.split("").collect::<Vec<_>>();
         let val = parts
             .get_mut(1)
-            .ok_or_else(|| Error::InvalidTime(value.to_string()))?;
+            .ok_or_else(|| Error::InvalidTime(snippet(value)))?;
         if *val == "-" {
             *val = "00";
         }
 
         let val = parts
             .get_mut(2)
-            .ok_or_else(|| Error::InvalidTime(value.to_string()))?;
+            .ok_or_else(|| Error::InvalidTime(snippet(value)))?;
 
         if val.is_empty() {
-            return Err(Error::InvalidTime(value.to_string()));
+            return Err(Error::InvalidTime(snippet(value)));
         }
 
         if *val == "-" {
@@ -82,27 +85,40 @@ pub fn parse_time(value: &str) -> Result<(Time, UtcOffset)> {
     }
 }
 
-fn do_parse_time(mut value: &str) -> Result<(Time, UtcOffset)> {
-    let mut offset = UtcOffset::UTC;
-    let pos = value.find('-').or_else(|| value.find('+'));
-    if let Some(pos) = pos {
-        let offset_value = &value[pos..];
-        offset = parse_utc_offset(offset_value)?;
-        value = &value[0..pos];
-    }
+/// Parse a time with an optional zone designator.
+///
+/// Only a trailing `Z` or `[+-]HH[MM]` is a zone designator;
+/// any other sign or `Z` in the value is an error.
+fn do_parse_time(value: &str) -> Result<(Time, UtcOffset)> {
+    let invalid = || Error::InvalidTime(snippet(value));
+    let (time, offset) = if let Some(time) = value.strip_suffix('Z') {
+        (time, UtcOffset::UTC)
+    } else if let Some(pos) = value.rfind(['-', '+']) {
+        let (time, zone) = value.split_at(pos);
+        let digits = &zone[1..];
+        if !matches!(digits.len(), 2 | 4)
+            || !digits.bytes().all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        (time, parse_utc_offset(zone)?)
+    } else {
+        (value, UtcOffset::UTC)
+    };
 
-    if value.ends_with('Z') {
-        value = &value[0..value.len() - 1];
+    if time.contains(['-', '+', 'Z']) {
+        return Err(invalid());
     }
 
-    let time = Time::parse(value, &Iso8601::DEFAULT)?;
+    let time = Time::parse(time, &Iso8601::DEFAULT)?;
     Ok((time, offset))
 }
 
 pub(crate) fn format_time(value: &(Time, UtcOffset)) -> Result<String> {
     let (time, offset) = value;
-    let format = format_description::parse("[hour][minute][second]")?;
-    let offset_format = format_description::parse(
+    let format =
+        format_description::parse_borrowed::<2>("[hour][minute][second]")?;
+    let offset_format = format_description::parse_borrowed::<2>(
         "[offset_hour sign:mandatory][offset_minute]",
     )?;
 
@@ -130,7 +146,7 @@ pub(crate) fn format_time_list(
 // DATE
 
 /// Parse a list of dates separated by a comma.
-pub fn parse_date_list(value: &str) -> Result<Vec<Date>> {
+pub fn parse_date_list(value: &str) -> Result<Vec<crate::Date>> {
     let mut values = Vec::new();
     for value in value.split(',') {
         values.push(parse_date(value)?);
@@ -139,18 +155,22 @@ pub fn parse_date_list(value: &str) -> Result<Vec<Date>> {
 }
 
 /// Parse a date.
-pub fn parse_date(value: &str) -> Result<Date> {
-    if value.starts_with('-') {
+///
+/// Truncated dates use zero for a missing year and one for a
+/// missing month or day; use [PartialDate](crate::PartialDate)
+/// to preserve the missing components.
+pub fn parse_date(value: &str) -> Result<crate::Date> {
+    let value = if value.starts_with('-') {
         let mut parts = value.split("").collect::<Vec<_>>();
         let val = parts
             .get_mut(1)
-            .ok_or_else(|| Error::InvalidDate(value.to_string()))?;
+            .ok_or_else(|| Error::InvalidDate(snippet(value)))?;
         if *val == "-" {
             *val = "00";
         }
         let val = parts
             .get_mut(2)
-            .ok_or_else(|| Error::InvalidDate(value.to_string()))?;
+            .ok_or_else(|| Error::InvalidDate(snippet(value)))?;
         if *val == "-" {
             *val = "00";
         }
@@ -160,28 +180,29 @@ pub fn parse_date(value: &str) -> Result<Date> {
             }
         }
 
-        let value = parts.join("");
-        do_parse_date(&value)
+        parts.join("")
     // Got a YYYY-MM format need to use 01 for the day
     } else if value.len() == 7 {
-        let value = format!("{}-01", value);
-        do_parse_date(&value)
+        format!("{}-01", value)
     // Got a YYYY format need to use 01 for the month and day
     } else if value.len() == 4 {
-        let value = format!("{}-01-01", value);
-        do_parse_date(&value)
+        format!("{}-01-01", value)
     } else {
-        do_parse_date(value)
-    }
+        value.to_owned()
+    };
+    Ok(do_parse_date(&value)?.into())
 }
 
 fn do_parse_date(s: &str) -> Result<Date> {
-    let date_separator = format_description::parse("[year]-[month]-[day]")?;
-    let date = format_description::parse("[year][month][day]")?;
+    let date_separator =
+        format_description::parse_borrowed::<2>("[year]-[month]-[day]")?;
+    let date = format_description::parse_borrowed::<2>("[year][month][day]")?;
 
-    let year_month_separator = format_description::parse("[year]-[month]")?;
+    let year_month_separator =
+        format_description::parse_borrowed::<2>("[year]-[month]")?;
 
-    let year_month = format_description::parse("[year][month]")?;
+    let year_month =
+        format_description::parse_borrowed::<2>("[year][month]")?;
 
     if let Ok(result) = Date::parse(s, &date_separator) {
         Ok(result)
@@ -197,7 +218,7 @@ fn do_parse_date(s: &str) -> Result<Date> {
 }
 
 pub(crate) fn format_date(value: &crate::Date) -> Result<String> {
-    let date = format_description::parse("[year][month][day]")?;
+    let date = format_description::parse_borrowed::<2>("[year][month][day]")?;
     Ok(value.as_ref().format(&date)?)
 }
 
@@ -230,16 +251,16 @@ pub fn parse_date_time(value: &str) -> Result<DateTime> {
     let mut it = value.splitn(2, 'T');
     let date = it
         .next()
-        .ok_or_else(|| Error::InvalidDateTime(value.to_owned()))?;
+        .ok_or_else(|| Error::InvalidDateTime(snippet(value)))?;
     let time = it
         .next()
-        .ok_or_else(|| Error::InvalidDateTime(value.to_owned()))?;
+        .ok_or_else(|| Error::InvalidDateTime(snippet(value)))?;
 
     let date = parse_date(date)?;
     let (time, offset) = parse_time(time)?;
 
     let utc = OffsetDateTime::now_utc()
-        .replace_date(date)
+        .replace_date(date.into())
         .replace_time(time)
         .replace_offset(offset);
     Ok(utc.into())
@@ -250,11 +271,11 @@ pub(crate) fn format_date_time(d: &DateTime) -> Result<String> {
     let offset = (*d).offset();
 
     let format = if offset == UtcOffset::UTC {
-        format_description::parse(
+        format_description::parse_borrowed::<2>(
             "[year][month][day]T[hour][minute][second]Z",
         )?
     } else {
-        format_description::parse(
+        format_description::parse_borrowed::<2>(
             "[year][month][day]T[hour][minute][second][offset_hour sign:mandatory][offset_minute]",
         )?
     };
@@ -278,24 +299,37 @@ pub(crate) fn format_date_time_list(
 // TIMESTAMP
 
 /// Parse a timestamp.
+///
+/// The basic format from RFC 6350 is expected but for
+/// interoperability the extended ISO 8601 format with separators
+/// (`1995-10-31T22:27:10Z`) and fractional seconds are accepted.
 pub fn parse_timestamp(value: &str) -> Result<DateTime> {
-    let offset_format = format_description::parse(
-            "[year][month][day]T[hour][minute][second][offset_hour sign:mandatory][offset_minute]",
+    let basic;
+    let value = match extended_to_basic(value) {
+        Some(value) => {
+            basic = value;
+            &basic[..]
+        }
+        None => value,
+    };
+
+    let offset_format = format_description::parse_owned::<2>(
+            "[year][month][day]T[hour][minute][second][optional [.[subsecond]]][offset_hour sign:mandatory][offset_minute]",
         )?;
-    let offset_format_hours = format_description::parse(
-            "[year][month][day]T[hour][minute][second][offset_hour sign:mandatory]",
+    let offset_format_hours = format_description::parse_owned::<2>(
+            "[year][month][day]T[hour][minute][second][optional [.[subsecond]]][offset_hour sign:mandatory]",
         )?;
-    let utc_format = format_description::parse(
-        "[year][month][day]T[hour][minute][second]Z",
+    let utc_format = format_description::parse_owned::<2>(
+        "[year][month][day]T[hour][minute][second][optional [.[subsecond]]]Z",
     )?;
-    let implicit_utc_format = format_description::parse(
-        "[year][month][day]T[hour][minute][second]",
+    let implicit_utc_format = format_description::parse_owned::<2>(
+        "[year][month][day]T[hour][minute][second][optional [.[subsecond]]]",
     )?;
 
     if let Ok(result) = OffsetDateTime::parse(value, &offset_format) {
         Ok(result.into())
     } else if let Ok(result) =
-        OffsetDateTime::parse(value, &offset_format_hours).into()
+        OffsetDateTime::parse(value, &offset_format_hours)
     {
         Ok(result.into())
     } else if let Ok(result) = PrimitiveDateTime::parse(value, &utc_format) {
@@ -308,6 +342,21 @@ pub fn parse_timestamp(value: &str) -> Result<DateTime> {
     }
 }
 
+/// Remove the separators from a timestamp in the extended format.
+///
+/// Returns `None` when the date does not use separators.
+fn extended_to_basic(value: &str) -> Option<String> {
+    let (date, rest) = value.split_at_checked(10)?;
+    let bytes = date.as_bytes();
+    if bytes[4] != b'-' || bytes[7] != b'-' || !rest.starts_with('T') {
+        return None;
+    }
+    let mut basic = String::with_capacity(value.len());
+    basic.extend(date.chars().filter(|c| *c != '-'));
+    basic.extend(rest.chars().filter(|c| *c != ':'));
+    Some(basic)
+}
+
 pub(crate) fn format_timestamp_list(
     f: &mut fmt::Formatter<'_>,
     val: &[DateTime],
@@ -364,7 +413,7 @@ pub fn parse_boolean(value: &str) -> Result<bool> {
     match &lower[..] {
         "true" => Ok(true),
         "false" => Ok(false),
-        _ => Err(Error::InvalidBoolean(value.to_string())),
+        _ => Err(Error::InvalidBoolean(snippet(value))),
     }
 }
 
@@ -1,37 +1,148 @@
 //! Iterator for parsing vCards.
 use crate::{
-    parser::{Token, VcardParser},
-    Error, Result, Vcard,
+    parser::{skip_to_begin, Token, VcardParser},
+    Error, Limit, ParseOptions, Recovery, Result, Vcard,
 };
-use std::ops::Range;
+use std::{iter::FusedIterator, ops::Range};
+
+/// Result of parsing a vCard with the byte range in the source.
+type ParseResult = Result<(Vcard, Range<usize>)>;
 
 /// Iterator for parsing vCards.
+///
+/// An error stops the iteration unless the parse options set a
+/// [Recovery] mode other than [Recovery::Stop]; the error is
+/// returned and subsequent calls return `None`. With recovery
+/// enabled iteration continues at the next `BEGIN:VCARD` at the
+/// start of a line (the [limits](crate::Limit) always stop the
+/// iteration). Content that is not a vCard, such as a MIME
+/// boundary, is reported as [Error::SkippedContent].
+///
+/// Once `None` is returned the iterator is exhausted.
 pub struct VcardIterator<'s> {
     parser: VcardParser<'s>,
+    /// Offset in the unfolded source.
     offset: usize,
+    /// Number of vCards parsed.
+    count: usize,
+    /// Whether the iteration has finished.
+    done: bool,
 }
 
 impl<'s> VcardIterator<'s> {
     /// Create a new iterator.
     pub fn new(source: &'s str, strict: bool) -> Self {
+        Self::with_options(source, ParseOptions::new().strict(strict))
+    }
+
+    /// Create a new iterator with parse options.
+    pub fn with_options(source: &'s str, options: ParseOptions) -> Self {
         Self {
-            parser: VcardParser::new(source, strict),
+            parser: VcardParser::new(source, options),
             offset: 0,
+            count: 0,
+            done: false,
         }
     }
 
     /// Parse the next vCard.
-    fn parse_next(&self, offset: usize) -> Result<(Vcard, Range<usize>)> {
+    ///
+    /// Returns `None` when only newlines remain; otherwise the
+    /// result and the offset to continue from which is after the
+    /// vCard or, when recovering from an error, at the next
+    /// `BEGIN:VCARD`. The offset is `None` after an error that
+    /// stops the iteration.
+    fn parse_next(
+        &self,
+        offset: usize,
+    ) -> Option<(ParseResult, Option<usize>)> {
         let mut lex = self.parser.lexer();
         lex.bump(offset);
         while let Some(first) = lex.next() {
             if first == Ok(Token::NewLine) {
                 continue;
-            } else {
-                return self.parser.parse_one(&mut lex, Some(first));
+            } else if let Err(e) = ParseOptions::check_limit(
+                self.parser.options.max_cards,
+                Limit::Cards,
+                self.count + 1,
+            ) {
+                return Some((Err(e), None));
+            }
+
+            let start = lex.span().start;
+            // Content between vCards such as a MIME boundary
+            if first != Ok(Token::Begin)
+                && self.parser.options.recovery != Recovery::Stop
+            {
+                let resume = match skip_to_begin(&mut lex, start) {
+                    Some(_) => lex.span().start,
+                    None => self.parser.unfolded.text.len(),
+                };
+                let range = self.parser.unfolded.source_range(start..resume);
+                return Some((
+                    Err(Error::SkippedContent(range)),
+                    Some(resume),
+                ));
+            }
+            return match self.parser.parse_one(&mut lex, Some(first)) {
+                Ok((card, span)) => {
+                    let end = span.end;
+                    Some((Ok((card, span)), Some(end)))
+                }
+                Err(e) if self.parser.recovers(&e) => {
+                    let resume = match skip_to_begin(&mut lex, start) {
+                        Some(_) => lex.span().start,
+                        None => self.parser.unfolded.text.len(),
+                    };
+                    Some((Err(e), Some(resume)))
+                }
+                Err(e) => Some((Err(e), None)),
+            };
+        }
+        None
+    }
+}
+
+impl<'s> VcardIterator<'s> {
+    /// Parse the next vCard and get the byte range
+    /// of the vCard in the source.
+    ///
+    /// The range starts at the BEGIN property and ends
+    /// after the END property so slicing the source by the
+    /// range gives the text of the vCard.
+    pub fn next_with_span(
+        &mut self,
+    ) -> Option<Result<(Vcard, Range<usize>)>> {
+        loop {
+            if self.done || self.offset >= self.parser.unfolded.text.len() {
+                self.done = true;
+                return None;
+            }
+            let Some((result, offset)) = self.parse_next(self.offset) else {
+                self.done = true;
+                return None;
+            };
+            match offset {
+                Some(offset) => self.offset = offset,
+                None => self.done = true,
+            }
+            match result {
+                Ok((card, span)) => {
+                    self.count += 1;
+                    return Some(Ok((
+                        card,
+                        self.parser.unfolded.source_range(span),
+                    )));
+                }
+                Err(e)
+                    if self.parser.options.recovery == Recovery::Skip
+                        && self.parser.recovers(&e) =>
+                {
+                    continue;
+                }
+                Err(e) => return Some(Err(e)),
             }
         }
-        Err(Error::TokenExpected)
     }
 }
 
@@ -39,15 +150,9 @@ impl<'s> Iterator for VcardIterator<'s> {
     type Item = Result<Vcard>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.offset >= self.parser.source.len() {
-            return None;
-        }
-        match self.parse_next(self.offset) {
-            Ok((card, span)) => {
-                self.offset = span.end;
-                Some(Ok(card))
-            }
-            Err(e) => Some(Err(e)),
-        }
+        self.next_with_span()
+            .map(|result| result.map(|(card, _)| card))
     }
 }
+
+impl FusedIterator for VcardIterator<'_> {}
@@ -0,0 +1,719 @@
+//! Convert to and from jCard.
+//!
+//! jCard is the JSON format for vCard data defined by
+//! [RFC7095](https://www.rfc-editor.org/rfc/rfc7095); each property
+//! is an array of the name, a parameters object, the value type
+//! and one or more values.
+//!
+//! The VALUE parameter is represented by the value type so it is
+//! not included in the parameters object; when converting from
+//! jCard a VALUE parameter is only added when the value type is
+//! not the default type for the property.
+//!
+//! ```
+//! use vcard4::{jcard, Vcard};
+//! let card = Vcard::new("Jane Doe".to_owned());
+//! let value = jcard::to_jcard(&card);
+//! assert_eq!(
+//!     r#"["vcard",[["version",{},"text","4.0"],["fn",{},"text","Jane Doe"]]]"#,
+//!     value.to_string(),
+//! );
+//! let decoded = jcard::from_jcard(&value.to_string()).unwrap();
+//! assert_eq!(card, decoded);
+//! ```
+use serde_json::{Map, Value};
+use time::{Time, UtcOffset};
+
+use crate::{
+    error::snippet,
+    escape::{escape_value, format_parameter_values},
+    name::*,
+    parameter::{Parameters, TimeZoneParameter},
+    property::{
+        AddressProperty, AnyProperty, ClientPidMapProperty, DateAndOrTime,
+        DateAndOrTimeProperty, DateTimeOrTextProperty, DateTimeProperty,
+        ExtensionProperty, GenderProperty, KindProperty, LanguageProperty,
+        NameProperty, Property, TextListDelimiter, TextListProperty,
+        TextOrUriProperty, TextProperty, TimeZoneProperty, UriProperty,
+        UtcOffsetProperty,
+    },
+    Date, DateTime, Error, PartialDate, Result, Vcard,
+};
+
+const JCARD: &str = "vcard";
+const JCARD_GROUP: &str = "group";
+const JCARD_VALUE: &str = "value";
+
+/// Convert a vCard to a jCard.
+pub fn to_jcard(card: &Vcard) -> Value {
+    let mut properties = vec![Value::Array(vec![
+        VERSION.to_lowercase().into(),
+        Value::Object(Map::new()),
+        "text".into(),
+        "4.0".into(),
+    ])];
+
+    macro_rules! push {
+        ($($field:ident => $name:expr),+ $(,)?) => {
+            $(
+                for prop in card.$field.iter() {
+                    properties.push(jcard_property($name, prop));
+                }
+            )+
+        };
+    }
+
+    push!(
+        source => SOURCE,
+        kind => KIND,
+        xml => XML,
+        formatted_name => FN,
+        name => N,
+        nickname => NICKNAME,
+        photo => PHOTO,
+        bday => BDAY,
+        anniversary => ANNIVERSARY,
+        birthplace => BIRTHPLACE,
+        deathplace => DEATHPLACE,
+        deathdate => DEATHDATE,
+        gender => GENDER,
+        pronouns => PRONOUNS,
+        url => URL,
+        address => ADR,
+        title => TITLE,
+        role => ROLE,
+        logo => LOGO,
+        org => ORG,
+        member => MEMBER,
+        related => RELATED,
+        expertise => EXPERTISE,
+        hobby => HOBBY,
+        interest => INTEREST,
+        org_directory => ORG_DIRECTORY,
+        tel => TEL,
+        email => EMAIL,
+        impp => IMPP,
+        social_profile => SOCIALPROFILE,
+        lang => LANG,
+        language => LANGUAGE,
+        timezone => TZ,
+        geo => GEO,
+        categories => CATEGORIES,
+        note => NOTE,
+        prod_id => PRODID,
+        rev => REV,
+        created => CREATED,
+        sound => SOUND,
+        uid => UID,
+        client_pid_map => CLIENTPIDMAP,
+        key => KEY,
+        fburl => FBURL,
+        cal_adr_uri => CALADRURI,
+        cal_uri => CALURI,
+    );
+
+    for prop in &card.extensions {
+        properties.push(jcard_property(&prop.name, prop));
+    }
+
+    Value::Array(vec![JCARD.into(), Value::Array(properties)])
+}
+
+/// Parse a jCard into a vCard.
+///
+/// The jCard is converted to content lines and parsed so the
+/// same validation rules apply as for the text format.
+pub fn from_jcard(input: &str) -> Result<Vcard> {
+    let value: Value = serde_json::from_str(input)?;
+    from_value(&value)
+}
+
+/// Convert a parsed jCard into a vCard.
+pub(crate) fn from_value(value: &Value) -> Result<Vcard> {
+    let properties = match value.as_array().map(Vec::as_slice) {
+        Some([Value::String(kind), Value::Array(properties)])
+            if kind == JCARD =>
+        {
+            properties
+        }
+        _ => return Err(invalid("expected a vcard array")),
+    };
+
+    let mut content = format!("{}\r\n{}\r\n", BEGIN, VERSION_4);
+    for prop in properties {
+        let Some(
+            [Value::String(name), Value::Object(parameters), Value::String(value_type), values @ ..],
+        ) = prop.as_array().map(Vec::as_slice)
+        else {
+            return Err(invalid("expected a property array"));
+        };
+
+        if values.is_empty() {
+            return Err(invalid(format!(
+                "property '{}' has no value",
+                snippet(name)
+            )));
+        }
+
+        if name.eq_ignore_ascii_case(VERSION) {
+            if values != [Value::String("4.0".to_owned())] {
+                return Err(invalid("version must be 4.0"));
+            }
+            continue;
+        }
+
+        content
+            .push_str(&content_line(name, parameters, value_type, values)?);
+        content.push_str("\r\n");
+    }
+    content.push_str(END);
+
+    let mut cards = crate::parse(content)?;
+    Ok(cards.remove(0))
+}
+
+/// Value type and values for a property.
+trait JcardValue {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>);
+}
+
+impl JcardValue for TextProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        ("text", vec![self.value.clone().into()])
+    }
+}
+
+impl JcardValue for UriProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        ("uri", vec![self.value.to_string().into()])
+    }
+}
+
+impl JcardValue for TextOrUriProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        match self {
+            Self::Text(val) => val.jcard_value(),
+            Self::Uri(val) => val.jcard_value(),
+        }
+    }
+}
+
+impl JcardValue for KindProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        ("text", vec![self.value.to_string().into()])
+    }
+}
+
+impl JcardValue for NameProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        let components = self.value.components().map(|c| component(c));
+        ("text", vec![Value::Array(components.into())])
+    }
+}
+
+impl JcardValue for AddressProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        let components = self.value.components().map(|c| component(c));
+        ("text", vec![Value::Array(components.into())])
+    }
+}
+
+impl JcardValue for GenderProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        let sex = self.value.sex.to_string();
+        let value = if let Some(identity) = &self.value.identity {
+            Value::Array(vec![sex.into(), identity.clone().into()])
+        } else {
+            sex.into()
+        };
+        ("text", vec![value])
+    }
+}
+
+impl JcardValue for TextListProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        let values = self.value.iter().map(|v| Value::from(v.clone()));
+        match self.delimiter {
+            TextListDelimiter::Comma => ("text", values.collect()),
+            TextListDelimiter::SemiColon => {
+                ("text", vec![component(&self.value)])
+            }
+        }
+    }
+}
+
+impl JcardValue for DateAndOrTimeProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        let values = self
+            .value
+            .iter()
+            .map(|value| format_date_and_or_time(value).into())
+            .collect();
+        ("date-and-or-time", values)
+    }
+}
+
+impl JcardValue for DateTimeOrTextProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        match self {
+            Self::DateTime(val) => val.jcard_value(),
+            Self::Text(val) => val.jcard_value(),
+        }
+    }
+}
+
+impl JcardValue for DateTimeProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        ("timestamp", vec![format_date_time(&self.value).into()])
+    }
+}
+
+impl JcardValue for LanguageProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        ("language-tag", vec![self.value.to_string().into()])
+    }
+}
+
+impl JcardValue for UtcOffsetProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        ("utc-offset", vec![format_utc_offset(&self.value).into()])
+    }
+}
+
+impl JcardValue for TimeZoneProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        match self {
+            Self::Text(val) => val.jcard_value(),
+            Self::Uri(val) => val.jcard_value(),
+            Self::UtcOffset(val) => val.jcard_value(),
+        }
+    }
+}
+
+impl JcardValue for ClientPidMapProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        let value =
+            vec![self.value.source.into(), self.value.uri.to_string().into()];
+        ("text", vec![Value::Array(value)])
+    }
+}
+
+impl JcardValue for ExtensionProperty {
+    fn jcard_value(&self) -> (&'static str, Vec<Value>) {
+        fn list<T>(values: &[T], f: impl Fn(&T) -> Value) -> Vec<Value> {
+            values.iter().map(f).collect()
+        }
+
+        match &self.value {
+            AnyProperty::Text(val) => ("text", vec![val.clone().into()]),
+            AnyProperty::Integer(val) => {
+                ("integer", list(val, |v| (*v).into()))
+            }
+            AnyProperty::Float(val) => ("float", list(val, |v| (*v).into())),
+            AnyProperty::Boolean(val) => ("boolean", vec![(*val).into()]),
+            AnyProperty::Date(val) => {
+                ("date", list(val, |v| format_date(v).into()))
+            }
+            AnyProperty::DateTime(val) => {
+                ("date-time", list(val, |v| format_date_time(v).into()))
+            }
+            AnyProperty::Time(val) => {
+                ("time", list(val, |v| format_time(v).into()))
+            }
+            AnyProperty::DateAndOrTime(val) => (
+                "date-and-or-time",
+                list(val, |v| format_date_and_or_time(v).into()),
+            ),
+            AnyProperty::Timestamp(val) => {
+                ("timestamp", list(val, |v| format_date_time(v).into()))
+            }
+            AnyProperty::UtcOffset(val) => {
+                ("utc-offset", vec![format_utc_offset(val).into()])
+            }
+            AnyProperty::Uri(val) => ("uri", vec![val.to_string().into()]),
+            AnyProperty::Language(val) => {
+                ("language-tag", vec![val.to_string().into()])
+            }
+        }
+    }
+}
+
+/// Encode a property as a jCard array.
+fn jcard_property(name: &str, prop: &(impl Property + JcardValue)) -> Value {
+    let (value_type, values) = prop.jcard_value();
+    let parameters = prop.parameters();
+    let value_type = parameters
+        .and_then(|params| params.value.as_ref())
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| value_type.to_owned());
+
+    let mut property = vec![
+        name.to_lowercase().into(),
+        Value::Object(jcard_parameters(prop.group(), parameters)),
+        value_type.into(),
+    ];
+    property.extend(values);
+    Value::Array(property)
+}
+
+/// Encode the group and parameters as a jCard parameters object.
+fn jcard_parameters(
+    group: Option<&String>,
+    parameters: Option<&Parameters>,
+) -> Map<String, Value> {
+    let mut map = Map::new();
+    let mut insert = |name: &str, value: Value| {
+        map.insert(name.to_lowercase(), value);
+    };
+
+    if let Some(group) = group {
+        insert(JCARD_GROUP, group.clone().into());
+    }
+
+    let Some(params) = parameters else {
+        return map;
+    };
+
+    if let Some(language) = &params.language {
+        insert(LANGUAGE, language.to_string().into());
+    }
+    if let Some(pref) = &params.pref {
+        insert(PREF, pref.to_string().into());
+    }
+    if let Some(alt_id) = &params.alt_id {
+        insert(ALTID, alt_id.clone().into());
+    }
+    if let Some(pids) = &params.pid {
+        insert(PID, multiple(pids.iter().map(|v| v.to_string())));
+    }
+    if let Some(types) = &params.types {
+        insert(TYPE, multiple(types.iter().map(|v| v.to_string())));
+    }
+    if let Some(media_type) = &params.media_type {
+        insert(MEDIATYPE, media_type.to_string().into());
+    }
+    if let Some(calscale) = &params.calscale {
+        insert(CALSCALE, calscale.clone().into());
+    }
+    if let Some(sort_as) = &params.sort_as {
+        insert(SORT_AS, multiple(sort_as.iter().cloned()));
+    }
+    if let Some(geo) = &params.geo {
+        insert(GEO, geo.to_string().into());
+    }
+    if let Some(tz) = &params.timezone {
+        let value = match tz {
+            TimeZoneParameter::Text(val) => val.clone(),
+            TimeZoneParameter::Uri(val) => val.to_string(),
+            // Must not be quoted otherwise it is parsed as a URI
+            TimeZoneParameter::UtcOffset(val) => {
+                format_utc_offset(val).replace(':', "")
+            }
+        };
+        insert(TZ, value.into());
+    }
+    if let Some(label) = &params.label {
+        insert(LABEL, label.clone().into());
+    }
+    if let Some(level) = &params.level {
+        insert(LEVEL, level.clone().into());
+    }
+    if let Some(service_type) = &params.service_type {
+        insert(SERVICE_TYPE, service_type.clone().into());
+    }
+    if let Some(country_code) = &params.country_code {
+        insert(CC, country_code.clone().into());
+    }
+    if let Some(index) = &params.index {
+        insert(INDEX, index.to_string().into());
+    }
+    if let Some(extensions) = &params.extensions {
+        for (name, values) in extensions {
+            insert(name, multiple(values.iter().cloned()));
+        }
+    }
+    map
+}
+
+/// Parameter values are a string or an array when there are
+/// multiple values.
+fn multiple(values: impl Iterator<Item = String>) -> Value {
+    let mut values: Vec<Value> = values.map(Value::from).collect();
+    if values.len() == 1 {
+        values.remove(0)
+    } else {
+        Value::Array(values)
+    }
+}
+
+/// Structured value components are a string or an array when
+/// there are multiple values.
+fn component(values: &[String]) -> Value {
+    match values {
+        [] => "".into(),
+        [value] => value.clone().into(),
+        _ => values.iter().cloned().map(Value::from).collect(),
+    }
+}
+
+/// Convert a jCard property to a content line.
+fn content_line(
+    name: &str,
+    parameters: &Map<String, Value>,
+    value_type: &str,
+    values: &[Value],
+) -> Result<String> {
+    let name = name.to_uppercase();
+    if !is_name(&name) {
+        return Err(invalid(format!(
+            "property name '{}' is invalid",
+            snippet(&name)
+        )));
+    }
+
+    let mut line = String::new();
+    if let Some(group) = parameters.get(JCARD_GROUP) {
+        match group {
+            Value::String(group) if is_name(group) => {
+                line.push_str(group);
+                line.push('.');
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "group for property '{}' is invalid",
+                    snippet(&name)
+                )))
+            }
+        }
+    }
+    line.push_str(&name);
+
+    let value_type = value_type.to_lowercase();
+    if !is_default_type(&name, &value_type) {
+        if !is_name(&value_type) {
+            return Err(invalid(format!(
+                "value type '{}' is invalid",
+                snippet(&value_type)
+            )));
+        }
+        line.push_str(&format!(";{}={}", VALUE, value_type));
+    }
+
+    for (key, value) in parameters {
+        if key == JCARD_GROUP || key.eq_ignore_ascii_case(JCARD_VALUE) {
+            continue;
+        }
+        if !is_name(key) {
+            return Err(invalid(format!(
+                "parameter '{}' is invalid",
+                snippet(key)
+            )));
+        }
+        line.push_str(&format!(
+            ";{}={}",
+            key.to_uppercase(),
+            parameter_value(key, value)?
+        ));
+    }
+
+    line.push(':');
+    line.push_str(&property_value(&name, &value_type, values)?);
+
+    if line.contains(['\r', '\n']) {
+        return Err(invalid(format!(
+            "property '{}' contains a line break",
+            snippet(&name)
+        )));
+    }
+    Ok(line)
+}
+
+/// Encode a jCard parameter value.
+fn parameter_value(key: &str, value: &Value) -> Result<String> {
+    let scalar = |value: &Value| -> Result<String> {
+        match value {
+            Value::String(val) => Ok(val.clone()),
+            Value::Number(val) => Ok(val.to_string()),
+            _ => Err(invalid(format!(
+                "parameter '{}' has an invalid value",
+                snippet(key)
+            ))),
+        }
+    };
+
+    // A single value is encoded as a list so that commas are
+    // escaped for extension parameters which hold several values
+    let values = match value {
+        Value::Array(values) => values.iter().map(scalar).collect(),
+        value => scalar(value).map(|value| vec![value]),
+    }?;
+    Ok(format_parameter_values(values))
+}
+
+/// Encode the values for a jCard property.
+fn property_value(
+    name: &str,
+    value_type: &str,
+    values: &[Value],
+) -> Result<String> {
+    let invalid_value = || {
+        invalid(format!("property '{}' has an invalid value", snippet(name)))
+    };
+    let scalar = |value: &Value| -> Result<String> {
+        match value {
+            Value::String(val) => Ok(val.clone()),
+            Value::Number(val) => Ok(val.to_string()),
+            Value::Bool(val) => {
+                Ok(if *val { "TRUE" } else { "FALSE" }.to_owned())
+            }
+            _ => Err(invalid_value()),
+        }
+    };
+
+    // Structured value
+    if let [Value::Array(components)] = values {
+        let mut encoded = Vec::new();
+        for component in components {
+            let value = match component {
+                Value::Array(values) => values
+                    .iter()
+                    .map(|v| Ok(escape_value(&scalar(v)?, true)))
+                    .collect::<Result<Vec<_>>>()?
+                    .join(","),
+                value => escape_value(&scalar(value)?, true),
+            };
+            encoded.push(value);
+        }
+        return Ok(encoded.join(";"));
+    }
+
+    let mut encoded = Vec::new();
+    for value in values {
+        let value = scalar(value)?;
+        let value = match value_type {
+            "uri" | "integer" | "float" | "boolean" | "language-tag" => value,
+            "date" | "date-time" | "date-and-or-time" | "timestamp"
+            | "time" | "utc-offset" => basic_value(value_type, &value),
+            _ => escape_value(&value, false),
+        };
+        encoded.push(value);
+    }
+    Ok(encoded.join(","))
+}
+
+/// Determine if a value type is the default for a property.
+fn is_default_type(name: &str, value_type: &str) -> bool {
+    let default_type = match name {
+        SOURCE | PHOTO | IMPP | GEO | LOGO | MEMBER | RELATED | SOUND
+        | UID | URL | KEY | FBURL | CALADRURI | CALURI | ORG_DIRECTORY
+        | SOCIALPROFILE => "uri",
+        BDAY | ANNIVERSARY | DEATHDATE => "date-and-or-time",
+        LANG | LANGUAGE => "language-tag",
+        REV | CREATED => "timestamp",
+        _ => "text",
+    };
+    value_type == default_type
+        || (default_type == "text" && value_type == "unknown")
+        || (default_type == "date-and-or-time"
+            && matches!(value_type, "date" | "date-time" | "time"))
+}
+
+/// Names for groups, properties, parameters and value types.
+fn is_name(value: &str) -> bool {
+    !value.is_empty()
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Convert a date, time or UTC offset from the extended format
+/// used by jCard to the basic format used by vCard.
+///
+/// Values of other types are returned unchanged.
+pub(crate) fn basic_value(value_type: &str, value: &str) -> String {
+    match value_type {
+        "date" | "date-time" | "date-and-or-time" | "timestamp" => {
+            basic_date_time(value)
+        }
+        "time" | "utc-offset" => value.replace(':', ""),
+        _ => value.to_owned(),
+    }
+}
+
+fn basic_date_time(value: &str) -> String {
+    let (date, time) = value.split_at(value.find('T').unwrap_or(value.len()));
+    let date = match date.as_bytes() {
+        // YYYY-MM-DD
+        [_, _, _, _, b'-', _, _, b'-', _, _] => date.replace('-', ""),
+        // --MM-DD
+        [b'-', b'-', _, _, b'-', _, _] => {
+            format!("--{}{}", &date[2..4], &date[5..])
+        }
+        _ => date.to_owned(),
+    };
+    format!("{}{}", date, time.replace(':', ""))
+}
+
+fn format_date_and_or_time(value: &DateAndOrTime) -> String {
+    match value {
+        DateAndOrTime::Date(val) => format_date(val),
+        DateAndOrTime::DateTime(val) => format_date_time(val),
+        DateAndOrTime::Time(val) => format_time(val),
+        DateAndOrTime::PartialDate(val) => format_partial_date(val),
+    }
+}
+
+fn format_partial_date(value: &PartialDate) -> String {
+    match (value.year, value.month, value.day) {
+        (None, Some(month), Some(day)) => {
+            format!("--{:02}-{:02}", month, day)
+        }
+        _ => value.to_string(),
+    }
+}
+
+fn format_date(value: &Date) -> String {
+    let date = value.as_ref();
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+fn format_date_time(value: &DateTime) -> String {
+    let value = value.as_ref();
+    format!(
+        "{}T{}",
+        format_date(&value.date().into()),
+        format_time(&(value.time(), value.offset()))
+    )
+}
+
+fn format_time(value: &(Time, UtcOffset)) -> String {
+    let (time, offset) = value;
+    format!(
+        "{:02}:{:02}:{:02}{}",
+        time.hour(),
+        time.minute(),
+        time.second(),
+        if offset.is_utc() {
+            "Z".to_owned()
+        } else {
+            format_utc_offset(offset)
+        }
+    )
+}
+
+fn format_utc_offset(value: &UtcOffset) -> String {
+    let (hours, minutes, _) = value.as_hms();
+    format!(
+        "{}{:02}:{:02}",
+        if value.is_negative() { '-' } else { '+' },
+        hours.abs(),
+        minutes.abs()
+    )
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidJcard(message.into())
+}
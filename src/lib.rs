@@ -24,7 +24,25 @@
 //! and the LANGUAGE parameter are parsed using the
 //! [language-tags](https://docs.rs/language-tags/latest/language_tags/) crate.
 //!
-//! Serde support can be enabled with the `serde` feature.
+//! Serde support can be enabled with the `serde` feature. The
+//! serialized form does not depend on the other features; URIs,
+//! language tags and media types are strings and enums with values
+//! are tagged using `kind` and `value` fields. Dates and times are
+//! strings for human-readable formats.
+//!
+//! The `jcard` feature adds conversion to and from the JSON
+//! representation defined by
+//! [RFC7095](https://www.rfc-editor.org/rfc/rfc7095) and the `xml`
+//! feature adds the XML representation defined by
+//! [RFC6351](https://www.rfc-editor.org/rfc/rfc6351).
+//!
+//! The `chrono` feature adds conversions between the date and time
+//! values and the [chrono](https://docs.rs/chrono) types.
+//!
+//! The `tracing` feature emits [tracing](https://docs.rs/tracing)
+//! spans for each vCard and property parsed; warnings are emitted
+//! when a property is skipped in loose mode and parser decisions
+//! such as falling back to a text value are logged at the trace level.
 //!
 //! ## Examples
 //!
@@ -87,85 +105,232 @@
 //! ## Implementation
 //!
 //! * The `XML` property is parsed and propagated but it is not
-//!   validated as it is optional in the RFC.
-//! * IANA Tokens are not implemented.
+//!   validated as it is optional in the RFC; with the `xml`
+//!   feature malformed values are reported as warnings by
+//!   [Vcard::validation_issues].
+//! * Properties named by an IANA token that are not known to this
+//!   library (for example `GRAMGENDER` from RFC 9554) are stored
+//!   with the extensions.
 //! * The RFC requires a CRLF sequence for line breaks but for
 //!   easier interoperability between platforms we treat the
 //!   carriage return as optional.
 //!
 
+pub mod borrowed;
 mod builder;
+mod canonical;
+#[cfg(feature = "chrono")]
+mod chrono;
+mod compare;
+pub mod data;
 mod date_time;
+mod edit;
 mod error;
+mod escape;
+pub mod geo;
 pub mod helper;
 mod iter;
+#[cfg(feature = "jcard")]
+pub mod jcard;
+mod lint;
+mod merge;
 mod name;
+pub mod normalize;
+mod options;
 pub mod parameter;
 mod parser;
 pub mod property;
+mod property_ref;
+pub mod qr;
+mod redact;
+pub mod refs;
 #[cfg(feature = "serde")]
 mod serde;
+mod sort;
+pub mod tel;
+mod trace;
+mod unfold;
 mod uri;
+mod v3;
+mod validation;
 mod vcard;
+mod verify;
+#[cfg(feature = "xml")]
+pub mod xcard;
 
 pub use builder::VcardBuilder;
-pub use error::Error;
+pub use error::{Error, PropertyError};
 pub use iter::VcardIterator;
+pub use lint::Lint;
+pub use options::{
+    CompareOptions, Limit, LineEnding, ParseOptions, Recovery, V3Options,
+    WriteOptions,
+};
+pub use property_ref::{
+    preferred_in_group, PropertyHandle, PropertyKind, PropertyRef,
+};
+pub use redact::RedactedVcard;
+pub use sort::{sort, SortKey};
+pub use v3::V3Output;
+#[cfg(feature = "serde")]
+pub use validation::ValidatedVcard;
+pub use validation::{Severity, ValidationIssue};
 pub use vcard::Vcard;
+pub use verify::{verify, CardReport};
 
-pub use date_time::{Date, DateTime};
+pub use date_time::{Clock, Date, DateTime, PartialDate, SystemClock};
 pub use time;
 pub use uri::Uri;
 
+pub(crate) use escape::{
+    escape_value, format_parameter_value, format_parameter_values,
+};
+
 /// Result type for the vCard library.
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Parse a vCard string into a collection of vCards.
 pub fn parse<S: AsRef<str>>(input: S) -> Result<Vec<Vcard>> {
-    let parser = parser::VcardParser::new(input.as_ref(), true);
-    parser.parse()
+    parse_inner(input.as_ref(), ParseOptions::new())
+}
+
+/// Parse a vCard string into a collection of vCards that borrow
+/// the text of the FN, N, NOTE, EMAIL, ORG and CATEGORIES
+/// properties from the input.
+///
+/// Other properties are skipped without being parsed; use
+/// [parse()] for the complete vCards. Values that contain
+/// escape sequences or folded lines are copied.
+///
+/// ```
+/// use std::borrow::Cow;
+/// use vcard4::parse_borrowed;
+/// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nORG:ABC\\, Inc.;Sales\nEND:VCARD";
+/// let card = parse_borrowed(input).unwrap().remove(0);
+/// assert!(matches!(card.formatted_name[0].value, Cow::Borrowed("Jane Doe")));
+/// assert_eq!(vec!["ABC, Inc.", "Sales"], card.org[0].value);
+/// let card = card.into_owned();
+/// assert_eq!("Jane Doe", card.formatted_name[0].value);
+/// ```
+pub fn parse_borrowed(
+    input: &str,
+) -> Result<Vec<borrowed::BorrowedVcard<'_>>> {
+    borrowed::parse(input)
 }
 
 /// Parse a vCard string into a collection of vCards ignoring properties
 /// that generate errors.
 pub fn parse_loose<S: AsRef<str>>(input: S) -> Result<Vec<Vcard>> {
-    let parser = parser::VcardParser::new(input.as_ref(), false);
-    parser.parse()
+    Ok(parse_collect(input)?
+        .into_iter()
+        .map(|(card, _)| card)
+        .collect())
 }
 
-/// Create a parser iterator.
-pub fn iter(source: &str, strict: bool) -> VcardIterator<'_> {
-    VcardIterator::new(source, strict)
+/// Parse a vCard string into a collection of vCards ignoring properties
+/// that generate errors and collect the errors for each vCard.
+///
+/// ```
+/// use vcard4::parse_collect;
+/// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nBDAY;VALUE=date-and-or-time:not-a-date\nEND:VCARD";
+/// let (card, errors) = parse_collect(input).unwrap().remove(0);
+/// assert_eq!("Jane Doe", card.formatted_name[0].value);
+/// assert_eq!(1, errors.len());
+/// assert_eq!("BDAY", errors[0].name);
+/// assert_eq!("BDAY;VALUE=date-and-or-time:not-a-date", errors[0].raw);
+/// ```
+pub fn parse_collect<S: AsRef<str>>(
+    input: S,
+) -> Result<Vec<(Vcard, Vec<PropertyError>)>> {
+    let parser = parser::VcardParser::new(
+        input.as_ref(),
+        ParseOptions::new().strict(false),
+    );
+    parser.parse_collect()
 }
 
-/// Helper for escaping values.
-pub(crate) fn escape_value(value: &str, semi_colons: bool) -> String {
-    use aho_corasick::AhoCorasick;
-    if semi_colons {
-        let patterns = &["\\", "\n", ",", ";"];
-        let replace_with = &["\\\\", "\\n", "\\,", "\\;"];
-        let ac = AhoCorasick::new(patterns).unwrap();
-        ac.replace_all(value, replace_with)
-    } else {
-        let patterns = &["\\", "\n", ","];
-        let replace_with = &["\\\\", "\\n", "\\,"];
-        let ac = AhoCorasick::new(patterns).unwrap();
-        ac.replace_all(value, replace_with)
+/// Parse a vCard string into a collection of vCards using
+/// the given options.
+pub fn parse_with_options<S: AsRef<str>>(
+    input: S,
+    options: ParseOptions,
+) -> Result<Vec<Vcard>> {
+    parse_inner(input.as_ref(), options)
+}
+
+/// Parse UTF-8 encoded bytes into a collection of vCards.
+///
+/// A leading byte order mark is removed. Input that is UTF-16
+/// or UTF-32 encoded is an [Error::Encoding] naming the
+/// detected encoding.
+///
+/// ```
+/// use vcard4::parse_bytes;
+/// let input = b"\xEF\xBB\xBFBEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nEND:VCARD";
+/// let card = parse_bytes(input).unwrap().remove(0);
+/// assert_eq!("Jane Doe", card.formatted_name[0].value);
+/// ```
+pub fn parse_bytes(input: &[u8]) -> Result<Vec<Vcard>> {
+    parse_inner(decode_bytes(input)?, ParseOptions::new())
+}
+
+/// Convert bytes to text removing a UTF-8 byte order mark.
+fn decode_bytes(input: &[u8]) -> Result<&str> {
+    let encoding = match input {
+        [0xFF, 0xFE, 0x00, 0x00, ..] => Some("UTF-32LE"),
+        [0x00, 0x00, 0xFE, 0xFF, ..] => Some("UTF-32BE"),
+        [0xFF, 0xFE, ..] => Some("UTF-16LE"),
+        [0xFE, 0xFF, ..] => Some("UTF-16BE"),
+        // Without a byte order mark the first character
+        // of a vCard is ASCII
+        [b, 0x00, ..] if *b != 0 => Some("UTF-16LE"),
+        [0x00, b, ..] if *b != 0 => Some("UTF-16BE"),
+        _ => None,
+    };
+    if let Some(encoding) = encoding {
+        return Err(Error::Encoding(encoding));
     }
+    let input = input.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(input);
+    Ok(std::str::from_utf8(input)?)
+}
+
+// Non-generic implementation so the parser is only
+// instantiated once regardless of the input type.
+fn parse_inner(input: &str, options: ParseOptions) -> Result<Vec<Vcard>> {
+    let parser = parser::VcardParser::new(input, options);
+    parser.parse()
+}
+
+/// Parse a single content line into a typed property.
+///
+/// Returns the property name (for extensions the name of the
+/// extension), the group and the property. The line is parsed
+/// in strict mode by the same code as a complete vCard so the
+/// values are the same; folded lines are unfolded and a
+/// trailing line break is allowed.
+///
+/// ```
+/// use vcard4::{parse_property, property::ParsedProperty};
+/// let (name, group, prop) =
+///     parse_property("work.EMAIL;TYPE=work:jane@\r\n corp.example").unwrap();
+/// assert_eq!("EMAIL", name);
+/// assert_eq!(Some("work".to_owned()), group);
+/// let ParsedProperty::Text(email) = prop else {
+///     panic!("expecting a text property");
+/// };
+/// assert_eq!("jane@corp.example", email.value);
+/// ```
+pub fn parse_property(
+    line: &str,
+) -> Result<(String, Option<String>, property::ParsedProperty)> {
+    edit::parse_property(line)
 }
 
-pub(crate) fn unescape_value(value: &str) -> String {
-    use aho_corasick::AhoCorasick;
-    let patterns = &["\r", "\n ", "\n\t", "\\n", "\\N", "\\,"];
-    let replace_with = &["", "", "", "\n", "\n", ","];
-    let ac = AhoCorasick::new(patterns).unwrap();
-    ac.replace_all(value, replace_with)
+/// Create a parser iterator.
+pub fn iter(source: &str, strict: bool) -> VcardIterator<'_> {
+    VcardIterator::new(source, strict)
 }
 
 pub(crate) fn escape_control(value: &str) -> String {
-    let values = value
-        .chars()
-        .map(|c| c.escape_unicode().to_string())
-        .collect::<Vec<_>>();
-    values.join("")
+    value.chars().flat_map(char::escape_unicode).collect()
 }
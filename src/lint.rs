@@ -0,0 +1,20 @@
+//! Findings for vCards that are valid but may be
+//! interpreted inconsistently by other software.
+use std::fmt;
+
+/// Finding for a valid vCard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lint {
+    /// More than one LANG property has the same PREF value.
+    DuplicateLanguagePref(u8),
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateLanguagePref(pref) => {
+                write!(f, "more than one LANG has PREF={}", pref)
+            }
+        }
+    }
+}
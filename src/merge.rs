@@ -0,0 +1,292 @@
+//! Merge two versions of a vCard.
+//!
+//! Properties with a PID parameter whose source identifiers map
+//! to the same CLIENTPIDMAP URI represent the same logical value
+//! ([RFC6350](https://www.rfc-editor.org/rfc/rfc6350#section-7))
+//! so only one version of the property is kept.
+use std::collections::HashMap;
+
+use crate::{
+    error::snippet,
+    parameter::{Parameters, Pid},
+    property::{
+        AddressProperty, ClientPidMapProperty, DateAndOrTimeProperty,
+        DateTimeOrTextProperty, DateTimeProperty, ExtensionProperty,
+        GenderProperty, KindProperty, LanguageProperty, NameProperty,
+        Property, TextListProperty, TextOrUriProperty, TextProperty,
+        TimeZoneProperty, UriProperty, UtcOffsetProperty,
+    },
+    Error, Result, Vcard,
+};
+
+/// Mutable access to the parameters of a property.
+pub(crate) trait ParametersMut {
+    fn parameters_mut(&mut self) -> &mut Option<Box<Parameters>>;
+}
+
+/// Mutable access to the group of a property.
+pub(crate) trait GroupMut {
+    fn group_mut(&mut self) -> &mut Option<String>;
+}
+
+macro_rules! property_mut_impl {
+    ($($prop:ty),+) => {
+        $(
+            impl ParametersMut for $prop {
+                fn parameters_mut(&mut self) -> &mut Option<Box<Parameters>> {
+                    &mut self.parameters
+                }
+            }
+
+            impl GroupMut for $prop {
+                fn group_mut(&mut self) -> &mut Option<String> {
+                    &mut self.group
+                }
+            }
+        )+
+    };
+}
+
+property_mut_impl!(
+    AddressProperty,
+    ClientPidMapProperty,
+    DateAndOrTimeProperty,
+    DateTimeProperty,
+    ExtensionProperty,
+    GenderProperty,
+    KindProperty,
+    LanguageProperty,
+    NameProperty,
+    TextListProperty,
+    TextProperty,
+    UriProperty,
+    UtcOffsetProperty
+);
+
+impl ParametersMut for TextOrUriProperty {
+    fn parameters_mut(&mut self) -> &mut Option<Box<Parameters>> {
+        match self {
+            Self::Text(val) => val.parameters_mut(),
+            Self::Uri(val) => val.parameters_mut(),
+        }
+    }
+}
+
+impl GroupMut for TextOrUriProperty {
+    fn group_mut(&mut self) -> &mut Option<String> {
+        match self {
+            Self::Text(val) => val.group_mut(),
+            Self::Uri(val) => val.group_mut(),
+        }
+    }
+}
+
+impl ParametersMut for DateTimeOrTextProperty {
+    fn parameters_mut(&mut self) -> &mut Option<Box<Parameters>> {
+        match self {
+            Self::DateTime(val) => val.parameters_mut(),
+            Self::Text(val) => val.parameters_mut(),
+        }
+    }
+}
+
+impl GroupMut for DateTimeOrTextProperty {
+    fn group_mut(&mut self) -> &mut Option<String> {
+        match self {
+            Self::DateTime(val) => val.group_mut(),
+            Self::Text(val) => val.group_mut(),
+        }
+    }
+}
+
+impl ParametersMut for TimeZoneProperty {
+    fn parameters_mut(&mut self) -> &mut Option<Box<Parameters>> {
+        match self {
+            Self::Text(val) => val.parameters_mut(),
+            Self::Uri(val) => val.parameters_mut(),
+            Self::UtcOffset(val) => val.parameters_mut(),
+        }
+    }
+}
+
+impl GroupMut for TimeZoneProperty {
+    fn group_mut(&mut self) -> &mut Option<String> {
+        match self {
+            Self::Text(val) => val.group_mut(),
+            Self::Uri(val) => val.group_mut(),
+            Self::UtcOffset(val) => val.group_mut(),
+        }
+    }
+}
+
+/// Merge `other` into a copy of `card`.
+pub(crate) fn merge(card: &Vcard, other: &Vcard) -> Result<Vcard> {
+    if let (Some(uid), Some(other_uid)) = (&card.uid, &other.uid) {
+        let (uid, other_uid) = (uid.to_string(), other_uid.to_string());
+        if uid != other_uid {
+            return Err(Error::UidConflict(snippet(uid), snippet(other_uid)));
+        }
+    }
+
+    let rev = card.rev.as_ref().map(|rev| *rev.value.as_ref());
+    let other_rev = other.rev.as_ref().map(|rev| *rev.value.as_ref());
+    let other_newer = other_rev > rev;
+
+    let mut merged = card.clone();
+    merged.order = Default::default();
+    let sources = merge_client_pid_maps(
+        &mut merged.client_pid_map,
+        &other.client_pid_map,
+    )?;
+
+    macro_rules! merge_single {
+        ($($field:ident),+) => {
+            $(
+                if let Some(prop) = &other.$field {
+                    if other_newer || merged.$field.is_none() {
+                        let mut prop = prop.clone();
+                        remap_pids(prop.parameters_mut(), &sources);
+                        merged.$field = Some(prop);
+                    }
+                }
+            )+
+        };
+    }
+
+    macro_rules! merge_multiple {
+        ($($field:ident),+) => {
+            $(
+                for prop in &other.$field {
+                    let mut prop = prop.clone();
+                    remap_pids(prop.parameters_mut(), &sources);
+                    let pids = source_pids(prop.parameters());
+                    if let Some(existing) =
+                        merged.$field.iter_mut().find(|existing| {
+                            source_pids(existing.parameters())
+                                .iter()
+                                .any(|pid| pids.contains(pid))
+                        })
+                    {
+                        if other_newer {
+                            *existing = prop;
+                        }
+                    } else if !merged.$field.contains(&prop) {
+                        merged.$field.push(prop);
+                    }
+                }
+            )+
+        };
+    }
+
+    merge_single!(
+        kind,
+        name,
+        bday,
+        anniversary,
+        birthplace,
+        deathplace,
+        deathdate,
+        prod_id,
+        rev,
+        created,
+        language,
+        uid
+    );
+    merge_multiple!(
+        source,
+        xml,
+        formatted_name,
+        nickname,
+        photo,
+        gender,
+        pronouns,
+        url,
+        address,
+        tel,
+        email,
+        impp,
+        social_profile,
+        lang,
+        title,
+        role,
+        logo,
+        org,
+        member,
+        related,
+        expertise,
+        hobby,
+        interest,
+        org_directory,
+        timezone,
+        geo,
+        categories,
+        note,
+        sound,
+        key,
+        fburl,
+        cal_adr_uri,
+        cal_uri,
+        extensions
+    );
+
+    Ok(merged)
+}
+
+/// Add the client PID maps from another vCard.
+///
+/// Returns a mapping from the source identifiers of the other
+/// vCard to the source identifiers of the merged vCard.
+///
+/// New clients are assigned the lowest source identifier that
+/// is not in use so it remains one or two digits.
+fn merge_client_pid_maps(
+    merged: &mut Vec<ClientPidMapProperty>,
+    other: &[ClientPidMapProperty],
+) -> Result<HashMap<u64, u64>> {
+    let mut sources = HashMap::new();
+    for prop in other {
+        let source = if let Some(existing) =
+            merged.iter().find(|p| p.value.uri == prop.value.uri)
+        {
+            existing.value.source
+        } else {
+            let source = (1..=99)
+                .find(|source| {
+                    !merged.iter().any(|p| p.value.source == *source)
+                })
+                .ok_or(Error::ClientPidMapExhausted)?;
+            let mut prop = prop.clone();
+            prop.value.source = source;
+            merged.push(prop);
+            source
+        };
+        sources.insert(prop.value.source, source);
+    }
+    Ok(sources)
+}
+
+/// Rewrite PID source identifiers using a source mapping.
+fn remap_pids(
+    parameters: &mut Option<Box<Parameters>>,
+    sources: &HashMap<u64, u64>,
+) {
+    let pids = parameters.as_mut().and_then(|p| p.pid.as_mut());
+    for pid in pids.into_iter().flatten() {
+        if let Some(source) = pid.source.and_then(|s| sources.get(&s)) {
+            pid.source = Some(*source);
+        }
+    }
+}
+
+/// PID values that have a source identifier.
+fn source_pids(parameters: Option<&Parameters>) -> Vec<Pid> {
+    parameters
+        .and_then(|p| p.pid.as_ref())
+        .map(|pids| {
+            pids.iter()
+                .filter(|pid| pid.source.is_some())
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -1,9 +1,10 @@
-/// Constants for property and parameter names.
+//! Constants for property and parameter names.
 
 pub(crate) const HOME: &str = "home";
 pub(crate) const WORK: &str = "work";
 
 pub(crate) const BEGIN: &str = "BEGIN:VCARD";
+pub(crate) const VERSION_3: &str = "VERSION:3.0";
 pub(crate) const VERSION_4: &str = "VERSION:4.0";
 pub(crate) const END: &str = "END:VCARD";
 
@@ -17,11 +18,16 @@ pub(crate) const NICKNAME: &str = "NICKNAME";
 pub(crate) const PHOTO: &str = "PHOTO";
 pub(crate) const BDAY: &str = "BDAY";
 pub(crate) const ANNIVERSARY: &str = "ANNIVERSARY";
+pub(crate) const BIRTHPLACE: &str = "BIRTHPLACE";
+pub(crate) const DEATHPLACE: &str = "DEATHPLACE";
+pub(crate) const DEATHDATE: &str = "DEATHDATE";
 pub(crate) const GENDER: &str = "GENDER";
+pub(crate) const PRONOUNS: &str = "PRONOUNS";
 pub(crate) const ADR: &str = "ADR";
 pub(crate) const TEL: &str = "TEL";
 pub(crate) const EMAIL: &str = "EMAIL";
 pub(crate) const IMPP: &str = "IMPP";
+pub(crate) const SOCIALPROFILE: &str = "SOCIALPROFILE";
 pub(crate) const LANG: &str = "LANG";
 pub(crate) const TZ: &str = "TZ";
 pub(crate) const GEO: &str = "GEO";
@@ -31,10 +37,15 @@ pub(crate) const LOGO: &str = "LOGO";
 pub(crate) const ORG: &str = "ORG";
 pub(crate) const MEMBER: &str = "MEMBER";
 pub(crate) const RELATED: &str = "RELATED";
+pub(crate) const EXPERTISE: &str = "EXPERTISE";
+pub(crate) const HOBBY: &str = "HOBBY";
+pub(crate) const INTEREST: &str = "INTEREST";
+pub(crate) const ORG_DIRECTORY: &str = "ORG-DIRECTORY";
 pub(crate) const CATEGORIES: &str = "CATEGORIES";
 pub(crate) const NOTE: &str = "NOTE";
 pub(crate) const PRODID: &str = "PRODID";
 pub(crate) const REV: &str = "REV";
+pub(crate) const CREATED: &str = "CREATED";
 pub(crate) const SOUND: &str = "SOUND";
 pub(crate) const UID: &str = "UID";
 pub(crate) const CLIENTPIDMAP: &str = "CLIENTPIDMAP";
@@ -45,7 +56,11 @@ pub(crate) const CALADRURI: &str = "CALADRURI";
 pub(crate) const CALURI: &str = "CALURI";
 pub(crate) const XML: &str = "XML";
 
+// Apple uses this extension for custom labels on grouped properties
+pub(crate) const X_ABLABEL: &str = "X-ABLabel";
+
 // Parameter
+// NOTE: LANGUAGE is also the name of a property (RFC 9554)
 pub(crate) const LANGUAGE: &str = "LANGUAGE";
 pub(crate) const VALUE: &str = "VALUE";
 pub(crate) const PREF: &str = "PREF";
@@ -58,9 +73,14 @@ pub(crate) const SORT_AS: &str = "SORT-AS";
 // NOTE: we use GEO from the property names
 // NOTE: we use TZ from the property names
 pub(crate) const LABEL: &str = "LABEL";
+pub(crate) const LEVEL: &str = "LEVEL";
+pub(crate) const INDEX: &str = "INDEX";
+pub(crate) const SERVICE_TYPE: &str = "SERVICE-TYPE";
+pub(crate) const CC: &str = "CC";
 // RFC 6350 removed the CHARSET parameter because it requires UTF-8, but some
 // implementations still emit CHARSET=UTF-8. This is the only value we allow.
 pub(crate) const CHARSET: &str = "CHARSET";
 
 // Apple uses this for embedded photos
 pub(crate) const ENCODING: &str = "ENCODING";
+pub(crate) const QUOTED_PRINTABLE: &str = "QUOTED-PRINTABLE";
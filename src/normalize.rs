@@ -0,0 +1,151 @@
+//! Text normalization helpers.
+//!
+//! Used to compare values that differ only in their Unicode
+//! representation, whitespace or case.
+use std::borrow::Cow;
+use unicode_normalization::UnicodeNormalization;
+
+/// Code points for the zero digit of the decimal digit ranges
+/// converted to ASCII; each range is ten contiguous code points.
+const DIGIT_ZEROS: [u32; 21] = [
+    0x0660, // Arabic-Indic
+    0x06F0, // Extended Arabic-Indic
+    0x07C0, // NKo
+    0x0966, // Devanagari
+    0x09E6, // Bengali
+    0x0A66, // Gurmukhi
+    0x0AE6, // Gujarati
+    0x0B66, // Oriya
+    0x0BE6, // Tamil
+    0x0C66, // Telugu
+    0x0CE6, // Kannada
+    0x0D66, // Malayalam
+    0x0DE6, // Sinhala
+    0x0E50, // Thai
+    0x0ED0, // Lao
+    0x0F20, // Tibetan
+    0x1040, // Myanmar
+    0x17E0, // Khmer
+    0x1810, // Mongolian
+    0x1946, // Limbu
+    0xFF10, // Fullwidth
+];
+
+/// Which property to keep when duplicates are removed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum KeepDuplicate {
+    /// Keep the first property.
+    #[default]
+    First,
+    /// Keep the property with the richest casing; mixed case is
+    /// preferred over all upper case which is preferred over
+    /// all lower case. When the casing is equivalent the first
+    /// property is kept.
+    RicherCasing,
+}
+
+/// Options for removing duplicate values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizeOptions {
+    /// Which property to keep for duplicate values.
+    pub keep: KeepDuplicate,
+}
+
+/// Normalize text for comparison.
+///
+/// The text is converted to Unicode normalization form C (NFC),
+/// runs of whitespace are collapsed to a single space and leading
+/// and trailing whitespace is removed.
+pub fn normalize_text(s: &str) -> String {
+    let mut normalized = String::with_capacity(s.len());
+    for word in s.nfc().collect::<String>().split_whitespace() {
+        if !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        normalized.push_str(word);
+    }
+    normalized
+}
+
+/// Normalize text and apply full Unicode case folding
+/// so the result may be compared case-insensitively.
+pub(crate) fn fold_text(s: &str) -> String {
+    caseless::default_case_fold_str(&normalize_text(s))
+        .nfc()
+        .collect()
+}
+
+/// Score the casing of a value; higher is richer.
+pub(crate) fn casing_score(s: &str) -> u8 {
+    let upper = s.chars().any(char::is_uppercase);
+    let lower = s.chars().any(char::is_lowercase);
+    match (upper, lower) {
+        (true, true) => 2,
+        (true, false) => 1,
+        _ => 0,
+    }
+}
+
+/// Determine if a character is a bidirectional control character.
+pub(crate) fn is_bidi_control(c: char) -> bool {
+    matches!(
+        c,
+        '\u{061C}'
+            | '\u{200E}'
+            | '\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2066}'..='\u{2069}'
+    )
+}
+
+/// Convert a Unicode decimal digit to the equivalent ASCII digit.
+pub(crate) fn ascii_digit(c: char) -> Option<char> {
+    let code = c as u32;
+    DIGIT_ZEROS.iter().find_map(|zero| {
+        if (*zero..*zero + 10).contains(&code) {
+            char::from_digit(code - zero, 10)
+        } else {
+            None
+        }
+    })
+}
+
+/// Convert Unicode decimal digits to ASCII digits and remove
+/// bidirectional control characters.
+pub(crate) fn ascii_digits(value: &str) -> Cow<'_, str> {
+    if value.is_ascii() {
+        return Cow::Borrowed(value);
+    }
+    Cow::Owned(
+        value
+            .chars()
+            .filter(|c| !is_bidi_control(*c))
+            .map(|c| ascii_digit(c).unwrap_or(c))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_ascii_digits() {
+        assert_eq!("12", ascii_digits("１２"));
+        assert_eq!("34", ascii_digits("٣٤"));
+        assert_eq!("5", ascii_digits("\u{200F}5\u{200F}"));
+        assert_eq!("1.2", ascii_digits("\u{2067}१.२\u{2069}"));
+        assert!(matches!(ascii_digits("1,2"), Cow::Borrowed(_)));
+        assert_eq!(None, ascii_digit('a'));
+    }
+
+    #[test]
+    fn normalize_fold_text() {
+        assert_eq!("Jane Doe", normalize_text("  Jane \t Doe\n"));
+        assert_eq!("Jos\u{e9}", normalize_text("Jose\u{301}"));
+        assert_eq!(fold_text("STRASSE"), fold_text("Stra\u{df}e"));
+        assert_eq!(2, casing_score("Jane"));
+        assert_eq!(1, casing_score("JANE"));
+        assert_eq!(0, casing_score("jane"));
+    }
+}
@@ -0,0 +1,558 @@
+//! Options for parsing and writing vCards.
+use std::fmt;
+
+/// Limit for parsing vCards.
+///
+/// See [ParseOptions::max_cards] for the limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// Number of vCards in the source.
+    Cards,
+    /// Number of content lines in a vCard.
+    PropertiesPerCard,
+    /// Size of a content line.
+    PropertySize,
+    /// Number of parameters for a property.
+    ParametersPerProperty,
+}
+
+/// How parsing continues after a vCard that cannot be parsed.
+///
+/// See [ParseOptions::recovery].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Recovery {
+    /// Stop at the first error.
+    #[default]
+    Stop,
+    /// Yield an error for the content that was skipped and
+    /// continue at the next `BEGIN:VCARD`.
+    ///
+    /// A vCard that cannot be parsed yields the parse error and
+    /// content that is not a vCard yields
+    /// [Error::SkippedContent](crate::Error::SkippedContent).
+    Report,
+    /// Skip to the next `BEGIN:VCARD` without an error.
+    Skip,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Cards => "cards",
+                Self::PropertiesPerCard => "properties per card",
+                Self::PropertySize => "property size",
+                Self::ParametersPerProperty => "parameters per property",
+            }
+        )
+    }
+}
+
+/// Options for parsing vCards.
+///
+/// The default options parse in strict mode.
+///
+/// ```
+/// use vcard4::{parse_with_options, ParseOptions};
+/// let input = "BEGIN:VCARD\nVERSION:4.0\nFN;PREF=１:Jane Doe\nEND:VCARD";
+/// let options = ParseOptions::new()
+///     .strict(false)
+///     .normalize_unicode_digits(true);
+/// let card = parse_with_options(input, options).unwrap().remove(0);
+/// let params = card.formatted_name[0].parameters.as_ref().unwrap();
+/// assert_eq!(Some(1), params.pref);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub(crate) strict: bool,
+    pub(crate) normalize_unicode_digits: bool,
+    pub(crate) allow_v3: bool,
+    pub(crate) locations: bool,
+    pub(crate) keep_unknown_parameters: bool,
+    pub(crate) preserve_source: bool,
+    pub(crate) date_text_fallback: bool,
+    pub(crate) keep_inline_binary: bool,
+    pub(crate) max_cards: Option<usize>,
+    pub(crate) max_properties_per_card: Option<usize>,
+    pub(crate) max_property_size: Option<usize>,
+    pub(crate) max_parameters_per_property: Option<usize>,
+    pub(crate) recovery: Recovery,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: true,
+            normalize_unicode_digits: false,
+            allow_v3: false,
+            locations: false,
+            keep_unknown_parameters: false,
+            preserve_source: false,
+            date_text_fallback: false,
+            keep_inline_binary: false,
+            max_cards: None,
+            max_properties_per_card: None,
+            max_property_size: None,
+            max_parameters_per_property: None,
+            recovery: Recovery::Stop,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Create the default parse options.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set whether properties that generate errors are rejected.
+    ///
+    /// When strict mode is disabled properties that generate
+    /// errors are ignored and parsing resumes at the next line
+    /// so the remainder of a rejected property is not read as
+    /// another property.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Set whether Unicode decimal digits in numeric parameter
+    /// values (PREF and PID) are converted to ASCII digits
+    /// before parsing.
+    ///
+    /// Bidirectional control characters that surround or are
+    /// embedded in the value are also removed.
+    ///
+    /// Only applies when strict mode is disabled.
+    pub fn normalize_unicode_digits(mut self, normalize: bool) -> Self {
+        self.normalize_unicode_digits = normalize;
+        self
+    }
+
+    /// Set whether vCards with `VERSION:3.0` are upgraded
+    /// to version 4.0 structures.
+    ///
+    /// When enabled the following constructs from
+    /// [RFC2426](https://www.rfc-editor.org/rfc/rfc2426) are accepted
+    /// for version 3.0 vCards:
+    ///
+    /// * Bare TYPE values such as `TEL;HOME;VOICE:`; the `PREF` type
+    ///   is converted to the PREF parameter and `INTERNET` is removed.
+    /// * The CHARSET parameter is removed; quoted-printable
+    ///   values may also use the ISO-8859-1 charset.
+    /// * LABEL properties are converted to the LABEL parameter of the
+    ///   ADR property with the same group or TYPE values.
+    /// * Colons escaped with a backslash are unescaped.
+    pub fn allow_v3(mut self, allow: bool) -> Self {
+        self.allow_v3 = allow;
+        self
+    }
+
+    /// Set whether parse errors include the location in the source.
+    ///
+    /// When enabled errors are wrapped in
+    /// [Error::Parse](crate::Error::Parse) with the line and column
+    /// of the property (or token) that caused the error.
+    ///
+    /// ```
+    /// use vcard4::{parse_with_options, Error, ParseOptions};
+    /// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nNOTE;LABEL=x:bar\nEND:VCARD";
+    /// let options = ParseOptions::new().locations(true);
+    /// let err = parse_with_options(input, options).unwrap_err();
+    /// assert!(matches!(err, Error::Parse { line: 4, column: 1, .. }));
+    /// assert_eq!(
+    ///     "line 4, column 1: parameter LABEL can only be applied to ADR but used on 'NOTE'",
+    ///     err.to_string(),
+    /// );
+    /// ```
+    pub fn locations(mut self, locations: bool) -> Self {
+        self.locations = locations;
+        self
+    }
+
+    /// Set whether unknown parameters are kept.
+    ///
+    /// When enabled parameters that are not known to this library
+    /// (such as `DERIVED` or `CREATED`) are added to the
+    /// parameter extensions even when the name does not have
+    /// the `X-` prefix so they are written back out; otherwise
+    /// they are rejected in strict mode and skipped when not in
+    /// strict mode.
+    ///
+    /// Malformed parameters are still rejected.
+    ///
+    /// ```
+    /// use vcard4::{parse_with_options, ParseOptions};
+    /// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nIMPP;DERIVED=TRUE:skype:jane\nEND:VCARD";
+    /// let options = ParseOptions::new().keep_unknown_parameters(true);
+    /// let card = parse_with_options(input, options).unwrap().remove(0);
+    /// let params = card.impp[0].parameters.as_ref().unwrap();
+    /// assert_eq!(
+    ///     &vec![("DERIVED".to_owned(), vec!["TRUE".to_owned()])],
+    ///     params.extensions.as_ref().unwrap(),
+    /// );
+    /// ```
+    pub fn keep_unknown_parameters(mut self, keep: bool) -> Self {
+        self.keep_unknown_parameters = keep;
+        self
+    }
+
+    /// Set whether BDAY, ANNIVERSARY and DEATHDATE values
+    /// that are not dates are kept as text.
+    ///
+    /// Many producers omit the `VALUE=text` parameter for free
+    /// text values; when enabled a value without a VALUE
+    /// parameter that cannot be parsed as a date and or time
+    /// is kept as text and `VALUE=text` is added so the vCard
+    /// is written back out correctly.
+    ///
+    /// This is always enabled when not in strict mode.
+    ///
+    /// ```
+    /// use vcard4::{parse_with_options, property::DateTimeOrTextProperty, ParseOptions};
+    /// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nBDAY:circa 1800\nEND:VCARD";
+    /// let options = ParseOptions::new().date_text_fallback(true);
+    /// let card = parse_with_options(input, options).unwrap().remove(0);
+    /// assert!(matches!(card.bday, Some(DateTimeOrTextProperty::Text(_))));
+    /// assert!(card.to_string().contains("BDAY;VALUE=text:circa 1800\r\n"));
+    /// ```
+    pub fn date_text_fallback(mut self, fallback: bool) -> Self {
+        self.date_text_fallback = fallback;
+        self
+    }
+
+    /// Set whether inline binary values are kept in their
+    /// original form.
+    ///
+    /// Version 2.1 and 3.0 producers write the PHOTO, LOGO, SOUND
+    /// and KEY properties as base64 with an `ENCODING=b` (or
+    /// `ENCODING=BASE64`) parameter. By default these values are
+    /// converted to a `data:` URI; the media type is taken from
+    /// the MEDIATYPE parameter or a legacy TYPE value such as
+    /// `JPEG` which is then removed. When enabled the value and
+    /// the parameters are kept as they appear in the source.
+    ///
+    /// ```
+    /// use vcard4::{parse_with_options, property::TextOrUriProperty, ParseOptions};
+    /// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nPHOTO;ENCODING=b;TYPE=JPEG:/9j/4A==\nEND:VCARD";
+    /// let card = parse_with_options(input, ParseOptions::new()).unwrap().remove(0);
+    /// let TextOrUriProperty::Uri(photo) = &card.photo[0] else { panic!() };
+    /// assert_eq!("data:image/jpeg;base64,/9j/4A==", photo.value.to_string());
+    ///
+    /// let options = ParseOptions::new().keep_inline_binary(true);
+    /// let card = parse_with_options(input, options).unwrap().remove(0);
+    /// assert!(matches!(card.photo[0], TextOrUriProperty::Text(_)));
+    /// ```
+    pub fn keep_inline_binary(mut self, keep: bool) -> Self {
+        self.keep_inline_binary = keep;
+        self
+    }
+
+    /// Set how parsing continues after a vCard that cannot
+    /// be parsed.
+    ///
+    /// Exports from mail clients may have text such as MIME
+    /// boundaries between vCards or a truncated vCard. When
+    /// recovery is enabled the content is skipped up to the next
+    /// `BEGIN:VCARD` at the start of a line; a `BEGIN:VCARD`
+    /// before the `END:VCARD` of a vCard is also an error for
+    /// the truncated vCard.
+    ///
+    /// The [VcardIterator](crate::VcardIterator) yields an error
+    /// for the skipped content with [Recovery::Report]; when
+    /// parsing all the vCards at once the content is always
+    /// skipped without an error. Errors for exceeded limits
+    /// are never recovered.
+    ///
+    /// ```
+    /// use vcard4::{parse_with_options, ParseOptions, Recovery};
+    /// let input = "--boundary\nBEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nEND:VCARD\n--boundary--\n";
+    /// let options = ParseOptions::new().recovery(Recovery::Skip);
+    /// let cards = parse_with_options(input, options).unwrap();
+    /// assert_eq!(1, cards.len());
+    /// ```
+    pub fn recovery(mut self, recovery: Recovery) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// Set the maximum number of vCards in the source.
+    ///
+    /// Limits guard against sources that would allocate a lot
+    /// of memory; when a limit is exceeded parsing stops with
+    /// [Error::LimitExceeded](crate::Error::LimitExceeded) even
+    /// when strict mode is disabled. There are no limits by
+    /// default.
+    ///
+    /// ```
+    /// use vcard4::{parse_with_options, Error, Limit, ParseOptions};
+    /// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nEND:VCARD\n\
+    ///     BEGIN:VCARD\nVERSION:4.0\nFN:John Doe\nEND:VCARD\n";
+    /// let options = ParseOptions::new().max_cards(Some(1));
+    /// assert!(matches!(
+    ///     parse_with_options(input, options),
+    ///     Err(Error::LimitExceeded { which: Limit::Cards, limit: 1 }),
+    /// ));
+    /// ```
+    pub fn max_cards(mut self, limit: Option<usize>) -> Self {
+        self.max_cards = limit;
+        self
+    }
+
+    /// Set the maximum number of content lines in a vCard
+    /// excluding the BEGIN, VERSION and END lines.
+    pub fn max_properties_per_card(mut self, limit: Option<usize>) -> Self {
+        self.max_properties_per_card = limit;
+        self
+    }
+
+    /// Set the maximum size in bytes of an unfolded
+    /// content line excluding the line break.
+    ///
+    /// The size is checked before the parameters and
+    /// value of the line are parsed.
+    pub fn max_property_size(mut self, limit: Option<usize>) -> Self {
+        self.max_property_size = limit;
+        self
+    }
+
+    /// Set the maximum number of parameters for a property;
+    /// empty parameters are not counted.
+    pub fn max_parameters_per_property(
+        mut self,
+        limit: Option<usize>,
+    ) -> Self {
+        self.max_parameters_per_property = limit;
+        self
+    }
+
+    /// Error when a count exceeds a limit.
+    pub(crate) fn check_limit(
+        limit: Option<usize>,
+        which: Limit,
+        count: usize,
+    ) -> crate::Result<()> {
+        match limit {
+            Some(limit) if count > limit => {
+                Err(crate::Error::LimitExceeded { which, limit })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Set whether the source content lines are kept.
+    ///
+    /// When enabled each vCard keeps the content line of every
+    /// property as it appeared in the source so that
+    /// [Vcard::to_string_preserving](crate::Vcard::to_string_preserving)
+    /// can write unmodified properties verbatim.
+    ///
+    /// Content lines of vCards upgraded from version 3.0 and
+    /// lines that add several properties (such as LANG with
+    /// multiple values) are not kept.
+    ///
+    /// ```
+    /// use vcard4::{parse_with_options, ParseOptions};
+    /// let input = "BEGIN:VCARD\nVERSION:4.0\nfn:Jane Doe\nEND:VCARD\n";
+    /// let options = ParseOptions::new().preserve_source(true);
+    /// let card = parse_with_options(input, options).unwrap().remove(0);
+    /// assert_eq!(input, card.to_string_preserving());
+    /// ```
+    pub fn preserve_source(mut self, preserve: bool) -> Self {
+        self.preserve_source = preserve;
+        self
+    }
+}
+
+/// Line ending used when writing vCards.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Carriage return and line feed as required by RFC6350.
+    #[default]
+    CrLf,
+    /// Line feed only.
+    Lf,
+}
+
+impl LineEnding {
+    /// Get the characters for this line ending.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CrLf => "\r\n",
+            Self::Lf => "\n",
+        }
+    }
+}
+
+/// Options for writing vCards.
+///
+/// The default options fold lines at 75 octets and use
+/// CRLF line endings which is the same as the `Display`
+/// implementation for [Vcard](crate::Vcard).
+///
+/// ```
+/// use vcard4::{LineEnding, Vcard, WriteOptions};
+/// let card = Vcard::new("Jane Doe".to_owned());
+/// let options = WriteOptions::new()
+///     .fold_width(None)
+///     .line_ending(LineEnding::Lf);
+/// assert_eq!(
+///     "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nEND:VCARD\n",
+///     card.to_string_with(&options),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    pub(crate) fold_width: Option<usize>,
+    pub(crate) line_ending: LineEnding,
+    pub(crate) group_properties: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            fold_width: Some(75),
+            line_ending: LineEnding::CrLf,
+            group_properties: true,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Create the default write options.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the maximum number of octets for a line excluding
+    /// the line ending; `None` disables line folding.
+    ///
+    /// The minimum width is 5 octets so that a folded line
+    /// can always hold a character.
+    pub fn fold_width(mut self, width: Option<usize>) -> Self {
+        self.fold_width = width;
+        self
+    }
+
+    /// Set the line ending.
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Set whether properties that share a group are written
+    /// next to each other; enabled by default.
+    ///
+    /// When enabled the properties of a group are written at the
+    /// position of the first property of the group; properties
+    /// of the group that are not extensions are written before
+    /// the extensions so a label such as `X-ABLabel` follows the
+    /// property it describes. Properties without a group keep
+    /// the order described for [Vcard](crate::Vcard). Group names
+    /// are compared ignoring case.
+    ///
+    /// ```
+    /// use vcard4::{parse, WriteOptions};
+    /// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\n\
+    ///     item1.TEL:+1-555-555-5555\nitem1.X-ABLabel:Pager\n\
+    ///     NOTE:Note\nEND:VCARD";
+    /// let card = parse(input).unwrap().remove(0);
+    /// assert_eq!(
+    ///     "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\n\
+    ///     item1.TEL:+1-555-555-5555\r\nitem1.X-ABLabel:Pager\r\n\
+    ///     NOTE:Note\r\nEND:VCARD\r\n",
+    ///     card.to_string(),
+    /// );
+    /// let options = WriteOptions::new().group_properties(false);
+    /// assert_eq!(
+    ///     "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\n\
+    ///     item1.TEL:+1-555-555-5555\r\nNOTE:Note\r\n\
+    ///     item1.X-ABLabel:Pager\r\nEND:VCARD\r\n",
+    ///     card.to_string_with(&options),
+    /// );
+    /// ```
+    pub fn group_properties(mut self, group: bool) -> Self {
+        self.group_properties = group;
+        self
+    }
+}
+
+/// Options for writing vCards as version 3.0.
+///
+/// By default properties that do not exist in version 3.0
+/// are dropped.
+///
+/// ```
+/// use vcard4::{V3Options, VcardBuilder, V3Output};
+/// let card = VcardBuilder::new("Jane Doe".to_owned())
+///     .gender("F")
+///     .finish();
+/// let options = V3Options::new().extensions(true);
+/// let output = V3Output::new(&card, &options);
+/// assert!(output.encoded.contains("X-GENDER:F\r\n"));
+/// assert!(output.dropped.is_empty());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct V3Options {
+    pub(crate) write: WriteOptions,
+    pub(crate) extensions: bool,
+}
+
+impl V3Options {
+    /// Create the default version 3.0 options.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the options for folding lines and line endings.
+    pub fn write_options(mut self, options: WriteOptions) -> Self {
+        self.write = options;
+        self
+    }
+
+    /// Set whether properties that do not exist in version 3.0
+    /// are written as `X-` extensions rather than dropped.
+    pub fn extensions(mut self, extensions: bool) -> Self {
+        self.extensions = extensions;
+        self
+    }
+}
+
+/// Options for comparing vCards.
+///
+/// By default all properties are compared.
+///
+/// ```
+/// use vcard4::{parse, CompareOptions};
+/// let a = parse("BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nREV:20200101T000000Z\nEND:VCARD").unwrap().remove(0);
+/// let b = parse("BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nREV:20240101T000000Z\nEND:VCARD").unwrap().remove(0);
+/// assert!(!a.semantically_equals(&b));
+/// let options = CompareOptions::new().ignore_rev(true);
+/// assert!(a.semantically_equals_with(&b, &options));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CompareOptions {
+    pub(crate) ignore_rev: bool,
+    pub(crate) ignore_prod_id: bool,
+}
+
+impl CompareOptions {
+    /// Create the default compare options.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set whether the REV property is ignored.
+    pub fn ignore_rev(mut self, ignore: bool) -> Self {
+        self.ignore_rev = ignore;
+        self
+    }
+
+    /// Set whether the PRODID property is ignored.
+    pub fn ignore_prod_id(mut self, ignore: bool) -> Self {
+        self.ignore_prod_id = ignore;
+        self
+    }
+}
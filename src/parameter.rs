@@ -22,6 +22,8 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 use mime::Mime;
 
 use crate::{
+    error::snippet,
+    format_parameter_value, format_parameter_values,
     helper::format_utc_offset,
     name::{HOME, WORK},
     Error, Result, Uri,
@@ -90,8 +92,10 @@ impl fmt::Display for TypeParameter {
 impl FromStr for TypeParameter {
     type Err = Error;
 
+    /// Type values are case-insensitive; unknown values keep
+    /// their case as extensions.
     fn from_str(s: &str) -> Result<Self> {
-        match s {
+        match &s.to_lowercase()[..] {
             HOME => Ok(Self::Home),
             WORK => Ok(Self::Work),
             _ => {
@@ -116,6 +120,92 @@ impl FromStr for TypeParameter {
     }
 }
 
+impl TypeParameter {
+    /// Compare type parameters ignoring the case of extension values.
+    pub fn eq_ignore_case(&self, other: &TypeParameter) -> bool {
+        match (self, other) {
+            (Self::Extension(a), Self::Extension(b)) => {
+                a.eq_ignore_ascii_case(b)
+            }
+            _ => self == other,
+        }
+    }
+}
+
+/// List of TYPE parameter values.
+///
+/// Values are separated by commas and parsed in the same
+/// way as the TYPE parameter; use [TypeList::matches] to
+/// filter properties by type.
+///
+/// ```
+/// use vcard4::parameter::{TelephoneType, TypeList, TypeParameter};
+/// let list: TypeList = "home,cell,voice".parse().unwrap();
+/// assert_eq!(
+///     &[
+///         TypeParameter::Home,
+///         TypeParameter::Telephone(TelephoneType::Cell),
+///         TypeParameter::Telephone(TelephoneType::Voice),
+///     ],
+///     list.types()
+/// );
+/// assert_eq!("home,cell,voice", list.to_string());
+/// ```
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
+pub struct TypeList(Vec<TypeParameter>);
+
+impl TypeList {
+    /// Get the type values.
+    pub fn types(&self) -> &[TypeParameter] {
+        &self.0
+    }
+
+    /// Determine if parameters include every type in this list.
+    ///
+    /// Extension values are compared case-insensitively; an
+    /// empty list matches any parameters.
+    pub fn matches(&self, parameters: Option<&Parameters>) -> bool {
+        self.0.iter().all(|value| {
+            parameters.is_some_and(|params| params.has_type(value))
+        })
+    }
+}
+
+impl From<Vec<TypeParameter>> for TypeList {
+    fn from(value: Vec<TypeParameter>) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Display for TypeList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, value) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TypeList {
+    type Err = Error;
+
+    /// Empty values are ignored and duplicates are removed.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut types: Vec<TypeParameter> = Vec::new();
+        for value in s.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            let value: TypeParameter = value.parse()?;
+            if !types.iter().any(|t| t.eq_ignore_case(&value)) {
+                types.push(value);
+            }
+        }
+        Ok(Self(types))
+    }
+}
+
 /// Values for a PID parameter.
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -129,6 +219,9 @@ pub struct Pid {
 
 impl Pid {
     /// Create a new property identifier.
+    ///
+    /// The parts are not checked; when parsed each part
+    /// must be one or two digits.
     pub fn new(local: u64, source: Option<u64>) -> Self {
         Self { local, source }
     }
@@ -147,25 +240,26 @@ impl fmt::Display for Pid {
 impl FromStr for Pid {
     type Err = Error;
 
+    /// Each part of the identifier is one or two digits.
     fn from_str(s: &str) -> Result<Self> {
-        let mut parts = s.splitn(2, '.');
-        let local = parts
-            .next()
-            .ok_or_else(|| Error::InvalidPid(s.to_string()))?;
-        let local: u64 = local
-            .parse()
-            .map_err(|_| Error::InvalidPid(s.to_string()))?;
-        let mut pid = Pid {
-            local,
-            source: None,
+        let part = |value: &str| -> Result<u64> {
+            if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit())
+            {
+                return Err(Error::InvalidPid(snippet(s)));
+            }
+            if value.len() > 2 {
+                return Err(Error::PidOutOfRange(snippet(s)));
+            }
+            value.parse().map_err(|_| Error::InvalidPid(snippet(s)))
         };
-        if let Some(source) = parts.next() {
-            let source: u64 = source
-                .parse()
-                .map_err(|_| Error::InvalidPid(s.to_string()))?;
-            pid.source = Some(source);
-        }
-        Ok(pid)
+        let (local, source) = match s.split_once('.') {
+            Some((local, source)) => (local, Some(source)),
+            None => (s, None),
+        };
+        Ok(Pid {
+            local: part(local)?,
+            source: source.map(part).transpose()?,
+        })
     }
 }
 
@@ -251,7 +345,7 @@ impl FromStr for RelatedType {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        match s {
+        match &s.to_lowercase()[..] {
             "contact" => Ok(Self::Contact),
             "acquaintance" => Ok(Self::Acquaintance),
             "friend" => Ok(Self::Friend),
@@ -272,7 +366,7 @@ impl FromStr for RelatedType {
             "me" => Ok(Self::Me),
             "agent" => Ok(Self::Agent),
             "emergency" => Ok(Self::Emergency),
-            _ => Err(Error::UnknownRelatedType(s.to_string())),
+            _ => Err(Error::UnknownRelatedType(snippet(s))),
         }
     }
 }
@@ -331,7 +425,7 @@ impl FromStr for TelephoneType {
             "video" => Ok(Self::Video),
             "pager" => Ok(Self::Pager),
             "textphone" => Ok(Self::TextPhone),
-            _ => Err(Error::UnknownTelephoneType(s.to_string())),
+            _ => Err(Error::UnknownTelephoneType(snippet(s))),
         }
     }
 }
@@ -340,7 +434,10 @@ impl FromStr for TelephoneType {
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
-#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(
+    feature = "serde",
+    serde(rename_all = "kebab-case", tag = "kind", content = "value")
+)]
 pub enum ValueType {
     /// Text value.
     Text,
@@ -366,12 +463,12 @@ pub enum ValueType {
     UtcOffset,
     /// Language tag value.
     LanguageTag,
-    /*
-    /// IANA token value.
-    IanaToken,
-    /// X-name value.
-    XName,
-    */
+    /// IANA token value type that is not known to this library.
+    IanaToken(String),
+    /// Extension value type specified using the X- syntax.
+    ///
+    /// The value includes the `X-` prefix.
+    Extension(String),
 }
 
 impl fmt::Display for ValueType {
@@ -392,6 +489,8 @@ impl fmt::Display for ValueType {
                 Self::Float => "float",
                 Self::UtcOffset => "utc-offset",
                 Self::LanguageTag => "language-tag",
+                Self::IanaToken(value) => value,
+                Self::Extension(value) => value,
             }
         )
     }
@@ -414,7 +513,22 @@ impl FromStr for ValueType {
             "float" => Ok(Self::Float),
             "utc-offset" => Ok(Self::UtcOffset),
             "language-tag" => Ok(Self::LanguageTag),
-            _ => Err(Error::UnknownValueType(s.to_string())),
+            _ => {
+                // Must be an iana-token or x-name
+                // https://www.rfc-editor.org/rfc/rfc6350#section-3.3
+                if s.is_empty()
+                    || !s
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-')
+                {
+                    return Err(Error::UnknownValueType(snippet(s)));
+                }
+                if s.starts_with("x-") || s.starts_with("X-") {
+                    Ok(Self::Extension(s.to_string()))
+                } else {
+                    Ok(Self::IanaToken(s.to_string()))
+                }
+            }
         }
     }
 }
@@ -425,16 +539,22 @@ impl FromStr for ValueType {
 /// create infinite type recursion in `Parameters` which would
 /// require us to wrap it in a `Box`.
 #[derive(Debug, Eq, PartialEq, Clone)]
-#[cfg_attr(feature = "serde", cfg_eval::cfg_eval, serde_as)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
-#[allow(clippy::large_enum_variant)]
+#[cfg_attr(
+    feature = "serde",
+    serde(rename_all = "camelCase", tag = "kind", content = "value")
+)]
 pub enum TimeZoneParameter {
     /// Text value.
+    ///
+    /// Text containing a delimiter is quoted when encoded; quoted
+    /// text is parsed as a URI unless it ends with the minutes
+    /// of a UTC offset (for example `UTC+05:30`).
     Text(String),
     /// Uri value.
     #[cfg_attr(feature = "zeroize", zeroize(skip))]
-    Uri(#[cfg_attr(feature = "serde", serde_as(as = "DisplayFromStr"))] Uri),
+    Uri(Uri),
     /// UTC offset value.
     #[cfg_attr(feature = "zeroize", zeroize(skip))]
     UtcOffset(UtcOffset),
@@ -489,6 +609,9 @@ pub struct Parameters {
     )]
     pub pid: Option<Vec<Pid>>,
     /// The TYPE parameter.
+    ///
+    /// Duplicate values are removed when parsing, the order
+    /// of the first occurrence of each value is preserved.
     #[cfg_attr(
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
@@ -549,8 +672,42 @@ pub struct Parameters {
         serde(default, skip_serializing_if = "Option::is_none")
     )]
     pub label: Option<String>,
+    /// The LEVEL parameter.
+    ///
+    /// This only applies to the EXPERTISE, HOBBY and
+    /// INTEREST properties.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub level: Option<String>,
+    /// The INDEX parameter.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub index: Option<u32>,
+    /// The SERVICE-TYPE parameter.
+    ///
+    /// This applies to the IMPP and SOCIALPROFILE properties.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub service_type: Option<String>,
+    /// The CC parameter from
+    /// [RFC8605](https://www.rfc-editor.org/rfc/rfc8605).
+    ///
+    /// This only applies to the ADR property; the country
+    /// code is two uppercase ASCII letters.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub country_code: Option<String>,
 
-    /// Any `X-` parameter extensions.
+    /// Any `X-` parameter extensions and unknown parameters
+    /// when they are kept by the parse options.
     #[cfg_attr(
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
@@ -558,6 +715,262 @@ pub struct Parameters {
     pub extensions: Option<Vec<(String, Vec<String>)>>,
 }
 
+// Properties box their parameters so the property types stay small
+#[cfg(feature = "zeroize")]
+impl Zeroize for Box<Parameters> {
+    fn zeroize(&mut self) {
+        self.as_mut().zeroize();
+    }
+}
+
+impl Parameters {
+    /// Create empty parameters.
+    ///
+    /// Use the `with_` methods to set parameters:
+    ///
+    /// ```
+    /// use vcard4::{parameter::{Parameters, TypeParameter}, property::TextProperty};
+    /// let prop = TextProperty::from("Jane Doe".to_owned())
+    ///     .with_parameters(
+    ///         Parameters::new()
+    ///             .with_alt_id("1".to_owned())
+    ///             .with_pref(1)
+    ///             .with_types(vec![TypeParameter::Work]),
+    ///     );
+    /// assert_eq!(
+    ///     ";PREF=1;ALTID=1;TYPE=work",
+    ///     prop.parameters.as_ref().unwrap().to_string(),
+    /// );
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[cfg(feature = "language-tags")]
+    /// Set the LANGUAGE parameter.
+    pub fn with_language(mut self, language: LanguageTag) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    #[cfg(not(feature = "language-tags"))]
+    /// Set the LANGUAGE parameter.
+    pub fn with_language(mut self, language: String) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Set the VALUE parameter.
+    pub fn with_value(mut self, value: ValueType) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    /// Set the PREF parameter.
+    ///
+    /// The preference must be between 1 and 100 to be valid.
+    pub fn with_pref(mut self, pref: u8) -> Self {
+        self.pref = Some(pref);
+        self
+    }
+
+    /// Set the ALTID parameter.
+    pub fn with_alt_id(mut self, alt_id: String) -> Self {
+        self.alt_id = Some(alt_id);
+        self
+    }
+
+    /// Set the PID parameter.
+    pub fn with_pid(mut self, pid: Vec<Pid>) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// Set the TYPE parameter.
+    pub fn with_types(mut self, types: Vec<TypeParameter>) -> Self {
+        self.types = Some(types);
+        self
+    }
+
+    /// Add a TYPE parameter value.
+    ///
+    /// If an equivalent value already exists it is not added.
+    pub fn with_type(mut self, value: TypeParameter) -> Self {
+        self.add_type(value);
+        self
+    }
+
+    #[cfg(feature = "mime")]
+    /// Set the MEDIATYPE parameter.
+    pub fn with_media_type(mut self, media_type: Mime) -> Self {
+        self.media_type = Some(media_type);
+        self
+    }
+
+    #[cfg(not(feature = "mime"))]
+    /// Set the MEDIATYPE parameter.
+    pub fn with_media_type(mut self, media_type: String) -> Self {
+        self.media_type = Some(media_type);
+        self
+    }
+
+    /// Set the CALSCALE parameter.
+    pub fn with_calscale(mut self, calscale: String) -> Self {
+        self.calscale = Some(calscale);
+        self
+    }
+
+    /// Set the SORT-AS parameter.
+    pub fn with_sort_as(mut self, sort_as: Vec<String>) -> Self {
+        self.sort_as = Some(sort_as);
+        self
+    }
+
+    /// Set the GEO parameter.
+    pub fn with_geo(mut self, geo: Uri) -> Self {
+        self.geo = Some(geo);
+        self
+    }
+
+    /// Set the TZ parameter.
+    pub fn with_timezone(mut self, timezone: TimeZoneParameter) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// Set the LABEL parameter.
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Set the LEVEL parameter.
+    pub fn with_level(mut self, level: String) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Set the INDEX parameter.
+    pub fn with_index(mut self, index: u32) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Set the SERVICE-TYPE parameter.
+    pub fn with_service_type(mut self, service_type: String) -> Self {
+        self.service_type = Some(service_type);
+        self
+    }
+
+    /// Set the CC parameter.
+    pub fn with_country_code(mut self, country_code: String) -> Self {
+        self.country_code = Some(country_code);
+        self
+    }
+
+    /// Add an extension parameter.
+    pub fn with_extension(
+        mut self,
+        name: String,
+        values: Vec<String>,
+    ) -> Self {
+        self.extensions
+            .get_or_insert_with(Vec::new)
+            .push((name, values));
+        self
+    }
+
+    /// Parse parameters from a fragment in the `;KEY=value;...` form.
+    ///
+    /// When a property name is given the TYPE, LABEL and CC parameters
+    /// are only accepted if they are allowed for the property;
+    /// otherwise these checks are skipped.
+    ///
+    /// The [Display](fmt::Display) implementation encodes
+    /// parameters as a fragment.
+    pub fn from_fragment(
+        fragment: &str,
+        property: Option<&str>,
+    ) -> Result<Self> {
+        crate::parser::VcardParser::parse_parameter_fragment(
+            fragment, property,
+        )
+    }
+
+    /// Add a TYPE parameter value.
+    ///
+    /// If an equivalent value already exists this is a no-op.
+    pub fn add_type(&mut self, value: TypeParameter) {
+        if self.has_type(&value) {
+            return;
+        }
+        if let Some(types) = self.types.as_mut() {
+            types.push(value);
+        } else {
+            self.types = Some(vec![value]);
+        }
+    }
+
+    /// Add a PID parameter value.
+    ///
+    /// If the value already exists this is a no-op.
+    pub fn add_pid(&mut self, value: Pid) {
+        match self.pid.as_mut() {
+            Some(pids) if pids.contains(&value) => {}
+            Some(pids) => pids.push(value),
+            None => self.pid = Some(vec![value]),
+        }
+    }
+
+    /// Names of the parameters that are set in encoding order.
+    pub(crate) fn names(&self) -> Vec<&str> {
+        use crate::name::*;
+        let known = [
+            (LANGUAGE, self.language.is_some()),
+            (VALUE, self.value.is_some()),
+            (PREF, self.pref.is_some()),
+            (ALTID, self.alt_id.is_some()),
+            (PID, self.pid.is_some()),
+            (TYPE, self.types.is_some()),
+            (MEDIATYPE, self.media_type.is_some()),
+            (CALSCALE, self.calscale.is_some()),
+            (SORT_AS, self.sort_as.is_some()),
+            (GEO, self.geo.is_some()),
+            (TZ, self.timezone.is_some()),
+            (LABEL, self.label.is_some()),
+            (LEVEL, self.level.is_some()),
+            (INDEX, self.index.is_some()),
+            (SERVICE_TYPE, self.service_type.is_some()),
+            (CC, self.country_code.is_some()),
+        ];
+        let mut names: Vec<&str> = known
+            .into_iter()
+            .filter_map(|(name, is_set)| is_set.then_some(name))
+            .collect();
+        if let Some(extensions) = &self.extensions {
+            names.extend(extensions.iter().map(|(name, _)| name.as_str()));
+        }
+        names
+    }
+
+    /// Determine if these parameters include a TYPE value.
+    ///
+    /// Extension values are compared case-insensitively.
+    pub fn has_type(&self, value: &TypeParameter) -> bool {
+        self.types
+            .as_ref()
+            .map(|types| types.iter().any(|t| t.eq_ignore_case(value)))
+            .unwrap_or(false)
+    }
+
+    /// Iterate the TYPE values as strings.
+    ///
+    /// Extension values include the `X-` prefix.
+    pub fn type_strings(&self) -> impl Iterator<Item = String> + '_ {
+        self.types.iter().flatten().map(|value| value.to_string())
+    }
+}
+
 impl fmt::Display for Parameters {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use crate::name::*;
@@ -571,22 +984,29 @@ impl fmt::Display for Parameters {
             write!(f, ";{}={}", PREF, pref)?;
         }
         if let Some(alt_id) = &self.alt_id {
-            write!(f, ";{}=\"{}\"", ALTID, alt_id)?;
+            write!(f, ";{}={}", ALTID, format_parameter_value(alt_id))?;
         }
         if let Some(pids) = &self.pid {
             write!(f, ";{}={}", PID, comma_delimited(pids))?;
         }
         if let Some(types) = &self.types {
-            write!(f, ";{}={}", TYPE, comma_delimited(types))?;
+            let types = types.iter().map(|t| t.to_string());
+            write!(f, ";{}={}", TYPE, format_parameter_values(types))?;
         }
         if let Some(media_type) = &self.media_type {
-            write!(f, ";{}={}", MEDIATYPE, media_type)?;
+            let media_type = media_type.to_string();
+            write!(
+                f,
+                ";{}={}",
+                MEDIATYPE,
+                format_parameter_value(&media_type)
+            )?;
         }
         if let Some(calscale) = &self.calscale {
-            write!(f, ";{}={}", CALSCALE, calscale)?;
+            write!(f, ";{}={}", CALSCALE, format_parameter_value(calscale))?;
         }
         if let Some(sort_as) = &self.sort_as {
-            write!(f, ";{}=\"{}\"", SORT_AS, comma_delimited(sort_as))?;
+            write!(f, ";{}={}", SORT_AS, format_parameter_values(sort_as))?;
         }
         if let Some(geo) = &self.geo {
             write!(f, ";{}=\"{}\"", GEO, geo)?;
@@ -594,7 +1014,7 @@ impl fmt::Display for Parameters {
         if let Some(tz) = &self.timezone {
             match tz {
                 TimeZoneParameter::Text(val) => {
-                    write!(f, ";{}={}", TZ, val)?;
+                    write!(f, ";{}={}", TZ, format_parameter_value(val))?;
                 }
                 TimeZoneParameter::UtcOffset(val) => {
                     write!(f, ";{}=", TZ)?;
@@ -607,22 +1027,43 @@ impl fmt::Display for Parameters {
             }
         }
         if let Some(label) = &self.label {
-            write!(f, ";{}=\"{}\"", LABEL, escape_parameter(label))?;
+            write!(f, ";{}={}", LABEL, format_parameter_value(label))?;
+        }
+        if let Some(level) = &self.level {
+            write!(f, ";{}={}", LEVEL, level)?;
+        }
+        if let Some(index) = &self.index {
+            write!(f, ";{}={}", INDEX, index)?;
+        }
+        if let Some(service_type) = &self.service_type {
+            write!(
+                f,
+                ";{}={}",
+                SERVICE_TYPE,
+                format_parameter_value(service_type)
+            )?;
+        }
+        if let Some(country_code) = &self.country_code {
+            write!(f, ";{}={}", CC, format_parameter_value(country_code))?;
         }
         if let Some(extensions) = &self.extensions {
-            for (name, value) in extensions {
-                write!(f, ";{}=\"{}\"", name, comma_delimited(value))?;
+            for (name, values) in extensions {
+                write!(f, ";{}={}", name, format_parameter_values(values))?;
             }
         }
         Ok(())
     }
 }
 
-fn escape_parameter(s: &str) -> String {
-    s.replace('\n', "\\n")
+impl FromStr for Parameters {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_fragment(s, None)
+    }
 }
 
-fn comma_delimited(items: &Vec<impl std::fmt::Display>) -> String {
+fn comma_delimited(items: &[impl std::fmt::Display]) -> String {
     let mut value = String::new();
     for (index, item) in items.iter().enumerate() {
         value.push_str(&item.to_string());
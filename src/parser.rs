@@ -1,7 +1,11 @@
 //! Parse vCards based on [RFC6350](https://www.rfc-editor.org/rfc/rfc6350).
 
 use logos::{Lexer, Logos};
-use std::{borrow::Cow, ops::Range};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    ops::Range,
+};
 
 #[cfg(feature = "language-tags")]
 use language_tags::LanguageTag;
@@ -10,13 +14,30 @@ use language_tags::LanguageTag;
 use mime::Mime;
 
 use crate::{
-    error::LexError, escape_control, helper::*, name::*, parameter::*,
-    property::*, unescape_value, Error, Result, Uri, Vcard,
+    data::DataUri,
+    error::snippet,
+    error::{LexError, PropertyError},
+    escape::{
+        remove_folds, split_parameter_values, split_unescaped,
+        unescape_component,
+    },
+    escape_control,
+    helper::*,
+    name::*,
+    normalize::ascii_digits,
+    parameter::*,
+    property::*,
+    property_ref::{PropertyKind, SourceLine, SourceText},
+    tel::{is_tel_uri, TelUri},
+    trace::{debug_span, trace_event, warn_event},
+    unfold::Unfolded,
+    vcard::content_line_with,
+    Error, Limit, ParseOptions, Recovery, Result, Uri, Vcard, WriteOptions,
 };
 
-type LexResult<T> = std::result::Result<T, LexError>;
+pub(crate) type LexResult<T> = std::result::Result<T, LexError>;
 
-#[derive(Logos, Debug, PartialEq)]
+#[derive(Logos, Debug, Clone, PartialEq)]
 #[logos(error = LexError)]
 pub(crate) enum Token {
     #[regex("(?i:BEGIN:VCARD)")]
@@ -26,14 +47,14 @@ pub(crate) enum Token {
     Version,
 
     // Special case shared between property and parameter
-    #[token("TZ")]
+    #[regex("(?i:TZ)")]
     TimeZone,
 
     // Special case shared between property and parameter
-    #[token("GEO")]
+    #[regex("(?i:GEO)")]
     Geo,
 
-    #[regex("(?i:([a-z0-9-]+\\.)?(SOURCE|KIND|FN|N|NICKNAME|PHOTO|BDAY|ANNIVERSARY|GENDER|ADR|TEL|EMAIL|IMPP|LANG|TITLE|ROLE|LOGO|ORG|MEMBER|RELATED|CATEGORIES|NOTE|PRODID|REV|SOUND|UID|CLIENTPIDMAP|URL|KEY|FBURL|CALADRURI|CALURI|XML|VERSION|(X-[a-z0-9-]+)))")]
+    #[regex("(?i:([a-z0-9-]+\\.)?(SOURCE|KIND|FN|N|NICKNAME|PHOTO|BDAY|ANNIVERSARY|BIRTHPLACE|DEATHPLACE|DEATHDATE|GENDER|PRONOUNS|ADR|TEL|EMAIL|IMPP|SOCIALPROFILE|LANG|TITLE|ROLE|LOGO|ORG|MEMBER|RELATED|EXPERTISE|HOBBY|INTEREST|ORG-DIRECTORY|CATEGORIES|NOTE|PRODID|REV|CREATED|SOUND|UID|CLIENTPIDMAP|URL|KEY|FBURL|CALADRURI|CALURI|XML|VERSION|(X-[a-z0-9-]+)))")]
     PropertyName,
 
     #[regex("(?i:x-[a-z0-9-]+)")]
@@ -45,7 +66,7 @@ pub(crate) enum Token {
     #[token("\"")]
     DoubleQuote,
 
-    #[regex("(?i:LANGUAGE|VALUE|PREF|ALTID|PID|TYPE|MEDIATYPE|CALSCALE|SORT-AS|CHARSET|LABEL|ENCODING)")]
+    #[regex("(?i:LANGUAGE|VALUE|PREF|ALTID|PID|TYPE|MEDIATYPE|CALSCALE|SORT-AS|CHARSET|LABEL|LEVEL|INDEX|SERVICE-TYPE|CC|ENCODING)")]
     ParameterKey,
 
     #[token("=")]
@@ -54,7 +75,9 @@ pub(crate) enum Token {
     #[token(":")]
     PropertyDelimiter,
 
-    #[regex("\\r?\\n( |\\t)")]
+    // Stray carriage returns before a line feed are part
+    // of the line terminator
+    #[regex("\\r*\\n( |\\t)")]
     FoldedLine,
 
     #[token("\\,")]
@@ -69,7 +92,7 @@ pub(crate) enum Token {
     #[regex("(?i:\\\\n)")]
     EscapedNewLine,
 
-    #[regex("\\r?\\n", priority = 3)]
+    #[regex("\\r*\\n", priority = 3)]
     NewLine,
 
     #[regex("[[:blank:]]", priority = 2)]
@@ -83,35 +106,85 @@ pub(crate) enum Token {
 
     #[regex("[\u{00}-\u{7F}]", priority = 0)]
     Text,
+
+    // Non-ASCII scalar values are only allowed in values
+    #[regex("[\u{80}-\u{10FFFF}]", priority = 0)]
+    NonAscii,
 }
 
 /// Parses vCards from a string.
 pub(crate) struct VcardParser<'s> {
-    strict: bool,
-    pub(crate) source: &'s str,
+    pub(crate) options: ParseOptions,
+    source: &'s str,
+    /// The source with folded lines joined for lexing.
+    pub(crate) unfolded: Unfolded<'s>,
+    /// Whether the vCard being parsed is upgraded from version 3.0.
+    v3: Cell<bool>,
+    /// Properties skipped in loose mode for the vCard being parsed.
+    errors: RefCell<Vec<PropertyError>>,
 }
 
 impl<'s> VcardParser<'s> {
     /// Create a new parser.
-    pub fn new(source: &'s str, strict: bool) -> Self {
-        Self { source, strict }
+    pub fn new(source: &'s str, options: ParseOptions) -> Self {
+        Self {
+            source,
+            unfolded: Unfolded::new(source),
+            options,
+            v3: Cell::new(false),
+            errors: RefCell::new(Vec::new()),
+        }
     }
 
     /// Parse a UTF-8 encoded string into a list of vCards.
     pub(crate) fn parse(&self) -> Result<Vec<Vcard>> {
+        Ok(self
+            .parse_collect()?
+            .into_iter()
+            .map(|(card, _)| card)
+            .collect())
+    }
+
+    /// Parse a UTF-8 encoded string into a list of vCards and
+    /// the properties that were skipped for each vCard.
+    pub(crate) fn parse_collect(
+        &self,
+    ) -> Result<Vec<(Vcard, Vec<PropertyError>)>> {
         let mut cards = Vec::new();
         let mut lex = self.lexer();
 
-        while let Some(first) = lex.next() {
+        let mut next = lex.next();
+        while let Some(first) = next.take() {
             // Allow leading newlines and newlines between
             // vCard definitions
             if first == Ok(Token::NewLine) {
+                next = lex.next();
                 continue;
             }
 
-            let (card, _) = self.parse_one(&mut lex, Some(first))?;
-            card.validate()?;
-            cards.push(card);
+            let start = lex.span().start;
+            ParseOptions::check_limit(
+                self.options.max_cards,
+                Limit::Cards,
+                cards.len() + 1,
+            )?;
+            let result = self.parse_one(&mut lex, Some(first)).and_then(
+                |(card, _)| {
+                    card.validate().map_err(|e| self.located(e, start))?;
+                    Ok(card)
+                },
+            );
+            match result {
+                Ok(card) => {
+                    cards.push((card, self.errors.take()));
+                    next = lex.next();
+                }
+                Err(e) if self.recovers(&e) => {
+                    warn_event!(error = %e, start, "vCard skipped");
+                    next = skip_to_begin(&mut lex, start);
+                }
+                Err(e) => return Err(e),
+            }
         }
 
         if cards.is_empty() {
@@ -121,9 +194,25 @@ impl<'s> VcardParser<'s> {
         Ok(cards)
     }
 
+    /// Determine if parsing continues after an error.
+    pub(crate) fn recovers(&self, error: &Error) -> bool {
+        let error = match error {
+            Error::Parse { source, .. } => source.as_ref(),
+            error => error,
+        };
+        self.options.recovery != Recovery::Stop
+            && !matches!(error, Error::LimitExceeded { .. })
+    }
+
+    /// Determine if date values that cannot be parsed are kept as text.
+    fn date_text_fallback(&self) -> bool {
+        !self.options.strict || self.options.date_text_fallback
+    }
+
     /// Get a lexer for the current source.
-    pub(crate) fn lexer(&self) -> Lexer<'s, Token> {
-        Token::lexer(self.source)
+    pub(crate) fn lexer(&self) -> Lexer<'_, Token> {
+        // A trailing carriage return terminates the last line
+        Token::lexer(self.unfolded.text.trim_end_matches('\r'))
     }
 
     /// Parse a single vCard.
@@ -132,17 +221,56 @@ impl<'s> VcardParser<'s> {
         lex: &mut Lexer<'_, Token>,
         first: Option<LexResult<Token>>,
     ) -> Result<(Vcard, Range<usize>)> {
-        self.assert_token(first.as_ref(), &[Token::Begin])?;
-        self.assert_token(lex.next().as_ref(), &[Token::NewLine])?;
+        debug_span!("vcard", offset = lex.span().start);
+        self.parse_card(lex, first)
+            .map_err(|e| self.located(e, lex.span().start))
+    }
+
+    fn parse_card(
+        &self,
+        lex: &mut Lexer<'_, Token>,
+        first: Option<LexResult<Token>>,
+    ) -> Result<(Vcard, Range<usize>)> {
+        let start = lex.span().start;
+        self.errors.borrow_mut().clear();
+        assert_token(first.as_ref(), &[Token::Begin])?;
+        assert_token(lex.next().as_ref(), &[Token::NewLine])?;
 
-        self.assert_token(lex.next().as_ref(), &[Token::Version])?;
-        self.assert_token(lex.next().as_ref(), &[Token::NewLine])?;
+        assert_token(lex.next().as_ref(), &[Token::Version])?;
+        self.v3
+            .set(self.options.allow_v3 && lex.slice().ends_with("3.0"));
+        assert_token(lex.next().as_ref(), &[Token::NewLine])?;
 
         let mut card: Vcard = Default::default();
+        if self.options.preserve_source && !self.v3.get() {
+            let head = self.unfolded.source_range(start..lex.span().end);
+            let line_ending = if lex.slice().ends_with("\r\n") {
+                "\r\n"
+            } else {
+                "\n"
+            };
+            card.source_lines.0 = Some(Box::new(SourceText {
+                head: self.source[head].to_owned(),
+                tail: String::new(),
+                line_ending: line_ending.to_owned(),
+                lines: Vec::new(),
+            }));
+        }
 
         self.parse_properties(lex, &mut card)?;
+        if let Some(lines) = card.source_lines.0.as_mut() {
+            if lex.slice().eq_ignore_ascii_case("END:VCARD") {
+                let tail = self.unfolded.source_range(lex.span());
+                lines.tail = self.source[tail].to_owned();
+            } else {
+                lines.tail = END.to_owned();
+            }
+        }
+        if self.v3.get() {
+            attach_labels(&mut card);
+        }
 
-        Ok((card, lex.span()))
+        Ok((card, start..lex.span().end))
     }
 
     /// Parse the properties of a vCard.
@@ -151,6 +279,7 @@ impl<'s> VcardParser<'s> {
         lex: &mut Lexer<'_, Token>,
         card: &mut Vcard,
     ) -> Result<()> {
+        let mut lines = 0;
         while let Some(first) = lex.next() {
             if first == Ok(Token::End) {
                 break;
@@ -158,26 +287,196 @@ impl<'s> VcardParser<'s> {
             if let Ok(Token::Version) = first {
                 return Err(Error::VersionMisplaced);
             }
+            // A truncated vCard is followed by the next vCard
+            if first == Ok(Token::Begin)
+                && self.options.recovery != Recovery::Stop
+            {
+                return Err(Error::IncorrectToken(format!(
+                    "{:#?}",
+                    Token::Begin
+                )));
+            }
 
-            self.assert_token(
-                Some(&first),
-                &[
-                    Token::PropertyName,
-                    Token::ExtensionName,
-                    Token::TimeZone,
-                    Token::Geo,
-                ],
+            // Limits are checked before the line is parsed
+            lines += 1;
+            ParseOptions::check_limit(
+                self.options.max_properties_per_card,
+                Limit::PropertiesPerCard,
+                lines,
             )?;
+            ParseOptions::check_limit(
+                self.options.max_property_size,
+                Limit::PropertySize,
+                line_len(lex.source(), lex.span().start),
+            )
+            .map_err(|e| self.located(e, lex.span().start))?;
+
+            let iana = self.property_name(lex, &first)?;
+
+            let name = lex.slice();
+            let start = lex.span().start;
+
+            // Record the source order, a single line may add
+            // several properties (LANG) or none when skipped
+            let kind = lex
+                .slice()
+                .rsplit('.')
+                .next()
+                .and_then(PropertyKind::from_name)
+                .or(iana.then_some(PropertyKind::Extension));
+            let count = kind.map(|kind| card.property_count(kind));
+
+            let result = self.parse_property(lex, first, card);
+            if let (Some(kind), Some(count)) = (kind, count) {
+                let added = card.property_count(kind).saturating_sub(count);
+                card.order.0.extend(std::iter::repeat_n(kind, added));
+                if added == 1 && card.source_lines.0.is_some() {
+                    self.keep_source_line(lex, card, kind, count, start);
+                }
+            }
 
-            if let Err(e) = self.parse_property(lex, first, card) {
-                if self.strict {
-                    return Err(e);
+            if let Err(e) = result {
+                if self.options.strict
+                    || matches!(e, Error::LimitExceeded { .. })
+                {
+                    return Err(self.located(e, start));
                 }
+                skip_line(lex);
+                let span = self.unfolded.source_range(start..lex.span().end);
+                warn_event!(
+                    property = name,
+                    error = %e,
+                    start = span.start,
+                    end = span.end,
+                    "property skipped"
+                );
+                let raw =
+                    self.source[span.clone()].trim_end_matches(['\r', '\n']);
+                self.errors.borrow_mut().push(PropertyError {
+                    name: name.rsplit('.').next().unwrap_or(name).to_owned(),
+                    raw: raw.to_owned(),
+                    span,
+                    error: e,
+                });
             }
         }
         Ok(())
     }
 
+    /// Check the name of a content line returning whether
+    /// it is an IANA token not known to the lexer.
+    ///
+    /// The lexer is advanced to the end of the name.
+    fn property_name(
+        &self,
+        lex: &mut Lexer<'_, Token>,
+        first: &LexResult<Token>,
+    ) -> Result<bool> {
+        // The LABEL property from version 3.0 is lexed
+        // as a parameter name
+        let is_label = self.v3.get()
+            && first == &Ok(Token::ParameterKey)
+            && lex.slice().eq_ignore_ascii_case(LABEL);
+        if is_label {
+            return Ok(false);
+        }
+
+        // Names not known to the lexer may be IANA tokens
+        if let Some(len) = iana_name_len(lex, first) {
+            lex.bump(len - lex.slice().len());
+            return Ok(true);
+        }
+        assert_token(
+            Some(first),
+            &[
+                Token::PropertyName,
+                Token::ExtensionName,
+                Token::TimeZone,
+                Token::Geo,
+            ],
+        )?;
+        Ok(false)
+    }
+
+    /// Parse a single content line into a vCard that only
+    /// has the property for the line.
+    pub(crate) fn parse_content_line(&self) -> Result<(Vcard, PropertyKind)> {
+        let mut lex = self.lexer();
+        let first = lex.next().ok_or(Error::TokenExpected)?;
+        let iana = self.property_name(&mut lex, &first)?;
+        let kind = lex
+            .slice()
+            .rsplit('.')
+            .next()
+            .and_then(PropertyKind::from_name)
+            .or(iana.then_some(PropertyKind::Extension))
+            .ok_or_else(|| {
+                Error::UnknownPropertyName(snippet(lex.slice()))
+            })?;
+
+        let mut card = Vcard::default();
+        self.parse_property(&mut lex, first, &mut card)
+            .map_err(|e| self.located(e, 0))?;
+
+        // Only a trailing line break may follow the value
+        if let Some(token) = lex.find(|token| token != &Ok(Token::NewLine)) {
+            assert_token(Some(&token), &[Token::NewLine])
+                .map_err(|e| self.located(e, lex.span().start))?;
+        }
+        if card.property_count(kind) != 1 {
+            return Err(Error::InvalidPropertyValue);
+        }
+        Ok((card, kind))
+    }
+
+    /// Keep the content line of a property that was added
+    /// from the source.
+    fn keep_source_line(
+        &self,
+        lex: &Lexer<'_, Token>,
+        card: &mut Vcard,
+        kind: PropertyKind,
+        index: usize,
+        start: usize,
+    ) {
+        let Some(prop) = card.property_at(kind, index) else {
+            return;
+        };
+        let options = WriteOptions::new().fold_width(None);
+        let encoded =
+            content_line_with(prop.property(), prop.name(), &options);
+        let span = self.unfolded.source_range(start..lex.span().end);
+        if let Some(lines) = card.source_lines.0.as_mut() {
+            lines.lines.push(SourceLine {
+                kind,
+                encoded,
+                raw: self.source[span].to_owned(),
+            });
+        }
+    }
+
+    /// Wrap an error with the line and column for a byte offset
+    /// when the locations option is enabled.
+    pub(crate) fn located(&self, error: Error, offset: usize) -> Error {
+        if !self.options.locations || matches!(error, Error::Parse { .. }) {
+            return error;
+        }
+        let offset = self.unfolded.source_offset(offset);
+        let before = self.source.get(..offset).unwrap_or(self.source);
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rsplit('\n')
+            .next()
+            .map(|text| text.chars().count())
+            .unwrap_or_default()
+            + 1;
+        Error::Parse {
+            line,
+            column,
+            source: Box::new(error),
+        }
+    }
+
     /// Parse a single property.
     fn parse_property(
         &self,
@@ -185,28 +484,26 @@ impl<'s> VcardParser<'s> {
         token: LexResult<Token>,
         card: &mut Vcard,
     ) -> Result<()> {
+        debug_span!("property", name = lex.slice());
         let mut group: Option<String> = None;
         let mut name = lex.slice();
 
-        let period = name.find('.');
-        if let Some(pos) = period {
-            let group_name = &name[0..pos];
+        if let Some((group_name, property_name)) = name.split_once('.') {
             group = Some(group_name.to_string());
-            name = &name[pos + 1..];
+            name = property_name;
         }
 
         let delimiter = lex.next();
 
         if let Some(delimiter) = delimiter {
             if delimiter == Ok(Token::ParameterDelimiter) {
-                let parameters = self.parse_parameters(lex, name)?;
+                // Parameters may all be empty
+                let parameters =
+                    Some(self.parse_parameters(lex, Some(name))?)
+                        .filter(|params| params != &Parameters::default())
+                        .map(Box::new);
                 self.parse_property_by_name(
-                    lex,
-                    token,
-                    card,
-                    name,
-                    Some(parameters),
-                    group,
+                    lex, token, card, name, parameters, group,
                 )?;
             } else if delimiter == Ok(Token::PropertyDelimiter) {
                 self.parse_property_by_name(
@@ -222,14 +519,26 @@ impl<'s> VcardParser<'s> {
         Ok(())
     }
 
+    /// Value of a numeric parameter with Unicode digits
+    /// converted when enabled in loose mode.
+    fn numeric_value<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        if !self.options.strict && self.options.normalize_unicode_digits {
+            let normalized = ascii_digits(value);
+            if let Cow::Owned(_) = &normalized {
+                trace_event!(value, "numeric parameter normalized");
+            }
+            normalized
+        } else {
+            Cow::Borrowed(value)
+        }
+    }
+
     fn add_extension_parameter(
         &self,
         parameter_name: &str,
-        value: String,
+        values: Vec<String>,
         params: &mut Parameters,
     ) {
-        let values =
-            value.split(',').map(|s| s.to_owned()).collect::<Vec<_>>();
         let x_param = (parameter_name.to_owned(), values);
         if let Some(extensions) = params.extensions.as_mut() {
             extensions.push(x_param);
@@ -238,17 +547,174 @@ impl<'s> VcardParser<'s> {
         }
     }
 
+    /// Add a version 3.0 parameter value given without a name.
+    fn add_bare_parameter(
+        &self,
+        value: &str,
+        params: &mut Parameters,
+    ) -> Result<()> {
+        if value.contains('=') {
+            return Err(Error::UnknownParameter(snippet(value)));
+        }
+        match &value.to_uppercase()[..] {
+            QUOTED_PRINTABLE => self.add_extension_parameter(
+                ENCODING,
+                vec![QUOTED_PRINTABLE.to_owned()],
+                params,
+            ),
+            "B" | "BASE64" => self.add_extension_parameter(
+                ENCODING,
+                vec!["b".to_owned()],
+                params,
+            ),
+            _ => self.add_v3_type(value, params)?,
+        }
+        Ok(())
+    }
+
+    /// Add a version 3.0 TYPE value.
+    ///
+    /// The `pref` type is converted to the PREF parameter and the
+    /// `internet` type (the default for EMAIL) is removed.
+    fn add_v3_type(
+        &self,
+        value: &str,
+        params: &mut Parameters,
+    ) -> Result<()> {
+        let lower = value.to_lowercase();
+        match &lower[..] {
+            "" | "internet" => {}
+            "pref" => {
+                if params.pref.is_none() {
+                    params.pref = Some(1);
+                }
+            }
+            _ => params.add_type(value.parse()?),
+        }
+        Ok(())
+    }
+
+    /// Parse the value of a LEVEL parameter.
+    ///
+    /// Values that are not allowed for the property are kept
+    /// in loose mode.
+    fn parse_level(
+        &self,
+        value: String,
+        property: Option<&str>,
+    ) -> Result<String> {
+        let allowed: &[&str] = match property {
+            Some(EXPERTISE) => &["beginner", "average", "expert"],
+            Some(HOBBY | INTEREST) => &["high", "medium", "low"],
+            // Extensions and IANA tokens
+            Some(name)
+                if PropertyKind::from_name(name)
+                    .is_some_and(|kind| kind != PropertyKind::Extension) =>
+            {
+                return Err(Error::InvalidLevel(snippet(name)));
+            }
+            _ => return Ok(value),
+        };
+        let lower = value.to_lowercase();
+        if allowed.contains(&&lower[..]) {
+            Ok(lower)
+        } else if self.options.strict {
+            Err(Error::UnknownLevel(
+                snippet(value),
+                snippet(property.unwrap_or_default()),
+            ))
+        } else {
+            warn_event!(value, "unknown level kept");
+            Ok(value)
+        }
+    }
+
     /// Parse property parameters.
-    fn parse_parameters(
+    ///
+    /// When the property name is not given the checks for
+    /// parameters that are only allowed on certain properties
+    /// are skipped.
+    pub(crate) fn parse_parameters(
         &self,
         lex: &mut Lexer<'_, Token>,
-        name: &str,
+        name: Option<&str>,
     ) -> Result<Parameters> {
-        let property_upper_name = name.to_uppercase();
+        let property_upper_name = name.map(|name| name.to_uppercase());
         let mut params: Parameters = Default::default();
         let mut next: Option<LexResult<Token>> = lex.next();
+        let mut count = 0;
 
         while let Some(token) = next.take() {
+            // Empty parameters are ignored
+            if token == Ok(Token::ParameterDelimiter) {
+                next = lex.next();
+                continue;
+            } else if token == Ok(Token::PropertyDelimiter) {
+                break;
+            }
+
+            count += 1;
+            ParseOptions::check_limit(
+                self.options.max_parameters_per_property,
+                Limit::ParametersPerProperty,
+                count,
+            )?;
+
+            // Unknown parameters are kept when enabled otherwise
+            // they are skipped in loose mode
+            if let Some(len) = (self.options.keep_unknown_parameters
+                || !self.options.strict)
+                .then(|| unknown_parameter_len(lex, &token))
+                .flatten()
+            {
+                lex.bump(len - lex.slice().len());
+                let parameter_name = lex.slice();
+                assert_token(lex.next().as_ref(), &[Token::ValueDelimiter])?;
+                let (values, next_token, _) =
+                    self.parse_parameter_value(lex)?;
+                if self.options.keep_unknown_parameters {
+                    warn_event!(parameter_name, "unknown parameter kept");
+                    self.add_extension_parameter(
+                        parameter_name,
+                        values,
+                        &mut params,
+                    );
+                } else {
+                    warn_event!(parameter_name, "unknown parameter skipped");
+                }
+                if next_token == Ok(Token::PropertyDelimiter) {
+                    break;
+                } else if next_token == Ok(Token::ParameterKey) {
+                    next = Some(next_token);
+                } else {
+                    next = lex.next();
+                }
+                continue;
+            }
+
+            // Version 3.0 allows TYPE values without the parameter name
+            if self.v3.get() && !is_parameter_name(lex, &token) {
+                let start = lex.span().start;
+                let mut delimiter = token;
+                while delimiter != Ok(Token::ParameterDelimiter)
+                    && delimiter != Ok(Token::PropertyDelimiter)
+                {
+                    match lex.next() {
+                        Some(token) if token != Ok(Token::NewLine) => {
+                            delimiter = token;
+                        }
+                        _ => return Err(Error::TokenExpected),
+                    }
+                }
+                let value = &lex.source()[start..lex.span().start];
+                self.add_bare_parameter(value, &mut params)?;
+                if delimiter == Ok(Token::PropertyDelimiter) {
+                    break;
+                }
+                next = lex.next();
+                continue;
+            }
+
             if token == Ok(Token::ParameterKey)
                 || token == Ok(Token::ExtensionName)
                 || token == Ok(Token::TimeZone)
@@ -259,20 +725,64 @@ impl<'s> VcardParser<'s> {
                 let parameter_name = &source[span.start..span.end];
                 let upper_name = parameter_name.to_uppercase();
 
-                self.assert_token(
-                    lex.next().as_ref(),
-                    &[Token::ValueDelimiter],
-                )?;
+                assert_token(lex.next().as_ref(), &[Token::ValueDelimiter])?;
 
-                let (value, next_token, quoted) =
+                let (mut values, mut next_token, quoted) =
                     self.parse_parameter_value(lex)?;
+                // An unquoted UTC offset such as TZ=UTC+05:30 is
+                // ended early by the colon before the minutes
+                if upper_name == TZ
+                    && !quoted
+                    && next_token == Ok(Token::PropertyDelimiter)
+                    && values
+                        .last()
+                        .is_some_and(|v| ends_with_offset_hours(v))
+                {
+                    if let Some(minutes) = offset_minutes(lex.remainder()) {
+                        if let Some(value) = values.last_mut() {
+                            value.push(':');
+                            value.push_str(minutes);
+                        }
+                        lex.bump(minutes.len());
+                        next_token =
+                            lex.next().ok_or(Error::TokenExpected)?;
+                    }
+                }
+                // An unquoted URI such as GEO=geo:12.3,45.6 is
+                // ended early by the colon after the scheme
+                let mut unquoted_uri = false;
+                if !quoted && next_token == Ok(Token::PropertyDelimiter) {
+                    if let Some(rest) = unquoted_uri_rest(
+                        &upper_name,
+                        &values,
+                        lex.remainder(),
+                    ) {
+                        let uri = format!(
+                            "{}:{}",
+                            values.join(","),
+                            rest.replace("\\,", ",")
+                        );
+                        values = vec![uri];
+                        lex.bump(rest.len());
+                        next_token =
+                            lex.next().ok_or(Error::TokenExpected)?;
+                        unquoted_uri = true;
+                    }
+                }
+                // Parameters that are not lists use the whole value
+                let value = values.join(",");
 
                 if token == Ok(Token::ExtensionName) {
                     self.add_extension_parameter(
                         parameter_name,
-                        value,
+                        values,
                         &mut params,
                     );
+                } else if value.is_empty() {
+                    trace_event!(
+                        parameter = parameter_name,
+                        "empty parameter value ignored"
+                    );
                 } else {
                     match &upper_name[..] {
                         LANGUAGE => {
@@ -284,9 +794,15 @@ impl<'s> VcardParser<'s> {
                             params.value = Some(value);
                         }
                         PREF => {
-                            let value: u8 = value.parse()?;
+                            let mut value: u8 =
+                                self.numeric_value(&value).parse()?;
                             if !(1..=100).contains(&value) {
-                                return Err(Error::PrefOutOfRange(value));
+                                if self.options.strict {
+                                    return Err(Error::PrefOutOfRange(value));
+                                }
+                                let clamped = value.clamp(1, 100);
+                                warn_event!(value, clamped, "pref clamped");
+                                value = clamped;
                             }
                             params.pref = Some(value);
                         }
@@ -295,35 +811,58 @@ impl<'s> VcardParser<'s> {
                         }
                         PID => {
                             let mut pids: Vec<Pid> = Vec::new();
-                            let values = value.split(',');
-                            for value in values {
-                                pids.push(value.parse()?);
+                            for value in &values {
+                                pids.push(self.numeric_value(value).parse()?);
                             }
                             params.pid = Some(pids);
                         }
                         TYPE => {
                             // Check this parameter is allowed
-                            if !TYPE_PROPERTIES
-                                .contains(&&property_upper_name[..])
-                                && !property_upper_name.starts_with("X-")
+                            if let Some(property_upper_name) =
+                                &property_upper_name
                             {
-                                return Err(Error::TypeParameter(
+                                // Extensions, IANA tokens and the
+                                // version 3.0 LABEL property (which is
+                                // converted to an ADR parameter) are
+                                // not known properties
+                                let is_known = PropertyKind::from_name(
                                     property_upper_name,
-                                ));
+                                )
+                                .is_some_and(|kind| {
+                                    kind != PropertyKind::Extension
+                                });
+                                if is_known
+                                    && !TYPE_PROPERTIES
+                                        .contains(&&property_upper_name[..])
+                                {
+                                    return Err(Error::TypeParameter(
+                                        snippet(property_upper_name),
+                                    ));
+                                }
                             }
 
-                            let mut type_params: Vec<TypeParameter> =
-                                Vec::new();
-
-                            for val in value.split(',') {
+                            for val in values.iter().map(|v| v.as_str()) {
+                                if self.v3.get() {
+                                    self.add_v3_type(val, &mut params)?;
+                                    continue;
+                                }
+                                // Some clients (notably Apple) use a
+                                // `pref` type to mark preference
+                                if val.eq_ignore_ascii_case("pref") {
+                                    if params.pref.is_none() {
+                                        params.pref = Some(1);
+                                    }
+                                    continue;
+                                }
                                 let param: TypeParameter = val.parse()?;
-                                type_params.push(param);
-                            }
-
-                            if let Some(types) = params.types.as_mut() {
-                                types.append(&mut type_params);
-                            } else {
-                                params.types = Some(type_params);
+                                if params.has_type(&param) {
+                                    trace_event!(
+                                        property = name,
+                                        value = val,
+                                        "duplicate TYPE removed"
+                                    );
+                                }
+                                params.add_type(param);
                             }
                         }
                         MEDIATYPE => {
@@ -333,23 +872,40 @@ impl<'s> VcardParser<'s> {
                             params.calscale = Some(value);
                         }
                         SORT_AS => {
-                            let sort_values = value
-                                .split(',')
-                                .map(|s| s.to_string())
-                                .collect::<Vec<_>>();
-                            params.sort_as = Some(sort_values);
+                            params.sort_as = Some(values);
                         }
                         GEO => {
                             if !quoted {
-                                return Err(Error::NotQuoted(
-                                    property_upper_name,
-                                ));
+                                if self.options.strict {
+                                    return Err(Error::NotQuoted(
+                                        GEO.to_owned(),
+                                    ));
+                                }
+                                warn_event!(
+                                    property = name,
+                                    "unquoted GEO parameter accepted"
+                                );
                             }
                             let geo = value.parse()?;
                             params.geo = Some(geo);
                         }
                         TZ => {
-                            if quoted {
+                            if unquoted_uri {
+                                if self.options.strict {
+                                    return Err(Error::NotQuoted(
+                                        TZ.to_owned(),
+                                    ));
+                                }
+                                warn_event!(
+                                    property = name,
+                                    "unquoted TZ parameter URI accepted"
+                                );
+                            }
+                            // Quoted values are URIs unless they end
+                            // with the minutes of a UTC offset
+                            if unquoted_uri
+                                || (quoted && !is_offset_text(&value))
+                            {
                                 let value = value.parse()?;
                                 params.timezone =
                                     Some(TimeZoneParameter::Uri(value));
@@ -363,6 +919,10 @@ impl<'s> VcardParser<'s> {
                                         );
                                     }
                                     Err(_) => {
+                                        trace_event!(
+                                            property = name,
+                                            "TZ parameter is not a UTC offset, using text"
+                                        );
                                         params.timezone = Some(
                                             TimeZoneParameter::Text(value),
                                         );
@@ -370,32 +930,98 @@ impl<'s> VcardParser<'s> {
                                 }
                             }
                         }
+                        CHARSET if self.v3.get() => {
+                            // Removed once the value has been decoded
+                            self.add_extension_parameter(
+                                parameter_name,
+                                values,
+                                &mut params,
+                            );
+                        }
                         CHARSET => {
                             // Ignore CHARSET=UTF-8 for compatibility with software that
                             // unnecessarily (and in spite of RFC 6350) adds this parameter.
                             if value != "UTF-8" {
-                                return Err(Error::CharsetParameter(value));
+                                return Err(Error::CharsetParameter(
+                                    snippet(value),
+                                ));
                             }
                         }
                         LABEL => {
-                            if property_upper_name != ADR {
-                                return Err(Error::InvalidLabel(
+                            if let Some(property_upper_name) =
+                                property_upper_name
+                                    .as_ref()
+                                    .filter(|name| *name != ADR)
+                            {
+                                return Err(Error::InvalidLabel(snippet(
                                     property_upper_name,
-                                ));
+                                )));
                             }
                             params.label = Some(value);
                         }
+                        LEVEL => {
+                            params.level = Some(self.parse_level(
+                                value,
+                                property_upper_name.as_deref(),
+                            )?);
+                        }
+                        INDEX => {
+                            let index = self
+                                .numeric_value(&value)
+                                .parse::<u32>()
+                                .ok()
+                                .filter(|index| *index > 0)
+                                .ok_or(Error::InvalidIndex(snippet(value)))?;
+                            params.index = Some(index);
+                        }
+                        SERVICE_TYPE => {
+                            params.service_type = Some(value);
+                        }
+                        CC => {
+                            if let Some(property_upper_name) =
+                                property_upper_name
+                                    .as_ref()
+                                    .filter(|name| *name != ADR)
+                            {
+                                return Err(Error::InvalidCountryCode(
+                                    snippet(property_upper_name),
+                                ));
+                            }
+                            if value.len() == 2
+                                && value
+                                    .bytes()
+                                    .all(|b| b.is_ascii_alphabetic())
+                            {
+                                params.country_code =
+                                    Some(value.to_uppercase());
+                            } else if self.options.strict {
+                                return Err(Error::UnknownCountryCode(
+                                    snippet(value),
+                                ));
+                            } else {
+                                warn_event!(
+                                    property = name,
+                                    value,
+                                    "invalid country code kept as an extension"
+                                );
+                                self.add_extension_parameter(
+                                    parameter_name,
+                                    values,
+                                    &mut params,
+                                );
+                            }
+                        }
                         ENCODING => {
                             self.add_extension_parameter(
                                 parameter_name,
-                                value,
+                                values,
                                 &mut params,
                             );
                         }
                         _ => {
-                            return Err(Error::UnknownParameter(
-                                parameter_name.to_string(),
-                            ))
+                            return Err(Error::UnknownParameter(snippet(
+                                parameter_name,
+                            )))
                         }
                     }
                 }
@@ -408,86 +1034,104 @@ impl<'s> VcardParser<'s> {
                     next = lex.next();
                 }
             } else {
-                return Err(Error::UnknownParameter(lex.slice().to_string()));
+                return Err(Error::UnknownParameter(snippet(lex.slice())));
             }
         }
         Ok(params)
     }
 
-    /// Parse the raw value for a property parameter.
+    /// Parse a parameter fragment in the `;KEY=value` form.
+    pub(crate) fn parse_parameter_fragment(
+        fragment: &str,
+        name: Option<&str>,
+    ) -> Result<Parameters> {
+        if fragment.is_empty() {
+            return Ok(Default::default());
+        }
+        let fragment =
+            fragment.strip_prefix(';').ok_or(Error::DelimiterExpected)?;
+        // Terminate with a property delimiter so the fragment
+        // is parsed in the same way as the parameters for a property
+        let source = format!("{}:", fragment);
+        let parser = VcardParser::new(&source, ParseOptions::new());
+        let mut lex = parser.lexer();
+        let params = parser.parse_parameters(&mut lex, name)?;
+        if lex.next().is_some() {
+            return Err(Error::IncorrectToken(lex.slice().to_owned()));
+        }
+        Ok(params)
+    }
+
+    /// Parse the values for a property parameter.
+    ///
+    /// The value is a list separated by commas where each value
+    /// may be quoted; returns the values, the delimiter token that
+    /// ends the parameter and whether the first value was quoted.
     fn parse_parameter_value(
         &self,
         lex: &mut Lexer<'_, Token>,
-    ) -> Result<(String, LexResult<Token>, bool)> {
-        let mut first_range: Option<Range<usize>> = None;
+    ) -> Result<(Vec<String>, LexResult<Token>, bool)> {
+        // Called after the value delimiter
+        let begin = lex.span().end;
         let mut quoted = false;
-        let mut is_folded_or_escaped = false;
+        let mut in_quotes = false;
+        let mut closed = false;
+        let mut value_start = true;
+        let mut is_folded = false;
 
-        while let Some(mut token) = lex.next() {
+        while let Some(token) = lex.next() {
             let span = lex.span();
 
             if token == Ok(Token::Control) {
-                return Err(Error::ControlCharacter(escape_control(
-                    lex.slice(),
+                return Err(Error::ControlCharacter(snippet(
+                    escape_control(lex.slice()),
                 )));
             }
 
-            if token == Ok(Token::FoldedLine)
-                || token == Ok(Token::EscapedNewLine)
-                || token == Ok(Token::EscapedComma)
-                || token == Ok(Token::EscapedBackSlash)
-            {
-                is_folded_or_escaped = true;
+            if token == Ok(Token::FoldedLine) {
+                is_folded = true;
             }
 
-            let completed = if first_range.is_some() && quoted {
-                token == Ok(Token::DoubleQuote)
-            } else {
-                token == Ok(Token::PropertyDelimiter)
-                    || token == Ok(Token::ParameterDelimiter)
-                //|| token == Token::ParameterKey
-            };
-
-            if first_range.is_none() {
-                first_range = Some(span.clone());
-                if token == Ok(Token::DoubleQuote) {
-                    quoted = true;
+            let is_delimiter = token == Ok(Token::PropertyDelimiter)
+                || token == Ok(Token::ParameterDelimiter);
+
+            // A closing quote must be followed by a delimiter
+            if closed {
+                closed = false;
+                if lex.slice() == "," {
+                    value_start = true;
+                    continue;
+                } else if !is_delimiter {
+                    return Err(Error::DelimiterExpected);
                 }
             }
 
-            if completed {
-                let source = lex.source();
-                let begin = first_range.unwrap().start;
-                let end = span.start;
-                let mut value = &source[begin..end];
-
-                // Remove double quotes if necessary
-                if value.len() >= 2 && quoted {
-                    value = &source[begin + 1..end];
+            if in_quotes {
+                if token == Ok(Token::DoubleQuote) {
+                    in_quotes = false;
+                    closed = true;
                 }
+                continue;
+            }
 
-                // Must consumer the next token
-                if quoted {
-                    token = if let Some(Ok(token)) = lex.next() {
-                        if token != Token::PropertyDelimiter
-                            && token != Token::ParameterDelimiter
-                        {
-                            return Err(Error::DelimiterExpected);
-                        }
-                        Ok(token)
-                    } else {
-                        return Err(Error::TokenExpected);
-                    };
-                }
+            if token == Ok(Token::DoubleQuote) && value_start {
+                quoted = quoted || span.start == begin;
+                in_quotes = true;
+                value_start = false;
+                continue;
+            }
 
-                let value = if is_folded_or_escaped {
-                    unescape_value(value)
+            if is_delimiter {
+                let value = &lex.source()[begin..span.start];
+                let values = if is_folded {
+                    split_parameter_values(&remove_folds(value))
                 } else {
-                    value.to_string()
+                    split_parameter_values(value)
                 };
-
-                return Ok((value, token, quoted));
+                return Ok((values, token, quoted));
             }
+
+            value_start = lex.slice() == ",";
         }
         Err(Error::TokenExpected)
     }
@@ -499,13 +1143,42 @@ impl<'s> VcardParser<'s> {
         token: LexResult<Token>,
         card: &mut Vcard,
         name: &str,
-        parameters: Option<Parameters>,
+        mut parameters: Option<Box<Parameters>>,
         group: Option<String>,
     ) -> Result<()> {
-        let value = self.parse_property_value(lex)?;
-
         let upper_name = name.to_uppercase();
 
+        // Structured values are split before escape
+        // sequences are removed
+        let unescape =
+            !matches!(&upper_name[..], N | ADR | ORG | CATEGORIES | GENDER);
+        let quoted_printable = is_quoted_printable(&parameters);
+        let value =
+            self.parse_property_value(lex, quoted_printable, unescape)?;
+        let value = if self.v3.get() || quoted_printable {
+            let value = value.into_owned();
+            Cow::Owned(self.decode_value(
+                lex,
+                value,
+                &mut parameters,
+                unescape,
+            )?)
+        } else {
+            value
+        };
+
+        let value = if !self.options.keep_inline_binary
+            && matches!(&upper_name[..], PHOTO | LOGO | SOUND | KEY)
+        {
+            Cow::Owned(self.decode_inline_binary(
+                &upper_name,
+                value.into_owned(),
+                &mut parameters,
+            )?)
+        } else {
+            value
+        };
+
         if token == Ok(Token::ExtensionName) || upper_name.starts_with("X-") {
             self.parse_extension_property_by_name(
                 card, name, value, parameters, group,
@@ -555,16 +1228,14 @@ impl<'s> VcardParser<'s> {
                 if card.name.is_some() {
                     return Err(Error::OnlyOnce(upper_name));
                 }
-                let value = value
-                    .as_ref()
-                    .split(';')
-                    .map(|s| s.to_string())
-                    .collect::<Vec<_>>();
-                card.name = Some(TextListProperty {
+                let value = StructuredName::parse_components(
+                    value.as_ref(),
+                    self.options.strict,
+                )?;
+                card.name = Some(NameProperty {
                     value,
                     parameters,
                     group,
-                    delimiter: TextListDelimiter::SemiColon,
                 });
             }
             NICKNAME => {
@@ -583,6 +1254,10 @@ impl<'s> VcardParser<'s> {
                     }));
                 }
                 Err(_) => {
+                    trace_event!(
+                        property = name,
+                        "value is not a URI, using text"
+                    );
                     card.photo.push(TextOrUriProperty::Text(TextProperty {
                         value: value.into_owned(),
                         parameters,
@@ -600,6 +1275,7 @@ impl<'s> VcardParser<'s> {
                     value,
                     parameters,
                     group,
+                    self.date_text_fallback(),
                 )?;
                 card.bday = Some(prop);
             }
@@ -613,25 +1289,89 @@ impl<'s> VcardParser<'s> {
                     value,
                     parameters,
                     group,
+                    self.date_text_fallback(),
                 )?;
                 card.anniversary = Some(prop);
             }
-            GENDER => {
-                if card.gender.is_some() {
+            BIRTHPLACE | DEATHPLACE => {
+                let place = if upper_name == BIRTHPLACE {
+                    &mut card.birthplace
+                } else {
+                    &mut card.deathplace
+                };
+                if place.is_some() {
+                    return Err(Error::OnlyOnce(upper_name));
+                }
+
+                // The default value type is text
+                let has_value_type =
+                    parameters.as_ref().is_some_and(|p| p.value.is_some());
+                let prop = if has_value_type {
+                    self.parse_text_or_uri(value.as_ref(), parameters, group)?
+                } else {
+                    TextOrUriProperty::Text(TextProperty {
+                        value: value.into_owned(),
+                        parameters,
+                        group,
+                    })
+                };
+                *place = Some(prop);
+            }
+            DEATHDATE => {
+                if card.deathdate.is_some() {
                     return Err(Error::OnlyOnce(upper_name));
                 }
+
+                let prop = parse_date_time_or_text(
+                    &upper_name,
+                    value,
+                    parameters,
+                    group,
+                    self.date_text_fallback(),
+                )?;
+                card.deathdate = Some(prop);
+            }
+            GENDER => {
+                // Multiple values are only allowed as alternative
+                // representations sharing the same ALTID
+                if let Some(existing) = card.gender.first() {
+                    let alt_id = |params: Option<&Parameters>| {
+                        params.and_then(|p| p.alt_id.clone())
+                    };
+                    let current = alt_id(existing.parameters.as_deref());
+                    if current.is_none()
+                        || current != alt_id(parameters.as_deref())
+                    {
+                        return Err(Error::OnlyOnce(upper_name));
+                    }
+                }
                 let value: Gender = value.as_ref().parse()?;
-                card.gender = Some(GenderProperty {
+                if let Sex::Unrecognized(c) = value.sex {
+                    if self.options.strict {
+                        return Err(Error::UnknownSex(c.to_string()));
+                    }
+                }
+                card.gender.push(GenderProperty {
                     value,
                     parameters,
                     group,
                 });
             }
+            PRONOUNS => {
+                card.pronouns.push(TextProperty {
+                    value: value.into_owned(),
+                    parameters,
+                    group,
+                });
+            }
 
             // Delivery Addressing
             // https://www.rfc-editor.org/rfc/rfc6350#section-6.3
             ADR => {
-                let value: DeliveryAddress = value.as_ref().parse()?;
+                let value = DeliveryAddress::parse_components(
+                    value.as_ref(),
+                    self.options.strict,
+                )?;
                 card.address.push(AddressProperty {
                     value,
                     parameters,
@@ -647,6 +1387,11 @@ impl<'s> VcardParser<'s> {
                     parameters,
                     group,
                 )?;
+                if let TextOrUriProperty::Uri(prop) = &value {
+                    if is_tel_uri(&prop.value) {
+                        TelUri::try_from(&prop.value)?;
+                    }
+                }
                 card.tel.push(value);
             }
             EMAIL => {
@@ -664,7 +1409,36 @@ impl<'s> VcardParser<'s> {
                     group,
                 });
             }
+            SOCIALPROFILE => {
+                let prop = self.parse_text_or_uri(
+                    value.as_ref(),
+                    parameters,
+                    group,
+                )?;
+                card.social_profile.push(prop);
+            }
             LANG => {
+                // LANG is single-valued but multiple comma separated
+                // values are accepted in loose mode
+                if value.contains(',') {
+                    if self.options.strict {
+                        return Err(Error::MultipleLanguages(snippet(value)));
+                    }
+                    warn_event!(
+                        value = value.as_ref(),
+                        "LANG has multiple values, splitting"
+                    );
+                    for tag in value.split(',') {
+                        let value =
+                            parse_language_tag(Cow::Borrowed(tag.trim()))?;
+                        card.lang.push(LanguageProperty {
+                            value,
+                            parameters: parameters.clone(),
+                            group: group.clone(),
+                        });
+                    }
+                    return Ok(());
+                }
                 let value = parse_language_tag(value)?;
                 card.lang.push(LanguageProperty {
                     value,
@@ -673,6 +1447,20 @@ impl<'s> VcardParser<'s> {
                 });
             }
 
+            // The name is lexed as a parameter key so it is
+            // parsed in the same way as an IANA token
+            LANGUAGE => {
+                if card.language.is_some() {
+                    return Err(Error::OnlyOnce(upper_name));
+                }
+                let value = parse_language_tag(value)?;
+                card.language = Some(LanguageProperty {
+                    value,
+                    parameters,
+                    group,
+                });
+            }
+
             // Geographic
             // https://www.rfc-editor.org/rfc/rfc6350#section-6.5
             TZ => {
@@ -692,6 +1480,15 @@ impl<'s> VcardParser<'s> {
                             card.timezone
                                 .push(TimeZoneProperty::UtcOffset(value));
                         }
+                        ValueType::Text => {
+                            card.timezone.push(TimeZoneProperty::Text(
+                                TextProperty {
+                                    value: value.into_owned(),
+                                    parameters,
+                                    group,
+                                },
+                            ));
+                        }
                         ValueType::Uri => {
                             let value = value.parse()?;
                             card.timezone.push(TimeZoneProperty::Uri(
@@ -704,8 +1501,8 @@ impl<'s> VcardParser<'s> {
                         }
                         _ => {
                             return Err(Error::UnsupportedValueType(
-                                value_type.to_string(),
-                                upper_name,
+                                snippet(value_type.to_string()),
+                                snippet(upper_name),
                             ))
                         }
                     }
@@ -753,10 +1550,9 @@ impl<'s> VcardParser<'s> {
                 });
             }
             ORG => {
-                let value = value
-                    .as_ref()
-                    .split(';')
-                    .map(|s| s.to_string())
+                let value = split_unescaped(value.as_ref(), ';')
+                    .into_iter()
+                    .map(unescape_component)
                     .collect::<Vec<_>>();
                 card.org.push(TextListProperty {
                     value,
@@ -782,13 +1578,35 @@ impl<'s> VcardParser<'s> {
                 card.related.push(text_or_uri);
             }
 
+            // OpenSocial
+            // https://www.rfc-editor.org/rfc/rfc6715
+            EXPERTISE | HOBBY | INTEREST => {
+                let prop = TextProperty {
+                    value: value.into_owned(),
+                    parameters,
+                    group,
+                };
+                match &upper_name[..] {
+                    EXPERTISE => card.expertise.push(prop),
+                    HOBBY => card.hobby.push(prop),
+                    _ => card.interest.push(prop),
+                }
+            }
+            ORG_DIRECTORY => {
+                let value = value.parse()?;
+                card.org_directory.push(UriProperty {
+                    value,
+                    parameters,
+                    group,
+                });
+            }
+
             // Explanatory
             // https://www.rfc-editor.org/rfc/rfc6350#section-6.7
             CATEGORIES => {
-                let value = value
-                    .as_ref()
-                    .split(',')
-                    .map(|s| s.to_string())
+                let value = split_unescaped(value.as_ref(), ',')
+                    .into_iter()
+                    .map(unescape_component)
                     .collect::<Vec<_>>();
                 card.categories.push(TextListProperty {
                     value,
@@ -825,6 +1643,17 @@ impl<'s> VcardParser<'s> {
                     group,
                 });
             }
+            CREATED => {
+                if card.created.is_some() {
+                    return Err(Error::OnlyOnce(upper_name));
+                }
+                let value = parse_timestamp(value.as_ref())?;
+                card.created = Some(DateTimeProperty {
+                    value,
+                    parameters,
+                    group,
+                });
+            }
             SOUND => {
                 let value = value.parse()?;
                 card.sound.push(UriProperty {
@@ -869,6 +1698,15 @@ impl<'s> VcardParser<'s> {
             VERSION => {
                 return Err(Error::VersionMisplaced);
             }
+            // Attached to an ADR once all the properties are parsed
+            LABEL if self.v3.get() => {
+                card.extensions.push(ExtensionProperty {
+                    name: LABEL.to_owned(),
+                    value: AnyProperty::Text(value.into_owned()),
+                    parameters,
+                    group,
+                });
+            }
 
             // Security
             // https://www.rfc-editor.org/rfc/rfc6350#section-6.8
@@ -907,18 +1745,164 @@ impl<'s> VcardParser<'s> {
                     group,
                 });
             }
-            _ => return Err(Error::UnknownPropertyName(name.to_string())),
+            // IANA tokens not known to this library
+            _ => self.parse_extension_property_by_name(
+                card, name, value, parameters, group,
+            )?,
         }
         Ok(())
     }
 
+    /// Decode a value using the ENCODING and CHARSET
+    /// parameters which are then removed.
+    ///
+    /// Quoted-printable values that end with a soft line break
+    /// continue on the next line; folding is removed before the
+    /// value is decoded so an escape sequence may span a fold.
+    fn decode_value(
+        &self,
+        lex: &mut Lexer<'_, Token>,
+        mut value: String,
+        parameters: &mut Option<Box<Parameters>>,
+        unescape: bool,
+    ) -> Result<String> {
+        let mut quoted_printable = false;
+        let mut charset = None;
+        if let Some(params) = parameters.as_mut() {
+            if let Some(extensions) = params.extensions.as_mut() {
+                extensions.retain(|(name, values)| {
+                    let value =
+                        values.first().map(|s| s.as_str()).unwrap_or("");
+                    if name.eq_ignore_ascii_case(ENCODING)
+                        && value.eq_ignore_ascii_case(QUOTED_PRINTABLE)
+                    {
+                        quoted_printable = true;
+                        false
+                    } else if name.eq_ignore_ascii_case(CHARSET) {
+                        charset = Some(value.to_owned());
+                        false
+                    } else {
+                        true
+                    }
+                });
+                if extensions.is_empty() {
+                    params.extensions = None;
+                }
+            }
+            // Parameters may be empty once the version 3.0
+            // parameters are removed
+            if **params == Parameters::default() {
+                *parameters = None;
+            }
+        }
+
+        if !quoted_printable {
+            return Ok(value);
+        }
+
+        while value.ends_with('=') {
+            // A soft line break on the last line of the value
+            if is_content_line(lex.remainder()) {
+                return Err(Error::QuotedPrintable(snippet(value)));
+            }
+            value.pop();
+            value.push_str(
+                self.parse_property_value(lex, true, unescape)?.as_ref(),
+            );
+        }
+
+        let Some(bytes) = decode_quoted_printable(&value) else {
+            return Err(Error::QuotedPrintable(snippet(value)));
+        };
+        let decoded: String =
+            match charset.as_deref().map(|s| s.to_uppercase()).as_deref() {
+                None | Some("UTF-8") => String::from_utf8(bytes)
+                    .map_err(|_| Error::QuotedPrintable(snippet(value)))?,
+                // Every byte is a code point in ISO-8859-1
+                Some("ISO-8859-1") | Some("LATIN1") => {
+                    bytes.into_iter().map(char::from).collect()
+                }
+                _ => {
+                    return Err(Error::CharsetParameter(snippet(
+                        charset.unwrap_or_default(),
+                    )))
+                }
+            };
+        // Line breaks in text values are a single newline
+        Ok(decoded.replace("\r\n", "\n"))
+    }
+
+    /// Convert a base64 value with an `ENCODING=b` parameter
+    /// to a data URI.
+    ///
+    /// The ENCODING parameter and a TYPE value used as the
+    /// media type are removed.
+    fn decode_inline_binary(
+        &self,
+        name: &str,
+        value: String,
+        parameters: &mut Option<Box<Parameters>>,
+    ) -> Result<String> {
+        let Some(params) = parameters.as_mut() else {
+            return Ok(value);
+        };
+        let Some(extensions) = params.extensions.as_mut() else {
+            return Ok(value);
+        };
+        let Some(index) = extensions.iter().position(|(name, values)| {
+            name.eq_ignore_ascii_case(ENCODING)
+                && values.first().is_some_and(|value| {
+                    value.eq_ignore_ascii_case("b")
+                        || value.eq_ignore_ascii_case("BASE64")
+                })
+        }) else {
+            return Ok(value);
+        };
+        extensions.remove(index);
+        if extensions.is_empty() {
+            params.extensions = None;
+        }
+
+        // The hint is removed as it is not a type in version 4.0
+        let mut hint = None;
+        if params.media_type.is_none() {
+            if let Some(types) = params.types.as_mut() {
+                if let Some(index) = types.iter().position(|value| {
+                    matches!(value, TypeParameter::Extension(_))
+                }) {
+                    if let TypeParameter::Extension(value) = &types[index] {
+                        hint = Some(value.clone());
+                    }
+                    types.remove(index);
+                }
+                if types.is_empty() {
+                    params.types = None;
+                }
+            }
+        }
+        let media_type = if let Some(media_type) = &params.media_type {
+            media_type.to_string()
+        } else if let Some(hint) = &hint {
+            legacy_media_type(name, hint)
+        } else {
+            "application/octet-stream".to_owned()
+        };
+        if **params == Parameters::default() {
+            *parameters = None;
+        }
+
+        let data: DataUri =
+            format!("data:{};base64,{}", media_type, value).parse()?;
+        Ok(data.to_string())
+    }
+
     /// Parse a private extension property (`x-`) by name.
     fn parse_extension_property_by_name(
         &self,
         card: &mut Vcard,
         name: &str,
         value: Cow<'_, str>,
-        parameters: Option<Parameters>,
+        parameters: Option<Box<Parameters>>,
         group: Option<String>,
     ) -> Result<()> {
         let value_type = if let Some(parameters) = &parameters {
@@ -938,12 +1922,15 @@ impl<'s> VcardParser<'s> {
                 ValueType::Boolean => {
                     AnyProperty::Boolean(parse_boolean(value.as_ref())?)
                 }
-                ValueType::Date => AnyProperty::Date(
-                    parse_date_list(value.as_ref())?
-                        .into_iter()
-                        .map(crate::Date::from)
-                        .collect(),
-                ),
+                // Truncated dates cannot be represented by `Date`
+                ValueType::Date if has_partial_date(value.as_ref()) => {
+                    AnyProperty::DateAndOrTime(parse_date_and_or_time_list(
+                        value.as_ref(),
+                    )?)
+                }
+                ValueType::Date => {
+                    AnyProperty::Date(parse_date_list(value.as_ref())?)
+                }
                 ValueType::DateTime => AnyProperty::DateTime(
                     parse_date_time_list(value.as_ref())?,
                 ),
@@ -967,6 +1954,10 @@ impl<'s> VcardParser<'s> {
                     let value = value.parse()?;
                     AnyProperty::Uri(value)
                 }
+                // Value types we cannot coerce are preserved as text
+                ValueType::IanaToken(_) | ValueType::Extension(_) => {
+                    AnyProperty::Text(value.into_owned())
+                }
             }
         } else {
             AnyProperty::Text(value.into_owned())
@@ -983,15 +1974,23 @@ impl<'s> VcardParser<'s> {
     }
 
     /// Get the slice for the property value.
-    fn parse_property_value<'a>(
+    ///
+    /// When `soft_breaks` is set a quoted-printable soft line
+    /// break before a folded line is removed with the fold.
+    ///
+    /// When `unescape` is not set escape sequences are
+    /// preserved and only folding is removed.
+    pub(crate) fn parse_property_value<'a>(
         &self,
-        lex: &'a mut Lexer<'_, Token>,
+        lex: &mut Lexer<'a, Token>,
+        soft_breaks: bool,
+        unescape: bool,
     ) -> Result<Cow<'a, str>> {
         let mut first_range: Option<Range<usize>> = None;
         let mut last_range: Option<Range<usize>> = None;
 
         let mut needs_transform = false;
-        let mut tokens = Vec::new();
+        let mut tokens: Vec<(LexResult<Token>, Range<usize>)> = Vec::new();
 
         while let Some(token) = lex.next() {
             let span = lex.span();
@@ -1000,8 +1999,8 @@ impl<'s> VcardParser<'s> {
             }
 
             if token == Ok(Token::Control) {
-                return Err(Error::ControlCharacter(escape_control(
-                    lex.slice(),
+                return Err(Error::ControlCharacter(snippet(
+                    escape_control(lex.slice()),
                 )));
             }
 
@@ -1019,47 +2018,62 @@ impl<'s> VcardParser<'s> {
                 break;
             }
 
+            // Version 3.0 exports may escape colons
+            let escaped_colon =
+                self.v3.get() && token == Ok(Token::PropertyDelimiter);
+            let soft_break = soft_breaks && token == Ok(Token::FoldedLine);
+            if escaped_colon || soft_break {
+                let escape = if escaped_colon { "\\" } else { "=" };
+                if let Some((_, last)) = tokens.last() {
+                    if &lex.source()[last.clone()] == escape {
+                        tokens.pop();
+                        needs_transform = true;
+                    }
+                }
+            }
+
             tokens.push((token, span));
         }
 
-        if let (Some(first), Some(last)) = (first_range, last_range) {
-            if needs_transform {
-                let mut value = String::new();
-                for (token, span) in tokens {
-                    if token == Ok(Token::FoldedLine) {
-                        continue;
-                    } else if token == Ok(Token::EscapedComma) {
-                        value.push(',');
-                        continue;
-                    } else if token == Ok(Token::EscapedSemiColon) {
-                        value.push(';');
-                        continue;
-                    } else if token == Ok(Token::EscapedNewLine) {
-                        value.push('\n');
-                        continue;
-                    } else if token == Ok(Token::EscapedBackSlash) {
-                        value.push('\\');
-                        continue;
-                    }
-
-                    let source = lex.source();
-                    value.push_str(&source[span]);
+        // The value may be empty or end with the source
+        let end = last_range.map(|last| last.start).unwrap_or(lex.span().end);
+        let start = first_range.map(|first| first.start).unwrap_or(end);
+        if needs_transform {
+            let mut value = String::new();
+            for (token, span) in tokens {
+                if token == Ok(Token::FoldedLine) {
+                    continue;
+                } else if !unescape {
+                    // Escape sequences are kept
+                } else if token == Ok(Token::EscapedComma) {
+                    value.push(',');
+                    continue;
+                } else if token == Ok(Token::EscapedSemiColon) {
+                    value.push(';');
+                    continue;
+                } else if token == Ok(Token::EscapedNewLine) {
+                    value.push('\n');
+                    continue;
+                } else if token == Ok(Token::EscapedBackSlash) {
+                    value.push('\\');
+                    continue;
                 }
-                Ok(Cow::Owned(value))
-            } else {
+
                 let source = lex.source();
-                Ok(Cow::Borrowed(&source[first.start..last.start]))
+                value.push_str(&source[span]);
             }
+            Ok(Cow::Owned(value))
         } else {
-            Err(Error::InvalidPropertyValue)
+            let source = lex.source();
+            Ok(Cow::Borrowed(&source[start..end]))
         }
     }
 
     /// Parse text or Uri from a value.
-    fn parse_text_or_uri<S: AsRef<str>>(
+    fn parse_text_or_uri(
         &self,
-        value: S,
-        parameters: Option<Parameters>,
+        value: &str,
+        parameters: Option<Box<Parameters>>,
         group: Option<String>,
     ) -> Result<TextOrUriProperty> {
         let value_type = if let Some(parameters) = &parameters {
@@ -1070,59 +2084,80 @@ impl<'s> VcardParser<'s> {
         if let Some(value_type) = value_type {
             if let ValueType::Text = value_type {
                 Ok(TextOrUriProperty::Text(TextProperty {
-                    value: value.as_ref().to_string(),
+                    value: value.to_string(),
                     parameters,
                     group,
                 }))
             } else if let ValueType::Uri = value_type {
-                let value = value.as_ref().parse()?;
+                let value = value.parse()?;
                 Ok(TextOrUriProperty::Uri(UriProperty {
                     value,
                     parameters,
                     group,
                 }))
             } else {
-                Err(Error::UnknownValueType(value_type.to_string()))
+                Err(Error::UnknownValueType(snippet(value_type.to_string())))
             }
         } else {
-            match value.as_ref().parse::<Uri>() {
+            match value.parse::<Uri>() {
                 Ok(value) => Ok(TextOrUriProperty::Uri(UriProperty {
                     value,
                     parameters,
                     group,
                 })),
-                Err(_) => Ok(TextOrUriProperty::Text(TextProperty {
-                    value: value.as_ref().to_string(),
-                    parameters,
-                    group,
-                })),
+                Err(_) => {
+                    trace_event!("value is not a URI, using text");
+                    Ok(TextOrUriProperty::Text(TextProperty {
+                        value: value.to_string(),
+                        parameters,
+                        group,
+                    }))
+                }
             }
         }
     }
+}
 
-    /// Assert we have an expected token.
-    fn assert_token(
-        &self,
-        value: Option<&LexResult<Token>>,
-        expected: &[Token],
-    ) -> Result<()> {
-        if let Some(Ok(value)) = value {
-            if expected.contains(value) {
-                Ok(())
-            } else {
-                Err(Error::IncorrectToken(format!("{:#?}", value)))
-            }
+/// Skip the remainder of a line after a property error
+/// so parsing resumes at the next property.
+pub(crate) fn skip_line(lex: &mut Lexer<'_, Token>) {
+    if lex.slice().ends_with('\n') {
+        return;
+    }
+    for token in lex.by_ref() {
+        if token == Ok(Token::NewLine) {
+            break;
+        }
+    }
+}
+
+/// Assert we have an expected token.
+pub(crate) fn assert_token(
+    value: Option<&LexResult<Token>>,
+    expected: &[Token],
+) -> Result<()> {
+    if let Some(Ok(value)) = value {
+        if expected.contains(value) {
+            Ok(())
         } else {
-            Err(Error::TokenExpected)
+            Err(Error::IncorrectToken(format!("{:#?}", value)))
         }
+    } else {
+        Err(Error::TokenExpected)
     }
 }
 
+/// Parse a date and or time or text value.
+///
+/// When text fallback is enabled a value without a value
+/// type that is not a date and or time is kept as text
+/// and the VALUE parameter is set to text.
 fn parse_date_time_or_text(
     prop_name: &str,
     value: Cow<'_, str>,
-    parameters: Option<Parameters>,
+    parameters: Option<Box<Parameters>>,
     group: Option<String>,
+    text_fallback: bool,
 ) -> Result<DateTimeOrTextProperty> {
     let value_type = if let Some(parameters) = &parameters {
         parameters.value.as_ref()
@@ -1148,17 +2183,34 @@ fn parse_date_time_or_text(
                 }))
             }
             _ => Err(Error::UnsupportedValueType(
-                value_type.to_string(),
+                snippet(value_type.to_string()),
                 String::from(prop_name),
             )),
         }
     } else {
-        let value = parse_date_and_or_time_list(value.as_ref())?;
-        Ok(DateTimeOrTextProperty::DateTime(DateAndOrTimeProperty {
-            value,
-            parameters,
-            group,
-        }))
+        match parse_date_and_or_time_list(value.as_ref()) {
+            Ok(value) => {
+                Ok(DateTimeOrTextProperty::DateTime(DateAndOrTimeProperty {
+                    value,
+                    parameters,
+                    group,
+                }))
+            }
+            Err(_) if text_fallback => {
+                trace_event!(
+                    property = prop_name,
+                    "value is not a date and or time, using text"
+                );
+                let mut parameters = parameters.unwrap_or_default();
+                parameters.value = Some(ValueType::Text);
+                Ok(DateTimeOrTextProperty::Text(TextProperty {
+                    value: value.into_owned(),
+                    parameters: Some(parameters),
+                    group,
+                }))
+            }
+            Err(e) => Err(e),
+        }
     }
 }
 
@@ -1185,3 +2237,335 @@ fn parse_language_tag(value: Cow<'_, str>) -> Result<LanguageTag> {
 fn parse_language_tag(value: Cow<'_, str>) -> Result<String> {
     Ok(value.into_owned())
 }
+
+/// Minutes of a UTC offset that follow the colon
+/// ending an unquoted TZ parameter.
+///
+/// The minutes are two digits followed by a parameter
+/// or property delimiter.
+fn offset_minutes(remainder: &str) -> Option<&str> {
+    let minutes = remainder.get(..2)?;
+    (minutes.bytes().all(|b| b.is_ascii_digit())
+        && remainder[2..].starts_with([':', ';']))
+    .then_some(minutes)
+}
+
+/// Determine if a value ends with the sign and hours of
+/// a UTC offset such as `+05`.
+fn ends_with_offset_hours(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() >= 3
+        && matches!(bytes[bytes.len() - 3], b'+' | b'-')
+        && bytes[bytes.len() - 2..].iter().all(u8::is_ascii_digit)
+}
+
+/// Find the rest of an unquoted GEO or TZ parameter URI after
+/// the scheme.
+///
+/// The rest of the URI ends at the next colon or semi-colon on
+/// the same line; a TZ value is only a URI when the rest starts
+/// with `//` or the scheme is `urn`.
+fn unquoted_uri_rest<'a>(
+    upper_name: &str,
+    values: &[String],
+    remainder: &'a str,
+) -> Option<&'a str> {
+    let [scheme] = values else {
+        return None;
+    };
+    let end = remainder.find([':', ';', '\r', '\n'])?;
+    let rest = &remainder[..end];
+    let is_uri = match upper_name {
+        GEO => scheme.eq_ignore_ascii_case("geo"),
+        TZ => rest.starts_with("//") || scheme.eq_ignore_ascii_case("urn"),
+        _ => false,
+    };
+    (is_uri && !rest.is_empty() && remainder[end..].starts_with([':', ';']))
+        .then_some(rest)
+}
+
+/// Advance the lexer to the next `BEGIN:VCARD` at the start of
+/// a line after an error in the vCard that starts at an offset.
+///
+/// Returns the BEGIN token when one is found.
+pub(crate) fn skip_to_begin(
+    lex: &mut Lexer<'_, Token>,
+    start: usize,
+) -> Option<LexResult<Token>> {
+    let at_line_start = |lex: &Lexer<'_, Token>| {
+        let offset = lex.span().start;
+        offset == 0 || lex.source()[..offset].ends_with('\n')
+    };
+    // The error may be for the BEGIN of the next vCard
+    if lex.span().start > start
+        && lex.slice().eq_ignore_ascii_case("BEGIN:VCARD")
+        && at_line_start(lex)
+    {
+        return Some(Ok(Token::Begin));
+    }
+    while let Some(token) = lex.next() {
+        if token == Ok(Token::Begin) && at_line_start(lex) {
+            return Some(token);
+        }
+    }
+    None
+}
+
+/// Determine if a TZ parameter value ends with the minutes
+/// of a UTC offset, for example `UTC+05:30`.
+fn is_offset_text(value: &str) -> bool {
+    value.rsplit_once(':').is_some_and(|(_, minutes)| {
+        minutes.len() == 2 && minutes.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+/// Determine if a token is the name of a parameter
+/// followed by a value.
+fn is_parameter_name(
+    lex: &Lexer<'_, Token>,
+    token: &LexResult<Token>,
+) -> bool {
+    (token == &Ok(Token::ParameterKey)
+        || token == &Ok(Token::ExtensionName)
+        || token == &Ok(Token::TimeZone)
+        || token == &Ok(Token::Geo))
+        && {
+            let mut lex = lex.clone();
+            lex.next() == Some(Ok(Token::ValueDelimiter))
+        }
+}
+
+/// Length of the content line that starts at an offset
+/// excluding the line break.
+///
+/// Quoted-printable values keep their folds in the unfolded
+/// text so the folds are included in the length.
+fn line_len(source: &str, start: usize) -> usize {
+    let bytes = source.as_bytes();
+    let mut offset = start;
+    while let Some(position) = source[offset..].find('\n') {
+        let index = offset + position;
+        if matches!(bytes.get(index + 1), Some(b' ' | b'\t')) {
+            offset = index + 1;
+            continue;
+        }
+        let end = if index > start && bytes[index - 1] == b'\r' {
+            index - 1
+        } else {
+            index
+        };
+        return end - start;
+    }
+    source.len() - start
+}
+
+/// Length of a property name in the iana-token form (with an
+/// optional group) that starts at the current token.
+///
+/// Names that are fully matched by a property name token
+/// are not IANA tokens.
+pub(crate) fn iana_name_len(
+    lex: &Lexer<'_, Token>,
+    token: &LexResult<Token>,
+) -> Option<usize> {
+    let rest = &lex.source()[lex.span().start..];
+    let len = rest.find(|c: char| {
+        !(c.is_ascii_alphanumeric() || c == '-' || c == '.')
+    })?;
+    if !rest[len..].starts_with([';', ':']) {
+        return None;
+    }
+
+    let name = &rest[..len];
+    let property = match name.split_once('.') {
+        Some((group, property)) if !group.is_empty() => property,
+        Some(_) => return None,
+        None => name,
+    };
+    let known = len == lex.slice().len()
+        && matches!(
+            token,
+            Ok(Token::PropertyName
+                | Token::ExtensionName
+                | Token::TimeZone
+                | Token::Geo)
+        );
+    if known
+        || len < lex.slice().len()
+        || property.is_empty()
+        || property.contains('.')
+        || property.eq_ignore_ascii_case("BEGIN")
+        || property.eq_ignore_ascii_case("END")
+    {
+        return None;
+    }
+    Some(len)
+}
+
+/// Length of an unknown parameter name in the iana-token form
+/// that starts at the current token and is followed by a value.
+///
+/// Names that are fully matched by a parameter name token
+/// are not unknown.
+fn unknown_parameter_len(
+    lex: &Lexer<'_, Token>,
+    token: &LexResult<Token>,
+) -> Option<usize> {
+    let rest = &lex.source()[lex.span().start..];
+    let len =
+        rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))?;
+    if len == 0 || len < lex.slice().len() || !rest[len..].starts_with('=') {
+        return None;
+    }
+    let known = len == lex.slice().len()
+        && matches!(
+            token,
+            Ok(Token::ParameterKey
+                | Token::ExtensionName
+                | Token::TimeZone
+                | Token::Geo)
+        );
+    (!known).then_some(len)
+}
+
+/// Media type for a legacy TYPE value of an inline binary property.
+fn legacy_media_type(name: &str, hint: &str) -> String {
+    let hint = hint.to_lowercase();
+    if hint.contains('/') {
+        return hint;
+    }
+    match (name, &hint[..]) {
+        (PHOTO | LOGO, "jpg") => "image/jpeg".to_owned(),
+        (PHOTO | LOGO, _) => format!("image/{}", hint),
+        (SOUND, _) => format!("audio/{}", hint),
+        (KEY, "pgp") => "application/pgp-keys".to_owned(),
+        (KEY, "x509") => "application/pkix-cert".to_owned(),
+        _ => format!("application/{}", hint),
+    }
+}
+
+/// Determine if parameters declare a quoted-printable value.
+fn is_quoted_printable(parameters: &Option<Box<Parameters>>) -> bool {
+    parameters
+        .as_ref()
+        .and_then(|params| params.extensions.as_ref())
+        .map(|extensions| {
+            extensions.iter().any(|(name, values)| {
+                name.eq_ignore_ascii_case(ENCODING)
+                    && values.first().is_some_and(|value| {
+                        value.eq_ignore_ascii_case(QUOTED_PRINTABLE)
+                    })
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Determine if the text starts with a content line (or the
+/// end of the source) rather than the continuation of a
+/// quoted-printable value.
+fn is_content_line(text: &str) -> bool {
+    if text.is_empty() {
+        return true;
+    }
+    let line = text.split(['\r', '\n']).next().unwrap_or_default();
+    let Some(name_end) = line.find([';', ':']) else {
+        return false;
+    };
+    let name = &line[..name_end];
+    let name = name.rsplit_once('.').map(|(_, name)| name).unwrap_or(name);
+    ["BEGIN", "END", VERSION]
+        .iter()
+        .any(|keyword| name.eq_ignore_ascii_case(keyword))
+        || PropertyKind::from_name(name).is_some()
+}
+
+/// Decode a quoted-printable value.
+///
+/// Returns `None` when the value contains an invalid escape sequence.
+fn decode_quoted_printable(value: &str) -> Option<Vec<u8>> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'=' {
+            let hex = bytes.get(index + 1..index + 3)?;
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            let hex = std::str::from_utf8(hex).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    Some(decoded)
+}
+
+/// Convert version 3.0 LABEL properties to the LABEL
+/// parameter of an ADR property.
+///
+/// A label is attached to the ADR in the same group, otherwise the
+/// ADR with the same TYPE values and finally the first ADR without a
+/// label. When there is no such ADR one is added with empty
+/// components.
+fn attach_labels(card: &mut Vcard) {
+    let (labels, extensions): (Vec<_>, Vec<_>) =
+        std::mem::take(&mut card.extensions)
+            .into_iter()
+            .partition(|ext| ext.name == LABEL);
+    card.extensions = extensions;
+
+    for label in labels {
+        let AnyProperty::Text(value) = &label.value else {
+            continue;
+        };
+        let types = label.parameters.as_ref().and_then(|p| p.types.as_ref());
+        let unlabelled = |adr: &AddressProperty| {
+            adr.parameters
+                .as_ref()
+                .map(|p| p.label.is_none())
+                .unwrap_or(true)
+        };
+
+        let position = card
+            .address
+            .iter()
+            .position(|adr| {
+                label.group.is_some()
+                    && adr.group == label.group
+                    && unlabelled(adr)
+            })
+            .or_else(|| {
+                card.address.iter().position(|adr| {
+                    let adr_types = adr
+                        .parameters
+                        .as_ref()
+                        .and_then(|p| p.types.as_ref());
+                    types.is_some() && adr_types == types && unlabelled(adr)
+                })
+            })
+            .or_else(|| card.address.iter().position(unlabelled));
+
+        if let Some(adr) = position.and_then(|i| card.address.get_mut(i)) {
+            adr.parameters.get_or_insert_with(Default::default).label =
+                Some(value.clone());
+        } else {
+            let mut params = label.parameters.clone().unwrap_or_default();
+            params.label = Some(value.clone());
+            card.address.push(AddressProperty {
+                value: Default::default(),
+                parameters: Some(params),
+                group: label.group.clone(),
+            });
+        }
+    }
+}
+
+/// Determine if a list of dates contains a truncated date.
+fn has_partial_date(value: &str) -> bool {
+    value
+        .split(',')
+        .any(|value| value.parse::<crate::PartialDate>().is_ok())
+}
@@ -1,6 +1,7 @@
 //! Types for properties.
 
 use std::{
+    borrow::Cow,
     fmt::{self, Display},
     str::FromStr,
 };
@@ -19,22 +20,28 @@ use serde_with::{serde_as, DisplayFromStr};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::{
+    data::{is_data_uri, DataUri},
+    error::snippet,
+    escape::{split_unescaped, unescape_component},
     escape_value,
+    geo::GeoUri,
     helper::{
         format_date, format_date_and_or_time_list, format_date_list,
         format_date_time, format_date_time_list, format_float_list,
         format_integer_list, format_time, format_time_list,
         format_timestamp_list, format_utc_offset, parse_date,
-        parse_date_time, parse_time, parse_utc_offset,
+        parse_date_and_or_time_list, parse_date_time, parse_time,
+        parse_utc_offset,
     },
-    parameter::Parameters,
-    Date, DateTime, Error, Result, Uri,
+    parameter::{Parameters, TelephoneType, TypeParameter, ValueType},
+    Date, DateTime, Error, PartialDate, Result, Uri,
 };
 
 const INDIVIDUAL: &str = "individual";
 const GROUP: &str = "group";
 const ORG: &str = "org";
 const LOCATION: &str = "location";
+const APPLICATION: &str = "application";
 
 /// Trait for vCard properties.
 pub trait Property: Display {
@@ -43,9 +50,103 @@ pub trait Property: Display {
 
     /// Get the property parameters.
     fn parameters(&self) -> Option<&Parameters>;
+
+    /// Get the parameters to write for the property.
+    ///
+    /// Extension properties set the VALUE parameter from
+    /// the type of the value; otherwise these are the
+    /// property parameters.
+    fn output_parameters(&self) -> Option<Cow<'_, Parameters>> {
+        self.parameters().map(Cow::Borrowed)
+    }
+
+    /// Get the TYPE parameter values.
+    fn types(&self) -> &[TypeParameter] {
+        self.parameters()
+            .and_then(|params| params.types.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Determine if the TYPE parameter includes `home`.
+    fn is_home(&self) -> bool {
+        self.types().contains(&TypeParameter::Home)
+    }
+
+    /// Determine if the TYPE parameter includes `work`.
+    fn is_work(&self) -> bool {
+        self.types().contains(&TypeParameter::Work)
+    }
+
+    /// Get the telephone types in the TYPE parameter.
+    fn telephone_types(&self) -> Vec<&TelephoneType> {
+        self.types()
+            .iter()
+            .filter_map(|value| match value {
+                TypeParameter::Telephone(tel) => Some(tel),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Validated name of a property group.
+///
+/// A group name is one or more letters, digits or dashes.
+///
+/// ```
+/// use vcard4::property::PropertyGroup;
+/// assert!(PropertyGroup::new("item-1").is_ok());
+/// assert!(PropertyGroup::new("my group!").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PropertyGroup(String);
+
+impl PropertyGroup {
+    /// Create a property group.
+    pub fn new(name: &str) -> Result<Self> {
+        if is_group_name(name) {
+            Ok(Self(name.to_owned()))
+        } else {
+            Err(Error::InvalidGroup(snippet(name)))
+        }
+    }
+
+    /// Get the group name.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for PropertyGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for PropertyGroup {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl From<PropertyGroup> for String {
+    fn from(value: PropertyGroup) -> Self {
+        value.0
+    }
+}
+
+/// Determine if a name is a valid property group name.
+pub(crate) fn is_group_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
 }
 
 /// Delivery address for the ADR property.
+///
+/// Each component may have multiple values; a single value
+/// is serialized as a string.
 #[derive(Default, Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
@@ -54,81 +155,212 @@ pub struct DeliveryAddress {
     /// The post office box.
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Option::is_none")
+        serde(
+            default,
+            skip_serializing_if = "Vec::is_empty",
+            with = "crate::serde::component_values"
+        )
     )]
-    pub po_box: Option<String>,
+    pub po_box: Vec<String>,
     /// The extended address (e.g: apartment or suite number).
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Option::is_none")
+        serde(
+            default,
+            skip_serializing_if = "Vec::is_empty",
+            with = "crate::serde::component_values"
+        )
     )]
-    pub extended_address: Option<String>,
+    pub extended_address: Vec<String>,
     /// The street address.
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Option::is_none")
+        serde(
+            default,
+            skip_serializing_if = "Vec::is_empty",
+            with = "crate::serde::component_values"
+        )
     )]
-    pub street_address: Option<String>,
+    pub street_address: Vec<String>,
     /// The locality (e.g: city).
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Option::is_none")
+        serde(
+            default,
+            skip_serializing_if = "Vec::is_empty",
+            with = "crate::serde::component_values"
+        )
     )]
-    pub locality: Option<String>,
+    pub locality: Vec<String>,
     /// The region (e.g: state or province).
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Option::is_none")
+        serde(
+            default,
+            skip_serializing_if = "Vec::is_empty",
+            with = "crate::serde::component_values"
+        )
     )]
-    pub region: Option<String>,
+    pub region: Vec<String>,
     /// The postal code.
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Option::is_none")
+        serde(
+            default,
+            skip_serializing_if = "Vec::is_empty",
+            with = "crate::serde::component_values"
+        )
     )]
-    pub postal_code: Option<String>,
+    pub postal_code: Vec<String>,
     /// The country name.
     #[cfg_attr(
         feature = "serde",
-        serde(skip_serializing_if = "Option::is_none")
+        serde(
+            default,
+            skip_serializing_if = "Vec::is_empty",
+            with = "crate::serde::component_values"
+        )
     )]
-    pub country_name: Option<String>,
+    pub country_name: Vec<String>,
+}
+
+impl DeliveryAddress {
+    /// Get the address components in order.
+    pub fn components(&self) -> [&Vec<String>; 7] {
+        [
+            &self.po_box,
+            &self.extended_address,
+            &self.street_address,
+            &self.locality,
+            &self.region,
+            &self.postal_code,
+            &self.country_name,
+        ]
+    }
+
+    /// Best effort parse of a delivery address from a formatted
+    /// label such as the value of the LABEL parameter.
+    ///
+    /// Each non-empty line is trimmed; when there are three or more
+    /// lines the last line is the country name and the line before
+    /// it holds the locality, region and postal code, the remaining
+    /// lines are values of the street address. A literal `\n`
+    /// escape sequence is treated as a line break.
+    ///
+    /// The locality line is split at the last comma into the
+    /// locality and a region followed by the words of the postal
+    /// code which contain a digit (`Any Town, CA 91921-1234`);
+    /// without a comma a leading or trailing word containing a
+    /// digit is the postal code (`51147 Koeln`).
+    pub fn from_label(label: &str) -> Self {
+        let label = unescape_component(label);
+        let mut lines: Vec<&str> = label
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let mut address = Self::default();
+        if lines.len() >= 3 {
+            if let Some(country) = lines.pop() {
+                address.country_name.push(country.to_owned());
+            }
+        }
+        if lines.len() >= 2 {
+            if let Some(line) = lines.pop() {
+                address.split_locality(line);
+            }
+        }
+        address.street_address =
+            lines.into_iter().map(str::to_owned).collect();
+        address
+    }
+
+    /// Split a line into the locality, region and postal code.
+    fn split_locality(&mut self, line: &str) {
+        let has_digit =
+            |word: &&str| word.contains(|c: char| c.is_ascii_digit());
+        let push = |component: &mut Vec<String>, words: &[&str]| {
+            if !words.is_empty() {
+                component.push(words.join(" "));
+            }
+        };
+
+        if let Some((locality, rest)) = line.rsplit_once(',') {
+            let words: Vec<&str> = rest.split_whitespace().collect();
+            let postal = words
+                .iter()
+                .rposition(|word| !has_digit(word))
+                .map(|index| index + 1)
+                .unwrap_or(0);
+            push(&mut self.locality, &[locality.trim()]);
+            push(&mut self.region, &words[..postal]);
+            push(&mut self.postal_code, &words[postal..]);
+            return;
+        }
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match (words.first(), words.last()) {
+            (Some(first), _) if words.len() > 1 && has_digit(first) => {
+                push(&mut self.postal_code, &words[..1]);
+                push(&mut self.locality, &words[1..]);
+            }
+            (_, Some(last)) if words.len() > 1 && has_digit(last) => {
+                let index = words.len() - 1;
+                push(&mut self.locality, &words[..index]);
+                push(&mut self.postal_code, &words[index..]);
+            }
+            _ => push(&mut self.locality, &words),
+        }
+    }
+
+    /// Parse a delivery address from its components.
+    ///
+    /// Missing trailing components are treated as empty; when there
+    /// are more than seven components it is an error in strict mode
+    /// otherwise the extra components are joined to the country name.
+    ///
+    /// Components are unescaped after the value is split.
+    pub(crate) fn parse_components(s: &str, strict: bool) -> Result<Self> {
+        let mut components = split_unescaped(s, ';');
+        let count = components.len();
+        if count > 7 {
+            if strict {
+                return Err(Error::InvalidAddress(snippet(s)));
+            }
+            crate::trace::warn_event!(
+                components = count,
+                "extra address components joined to country name"
+            );
+        }
+
+        let extra = components.split_off(count.min(6));
+        let country_name = if extra.is_empty() {
+            None
+        } else {
+            Some(extra.join(";"))
+        };
+
+        let mut it = components
+            .into_iter()
+            .chain(country_name.as_deref())
+            .map(component_values);
+
+        Ok(Self {
+            po_box: it.next().unwrap_or_default(),
+            extended_address: it.next().unwrap_or_default(),
+            street_address: it.next().unwrap_or_default(),
+            locality: it.next().unwrap_or_default(),
+            region: it.next().unwrap_or_default(),
+            postal_code: it.next().unwrap_or_default(),
+            country_name: it.next().unwrap_or_default(),
+        })
+    }
 }
 
 impl fmt::Display for DeliveryAddress {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{};{};{};{};{};{};{}",
-            self.po_box
-                .as_ref()
-                .map(|s| escape_value(&s[..], true))
-                .unwrap_or_default(),
-            self.extended_address
-                .as_ref()
-                .map(|s| escape_value(&s[..], true))
-                .unwrap_or_default(),
-            self.street_address
-                .as_ref()
-                .map(|s| escape_value(&s[..], true))
-                .unwrap_or_default(),
-            self.locality
-                .as_ref()
-                .map(|s| escape_value(&s[..], true))
-                .unwrap_or_default(),
-            self.region
-                .as_ref()
-                .map(|s| escape_value(&s[..], true))
-                .unwrap_or_default(),
-            self.postal_code
-                .as_ref()
-                .map(|s| escape_value(&s[..], true))
-                .unwrap_or_default(),
-            self.country_name
-                .as_ref()
-                .map(|s| escape_value(&s[..], true))
-                .unwrap_or_default(),
-        )
+        write_components(f, &self.components())
     }
 }
 
@@ -136,75 +368,39 @@ impl FromStr for DeliveryAddress {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let mut it = s.splitn(7, ';');
-        let po_box = it
-            .next()
-            .ok_or_else(|| Error::InvalidAddress(s.to_string()))?;
-        let extended_address = it
-            .next()
-            .ok_or_else(|| Error::InvalidAddress(s.to_string()))?;
-        let street_address = it
-            .next()
-            .ok_or_else(|| Error::InvalidAddress(s.to_string()))?;
-        let locality = it
-            .next()
-            .ok_or_else(|| Error::InvalidAddress(s.to_string()))?;
-        let region = it
-            .next()
-            .ok_or_else(|| Error::InvalidAddress(s.to_string()))?;
-        let postal_code = it
-            .next()
-            .ok_or_else(|| Error::InvalidAddress(s.to_string()))?;
-        let country_name = it
-            .next()
-            .ok_or_else(|| Error::InvalidAddress(s.to_string()))?;
+        Self::parse_components(s, true)
+    }
+}
 
-        let po_box = if !po_box.is_empty() {
-            Some(po_box.to_owned())
-        } else {
-            None
-        };
-        let extended_address = if !extended_address.is_empty() {
-            Some(extended_address.to_owned())
-        } else {
-            None
-        };
-        let street_address = if !street_address.is_empty() {
-            Some(street_address.to_owned())
-        } else {
-            None
-        };
-        let locality = if !locality.is_empty() {
-            Some(locality.to_owned())
-        } else {
-            None
-        };
-        let region = if !region.is_empty() {
-            Some(region.to_owned())
-        } else {
-            None
-        };
-        let postal_code = if !postal_code.is_empty() {
-            Some(postal_code.to_owned())
-        } else {
-            None
-        };
-        let country_name = if !country_name.is_empty() {
-            Some(country_name.to_owned())
-        } else {
-            None
-        };
+/// Split an escaped component into its unescaped values.
+fn component_values(component: &str) -> Vec<String> {
+    if component.is_empty() {
+        Vec::new()
+    } else {
+        split_unescaped(component, ',')
+            .into_iter()
+            .map(unescape_component)
+            .collect()
+    }
+}
 
-        Ok(Self {
-            po_box,
-            extended_address,
-            street_address,
-            locality,
-            region,
-            postal_code,
-            country_name,
-        })
+/// Write escaped components separated by semi-colons.
+fn write_components(
+    f: &mut fmt::Formatter<'_>,
+    components: &[&Vec<String>],
+) -> fmt::Result {
+    for (index, component) in components.iter().enumerate() {
+        if index > 0 {
+            write!(f, ";")?;
+        }
+        for (index, value) in component.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", escape_value(value, true))?;
+        }
     }
+    Ok(())
 }
 
 /// The ADR property.
@@ -225,7 +421,7 @@ pub struct AddressProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 impl From<DeliveryAddress> for AddressProperty {
@@ -238,6 +434,203 @@ impl From<DeliveryAddress> for AddressProperty {
     }
 }
 
+impl AddressProperty {
+    /// Get a formatted label for display.
+    ///
+    /// Prefers the LABEL parameter; otherwise the non-empty values
+    /// of the street address, locality, region, postal code and
+    /// country name are joined with newlines in that order.
+    pub fn display_label(&self) -> Cow<'_, str> {
+        let label = self
+            .parameters
+            .as_ref()
+            .and_then(|params| params.label.as_deref())
+            .filter(|label| !label.trim().is_empty());
+        if let Some(label) = label {
+            return if label.contains('\\') {
+                Cow::Owned(unescape_component(label))
+            } else {
+                Cow::Borrowed(label)
+            };
+        }
+
+        let value = &self.value;
+        let lines: Vec<&str> = [
+            &value.street_address,
+            &value.locality,
+            &value.region,
+            &value.postal_code,
+            &value.country_name,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .collect();
+        Cow::Owned(lines.join("\n"))
+    }
+}
+
+/// Structured name for the N property.
+///
+/// Each component may have multiple values.
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct StructuredName {
+    /// The family names (surnames).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub family_names: Vec<String>,
+    /// The given names.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub given_names: Vec<String>,
+    /// The additional names.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub additional_names: Vec<String>,
+    /// The honorific prefixes.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub honorific_prefixes: Vec<String>,
+    /// The honorific suffixes.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub honorific_suffixes: Vec<String>,
+}
+
+impl StructuredName {
+    /// Get the components in the order they are encoded.
+    pub fn components(&self) -> [&Vec<String>; 5] {
+        [
+            &self.family_names,
+            &self.given_names,
+            &self.additional_names,
+            &self.honorific_prefixes,
+            &self.honorific_suffixes,
+        ]
+    }
+
+    /// Parse a structured name from its components.
+    ///
+    /// Missing trailing components are treated as empty; when there
+    /// are more than five components it is an error in strict mode
+    /// otherwise the extra components are joined to the honorific
+    /// suffixes.
+    pub(crate) fn parse_components(s: &str, strict: bool) -> Result<Self> {
+        let mut components = split_unescaped(s, ';');
+        let count = components.len();
+        if count > 5 {
+            if strict {
+                return Err(Error::InvalidName(snippet(s)));
+            }
+            crate::trace::warn_event!(
+                components = count,
+                "extra name components joined to honorific suffixes"
+            );
+        }
+
+        let extra = components.split_off(count.min(4));
+        let suffixes = if extra.is_empty() {
+            None
+        } else {
+            Some(extra.join(";"))
+        };
+
+        let mut it = components
+            .into_iter()
+            .chain(suffixes.as_deref())
+            .map(component_values);
+        Ok(Self {
+            family_names: it.next().unwrap_or_default(),
+            given_names: it.next().unwrap_or_default(),
+            additional_names: it.next().unwrap_or_default(),
+            honorific_prefixes: it.next().unwrap_or_default(),
+            honorific_suffixes: it.next().unwrap_or_default(),
+        })
+    }
+}
+
+impl From<[String; 5]> for StructuredName {
+    fn from(value: [String; 5]) -> Self {
+        let [family, given, additional, prefixes, suffixes] =
+            value.map(|value| {
+                if value.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![value]
+                }
+            });
+        Self {
+            family_names: family,
+            given_names: given,
+            additional_names: additional,
+            honorific_prefixes: prefixes,
+            honorific_suffixes: suffixes,
+        }
+    }
+}
+
+impl fmt::Display for StructuredName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_components(f, &self.components())
+    }
+}
+
+impl FromStr for StructuredName {
+    type Err = Error;
+
+    /// Parse an escaped N value.
+    ///
+    /// Missing trailing components are treated as empty.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_components(s, true)
+    }
+}
+
+/// The N property.
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
+pub struct NameProperty {
+    /// Group for this property.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub group: Option<String>,
+    /// The value for the property.
+    pub value: StructuredName,
+    /// The property parameters.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub parameters: Option<Box<Parameters>>,
+}
+
+impl From<StructuredName> for NameProperty {
+    fn from(value: StructuredName) -> Self {
+        Self {
+            value,
+            group: None,
+            parameters: None,
+        }
+    }
+}
+
 /// Value for the CLIENTPIDMAP property.
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", cfg_eval::cfg_eval, serde_as)]
@@ -265,16 +658,16 @@ impl FromStr for ClientPidMap {
         let mut it = s.splitn(2, ';');
         let source = it
             .next()
-            .ok_or_else(|| Error::InvalidClientPidMap(s.to_string()))?;
+            .ok_or_else(|| Error::InvalidClientPidMap(snippet(s)))?;
         let uri = it
             .next()
-            .ok_or_else(|| Error::InvalidClientPidMap(s.to_string()))?;
+            .ok_or_else(|| Error::InvalidClientPidMap(snippet(s)))?;
         let source: u64 = source.parse()?;
 
         // Must be positive according to the RFC
         // https://www.rfc-editor.org/rfc/rfc6350#section-6.7.7
         if source == 0 {
-            return Err(Error::InvalidClientPidMap(s.to_string()));
+            return Err(Error::InvalidClientPidMap(snippet(s)));
         }
 
         let uri = uri.parse()?;
@@ -300,7 +693,7 @@ pub struct ClientPidMapProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 /// Extension property.
@@ -323,16 +716,17 @@ pub struct ExtensionProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 /// Value for any property type.
 #[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "serde", cfg_eval::cfg_eval, serde_as)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
-#[cfg_attr(feature = "serde", serde(untagged, rename_all = "camelCase"))]
-#[allow(clippy::large_enum_variant)]
+#[cfg_attr(
+    feature = "serde",
+    serde(rename_all = "camelCase", tag = "kind", content = "value")
+)]
 pub enum AnyProperty {
     /// Text property.
     Text(String),
@@ -360,7 +754,7 @@ pub enum AnyProperty {
     Timestamp(Vec<DateTime>),
     /// URI property.
     #[cfg_attr(feature = "zeroize", zeroize(skip))]
-    Uri(#[cfg_attr(feature = "serde", serde_as(as = "DisplayFromStr"))] Uri),
+    Uri(Uri),
     /// UTC offset property.
     #[cfg_attr(feature = "zeroize", zeroize(skip))]
     UtcOffset(UtcOffset),
@@ -420,7 +814,7 @@ pub struct LanguageProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 #[cfg(not(feature = "language-tags"))]
@@ -464,7 +858,7 @@ pub struct DateTimeProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 impl From<DateTime> for DateTimeProperty {
@@ -490,7 +884,10 @@ impl fmt::Display for DateTimeProperty {
 /// Date and or time.
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "serde", serde(untagged))]
+#[cfg_attr(
+    feature = "serde",
+    serde(rename_all = "camelCase", tag = "kind", content = "value")
+)]
 pub enum DateAndOrTime {
     /// Date value.
     Date(Date),
@@ -498,6 +895,9 @@ pub enum DateAndOrTime {
     DateTime(DateTime),
     /// Time value.
     Time((Time, UtcOffset)),
+    /// Date with a truncated representation such as
+    /// a month and day without a year.
+    PartialDate(PartialDate),
 }
 
 impl From<Date> for DateAndOrTime {
@@ -532,6 +932,7 @@ impl fmt::Display for DateAndOrTime {
             Self::Time(val) => {
                 write!(f, "{}", format_time(val).map_err(|_| fmt::Error)?)
             }
+            Self::PartialDate(val) => write!(f, "{}", val),
         }
     }
 }
@@ -540,14 +941,18 @@ impl FromStr for DateAndOrTime {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        if !s.is_empty() && &s[0..1] == "T" {
-            return Ok(Self::Time(parse_time(&s[1..])?));
+        if let Some(time) = s.strip_prefix('T') {
+            return Ok(Self::Time(parse_time(time)?));
+        }
+
+        if let Ok(value) = s.parse::<PartialDate>() {
+            return Ok(Self::PartialDate(value));
         }
 
         match parse_date_time(s) {
             Ok(value) => Ok(Self::DateTime(value)),
             Err(_) => match parse_date(s) {
-                Ok(value) => Ok(Self::Date(value.into())),
+                Ok(value) => Ok(Self::Date(value)),
                 Err(_) => match parse_time(s) {
                     Ok(val) => Ok(Self::Time(val)),
                     Err(e) => Err(e),
@@ -574,7 +979,7 @@ pub struct DateAndOrTimeProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 impl From<Date> for DateAndOrTimeProperty {
@@ -617,8 +1022,10 @@ impl fmt::Display for DateAndOrTimeProperty {
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
-#[cfg_attr(feature = "serde", serde(untagged))]
-#[allow(clippy::large_enum_variant)]
+#[cfg_attr(
+    feature = "serde",
+    serde(rename_all = "camelCase", tag = "kind", content = "value")
+)]
 pub enum TextOrUriProperty {
     /// Text value.
     Text(TextProperty),
@@ -654,6 +1061,79 @@ impl Property for TextOrUriProperty {
     }
 }
 
+impl TextOrUriProperty {
+    /// Create a KEY property with a base64 encoded data URI
+    /// for the key bytes.
+    pub fn from_key_bytes(media_type: &str, data: &[u8]) -> Result<Self> {
+        let data = DataUri::new(media_type.to_owned(), data.to_vec());
+        Ok(Self::Uri(Uri::try_from(data)?.into()))
+    }
+
+    /// Set the parameters for this property.
+    pub fn with_parameters(mut self, parameters: Parameters) -> Self {
+        let parameters = Some(Box::new(parameters));
+        match &mut self {
+            Self::Text(val) => val.parameters = parameters,
+            Self::Uri(val) => val.parameters = parameters,
+        }
+        self
+    }
+}
+
+/// Decoded value of a KEY property.
+///
+/// ```
+/// use vcard4::property::{KeyData, TextOrUriProperty};
+/// let prop = TextOrUriProperty::from_key_bytes(
+///     "application/pgp-keys", b"key").unwrap();
+/// let KeyData::Binary { media_type, bytes } =
+///     KeyData::try_from(&prop).unwrap() else {
+///     panic!("expecting binary key data");
+/// };
+/// assert_eq!("application/pgp-keys", media_type);
+/// assert_eq!(b"key", &bytes[..]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyData {
+    /// URI that refers to the key.
+    Uri(Uri),
+    /// Key embedded in a data URI.
+    Binary {
+        /// The media type of the data URI.
+        media_type: String,
+        /// The decoded key.
+        bytes: Vec<u8>,
+    },
+    /// Key as text such as an ASCII armored PGP key
+    /// or an SSH public key.
+    Text(String),
+}
+
+impl TryFrom<&TextOrUriProperty> for KeyData {
+    type Error = Error;
+
+    /// Data URIs are decoded including text values
+    /// that use the `data:` scheme.
+    fn try_from(value: &TextOrUriProperty) -> Result<Self> {
+        let data = match value {
+            TextOrUriProperty::Uri(prop) => match prop.data_uri()? {
+                Some(data) => data,
+                None => return Ok(Self::Uri(prop.value.clone())),
+            },
+            TextOrUriProperty::Text(prop) if is_data_uri(&prop.value) => {
+                prop.value.parse()?
+            }
+            TextOrUriProperty::Text(prop) => {
+                return Ok(Self::Text(prop.value.clone()))
+            }
+        };
+        Ok(Self::Binary {
+            media_type: data.media_type,
+            bytes: data.data,
+        })
+    }
+}
+
 impl fmt::Display for TextOrUriProperty {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -667,7 +1147,10 @@ impl fmt::Display for TextOrUriProperty {
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
-#[cfg_attr(feature = "serde", serde(untagged))]
+#[cfg_attr(
+    feature = "serde",
+    serde(rename_all = "camelCase", tag = "kind", content = "value")
+)]
 pub enum DateTimeOrTextProperty {
     /// Date time value.
     #[cfg_attr(feature = "zeroize", zeroize(skip))]
@@ -716,6 +1199,18 @@ impl Property for DateTimeOrTextProperty {
     }
 }
 
+impl DateTimeOrTextProperty {
+    /// Set the parameters for this property.
+    pub fn with_parameters(mut self, parameters: Parameters) -> Self {
+        let parameters = Some(Box::new(parameters));
+        match &mut self {
+            Self::Text(val) => val.parameters = parameters,
+            Self::DateTime(val) => val.parameters = parameters,
+        }
+        self
+    }
+}
+
 impl fmt::Display for DateTimeOrTextProperty {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -744,7 +1239,7 @@ pub struct UtcOffsetProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 impl From<UtcOffset> for UtcOffsetProperty {
@@ -759,27 +1254,14 @@ impl From<UtcOffset> for UtcOffsetProperty {
 
 impl fmt::Display for UtcOffsetProperty {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (h, m, _) = self.value.as_hms();
-        let sign = if h >= 0 { '+' } else { '-' };
-        let h = h.abs();
-        let m = m.abs();
-        let h = if h < 10 {
-            format!("0{}", h)
-        } else {
-            h.to_string()
-        };
-        let m = if m < 10 {
-            format!("0{}", m)
-        } else {
-            m.to_string()
-        };
-        write!(f, "{}{}{}", sign, h, m,)
+        format_utc_offset(f, &self.value)
     }
 }
 
 impl FromStr for UtcOffsetProperty {
     type Err = Error;
 
+    /// Parse a UTC offset in the `+HHMM`, `+HH` or `Z` form.
     fn from_str(s: &str) -> Result<Self> {
         let value = parse_utc_offset(s)?;
         Ok(Self {
@@ -794,8 +1276,10 @@ impl FromStr for UtcOffsetProperty {
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
-#[cfg_attr(feature = "serde", serde(untagged))]
-#[allow(clippy::large_enum_variant)]
+#[cfg_attr(
+    feature = "serde",
+    serde(rename_all = "camelCase", tag = "kind", content = "value")
+)]
 pub enum TimeZoneProperty {
     /// Text value.
     Text(TextProperty),
@@ -841,6 +1325,19 @@ impl Property for TimeZoneProperty {
     }
 }
 
+impl TimeZoneProperty {
+    /// Set the parameters for this property.
+    pub fn with_parameters(mut self, parameters: Parameters) -> Self {
+        let parameters = Some(Box::new(parameters));
+        match &mut self {
+            Self::Text(val) => val.parameters = parameters,
+            Self::Uri(val) => val.parameters = parameters,
+            Self::UtcOffset(val) => val.parameters = parameters,
+        }
+        self
+    }
+}
+
 impl fmt::Display for TimeZoneProperty {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -869,7 +1366,7 @@ pub struct TextProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 impl fmt::Display for TextProperty {
@@ -918,7 +1415,7 @@ pub struct TextListProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
     /// Delimiter for the list property.
     pub delimiter: TextListDelimiter,
 }
@@ -947,20 +1444,17 @@ impl TextListProperty {
 
 impl fmt::Display for TextListProperty {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let delimiter = match self.delimiter {
+            TextListDelimiter::Comma => ',',
+            TextListDelimiter::SemiColon => ';',
+        };
         for (index, item) in self.value.iter().enumerate() {
-            let semi_colons =
-                matches!(self.delimiter, TextListDelimiter::SemiColon);
-            write!(f, "{}", escape_value(item, semi_colons))?;
-            if index < self.value.len() - 1 {
-                write!(
-                    f,
-                    "{}",
-                    match self.delimiter {
-                        TextListDelimiter::Comma => ',',
-                        TextListDelimiter::SemiColon => ';',
-                    }
-                )?;
+            if index > 0 {
+                write!(f, "{}", delimiter)?;
             }
+            // Both delimiters are escaped so a component
+            // never splits when parsed
+            write!(f, "{}", escape_value(item, true))?;
         }
         Ok(())
     }
@@ -987,7 +1481,7 @@ pub struct UriProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 impl From<Uri> for UriProperty {
@@ -1000,6 +1494,36 @@ impl From<Uri> for UriProperty {
     }
 }
 
+impl UriProperty {
+    /// Create a property with a base64 encoded data URI
+    /// for the image bytes.
+    pub fn from_image_bytes(media_type: &str, data: &[u8]) -> Result<Self> {
+        let data = DataUri::new(media_type.to_owned(), data.to_vec());
+        Ok(Uri::try_from(data)?.into())
+    }
+
+    /// Parse the value as a geographic location URI.
+    ///
+    /// Returns `None` when the value is not a valid
+    /// `geo:` URI.
+    pub fn as_geo(&self) -> Option<GeoUri> {
+        GeoUri::try_from(&self.value).ok()
+    }
+
+    /// Decode the value when it is a data URI.
+    ///
+    /// Returns `None` when the value does not use the
+    /// `data:` scheme.
+    pub fn data_uri(&self) -> Result<Option<DataUri>> {
+        let value = self.value.to_string();
+        if is_data_uri(&value) {
+            Ok(Some(value.parse()?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 impl TryFrom<&str> for UriProperty {
     type Error = Error;
 
@@ -1027,7 +1551,7 @@ pub struct KindProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 impl From<Kind> for KindProperty {
@@ -1044,7 +1568,10 @@ impl From<Kind> for KindProperty {
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
-#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(
+    feature = "serde",
+    serde(rename_all = "lowercase", tag = "kind", content = "value")
+)]
 pub enum Kind {
     /// An individual.
     Individual,
@@ -1054,8 +1581,12 @@ pub enum Kind {
     Org,
     /// A location.
     Location,
-    // TODO: x-name
-    // TODO: iana-token
+    /// An application
+    /// ([RFC6473](https://www.rfc-editor.org/rfc/rfc6473)).
+    Application,
+    /// An x-name or iana-token kind that is not known to this
+    /// library; the value is kept as it was parsed.
+    Extension(String),
 }
 
 impl fmt::Display for Kind {
@@ -1068,6 +1599,8 @@ impl fmt::Display for Kind {
                 Self::Group => GROUP,
                 Self::Org => ORG,
                 Self::Location => LOCATION,
+                Self::Application => APPLICATION,
+                Self::Extension(value) => value,
             }
         )
     }
@@ -1076,13 +1609,25 @@ impl fmt::Display for Kind {
 impl FromStr for Kind {
     type Err = Error;
 
+    /// Known kinds are case-insensitive; other values must
+    /// be an x-name or iana-token.
     fn from_str(s: &str) -> Result<Self> {
-        match s {
+        match &s.to_lowercase()[..] {
             INDIVIDUAL => Ok(Self::Individual),
             GROUP => Ok(Self::Group),
             ORG => Ok(Self::Org),
             LOCATION => Ok(Self::Location),
-            _ => Err(Error::UnknownKind(s.to_string())),
+            APPLICATION => Ok(Self::Application),
+            _ => {
+                let is_token = !s.is_empty()
+                    && s.chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-');
+                if is_token {
+                    Ok(Self::Extension(s.to_owned()))
+                } else {
+                    Err(Error::UnknownKind(snippet(s)))
+                }
+            }
         }
     }
 }
@@ -1105,7 +1650,7 @@ pub struct GenderProperty {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub parameters: Option<Parameters>,
+    pub parameters: Option<Box<Parameters>>,
 }
 
 impl From<Gender> for GenderProperty {
@@ -1133,6 +1678,28 @@ pub struct Gender {
     pub identity: Option<String>,
 }
 
+impl Gender {
+    /// Create a new gender with the given sex.
+    pub fn new(sex: Sex) -> Self {
+        Self {
+            sex,
+            identity: None,
+        }
+    }
+
+    /// Set the identity text for this gender.
+    pub fn with_identity(mut self, identity: impl Into<String>) -> Self {
+        self.identity = Some(identity.into());
+        self
+    }
+}
+
+impl From<Sex> for Gender {
+    fn from(value: Sex) -> Self {
+        Self::new(value)
+    }
+}
+
 impl fmt::Display for Gender {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(identity) = &self.identity {
@@ -1154,21 +1721,56 @@ impl FromStr for Gender {
             });
         }
 
-        let mut it = s.splitn(2, ';');
-        let sex = it.next().ok_or(Error::NoSex)?;
+        // The identity may contain escaped semi-colons
+        let sex = split_unescaped(s, ';')
+            .into_iter()
+            .next()
+            .ok_or(Error::NoSex)?;
+        let identity = s.get(sex.len() + 1..);
         let sex: Sex = sex.parse()?;
         let mut gender = Gender {
             sex,
             identity: None,
         };
-        if let Some(identity) = it.next() {
-            gender.identity = Some(identity.to_string());
+        if let Some(identity) = identity {
+            gender.identity = Some(unescape_component(identity));
         }
 
         Ok(gender)
     }
 }
 
+/// GENDER property resolved from a set of alternative representations.
+///
+/// See [Vcard::gender_resolved](crate::Vcard::gender_resolved).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ResolvedGender<'a> {
+    property: &'a GenderProperty,
+}
+
+impl<'a> ResolvedGender<'a> {
+    /// The sex component of the gender.
+    pub fn sex(&self) -> &'a Sex {
+        &self.property.value.sex
+    }
+
+    /// The identity text of the gender.
+    pub fn identity(&self) -> Option<&'a str> {
+        self.property.value.identity.as_deref()
+    }
+
+    /// The underlying property.
+    pub fn property(&self) -> &'a GenderProperty {
+        self.property
+    }
+}
+
+impl<'a> From<&'a GenderProperty> for ResolvedGender<'a> {
+    fn from(property: &'a GenderProperty) -> Self {
+        Self { property }
+    }
+}
+
 /// Enumeration for sex.
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -1186,37 +1788,58 @@ pub enum Sex {
     NotApplicable,
     /// Unknown sex.
     Unknown,
+    /// Letter that is not a sex defined by RFC6350.
+    ///
+    /// Only retained by loose parsing; the letter is
+    /// preserved as it was parsed.
+    Unrecognized(char),
+}
+
+impl Sex {
+    /// Determine if a sex has been specified.
+    pub fn is_specified(&self) -> bool {
+        !matches!(self, Self::None)
+    }
 }
 
 impl fmt::Display for Sex {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::None => "",
-                Self::Male => "M",
-                Self::Female => "F",
-                Self::Other => "O",
-                Self::NotApplicable => "N",
-                Self::Unknown => "U",
-            }
-        )
+        match self {
+            Self::None => Ok(()),
+            Self::Male => write!(f, "M"),
+            Self::Female => write!(f, "F"),
+            Self::Other => write!(f, "O"),
+            Self::NotApplicable => write!(f, "N"),
+            Self::Unknown => write!(f, "U"),
+            Self::Unrecognized(c) => write!(f, "{}", c),
+        }
     }
 }
 
 impl FromStr for Sex {
     type Err = Error;
 
+    /// Parse the sex component case-insensitively.
+    ///
+    /// Single letters that are not defined by RFC6350 are
+    /// parsed as `Sex::Unrecognized`.
     fn from_str(s: &str) -> Result<Self> {
-        match s {
-            "" => Ok(Self::None),
-            "M" => Ok(Self::Male),
-            "F" => Ok(Self::Female),
-            "O" => Ok(Self::Other),
-            "N" => Ok(Self::NotApplicable),
-            "U" => Ok(Self::Unknown),
-            _ => Err(Error::UnknownSex(s.to_string())),
+        let mut chars = s.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return if s.is_empty() {
+                Ok(Self::None)
+            } else {
+                Err(Error::UnknownSex(snippet(s)))
+            };
+        };
+        match c.to_ascii_uppercase() {
+            'M' => Ok(Self::Male),
+            'F' => Ok(Self::Female),
+            'O' => Ok(Self::Other),
+            'N' => Ok(Self::NotApplicable),
+            'U' => Ok(Self::Unknown),
+            _ if c.is_alphabetic() => Ok(Self::Unrecognized(c)),
+            _ => Err(Error::UnknownSex(snippet(s))),
         }
     }
 }
@@ -1229,7 +1852,15 @@ macro_rules! property_impl {
             }
 
             fn parameters(&self) -> Option<&Parameters> {
-                self.parameters.as_ref()
+                self.parameters.as_deref()
+            }
+        }
+
+        impl $prop {
+            /// Set the parameters for this property.
+            pub fn with_parameters(mut self, parameters: Parameters) -> Self {
+                self.parameters = Some(Box::new(parameters));
+                self
             }
         }
     };
@@ -1248,6 +1879,9 @@ macro_rules! display_impl {
 property_impl!(AddressProperty);
 display_impl!(AddressProperty);
 
+property_impl!(NameProperty);
+display_impl!(NameProperty);
+
 property_impl!(UriProperty);
 display_impl!(UriProperty);
 
@@ -1269,13 +1903,317 @@ display_impl!(ClientPidMapProperty);
 property_impl!(GenderProperty);
 display_impl!(GenderProperty);
 
-property_impl!(ExtensionProperty);
 display_impl!(ExtensionProperty);
 
+impl Property for ExtensionProperty {
+    fn group(&self) -> Option<&String> {
+        self.group.as_ref()
+    }
+
+    fn parameters(&self) -> Option<&Parameters> {
+        self.parameters.as_deref()
+    }
+
+    // The VALUE parameter is set from the type of the value
+    // when it is missing or would not parse to the value so
+    // that the property round trips; a text value does not
+    // need a VALUE parameter.
+    fn output_parameters(&self) -> Option<Cow<'_, Parameters>> {
+        let value_type =
+            self.parameters.as_ref().and_then(|p| p.value.as_ref());
+        match value_type {
+            Some(value_type) if self.value.accepts(value_type) => {
+                self.parameters.as_deref().map(Cow::Borrowed)
+            }
+            None if matches!(self.value, AnyProperty::Text(_)) => {
+                self.parameters.as_deref().map(Cow::Borrowed)
+            }
+            _ => {
+                let mut parameters =
+                    self.parameters.as_deref().cloned().unwrap_or_default();
+                parameters.value = match self.value.value_type() {
+                    ValueType::Text => None,
+                    value_type => Some(value_type),
+                };
+                Some(Cow::Owned(parameters))
+            }
+        }
+    }
+}
+
+impl ExtensionProperty {
+    /// Set the parameters for this property.
+    pub fn with_parameters(mut self, parameters: Parameters) -> Self {
+        self.parameters = Some(Box::new(parameters));
+        self
+    }
+}
+
 // Bespoke Display implementations
 property_impl!(TextListProperty);
 property_impl!(UtcOffsetProperty);
 
+/// Declare the parsed property variants.
+macro_rules! parsed_property {
+    ($($(#[$meta:meta])* $variant:ident($prop:ty)),+ $(,)?) => {
+        /// Property parsed from a single content line.
+        ///
+        /// There is a variant for each type of property
+        /// stored by a [Vcard](crate::Vcard).
+        #[derive(Debug, Eq, PartialEq, Clone)]
+        pub enum ParsedProperty {
+            $($(#[$meta])* $variant($prop),)+
+        }
+
+        impl ParsedProperty {
+            /// The property.
+            pub fn property(&self) -> &dyn Property {
+                match self {
+                    $(Self::$variant(prop) => prop,)+
+                }
+            }
+        }
+
+        $(
+            impl From<$prop> for ParsedProperty {
+                fn from(value: $prop) -> Self {
+                    Self::$variant(value)
+                }
+            }
+        )+
+    };
+}
+
+parsed_property!(
+    /// Text property (for example FN, EMAIL or NOTE).
+    Text(TextProperty),
+    /// Text list property (ORG or CATEGORIES).
+    TextList(TextListProperty),
+    /// The N property.
+    Name(NameProperty),
+    /// The ADR property.
+    Address(AddressProperty),
+    /// URI property (for example URL or MEMBER).
+    Uri(UriProperty),
+    /// Text or URI property (for example TEL or PHOTO).
+    TextOrUri(TextOrUriProperty),
+    /// Date and time or text property (BDAY, ANNIVERSARY
+    /// or DEATHDATE).
+    DateTimeOrText(DateTimeOrTextProperty),
+    /// Timestamp property (REV or CREATED).
+    DateTime(DateTimeProperty),
+    /// The KIND property.
+    Kind(KindProperty),
+    /// The GENDER property.
+    Gender(GenderProperty),
+    /// Language property (LANG or LANGUAGE).
+    Language(LanguageProperty),
+    /// The TZ property.
+    TimeZone(TimeZoneProperty),
+    /// The CLIENTPIDMAP property.
+    ClientPidMap(ClientPidMapProperty),
+    /// Extension property.
+    Extension(ExtensionProperty),
+);
+
+impl AnyProperty {
+    /// Get the value type for the VALUE parameter.
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Self::Text(_) => ValueType::Text,
+            Self::Integer(_) => ValueType::Integer,
+            Self::Float(_) => ValueType::Float,
+            Self::Boolean(_) => ValueType::Boolean,
+            Self::Date(_) => ValueType::Date,
+            Self::DateTime(_) => ValueType::DateTime,
+            Self::Time(_) => ValueType::Time,
+            Self::DateAndOrTime(_) => ValueType::DateAndOrTime,
+            Self::Timestamp(_) => ValueType::Timestamp,
+            Self::Uri(_) => ValueType::Uri,
+            Self::UtcOffset(_) => ValueType::UtcOffset,
+            Self::Language(_) => ValueType::LanguageTag,
+        }
+    }
+
+    /// Determine if a VALUE parameter would be parsed to this
+    /// type of value.
+    fn accepts(&self, value_type: &ValueType) -> bool {
+        match (self, value_type) {
+            (
+                Self::Text(_),
+                ValueType::IanaToken(_) | ValueType::Extension(_),
+            ) => true,
+            // Truncated dates are parsed as date and or time
+            (Self::DateAndOrTime(_), ValueType::Date) => true,
+            _ => &self.value_type() == value_type,
+        }
+    }
+
+    /// Name of the value type.
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::Text(_) => "text",
+            Self::Integer(_) => "integer",
+            Self::Float(_) => "float",
+            Self::Boolean(_) => "boolean",
+            Self::Date(_) => "date",
+            Self::DateTime(_) => "date-time",
+            Self::Time(_) => "time",
+            Self::DateAndOrTime(_) => "date-and-or-time",
+            Self::Timestamp(_) => "timestamp",
+            Self::Uri(_) => "uri",
+            Self::UtcOffset(_) => "utc-offset",
+            Self::Language(_) => "language-tag",
+        }
+    }
+}
+
+impl ExtensionProperty {
+    /// Error for a value that can not be converted.
+    fn unsupported(&self) -> Error {
+        Error::UnsupportedValueType(
+            self.value.type_name().to_owned(),
+            snippet(&self.name),
+        )
+    }
+
+    /// Determine if the VALUE parameter is text.
+    fn is_text_value(&self) -> bool {
+        matches!(
+            self.parameters.as_ref().and_then(|p| p.value.as_ref()),
+            Some(ValueType::Text)
+        )
+    }
+}
+
+/// Convert the text value of an extension; group and
+/// parameters are kept.
+impl TryFrom<ExtensionProperty> for TextProperty {
+    type Error = Error;
+
+    fn try_from(mut prop: ExtensionProperty) -> Result<Self> {
+        match &mut prop.value {
+            AnyProperty::Text(value) => Ok(Self {
+                group: prop.group.take(),
+                value: std::mem::take(value),
+                parameters: prop.parameters.take(),
+            }),
+            _ => Err(prop.unsupported()),
+        }
+    }
+}
+
+/// Convert the URI value of an extension; a text value
+/// is parsed as a URI.
+impl TryFrom<ExtensionProperty> for UriProperty {
+    type Error = Error;
+
+    fn try_from(mut prop: ExtensionProperty) -> Result<Self> {
+        let value = match &prop.value {
+            AnyProperty::Uri(value) => value.clone(),
+            AnyProperty::Text(value) => value.parse()?,
+            _ => return Err(prop.unsupported()),
+        };
+        Ok(Self {
+            group: prop.group.take(),
+            value,
+            parameters: prop.parameters.take(),
+        })
+    }
+}
+
+/// Convert the text or URI value of an extension; unless
+/// the VALUE parameter is text a text value that is a valid
+/// URI is converted to a URI.
+impl TryFrom<ExtensionProperty> for TextOrUriProperty {
+    type Error = Error;
+
+    fn try_from(prop: ExtensionProperty) -> Result<Self> {
+        let is_uri = match &prop.value {
+            AnyProperty::Uri(_) => true,
+            AnyProperty::Text(value) => {
+                !prop.is_text_value() && value.parse::<Uri>().is_ok()
+            }
+            _ => return Err(prop.unsupported()),
+        };
+        if is_uri {
+            Ok(Self::Uri(prop.try_into()?))
+        } else {
+            Ok(Self::Text(prop.try_into()?))
+        }
+    }
+}
+
+/// Convert the date and or time value of an extension; a text
+/// value is parsed as a list of date and or time values.
+impl TryFrom<ExtensionProperty> for DateAndOrTimeProperty {
+    type Error = Error;
+
+    fn try_from(mut prop: ExtensionProperty) -> Result<Self> {
+        let value = match &prop.value {
+            AnyProperty::DateAndOrTime(value) => value.clone(),
+            AnyProperty::Date(value) => {
+                value.iter().cloned().map(DateAndOrTime::from).collect()
+            }
+            AnyProperty::DateTime(value) | AnyProperty::Timestamp(value) => {
+                value.iter().cloned().map(DateAndOrTime::from).collect()
+            }
+            AnyProperty::Time(value) => {
+                value.iter().cloned().map(DateAndOrTime::from).collect()
+            }
+            AnyProperty::Text(value) => parse_date_and_or_time_list(value)?,
+            _ => return Err(prop.unsupported()),
+        };
+        Ok(Self {
+            group: prop.group.take(),
+            value,
+            parameters: prop.parameters.take(),
+        })
+    }
+}
+
+/// Convert the value of an extension to a date and or time
+/// unless the VALUE parameter is text.
+impl TryFrom<ExtensionProperty> for DateTimeOrTextProperty {
+    type Error = Error;
+
+    fn try_from(prop: ExtensionProperty) -> Result<Self> {
+        if prop.is_text_value() {
+            Ok(Self::Text(prop.try_into()?))
+        } else {
+            Ok(Self::DateTime(prop.try_into()?))
+        }
+    }
+}
+
+/// Convert the text value of an extension to a gender.
+impl TryFrom<ExtensionProperty> for GenderProperty {
+    type Error = Error;
+
+    fn try_from(prop: ExtensionProperty) -> Result<Self> {
+        let mut text: TextProperty = prop.try_into()?;
+        Ok(Self {
+            value: text.value.parse()?,
+            group: text.group.take(),
+            parameters: text.parameters.take(),
+        })
+    }
+}
+
+/// Convert the text value of an extension to a kind.
+impl TryFrom<ExtensionProperty> for KindProperty {
+    type Error = Error;
+
+    fn try_from(prop: ExtensionProperty) -> Result<Self> {
+        let mut text: TextProperty = prop.try_into()?;
+        Ok(Self {
+            value: text.value.parse()?,
+            group: text.group.take(),
+            parameters: text.parameters.take(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
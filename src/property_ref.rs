@@ -0,0 +1,443 @@
+//! Generic traversal of the properties of a vCard.
+use std::fmt;
+
+use crate::{name::*, property::Property};
+
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+/// Kind of property stored by a vCard.
+///
+/// There is one variant for each property field of a
+/// [Vcard](crate::Vcard); all private extensions (`X-`)
+/// share the `Extension` kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropertyKind {
+    /// The SOURCE property.
+    Source,
+    /// The KIND property.
+    Kind,
+    /// The XML property.
+    Xml,
+    /// The FN property.
+    FormattedName,
+    /// The N property.
+    Name,
+    /// The NICKNAME property.
+    Nickname,
+    /// The PHOTO property.
+    Photo,
+    /// The BDAY property.
+    Bday,
+    /// The ANNIVERSARY property.
+    Anniversary,
+    /// The BIRTHPLACE property.
+    Birthplace,
+    /// The DEATHPLACE property.
+    Deathplace,
+    /// The DEATHDATE property.
+    Deathdate,
+    /// The GENDER property.
+    Gender,
+    /// The PRONOUNS property.
+    Pronouns,
+    /// The URL property.
+    Url,
+    /// The ADR property.
+    Address,
+    /// The TITLE property.
+    Title,
+    /// The ROLE property.
+    Role,
+    /// The LOGO property.
+    Logo,
+    /// The ORG property.
+    Org,
+    /// The MEMBER property.
+    Member,
+    /// The RELATED property.
+    Related,
+    /// The EXPERTISE property.
+    Expertise,
+    /// The HOBBY property.
+    Hobby,
+    /// The INTEREST property.
+    Interest,
+    /// The ORG-DIRECTORY property.
+    OrgDirectory,
+    /// The TEL property.
+    Tel,
+    /// The EMAIL property.
+    Email,
+    /// The IMPP property.
+    Impp,
+    /// The SOCIALPROFILE property.
+    SocialProfile,
+    /// The LANG property.
+    Lang,
+    /// The LANGUAGE property.
+    Language,
+    /// The TZ property.
+    TimeZone,
+    /// The GEO property.
+    Geo,
+    /// The CATEGORIES property.
+    Categories,
+    /// The NOTE property.
+    Note,
+    /// The PRODID property.
+    ProdId,
+    /// The REV property.
+    Rev,
+    /// The CREATED property.
+    Created,
+    /// The SOUND property.
+    Sound,
+    /// The UID property.
+    Uid,
+    /// The CLIENTPIDMAP property.
+    ClientPidMap,
+    /// The KEY property.
+    Key,
+    /// The FBURL property.
+    FbUrl,
+    /// The CALADRURI property.
+    CalAdrUri,
+    /// The CALURI property.
+    CalUri,
+    /// Private property extensions (`X-`).
+    Extension,
+}
+
+impl PropertyKind {
+    /// All the property kinds in the order they are encoded.
+    pub const ALL: [PropertyKind; 47] = [
+        PropertyKind::Source,
+        PropertyKind::Kind,
+        PropertyKind::Xml,
+        PropertyKind::FormattedName,
+        PropertyKind::Name,
+        PropertyKind::Nickname,
+        PropertyKind::Photo,
+        PropertyKind::Bday,
+        PropertyKind::Anniversary,
+        PropertyKind::Birthplace,
+        PropertyKind::Deathplace,
+        PropertyKind::Deathdate,
+        PropertyKind::Gender,
+        PropertyKind::Pronouns,
+        PropertyKind::Url,
+        PropertyKind::Address,
+        PropertyKind::Title,
+        PropertyKind::Role,
+        PropertyKind::Logo,
+        PropertyKind::Org,
+        PropertyKind::Member,
+        PropertyKind::Related,
+        PropertyKind::Expertise,
+        PropertyKind::Hobby,
+        PropertyKind::Interest,
+        PropertyKind::OrgDirectory,
+        PropertyKind::Tel,
+        PropertyKind::Email,
+        PropertyKind::Impp,
+        PropertyKind::SocialProfile,
+        PropertyKind::Lang,
+        PropertyKind::Language,
+        PropertyKind::TimeZone,
+        PropertyKind::Geo,
+        PropertyKind::Categories,
+        PropertyKind::Note,
+        PropertyKind::ProdId,
+        PropertyKind::Rev,
+        PropertyKind::Created,
+        PropertyKind::Sound,
+        PropertyKind::Uid,
+        PropertyKind::ClientPidMap,
+        PropertyKind::Key,
+        PropertyKind::FbUrl,
+        PropertyKind::CalAdrUri,
+        PropertyKind::CalUri,
+        PropertyKind::Extension,
+    ];
+
+    /// Get the property name.
+    ///
+    /// Extensions do not have a fixed name so `None`
+    /// is returned for the `Extension` kind.
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self {
+            PropertyKind::Source => SOURCE,
+            PropertyKind::Kind => KIND,
+            PropertyKind::Xml => XML,
+            PropertyKind::FormattedName => FN,
+            PropertyKind::Name => N,
+            PropertyKind::Nickname => NICKNAME,
+            PropertyKind::Photo => PHOTO,
+            PropertyKind::Bday => BDAY,
+            PropertyKind::Anniversary => ANNIVERSARY,
+            PropertyKind::Birthplace => BIRTHPLACE,
+            PropertyKind::Deathplace => DEATHPLACE,
+            PropertyKind::Deathdate => DEATHDATE,
+            PropertyKind::Gender => GENDER,
+            PropertyKind::Pronouns => PRONOUNS,
+            PropertyKind::Url => URL,
+            PropertyKind::Address => ADR,
+            PropertyKind::Title => TITLE,
+            PropertyKind::Role => ROLE,
+            PropertyKind::Logo => LOGO,
+            PropertyKind::Org => ORG,
+            PropertyKind::Member => MEMBER,
+            PropertyKind::Related => RELATED,
+            PropertyKind::Expertise => EXPERTISE,
+            PropertyKind::Hobby => HOBBY,
+            PropertyKind::Interest => INTEREST,
+            PropertyKind::OrgDirectory => ORG_DIRECTORY,
+            PropertyKind::Tel => TEL,
+            PropertyKind::Email => EMAIL,
+            PropertyKind::Impp => IMPP,
+            PropertyKind::SocialProfile => SOCIALPROFILE,
+            PropertyKind::Lang => LANG,
+            PropertyKind::Language => LANGUAGE,
+            PropertyKind::TimeZone => TZ,
+            PropertyKind::Geo => GEO,
+            PropertyKind::Categories => CATEGORIES,
+            PropertyKind::Note => NOTE,
+            PropertyKind::ProdId => PRODID,
+            PropertyKind::Rev => REV,
+            PropertyKind::Created => CREATED,
+            PropertyKind::Sound => SOUND,
+            PropertyKind::Uid => UID,
+            PropertyKind::ClientPidMap => CLIENTPIDMAP,
+            PropertyKind::Key => KEY,
+            PropertyKind::FbUrl => FBURL,
+            PropertyKind::CalAdrUri => CALADRURI,
+            PropertyKind::CalUri => CALURI,
+            PropertyKind::Extension => return None,
+        })
+    }
+
+    /// Find the kind for a property name without a group.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_uppercase();
+        if name.starts_with("X-") {
+            return Some(PropertyKind::Extension);
+        }
+        PropertyKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == Some(&name[..]))
+    }
+}
+
+/// Identifies a property of a vCard by kind and index.
+///
+/// Handles are obtained from [PropertyRef::handle] and are
+/// invalidated when a property of the same kind is removed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PropertyHandle {
+    /// Kind of the property.
+    pub kind: PropertyKind,
+    /// Index of the property in the list of properties of this kind.
+    pub index: usize,
+}
+
+impl PropertyHandle {
+    /// Create a property handle.
+    pub fn new(kind: PropertyKind, index: usize) -> Self {
+        Self { kind, index }
+    }
+}
+
+/// Reference to a property of a vCard.
+#[derive(Clone, Copy)]
+pub struct PropertyRef<'a> {
+    pub(crate) kind: PropertyKind,
+    pub(crate) index: usize,
+    pub(crate) name: &'a str,
+    pub(crate) property: &'a dyn Property,
+}
+
+impl<'a> PropertyRef<'a> {
+    /// Kind of the property.
+    pub fn kind(&self) -> PropertyKind {
+        self.kind
+    }
+
+    /// Index of the property in the list of properties of this kind.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Handle for editing the property.
+    pub fn handle(&self) -> PropertyHandle {
+        PropertyHandle::new(self.kind, self.index)
+    }
+
+    /// Property name; for extensions this is the
+    /// name of the extension.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The property.
+    pub fn property(&self) -> &'a dyn Property {
+        self.property
+    }
+}
+
+impl fmt::Debug for PropertyRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PropertyRef")
+            .field("kind", &self.kind)
+            .field("index", &self.index)
+            .field("name", &self.name)
+            .field("value", &self.property.to_string())
+            .finish()
+    }
+}
+
+impl fmt::Display for PropertyRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            crate::vcard::content_line(self.property, self.name)
+        )
+    }
+}
+
+/// Select the preferred property from a group of alternative
+/// representations.
+///
+/// When a language is given properties with a matching LANGUAGE
+/// parameter are considered first; the language matches a tag
+/// that is equal to it or begins with it followed by a hyphen,
+/// ignoring case. The property with the lowest PREF is selected
+/// and a property without a PREF is the least preferred.
+///
+/// ```
+/// use vcard4::{parse, preferred_in_group, PropertyKind};
+/// let input = "BEGIN:VCARD\nVERSION:4.0\nFN;ALTID=1;LANGUAGE=en:Taro Yamada\nFN;ALTID=1;LANGUAGE=ja:山田太郎\nEND:VCARD";
+/// let card = parse(input).unwrap().remove(0);
+/// let groups = card.altid_groups(PropertyKind::FormattedName);
+/// assert_eq!(1, groups.len());
+/// let name = preferred_in_group(&groups[0], Some("ja")).unwrap();
+/// assert_eq!("山田太郎", name.property().to_string());
+/// ```
+pub fn preferred_in_group<'a>(
+    group: &[PropertyRef<'a>],
+    language: Option<&str>,
+) -> Option<PropertyRef<'a>> {
+    let matches_language = |prop: &PropertyRef<'_>| {
+        let (Some(range), Some(tag)) = (
+            language,
+            prop.property()
+                .parameters()
+                .and_then(|params| params.language.as_ref())
+                .map(|tag| tag.to_string()),
+        ) else {
+            return false;
+        };
+        tag.eq_ignore_ascii_case(range)
+            || tag.get(..range.len() + 1).is_some_and(|prefix| {
+                prefix.ends_with('-')
+                    && prefix[..range.len()].eq_ignore_ascii_case(range)
+            })
+    };
+    let pref = |prop: &PropertyRef<'_>| {
+        prop.property()
+            .parameters()
+            .and_then(|params| params.pref)
+            .unwrap_or(u8::MAX)
+    };
+
+    let candidates: Vec<PropertyRef<'a>> =
+        group.iter().copied().filter(matches_language).collect();
+    let candidates = if candidates.is_empty() {
+        group
+    } else {
+        &candidates[..]
+    };
+    // The first property wins when preferences are equal
+    candidates.iter().copied().min_by_key(pref)
+}
+
+/// Order that properties appeared in the source of a parsed vCard.
+///
+/// Not considered when comparing vCards so that a parsed vCard
+/// is equal to the same vCard encoded and parsed again.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SourceOrder(pub(crate) Vec<PropertyKind>);
+
+impl PartialEq for SourceOrder {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for SourceOrder {}
+
+/// Content lines of a vCard parsed with the
+/// [preserve_source](crate::ParseOptions::preserve_source) option.
+///
+/// Not considered when comparing vCards.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SourceLines(pub(crate) Option<Box<SourceText>>);
+
+impl PartialEq for SourceLines {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for SourceLines {}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for SourceLines {
+    fn zeroize(&mut self) {
+        if let Some(text) = self.0.as_mut() {
+            text.zeroize();
+        }
+    }
+}
+
+/// Source text of the lines of a vCard.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "zeroize", derive(Zeroize))]
+pub(crate) struct SourceText {
+    /// The BEGIN and VERSION lines with their line endings.
+    pub(crate) head: String,
+    /// The END line without a line ending.
+    pub(crate) tail: String,
+    /// Line ending of the BEGIN line.
+    pub(crate) line_ending: String,
+    /// The content lines of the properties.
+    pub(crate) lines: Vec<SourceLine>,
+}
+
+impl SourceText {
+    /// Find an unused content line for a property of a kind
+    /// that was encoded the same way when parsed.
+    pub(crate) fn find(
+        &self,
+        kind: PropertyKind,
+        encoded: &str,
+        used: &[bool],
+    ) -> Option<usize> {
+        self.lines.iter().enumerate().position(|(index, line)| {
+            !used[index] && line.kind == kind && line.encoded == encoded
+        })
+    }
+}
+
+/// Content line of a property in the source.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "zeroize", derive(Zeroize))]
+pub(crate) struct SourceLine {
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    pub(crate) kind: PropertyKind,
+    /// The property encoded without folding when parsed.
+    pub(crate) encoded: String,
+    /// The content line including any folds and the line ending.
+    pub(crate) raw: String,
+}
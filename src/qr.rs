@@ -0,0 +1,367 @@
+//! Payloads for sharing vCards using QR codes.
+//!
+//! A payload is either a vCard trimmed to fit a byte budget or
+//! the compact [MECARD](https://en.wikipedia.org/wiki/MeCard_(QR_code))
+//! format; rendering the QR code is left to the caller.
+use std::fmt::Write;
+
+use crate::{
+    error::snippet,
+    escape::split_unescaped,
+    helper::{format_date, parse_date},
+    property::{
+        DateAndOrTime, DateAndOrTimeProperty, DateTimeOrTextProperty,
+        DeliveryAddress, StructuredName, TextOrUriProperty,
+    },
+    Error, Result, Uri, Vcard,
+};
+
+const MECARD: &str = "MECARD:";
+
+/// Notes longer than this number of bytes are dropped
+/// before shorter notes when trimming a vCard.
+const LONG_NOTE: usize = 128;
+
+/// Format for a QR code payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrFormat {
+    /// Encoded vCard.
+    Vcard {
+        /// Maximum size of the payload in bytes; when set
+        /// properties are dropped until the encoded vCard fits.
+        max_bytes: Option<usize>,
+    },
+    /// MECARD format.
+    MeCard,
+}
+
+/// Payload for a QR code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrPayload {
+    /// The encoded payload.
+    pub payload: String,
+    /// Names of the properties dropped to fit the byte budget
+    /// in the order they were dropped.
+    pub dropped: Vec<String>,
+}
+
+impl QrPayload {
+    /// Create a QR code payload for a vCard.
+    pub fn new(card: &Vcard, format: QrFormat) -> Result<Self> {
+        match format {
+            QrFormat::Vcard { max_bytes } => trim_vcard(card, max_bytes),
+            QrFormat::MeCard => Ok(Self {
+                payload: to_mecard(card),
+                dropped: Vec::new(),
+            }),
+        }
+    }
+}
+
+/// Properties dropped to fit a vCard in a byte budget,
+/// in priority order.
+#[derive(Debug, Clone, Copy)]
+enum Trim {
+    Photo,
+    Sound,
+    Logo,
+    LongNotes,
+    Extensions,
+    Key,
+    Xml,
+    Notes,
+}
+
+impl Trim {
+    const ORDER: [Trim; 8] = [
+        Trim::Photo,
+        Trim::Sound,
+        Trim::Logo,
+        Trim::LongNotes,
+        Trim::Extensions,
+        Trim::Key,
+        Trim::Xml,
+        Trim::Notes,
+    ];
+
+    /// Drop the properties returning the names of the
+    /// properties that were removed.
+    fn apply(&self, card: &mut Vcard) -> Vec<String> {
+        let removed = match self {
+            Trim::Photo => std::mem::take(&mut card.photo).len(),
+            Trim::Sound => std::mem::take(&mut card.sound).len(),
+            Trim::Logo => std::mem::take(&mut card.logo).len(),
+            Trim::LongNotes => {
+                let length = card.note.len();
+                card.note.retain(|note| note.value.len() <= LONG_NOTE);
+                length - card.note.len()
+            }
+            Trim::Extensions => {
+                let mut names: Vec<String> = Vec::new();
+                for ext in std::mem::take(&mut card.extensions) {
+                    let name = ext.name.to_uppercase();
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+                return names;
+            }
+            Trim::Key => std::mem::take(&mut card.key).len(),
+            Trim::Xml => std::mem::take(&mut card.xml).len(),
+            Trim::Notes => std::mem::take(&mut card.note).len(),
+        };
+        if removed > 0 {
+            vec![self.name().to_owned()]
+        } else {
+            vec![]
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Trim::Photo => "PHOTO",
+            Trim::Sound => "SOUND",
+            Trim::Logo => "LOGO",
+            Trim::LongNotes | Trim::Notes => "NOTE",
+            Trim::Extensions => "X-",
+            Trim::Key => "KEY",
+            Trim::Xml => "XML",
+        }
+    }
+}
+
+fn trim_vcard(card: &Vcard, max_bytes: Option<usize>) -> Result<QrPayload> {
+    let mut payload = card.to_string();
+    let mut dropped = Vec::new();
+    let Some(max_bytes) = max_bytes else {
+        return Ok(QrPayload { payload, dropped });
+    };
+
+    let mut card = card.clone();
+    for trim in Trim::ORDER {
+        if payload.len() <= max_bytes {
+            break;
+        }
+        let names = trim.apply(&mut card);
+        if !names.is_empty() {
+            for name in names {
+                if !dropped.contains(&name) {
+                    dropped.push(name);
+                }
+            }
+            payload = card.to_string();
+        }
+    }
+
+    if payload.len() > max_bytes {
+        return Err(Error::QrPayloadTooLarge(payload.len(), max_bytes));
+    }
+    Ok(QrPayload { payload, dropped })
+}
+
+/// Escape a MECARD value.
+fn escape_mecard(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Remove the escape sequences from a MECARD value.
+fn unescape_mecard(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(c) = chars.next() {
+                unescaped.push(c);
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+fn push_field(payload: &mut String, name: &str, value: &str) {
+    // Writing to a string is infallible
+    let _ = write!(payload, "{}:{};", name, value);
+}
+
+/// Encode a vCard as a MECARD.
+fn to_mecard(card: &Vcard) -> String {
+    let mut payload = String::from(MECARD);
+
+    let name = card.name.as_ref().and_then(|name| {
+        let family = name.value.family_names.join(" ");
+        let given = name.value.given_names.join(" ");
+        match (family.is_empty(), given.is_empty()) {
+            (true, true) => None,
+            (_, true) => Some(escape_mecard(&family)),
+            _ => Some(format!(
+                "{},{}",
+                escape_mecard(&family),
+                escape_mecard(&given)
+            )),
+        }
+    });
+    let name = name.or_else(|| {
+        card.formatted_name
+            .first()
+            .map(|name| escape_mecard(&name.value))
+    });
+    if let Some(name) = name {
+        push_field(&mut payload, "N", &name);
+    }
+
+    for tel in &card.tel {
+        let value = match tel {
+            TextOrUriProperty::Text(tel) => tel.value.clone(),
+            TextOrUriProperty::Uri(tel) => {
+                let value = tel.value.to_string();
+                match value.get(0..4) {
+                    Some(scheme) if scheme.eq_ignore_ascii_case("tel:") => {
+                        value[4..].to_owned()
+                    }
+                    _ => value,
+                }
+            }
+        };
+        push_field(&mut payload, "TEL", &escape_mecard(&value));
+    }
+
+    for email in &card.email {
+        push_field(&mut payload, "EMAIL", &escape_mecard(&email.value));
+    }
+
+    for address in &card.address {
+        let value = &address.value;
+        let components = value
+            .components()
+            .iter()
+            .map(|c| escape_mecard(&c.join(" ")))
+            .collect::<Vec<_>>();
+        push_field(&mut payload, "ADR", &components.join(","));
+    }
+
+    for url in &card.url {
+        push_field(
+            &mut payload,
+            "URL",
+            &escape_mecard(&url.value.to_string()),
+        );
+    }
+
+    for note in &card.note {
+        push_field(&mut payload, "NOTE", &escape_mecard(&note.value));
+    }
+
+    if let Some(DateTimeOrTextProperty::DateTime(bday)) = &card.bday {
+        let date = bday.value.iter().find_map(|value| match value {
+            DateAndOrTime::Date(date) => Some(date.clone()),
+            DateAndOrTime::DateTime(date_time) => {
+                Some(date_time.as_ref().date().into())
+            }
+            // Dates without a year cannot be represented
+            DateAndOrTime::Time(_) | DateAndOrTime::PartialDate(_) => None,
+        });
+        if let Some(date) = date {
+            if let Ok(date) = format_date(&date) {
+                push_field(&mut payload, "BDAY", &date);
+            }
+        }
+    }
+
+    payload.push(';');
+    payload
+}
+
+/// Parse a MECARD into a vCard.
+///
+/// Fields that do not map to a vCard property are ignored.
+pub fn parse_mecard(input: &str) -> Result<Vcard> {
+    let input = input.trim();
+    let body = match input.get(0..MECARD.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(MECARD) => {
+            &input[MECARD.len()..]
+        }
+        _ => return Err(Error::InvalidMeCard(snippet(input))),
+    };
+
+    let mut card: Vcard = Default::default();
+    for field in split_unescaped(body, ';') {
+        if field.is_empty() {
+            continue;
+        }
+        let (name, value) = field
+            .split_once(':')
+            .ok_or_else(|| Error::InvalidMeCard(snippet(field)))?;
+
+        match &name.to_uppercase()[..] {
+            "N" => {
+                let parts = split_unescaped(value, ',')
+                    .into_iter()
+                    .map(unescape_mecard)
+                    .collect::<Vec<_>>();
+                let family = parts.first().cloned().unwrap_or_default();
+                let given = parts.get(1).cloned().unwrap_or_default();
+                let formatted_name = if given.is_empty() {
+                    family.clone()
+                } else {
+                    format!("{} {}", given, family)
+                };
+                card.formatted_name.push(formatted_name.into());
+                if !given.is_empty() {
+                    let mut name = StructuredName::default();
+                    if !family.is_empty() {
+                        name.family_names.push(family);
+                    }
+                    name.given_names.push(given);
+                    card.name = Some(name.into());
+                }
+            }
+            "TEL" => card.tel.push(unescape_mecard(value).into()),
+            "EMAIL" => card.email.push(unescape_mecard(value).into()),
+            "ADR" => {
+                let mut it =
+                    split_unescaped(value, ',').into_iter().map(|value| {
+                        let value = unescape_mecard(value);
+                        if !value.is_empty() {
+                            vec![value]
+                        } else {
+                            Vec::new()
+                        }
+                    });
+                let address = DeliveryAddress {
+                    po_box: it.next().unwrap_or_default(),
+                    extended_address: it.next().unwrap_or_default(),
+                    street_address: it.next().unwrap_or_default(),
+                    locality: it.next().unwrap_or_default(),
+                    region: it.next().unwrap_or_default(),
+                    postal_code: it.next().unwrap_or_default(),
+                    country_name: it.next().unwrap_or_default(),
+                };
+                card.address.push(address.into());
+            }
+            "URL" => {
+                let url: Uri = unescape_mecard(value).parse()?;
+                card.url.push(url.into());
+            }
+            "NOTE" => card.note.push(unescape_mecard(value).into()),
+            "BDAY" => {
+                let date = parse_date(value)?;
+                card.bday = Some(DateTimeOrTextProperty::DateTime(
+                    DateAndOrTimeProperty::from(date),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    card.validate()?;
+    Ok(card)
+}
@@ -0,0 +1,57 @@
+//! Redacted view of a vCard.
+//!
+//! The [Debug](fmt::Debug) implementations of the vCard types
+//! include the property values; use [Vcard::redacted] when a
+//! vCard may be written to logs or crash reports.
+use std::fmt;
+
+use crate::{property_ref::PropertyRef, Vcard};
+
+/// View of a vCard that hides the property values.
+///
+/// The debug representation includes the property names,
+/// groups, the names of any parameters and the length of
+/// each value in characters.
+#[derive(Clone, Copy)]
+pub struct RedactedVcard<'a> {
+    card: &'a Vcard,
+}
+
+impl<'a> RedactedVcard<'a> {
+    pub(crate) fn new(card: &'a Vcard) -> Self {
+        Self { card }
+    }
+}
+
+impl fmt::Debug for RedactedVcard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vcard")
+            .field(
+                "properties",
+                &self
+                    .card
+                    .properties_in_source_order()
+                    .map(RedactedProperty)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Property with the value and parameter values hidden.
+struct RedactedProperty<'a>(PropertyRef<'a>);
+
+impl fmt::Debug for RedactedProperty<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prop = self.0.property();
+        let mut s = f.debug_struct(self.0.name());
+        if let Some(group) = prop.group() {
+            s.field("group", group);
+        }
+        if let Some(params) = prop.parameters() {
+            s.field("parameters", &params.names());
+        }
+        s.field("length", &prop.to_string().chars().count())
+            .finish()
+    }
+}
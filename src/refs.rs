@@ -0,0 +1,216 @@
+//! Integrity checks for references between vCards.
+//!
+//! The MEMBER, RELATED and SOURCE properties may refer to
+//! another vCard by its UID; when the UIDs for a collection
+//! of vCards are regenerated (for example, during an import) these
+//! references must be rewritten so they do not dangle.
+//!
+//! Values that use the `urn:uuid:` scheme are compared
+//! case-insensitively as required by
+//! [RFC4122](https://www.rfc-editor.org/rfc/rfc4122#section-3),
+//! all other values must match exactly.
+use std::collections::HashMap;
+
+use crate::{property::TextOrUriProperty, Uri, Vcard};
+
+const URN_UUID: &str = "urn:uuid:";
+
+/// Kind of property that holds a reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    /// The MEMBER property.
+    Member,
+    /// The RELATED property.
+    Related,
+    /// The SOURCE property.
+    Source,
+}
+
+/// Location of a reference in a list of vCards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// Index of the vCard that contains the reference.
+    pub card: usize,
+    /// Kind of property that contains the reference.
+    pub kind: ReferenceKind,
+    /// Index of the property in the list of properties of this kind.
+    pub index: usize,
+}
+
+/// Reference from one vCard to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// Location of the reference.
+    pub location: Location,
+    /// The referenced URI.
+    pub uri: String,
+    /// Index of the vCard with a matching UID; `None` when
+    /// the reference does not resolve to a vCard in the list.
+    pub target: Option<usize>,
+}
+
+/// Reference that was rewritten to a new UID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rewrite {
+    /// Location of the reference.
+    pub location: Location,
+    /// The previous value.
+    pub from: String,
+    /// The new value.
+    pub to: String,
+}
+
+/// Report of the references changed by [rewrite_uids].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RewriteReport {
+    /// References that were rewritten.
+    pub rewritten: Vec<Rewrite>,
+    /// References that matched an old UID but were not changed
+    /// because the new UID is not a valid URI.
+    pub invalid: Vec<Rewrite>,
+}
+
+impl RewriteReport {
+    /// Determine if no references were changed.
+    pub fn is_empty(&self) -> bool {
+        self.rewritten.is_empty()
+    }
+}
+
+/// Find all the MEMBER, RELATED and SOURCE references
+/// in a list of vCards.
+///
+/// RELATED properties with a text value are ignored.
+pub fn find_uid_references(cards: &[Vcard]) -> Vec<Reference> {
+    let uids: HashMap<String, usize> = cards
+        .iter()
+        .enumerate()
+        .filter_map(|(index, card)| {
+            card.uid
+                .as_ref()
+                .map(|uid| (normalize(&uid_value(uid)), index))
+        })
+        .collect();
+
+    let mut references = Vec::new();
+    for (card_index, card) in cards.iter().enumerate() {
+        for (location, uri) in uri_references(card_index, card) {
+            let uri = uri.to_string();
+            let target = uids.get(&normalize(&uri)).copied();
+            references.push(Reference {
+                location,
+                uri,
+                target,
+            });
+        }
+    }
+    references
+}
+
+/// Rewrite MEMBER, RELATED and SOURCE references using a mapping
+/// from old UIDs to new UIDs.
+///
+/// References that do not match an old UID are left untouched.
+pub fn rewrite_uids(
+    cards: &mut [Vcard],
+    mapping: &HashMap<String, String>,
+) -> RewriteReport {
+    let mapping: HashMap<String, &String> = mapping
+        .iter()
+        .map(|(old, new)| (normalize(old), new))
+        .collect();
+
+    let mut report = RewriteReport::default();
+    for (card_index, card) in cards.iter_mut().enumerate() {
+        for (location, uri) in uri_references_mut(card_index, card) {
+            let from = uri.to_string();
+            if let Some(to) = mapping.get(&normalize(&from)) {
+                let rewrite = Rewrite {
+                    location,
+                    from,
+                    to: to.to_string(),
+                };
+                match to.parse::<Uri>() {
+                    Ok(value) => {
+                        *uri = value;
+                        report.rewritten.push(rewrite);
+                    }
+                    Err(_) => report.invalid.push(rewrite),
+                }
+            }
+        }
+    }
+    report
+}
+
+fn uid_value(uid: &TextOrUriProperty) -> String {
+    match uid {
+        TextOrUriProperty::Text(val) => val.value.clone(),
+        TextOrUriProperty::Uri(val) => val.value.to_string(),
+    }
+}
+
+/// Normalize a UID so it may be compared.
+fn normalize(value: &str) -> String {
+    match value.get(0..URN_UUID.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(URN_UUID) => {
+            value.to_ascii_lowercase()
+        }
+        _ => value.to_owned(),
+    }
+}
+
+fn uri_references(
+    card_index: usize,
+    card: &Vcard,
+) -> impl Iterator<Item = (Location, &Uri)> {
+    let location = move |kind, index| Location {
+        card: card_index,
+        kind,
+        index,
+    };
+    let member = card.member.iter().enumerate().map(move |(i, prop)| {
+        (location(ReferenceKind::Member, i), &prop.value)
+    });
+    let related = card.related.iter().enumerate().filter_map(
+        move |(i, prop)| match prop {
+            TextOrUriProperty::Uri(prop) => {
+                Some((location(ReferenceKind::Related, i), &prop.value))
+            }
+            TextOrUriProperty::Text(_) => None,
+        },
+    );
+    let source = card.source.iter().enumerate().map(move |(i, prop)| {
+        (location(ReferenceKind::Source, i), &prop.value)
+    });
+    member.chain(related).chain(source)
+}
+
+fn uri_references_mut(
+    card_index: usize,
+    card: &mut Vcard,
+) -> impl Iterator<Item = (Location, &mut Uri)> {
+    let location = move |kind, index| Location {
+        card: card_index,
+        kind,
+        index,
+    };
+    let member = card.member.iter_mut().enumerate().map(move |(i, prop)| {
+        (location(ReferenceKind::Member, i), &mut prop.value)
+    });
+    let related =
+        card.related
+            .iter_mut()
+            .enumerate()
+            .filter_map(move |(i, prop)| match prop {
+                TextOrUriProperty::Uri(prop) => Some((
+                    location(ReferenceKind::Related, i),
+                    &mut prop.value,
+                )),
+                TextOrUriProperty::Text(_) => None,
+            });
+    let source = card.source.iter_mut().enumerate().map(move |(i, prop)| {
+        (location(ReferenceKind::Source, i), &mut prop.value)
+    });
+    member.chain(related).chain(source)
+}
@@ -63,6 +63,86 @@ pub(crate) mod media_type {
     }
 }
 
+/// Multiple values of a structured component; a single
+/// value is serialized as a string by human readable formats,
+/// other formats always use a sequence.
+pub(crate) mod component_values {
+    use serde::{
+        de::{Deserializer, Error, SeqAccess, Visitor},
+        ser::Serializer,
+    };
+    use std::fmt;
+
+    pub fn serialize<S>(
+        source: &[String],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if let (true, [value]) = (serializer.is_human_readable(), source) {
+            serializer.serialize_str(value)
+        } else {
+            serializer.collect_seq(source)
+        }
+    }
+
+    struct ComponentVisitor;
+
+    impl<'de> Visitor<'de> for ComponentVisitor {
+        type Value = Vec<String>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a string or a list of strings")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(vec![v.to_owned()])
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(Vec::new())
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(Vec::new())
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut values = Vec::new();
+            while let Some(value) = seq.next_element()? {
+                values.push(value);
+            }
+            Ok(values)
+        }
+    }
+
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<Vec<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ComponentVisitor)
+        } else {
+            deserializer.deserialize_seq(ComponentVisitor)
+        }
+    }
+}
+
 #[cfg(all(feature = "serde", feature = "mime"))]
 #[test]
 fn serde_media_type() -> anyhow::Result<()> {
@@ -79,3 +159,24 @@ fn serde_media_type() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_component_values() -> anyhow::Result<()> {
+    use crate::property::DeliveryAddress;
+
+    let mut address = DeliveryAddress::default();
+    address.street_address.push("123 Main Street".to_owned());
+    let encoded = serde_json::to_string(&address)?;
+    assert_eq!(r#"{"streetAddress":"123 Main Street"}"#, encoded);
+    assert_eq!(address, serde_json::from_str(&encoded)?);
+
+    address.street_address.push("Suite 100".to_owned());
+    let encoded = serde_json::to_string(&address)?;
+    assert_eq!(
+        r#"{"streetAddress":["123 Main Street","Suite 100"]}"#,
+        encoded
+    );
+    assert_eq!(address, serde_json::from_str(&encoded)?);
+    Ok(())
+}
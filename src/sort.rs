@@ -0,0 +1,84 @@
+//! Sort vCards by name.
+use crate::{normalize::fold_text, Vcard};
+
+/// Key used to sort vCards by name.
+///
+/// The key is a list of case folded components which are compared
+/// in order; see [Vcard::sort_key] for how the components are
+/// chosen. Text is compared by code point; locale specific
+/// collation is not applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortKey(Vec<String>);
+
+impl SortKey {
+    /// The case folded components of the key.
+    pub fn components(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl From<&Vcard> for SortKey {
+    fn from(card: &Vcard) -> Self {
+        if let Some(name) = &card.name {
+            let sort_as = name
+                .parameters
+                .as_ref()
+                .and_then(|params| params.sort_as.as_ref())
+                .filter(|values| values.iter().any(|v| !v.is_empty()));
+            if let Some(sort_as) = sort_as {
+                return components(sort_as.iter());
+            }
+
+            let value = &name.value;
+            let key = components(
+                [
+                    &value.family_names,
+                    &value.given_names,
+                    &value.additional_names,
+                ]
+                .into_iter()
+                .map(|names| names.join(" ")),
+            );
+            if !key.0.is_empty() {
+                return key;
+            }
+        }
+
+        components(card.formatted_name.first().map(|name| &name.value))
+    }
+}
+
+/// Create a key from the non-empty components.
+fn components<T: AsRef<str>>(values: impl IntoIterator<Item = T>) -> SortKey {
+    SortKey(
+        values
+            .into_iter()
+            .map(|value| fold_text(value.as_ref()))
+            .filter(|value| !value.is_empty())
+            .collect(),
+    )
+}
+
+/// Sort vCards by name.
+///
+/// The sort is stable so vCards with the same [SortKey] keep
+/// their relative order.
+///
+/// ```
+/// use vcard4::{sort, VcardBuilder};
+/// let mut cards = vec![
+///     VcardBuilder::new("Zoe Adams".to_owned()).finish(),
+///     VcardBuilder::new("Jane Doe".to_owned())
+///         .name(["Doe".to_owned(), "Jane".to_owned(),
+///             String::new(), String::new(), String::new()])
+///         .finish(),
+///     VcardBuilder::new("alice".to_owned()).finish(),
+/// ];
+/// sort(&mut cards);
+/// assert_eq!("alice", cards[0].formatted_name[0].value);
+/// assert_eq!("Jane Doe", cards[1].formatted_name[0].value);
+/// assert_eq!("Zoe Adams", cards[2].formatted_name[0].value);
+/// ```
+pub fn sort(cards: &mut [Vcard]) {
+    cards.sort_by_cached_key(|card| card.sort_key());
+}
@@ -0,0 +1,248 @@
+//! Telephone number URIs.
+//!
+//! The `tel:` scheme is defined in
+//! [RFC3966](https://www.rfc-editor.org/rfc/rfc3966) and is the
+//! recommended value type for the TEL property.
+//!
+//! ```
+//! use vcard4::tel::TelUri;
+//! let tel: TelUri = "tel:+1-555-555-5555;ext=5555".parse().unwrap();
+//! assert!(tel.is_global());
+//! assert_eq!("+1-555-555-5555", tel.number);
+//! assert_eq!(Some("5555"), tel.extension.as_deref());
+//! ```
+use std::{fmt, str::FromStr};
+
+use crate::{error::snippet, Error, Result, Uri};
+
+const SCHEME: &str = "tel:";
+const EXT: &str = "ext";
+const ISUB: &str = "isub";
+const PHONE_CONTEXT: &str = "phone-context";
+
+/// Telephone number URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TelUri {
+    /// The telephone number including any visual separators;
+    /// global numbers start with a plus sign.
+    pub number: String,
+    /// The extension.
+    pub extension: Option<String>,
+    /// The ISDN subaddress.
+    pub isdn_subaddress: Option<String>,
+    /// The context for a local number; either a global number
+    /// or a domain name.
+    pub phone_context: Option<String>,
+    /// Other parameters in the order they appeared.
+    pub parameters: Vec<(String, Option<String>)>,
+}
+
+impl TelUri {
+    /// Create a telephone number URI.
+    ///
+    /// Local numbers (that do not start with a plus sign)
+    /// must also have a phone context.
+    pub fn new(number: String) -> Result<Self> {
+        let tel = Self {
+            number,
+            extension: None,
+            isdn_subaddress: None,
+            phone_context: None,
+            parameters: Vec::new(),
+        };
+        tel.validate()?;
+        Ok(tel)
+    }
+
+    /// Determine if this is a global number.
+    pub fn is_global(&self) -> bool {
+        self.number.starts_with('+')
+    }
+
+    /// Validate the number and parameters.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = || Error::InvalidTelUri(snippet(self.to_string()));
+        if self.is_global() {
+            if !is_global_number(&self.number) {
+                return Err(invalid());
+            }
+        } else {
+            let local = &self.number;
+            let valid = !local.is_empty()
+                && local.chars().all(|c| {
+                    c.is_ascii_hexdigit()
+                        || is_visual_separator(c)
+                        || c == '*'
+                        || c == '#'
+                })
+                && local
+                    .chars()
+                    .any(|c| c.is_ascii_hexdigit() || c == '*' || c == '#');
+            if !valid || self.phone_context.is_none() {
+                return Err(invalid());
+            }
+        }
+
+        if let Some(extension) = &self.extension {
+            if extension.is_empty()
+                || !extension
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || is_visual_separator(c))
+            {
+                return Err(invalid());
+            }
+        }
+
+        if let Some(isub) = &self.isdn_subaddress {
+            if isub.is_empty() {
+                return Err(invalid());
+            }
+        }
+
+        if let Some(context) = &self.phone_context {
+            let valid = if context.starts_with('+') {
+                is_global_number(context)
+            } else {
+                is_domain_name(context)
+            };
+            if !valid {
+                return Err(invalid());
+            }
+        }
+
+        for (name, _) in &self.parameters {
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+            {
+                return Err(invalid());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for TelUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", SCHEME, self.number)?;
+        if let Some(isub) = &self.isdn_subaddress {
+            write!(f, ";{}={}", ISUB, isub)?;
+        }
+        if let Some(extension) = &self.extension {
+            write!(f, ";{}={}", EXT, extension)?;
+        }
+        if let Some(context) = &self.phone_context {
+            write!(f, ";{}={}", PHONE_CONTEXT, context)?;
+        }
+        for (name, value) in &self.parameters {
+            if let Some(value) = value {
+                write!(f, ";{}={}", name, value)?;
+            } else {
+                write!(f, ";{}", name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TelUri {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidTelUri(snippet(s));
+        let rest = match s.get(..SCHEME.len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(SCHEME) => {
+                &s[SCHEME.len()..]
+            }
+            _ => return Err(invalid()),
+        };
+
+        let mut parts = rest.split(';');
+        let mut tel = Self {
+            number: parts.next().unwrap_or_default().to_owned(),
+            extension: None,
+            isdn_subaddress: None,
+            phone_context: None,
+            parameters: Vec::new(),
+        };
+
+        for part in parts {
+            let (name, value) = match part.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_owned())),
+                None => (part, None),
+            };
+            let target = if name.eq_ignore_ascii_case(EXT) {
+                &mut tel.extension
+            } else if name.eq_ignore_ascii_case(ISUB) {
+                &mut tel.isdn_subaddress
+            } else if name.eq_ignore_ascii_case(PHONE_CONTEXT) {
+                &mut tel.phone_context
+            } else {
+                tel.parameters.push((name.to_owned(), value));
+                continue;
+            };
+            if target.is_some() || value.is_none() {
+                return Err(invalid());
+            }
+            *target = value;
+        }
+
+        tel.validate()?;
+        Ok(tel)
+    }
+}
+
+impl TryFrom<&Uri> for TelUri {
+    type Error = Error;
+
+    fn try_from(value: &Uri) -> Result<Self> {
+        value.to_string().parse()
+    }
+}
+
+impl TryFrom<TelUri> for Uri {
+    type Error = Error;
+
+    fn try_from(value: TelUri) -> Result<Self> {
+        value.to_string().parse()
+    }
+}
+
+/// Determine if a URI uses the `tel:` scheme.
+pub(crate) fn is_tel_uri(value: &Uri) -> bool {
+    value
+        .to_string()
+        .get(..SCHEME.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(SCHEME))
+}
+
+fn is_visual_separator(c: char) -> bool {
+    matches!(c, '-' | '.' | '(' | ')')
+}
+
+fn is_global_number(value: &str) -> bool {
+    match value.strip_prefix('+') {
+        Some(digits) => {
+            digits
+                .chars()
+                .all(|c| c.is_ascii_digit() || is_visual_separator(c))
+                && digits.chars().any(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+fn is_domain_name(value: &str) -> bool {
+    let value = value.strip_suffix('.').unwrap_or(value);
+    !value.is_empty()
+        && value.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
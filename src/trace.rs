@@ -0,0 +1,43 @@
+//! Macros for the optional `tracing` feature.
+//!
+//! When the feature is disabled the macros expand to nothing
+//! so the arguments are never evaluated.
+
+// Enters a debug span until the end of the enclosing block.
+#[cfg(feature = "tracing")]
+macro_rules! debug_span {
+    ($($arg:tt)*) => {
+        let _span = tracing::debug_span!($($arg)*).entered();
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! debug_span {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! warn_event {
+    ($($arg:tt)*) => {
+        tracing::warn!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! warn_event {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        tracing::trace!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {};
+}
+
+pub(crate) use {debug_span, trace_event, warn_event};
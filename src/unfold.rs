@@ -0,0 +1,143 @@
+//! Unfold content lines before lexing.
+//!
+//! A fold may appear anywhere in a content line including
+//! inside a property or parameter name so lines are unfolded
+//! before they are lexed; the positions of the removed folds
+//! are kept to map offsets back to the source.
+//!
+//! The values of quoted-printable properties keep their folds
+//! as a soft line break may precede a fold.
+use std::{borrow::Cow, ops::Range};
+
+/// Text with the folded lines joined.
+pub(crate) struct Unfolded<'s> {
+    /// The unfolded text; borrowed when the source has no folds.
+    pub(crate) text: Cow<'s, str>,
+    /// Offset in the text of each removed fold and the total
+    /// number of bytes removed up to and including the fold.
+    folds: Vec<(usize, usize)>,
+}
+
+impl<'s> Unfolded<'s> {
+    /// Unfold the lines in a source.
+    pub(crate) fn new(source: &'s str) -> Self {
+        if !source.contains("\n ") && !source.contains("\n\t") {
+            return Self {
+                text: Cow::Borrowed(source),
+                folds: Vec::new(),
+            };
+        }
+
+        let bytes = source.as_bytes();
+        let mut text = String::with_capacity(source.len());
+        let mut folds = Vec::new();
+        let mut removed = 0;
+        // Offset in the source that has been copied up to
+        let mut copied = 0;
+        // Offset in the text of the current line
+        let mut line_start = 0;
+
+        for (index, _) in source.match_indices('\n') {
+            // Carriage returns are part of the line terminator
+            let mut start = index;
+            while start > copied && bytes[start - 1] == b'\r' {
+                start -= 1;
+            }
+            text.push_str(&source[copied..start]);
+
+            let is_fold = matches!(bytes.get(index + 1), Some(b' ' | b'\t'));
+            if is_fold && !is_quoted_printable(&text[line_start..]) {
+                let end = index + 2;
+                removed += end - start;
+                folds.push((text.len(), removed));
+                copied = end;
+            } else {
+                text.push_str(&source[start..=index]);
+                copied = index + 1;
+                if !is_fold {
+                    line_start = text.len();
+                }
+            }
+        }
+        text.push_str(&source[copied..]);
+
+        Self {
+            text: Cow::Owned(text),
+            folds,
+        }
+    }
+
+    /// Map an offset in the text to an offset in the source.
+    ///
+    /// An offset at the position of a fold maps to the
+    /// source after the fold.
+    pub(crate) fn source_offset(&self, offset: usize) -> usize {
+        self.shift(self.folds.partition_point(|(pos, _)| *pos <= offset))
+            + offset
+    }
+
+    /// Map a range in the text to a range in the source.
+    ///
+    /// The end of the range does not include a fold at the end.
+    pub(crate) fn source_range(&self, range: Range<usize>) -> Range<usize> {
+        let end = self
+            .shift(self.folds.partition_point(|(pos, _)| *pos < range.end))
+            + range.end;
+        self.source_offset(range.start)..end
+    }
+
+    /// Total bytes removed by the first `count` folds.
+    fn shift(&self, count: usize) -> usize {
+        count
+            .checked_sub(1)
+            .map(|index| self.folds[index].1)
+            .unwrap_or_default()
+    }
+}
+
+/// Determine if the value of a partial content line
+/// is quoted-printable.
+fn is_quoted_printable(line: &str) -> bool {
+    let mut quoted = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => {
+                return line[..index]
+                    .to_ascii_uppercase()
+                    .contains("QUOTED-PRINTABLE");
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Unfolded;
+
+    #[test]
+    fn unfold_offsets() {
+        let source = "NO\r\n TE:a\n\tb\nFN:c";
+        let unfolded = Unfolded::new(source);
+        assert_eq!("NOTE:ab\nFN:c", unfolded.text);
+        assert_eq!(0, unfolded.source_offset(0));
+        // Start of TE
+        assert_eq!(5, unfolded.source_offset(2));
+        // Start of b
+        assert_eq!(11, unfolded.source_offset(6));
+        assert_eq!(13..17, unfolded.source_range(8..12));
+        assert_eq!(0..9, unfolded.source_range(0..6));
+    }
+
+    #[test]
+    fn unfold_quoted_printable() {
+        let source = "N\n OTE;ENCODING=QUOTED-PRINTABLE:a=\r\n =42\nFN:c\n d";
+        let unfolded = Unfolded::new(source);
+        assert_eq!(
+            "NOTE;ENCODING=QUOTED-PRINTABLE:a=\r\n =42\nFN:cd",
+            unfolded.text
+        );
+    }
+}
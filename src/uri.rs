@@ -1,10 +1,17 @@
 use crate::Error;
 use std::{fmt, str::FromStr};
-use uriparse::URI;
+use uriparse::{uri::URIError, URIReference, URI};
+
+#[cfg(feature = "serde")]
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 /// URI type for the library.
+///
+/// The parsed URI is boxed as it is large and is embedded in many
+/// property and parameter types; it serializes as a string.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Uri(URI<'static>);
+#[cfg_attr(feature = "serde", derive(DeserializeFromStr, SerializeDisplay))]
+pub struct Uri(Box<URI<'static>>);
 
 impl fmt::Display for Uri {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -15,6 +22,10 @@ impl FromStr for Uri {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(URI::try_from(s)?.into_owned()))
+        // Parse as a reference first; converting the error for a
+        // relative reference such as `:0` panics in `URI::try_from`.
+        let reference = URIReference::try_from(s)
+            .map_err(|e| URIError::try_from(e).unwrap_or(URIError::NotURI))?;
+        Ok(Self(Box::new(URI::try_from(reference)?.into_owned())))
     }
 }
@@ -0,0 +1,379 @@
+//! Encode vCards as version 3.0.
+use base64::{engine::general_purpose, Engine};
+
+use crate::{
+    data::{is_data_uri, DataUri},
+    escape::escape_value,
+    geo::GeoUri,
+    name::*,
+    parameter::Parameters,
+    property::{
+        DateAndOrTime, DateTimeOrTextProperty, Property, TextOrUriProperty,
+        TimeZoneProperty,
+    },
+    property_ref::{PropertyHandle, PropertyKind, PropertyRef},
+    tel::TelUri,
+    vcard::{fold_line, qualified_name},
+    Uri, V3Options, Vcard,
+};
+
+/// Value types that exist in version 3.0.
+const VALUE_TYPES: [&str; 9] = [
+    "text",
+    "uri",
+    "date",
+    "time",
+    "date-time",
+    "boolean",
+    "integer",
+    "float",
+    "utc-offset",
+];
+
+/// A vCard encoded as version 3.0.
+///
+/// Version 3.0 ([RFC2426](https://www.rfc-editor.org/rfc/rfc2426))
+/// cannot express everything in version 4.0 so the encoding is lossy:
+///
+/// * Properties that do not exist in version 3.0 (such as KIND,
+///   GENDER, ANNIVERSARY, LANG, MEMBER, RELATED and CLIENTPIDMAP)
+///   are dropped unless [V3Options::extensions] is enabled in which
+///   case they are written as `X-` extensions.
+/// * The ALTID, PID, MEDIATYPE, CALSCALE, SORT-AS, GEO, TZ, LEVEL,
+///   INDEX, SERVICE-TYPE and CC parameters are removed; a PREF of 1
+///   is written as the `pref` type.
+/// * The LABEL parameter of an ADR is written as a LABEL property
+///   with the same group and parameters.
+/// * BDAY dates and date times use the extended ISO 8601 format;
+///   partial dates, times and text values are dropped.
+/// * Data URIs for PHOTO, LOGO, SOUND and KEY are written inline
+///   with `ENCODING=b`; other URIs are marked with `VALUE=uri` and
+///   text values of PHOTO are dropped.
+/// * `tel:` URIs are written as the telephone number, `geo:` URIs
+///   as a latitude and longitude pair and UTC offsets for TZ in the
+///   extended format; other URIs for TEL, GEO and TZ are dropped.
+/// * An empty N is written when the vCard does not have one as
+///   it is required by version 3.0.
+///
+/// Parameters removed from a property are not reported as dropped.
+///
+/// ```
+/// use vcard4::{V3Options, V3Output, VcardBuilder};
+/// let card = VcardBuilder::new("Jane Doe".to_owned())
+///     .gender("F")
+///     .finish();
+/// let output = V3Output::new(&card, &V3Options::new());
+/// assert_eq!(
+///     "BEGIN:VCARD\r\nVERSION:3.0\r\nN:;;;;\r\nFN:Jane Doe\r\nEND:VCARD\r\n",
+///     output.encoded,
+/// );
+/// assert_eq!(1, output.dropped.len());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct V3Output {
+    /// The encoded vCard.
+    pub encoded: String,
+    /// Handles for the properties that were dropped.
+    pub dropped: Vec<PropertyHandle>,
+}
+
+impl V3Output {
+    /// Encode a vCard as version 3.0.
+    pub fn new(card: &Vcard, options: &V3Options) -> Self {
+        let mut writer = Writer {
+            options,
+            lines: Vec::new(),
+            dropped: Vec::new(),
+        };
+        writer.lines.push(BEGIN.to_owned());
+        writer.lines.push(VERSION_3.to_owned());
+        if card.name.is_none() {
+            writer.lines.push(format!("{}:;;;;", N));
+        }
+        for prop in card.properties() {
+            if !writer.property(card, prop) {
+                writer.dropped.push(prop.handle());
+            }
+        }
+        writer.lines.push(END.to_owned());
+
+        let eol = options.write.line_ending.as_str();
+        let mut encoded = String::new();
+        for line in writer.lines {
+            if let Some(wrap_at) = options.write.fold_width {
+                encoded.push_str(&fold_line(line, wrap_at, eol));
+            } else {
+                encoded.push_str(&line);
+            }
+            encoded.push_str(eol);
+        }
+
+        Self {
+            encoded,
+            dropped: writer.dropped,
+        }
+    }
+}
+
+struct Writer<'o> {
+    options: &'o V3Options,
+    lines: Vec<String>,
+    dropped: Vec<PropertyHandle>,
+}
+
+impl Writer<'_> {
+    /// Write a property returning whether it was written.
+    fn property(&mut self, card: &Vcard, prop: PropertyRef<'_>) -> bool {
+        use PropertyKind::*;
+        let index = prop.index();
+        let property = prop.property();
+        match prop.kind() {
+            Source | FormattedName | Name | Nickname | Email | Impp
+            | Title | Role | Org | Categories | Note | ProdId | Rev | Uid
+            | Url | FbUrl | CalAdrUri | CalUri | Extension => {
+                self.push(property, prop.name(), &[], property.to_string());
+                true
+            }
+            Address => {
+                self.push(property, ADR, &[], property.to_string());
+                let label =
+                    property.parameters().and_then(|p| p.label.as_ref());
+                if let Some(label) = label {
+                    self.push(
+                        property,
+                        LABEL,
+                        &[],
+                        escape_value(label, false),
+                    );
+                }
+                true
+            }
+            Photo => match &card.photo[index] {
+                TextOrUriProperty::Uri(uri) => {
+                    self.media(property, PHOTO, &uri.value)
+                }
+                TextOrUriProperty::Text(_) => false,
+            },
+            Key => match &card.key[index] {
+                TextOrUriProperty::Uri(uri) => {
+                    self.media(property, KEY, &uri.value)
+                }
+                TextOrUriProperty::Text(_) => {
+                    self.push(property, KEY, &[], property.to_string());
+                    true
+                }
+            },
+            Logo => self.media(property, LOGO, &card.logo[index].value),
+            Sound => self.media(property, SOUND, &card.sound[index].value),
+            Bday => self.birthday(property, card.bday.as_ref()),
+            Tel => match &card.tel[index] {
+                TextOrUriProperty::Text(_) => {
+                    self.push(property, TEL, &[], property.to_string());
+                    true
+                }
+                TextOrUriProperty::Uri(uri) => {
+                    match TelUri::try_from(&uri.value) {
+                        Ok(tel) => {
+                            let number = escape_value(&tel.number, false);
+                            self.push_converted(property, TEL, number);
+                            true
+                        }
+                        Err(_) => false,
+                    }
+                }
+            },
+            TimeZone => match &card.timezone[index] {
+                TimeZoneProperty::Text(_) => {
+                    let value = property.to_string();
+                    self.push(property, TZ, &["VALUE=text"], value);
+                    true
+                }
+                TimeZoneProperty::UtcOffset(offset) => {
+                    let (hours, minutes, _) = offset.value.as_hms();
+                    let sign =
+                        if offset.value.is_negative() { '-' } else { '+' };
+                    let value = format!(
+                        "{}{:02}:{:02}",
+                        sign,
+                        hours.abs(),
+                        minutes.abs()
+                    );
+                    self.push(property, TZ, &[], value);
+                    true
+                }
+                TimeZoneProperty::Uri(_) => false,
+            },
+            Geo => match GeoUri::try_from(&card.geo[index].value) {
+                Ok(geo) => {
+                    let value = format!("{};{}", geo.latitude, geo.longitude);
+                    self.push_converted(property, GEO, value);
+                    true
+                }
+                Err(_) => false,
+            },
+            Kind | Xml | Anniversary | Birthplace | Deathplace
+            | Deathdate | Gender | Pronouns | Member | Related
+            | Expertise | Hobby | Interest | OrgDirectory | SocialProfile
+            | Lang | Language | Created | ClientPidMap => {
+                if self.options.extensions {
+                    let name = format!("X-{}", prop.name());
+                    self.push(property, &name, &[], property.to_string());
+                }
+                self.options.extensions
+            }
+        }
+    }
+
+    /// Write a media property; data URIs are written inline.
+    fn media(
+        &mut self,
+        property: &dyn Property,
+        name: &str,
+        uri: &Uri,
+    ) -> bool {
+        let value = uri.to_string();
+        if is_data_uri(&value) {
+            if let Ok(data) = value.parse::<DataUri>() {
+                let subtype = data
+                    .media_type
+                    .split(';')
+                    .next()
+                    .and_then(|media_type| media_type.split_once('/'))
+                    .map(|(_, subtype)| subtype.to_uppercase());
+                let encoded = general_purpose::STANDARD.encode(&data.data);
+                if let Some(subtype) = subtype {
+                    let kind = format!("TYPE={}", subtype);
+                    self.push(
+                        property,
+                        name,
+                        &["ENCODING=b", &kind],
+                        encoded,
+                    );
+                } else {
+                    self.push(property, name, &["ENCODING=b"], encoded);
+                }
+                return true;
+            }
+        }
+        self.push(property, name, &["VALUE=uri"], value);
+        true
+    }
+
+    /// Write the birthday when it is a date or date and time.
+    fn birthday(
+        &mut self,
+        property: &dyn Property,
+        bday: Option<&DateTimeOrTextProperty>,
+    ) -> bool {
+        let Some(DateTimeOrTextProperty::DateTime(prop)) = bday else {
+            return false;
+        };
+        match &prop.value[..] {
+            [DateAndOrTime::Date(date)] => {
+                self.push(property, BDAY, &[], date.to_string());
+                true
+            }
+            [DateAndOrTime::DateTime(date_time)] => {
+                let value = date_time.to_string();
+                self.push(property, BDAY, &["VALUE=date-time"], value);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Add a content line.
+    ///
+    /// The extra parameters are written before the
+    /// parameters of the property.
+    fn push(
+        &mut self,
+        property: &dyn Property,
+        name: &str,
+        extra: &[&str],
+        value: String,
+    ) {
+        let keep_value = !extra.iter().any(|param| {
+            param.starts_with("VALUE=") || param.starts_with("ENCODING=")
+        });
+        self.push_line(property, name, extra, value, keep_value);
+    }
+
+    /// Add a content line for a value that was converted
+    /// so the VALUE parameter no longer applies.
+    fn push_converted(
+        &mut self,
+        property: &dyn Property,
+        name: &str,
+        value: String,
+    ) {
+        self.push_line(property, name, &[], value, false);
+    }
+
+    fn push_line(
+        &mut self,
+        property: &dyn Property,
+        name: &str,
+        extra: &[&str],
+        value: String,
+        keep_value: bool,
+    ) {
+        let name = qualified_name(property, name);
+        let mut line = name.into_owned();
+        for param in extra {
+            line.push(';');
+            line.push_str(param);
+        }
+        if let Some(params) = property.parameters() {
+            write_parameters(&mut line, params, keep_value);
+        }
+        line.push(':');
+        line.push_str(&value);
+        self.lines.push(line);
+    }
+}
+
+/// Write the parameters that exist in version 3.0.
+fn write_parameters(
+    line: &mut String,
+    params: &Parameters,
+    keep_value: bool,
+) {
+    if let Some(language) = &params.language {
+        line.push_str(&format!(";{}={}", LANGUAGE, language));
+    }
+    if let Some(value) = params.value.as_ref().filter(|_| keep_value) {
+        let value = value.to_string().to_lowercase();
+        if VALUE_TYPES.contains(&&value[..]) || value.starts_with("x-") {
+            line.push_str(&format!(";{}={}", VALUE, value));
+        }
+    }
+
+    let mut types: Vec<String> = params
+        .types
+        .iter()
+        .flatten()
+        .map(|kind| kind.to_string())
+        .collect();
+    if params.pref == Some(1) && !types.iter().any(|t| t == "pref") {
+        types.push("pref".to_owned());
+    }
+    if !types.is_empty() {
+        line.push_str(&format!(";{}={}", TYPE, types.join(",")));
+    }
+
+    for (name, values) in params.extensions.iter().flatten() {
+        let values: Vec<String> = values
+            .iter()
+            .map(|value| {
+                let value = value.replace('"', "");
+                if value.contains([';', ':', ',']) {
+                    format!("\"{}\"", value)
+                } else {
+                    value
+                }
+            })
+            .collect();
+        line.push_str(&format!(";{}={}", name, values.join(",")));
+    }
+}
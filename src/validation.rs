@@ -0,0 +1,205 @@
+//! Structural validation of a vCard.
+//!
+//! Rules from [RFC6350](https://www.rfc-editor.org/rfc/rfc6350)
+//! that are enforced when parsing are checked again so that
+//! vCards created programmatically can be validated.
+use crate::{
+    error::snippet,
+    property::{is_group_name, Kind},
+    Error, PropertyHandle, PropertyKind, Vcard,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Severity of a validation issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The vCard is invalid.
+    Error,
+    /// The vCard is valid but may not be understood by
+    /// other software.
+    Warning,
+}
+
+/// Problem found when validating a vCard.
+#[derive(Debug)]
+pub struct ValidationIssue {
+    /// Severity of the issue.
+    pub severity: Severity,
+    /// The property with the issue, if any.
+    pub property: Option<PropertyHandle>,
+    /// Description of the issue.
+    pub error: Error,
+}
+
+impl ValidationIssue {
+    fn error(property: Option<PropertyHandle>, error: Error) -> Self {
+        Self {
+            severity: Severity::Error,
+            property,
+            error,
+        }
+    }
+
+    fn warning(property: Option<PropertyHandle>, error: Error) -> Self {
+        Self {
+            severity: Severity::Warning,
+            property,
+            error,
+        }
+    }
+}
+
+/// vCard that has been validated.
+///
+/// Deserializing a [Vcard] does not run the checks applied when
+/// parsing so a vCard without a formatted name or with a PREF
+/// out of range can be loaded; deserialize to this type to
+/// reject the vCards that fail [Vcard::validate].
+///
+/// ```
+/// use vcard4::{ValidatedVcard, Vcard};
+/// let json = r#"{ "formattedName": [] }"#;
+/// assert!(serde_json::from_str::<ValidatedVcard>(json).is_err());
+/// assert!(serde_json::from_str::<Vcard>(json).is_ok());
+/// ```
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vcard", into = "Vcard")]
+pub struct ValidatedVcard(Vcard);
+
+#[cfg(feature = "serde")]
+impl ValidatedVcard {
+    /// Get the validated vCard.
+    pub fn into_inner(self) -> Vcard {
+        self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Vcard> for ValidatedVcard {
+    type Error = Error;
+
+    fn try_from(value: Vcard) -> Result<Self, Self::Error> {
+        value.validate()?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<ValidatedVcard> for Vcard {
+    fn from(value: ValidatedVcard) -> Self {
+        value.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl AsRef<Vcard> for ValidatedVcard {
+    fn as_ref(&self) -> &Vcard {
+        &self.0
+    }
+}
+
+/// Find all the validation issues for a vCard.
+pub(crate) fn validate(card: &Vcard) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    if card.formatted_name.is_empty() {
+        issues.push(ValidationIssue::error(None, Error::NoFormattedName));
+    }
+
+    let is_group = card
+        .kind
+        .as_ref()
+        .map(|kind| kind.value == Kind::Group)
+        .unwrap_or(false);
+    if !is_group {
+        for index in 0..card.member.len() {
+            issues.push(ValidationIssue::error(
+                Some(PropertyHandle::new(PropertyKind::Member, index)),
+                Error::MemberRequiresGroup,
+            ));
+        }
+    }
+
+    // Multiple genders must be alternative representations
+    if let Some(first) = card.gender.first() {
+        let alt_id =
+            first.parameters.as_ref().and_then(|p| p.alt_id.as_ref());
+        for (index, gender) in card.gender.iter().enumerate().skip(1) {
+            let other =
+                gender.parameters.as_ref().and_then(|p| p.alt_id.as_ref());
+            if alt_id.is_none() || alt_id != other {
+                issues.push(ValidationIssue::error(
+                    Some(PropertyHandle::new(PropertyKind::Gender, index)),
+                    Error::OnlyOnce(crate::name::GENDER.to_owned()),
+                ));
+            }
+        }
+    }
+
+    for prop in card.properties() {
+        let handle = Some(prop.handle());
+        if let Some(group) = prop.property().group() {
+            if !is_group_name(group) {
+                issues.push(ValidationIssue::error(
+                    handle,
+                    Error::InvalidGroup(snippet(group)),
+                ));
+            }
+        }
+
+        let Some(params) = prop.property().parameters() else {
+            continue;
+        };
+
+        if params.label.is_some() && prop.kind() != PropertyKind::Address {
+            issues.push(ValidationIssue::error(
+                handle,
+                Error::InvalidLabel(snippet(prop.name())),
+            ));
+        }
+
+        if let Some(pref) = params.pref {
+            if !(1..=100).contains(&pref) {
+                issues.push(ValidationIssue::error(
+                    handle,
+                    Error::PrefOutOfRange(pref),
+                ));
+            }
+        }
+
+        // Each part of a PID is one or two digits
+        for pid in params.pid.iter().flatten() {
+            if pid.local > 99 || pid.source.is_some_and(|source| source > 99)
+            {
+                issues.push(ValidationIssue::error(
+                    handle,
+                    Error::PidOutOfRange(pid.to_string()),
+                ));
+            }
+        }
+    }
+
+    for (handle, pid) in card.check_pid_integrity() {
+        if let Some(source) = pid.source {
+            issues.push(ValidationIssue::warning(
+                Some(handle),
+                Error::UnknownPidSource(source),
+            ));
+        }
+    }
+
+    #[cfg(feature = "xml")]
+    for (index, xml) in card.xml.iter().enumerate() {
+        if let Err(e) = xml.xml_root() {
+            issues.push(ValidationIssue::warning(
+                Some(PropertyHandle::new(PropertyKind::Xml, index)),
+                e,
+            ));
+        }
+    }
+
+    issues
+}
@@ -1,6 +1,6 @@
 //! Definition of a single vCard.
 
-use std::{borrow::Cow, fmt};
+use std::{borrow::Cow, collections::HashMap, fmt, io};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -10,10 +10,49 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use base64::{engine::general_purpose, Engine};
 
-use crate::{iter, property::*, Error, Result};
+use crate::{
+    data::{is_data_uri, DataUri},
+    iter,
+    normalize::{casing_score, fold_text, KeepDuplicate, NormalizeOptions},
+    parameter::{Pid, TelephoneType, TypeParameter, ValueType},
+    property::*,
+    property_ref::{
+        PropertyHandle, PropertyKind, PropertyRef, SourceLines, SourceOrder,
+        SourceText,
+    },
+    qr::{QrFormat, QrPayload},
+    tel::TelUri,
+    trace::warn_event,
+    Clock, CompareOptions, Error, LineEnding, Lint, RedactedVcard, Result,
+    Severity, SortKey, SystemClock, Uri, V3Output, ValidationIssue,
+    WriteOptions,
+};
+
+#[cfg(feature = "language-tags")]
+use language_tags::LanguageTag;
 
 /// The vCard type.
+///
+/// # Output order
+///
+/// The `Display` implementation writes BEGIN and VERSION
+/// followed by the properties by kind in the order of
+/// [PropertyKind::ALL] which follows the sections of RFC6350
+/// with private extensions (`X-`) last. Properties of the same
+/// kind are written in the order of their list. Properties that
+/// share a group are moved next to the first property of the
+/// group so a label such as `X-ABLabel` follows the property it
+/// describes; disable [WriteOptions::group_properties] to write
+/// all properties by kind.
+///
+/// # Construction
+///
+/// The vCard also records the order of the properties in the
+/// source so it cannot be created with a struct expression;
+/// use [Vcard::new], [Default] or the
+/// [VcardBuilder](crate::VcardBuilder) and assign the fields.
 #[derive(Debug, Default, Eq, PartialEq, Clone)]
+#[non_exhaustive]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 #[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
@@ -50,7 +89,7 @@ pub struct Vcard {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub name: Option<TextListProperty>,
+    pub name: Option<NameProperty>,
     /// Value of the NICKNAME property.
     #[cfg_attr(
         feature = "serde",
@@ -78,12 +117,43 @@ pub struct Vcard {
         serde(default, skip_serializing_if = "Option::is_none")
     )]
     pub anniversary: Option<DateTimeOrTextProperty>,
-    /// Value of the GENDER property.
+    /// Value of the BIRTHPLACE property
+    /// ([RFC6474](https://www.rfc-editor.org/rfc/rfc6474)).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub birthplace: Option<TextOrUriProperty>,
+    /// Value of the DEATHPLACE property
+    /// ([RFC6474](https://www.rfc-editor.org/rfc/rfc6474)).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub deathplace: Option<TextOrUriProperty>,
+    /// Value of the DEATHDATE property
+    /// ([RFC6474](https://www.rfc-editor.org/rfc/rfc6474)).
     #[cfg_attr(
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub gender: Option<GenderProperty>,
+    pub deathdate: Option<DateTimeOrTextProperty>,
+    /// Value of the GENDER property.
+    ///
+    /// More than one value is only allowed when they are
+    /// alternative representations that share an ALTID.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub gender: Vec<GenderProperty>,
+    /// Value of the PRONOUNS property
+    /// ([RFC9554](https://www.rfc-editor.org/rfc/rfc9554)).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub pronouns: Vec<TextProperty>,
     /// Value of the URL property.
     #[cfg_attr(
         feature = "serde",
@@ -118,6 +188,13 @@ pub struct Vcard {
         serde(default, skip_serializing_if = "Vec::is_empty")
     )]
     pub impp: Vec<UriProperty>,
+    /// Value of the SOCIALPROFILE property
+    /// ([RFC9554](https://www.rfc-editor.org/rfc/rfc9554)).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub social_profile: Vec<TextOrUriProperty>,
     /// Value of the LANG property.
     #[cfg_attr(feature = "zeroize", zeroize(skip))]
     #[cfg_attr(
@@ -125,6 +202,14 @@ pub struct Vcard {
         serde(default, skip_serializing_if = "Vec::is_empty")
     )]
     pub lang: Vec<LanguageProperty>,
+    /// Value of the LANGUAGE property
+    /// ([RFC9554](https://www.rfc-editor.org/rfc/rfc9554)).
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub language: Option<LanguageProperty>,
 
     // Organizational
     /// Value of the TITLE property.
@@ -163,6 +248,34 @@ pub struct Vcard {
         serde(default, skip_serializing_if = "Vec::is_empty")
     )]
     pub related: Vec<TextOrUriProperty>,
+    /// Value of the EXPERTISE property
+    /// ([RFC6715](https://www.rfc-editor.org/rfc/rfc6715)).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub expertise: Vec<TextProperty>,
+    /// Value of the HOBBY property
+    /// ([RFC6715](https://www.rfc-editor.org/rfc/rfc6715)).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub hobby: Vec<TextProperty>,
+    /// Value of the INTEREST property
+    /// ([RFC6715](https://www.rfc-editor.org/rfc/rfc6715)).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub interest: Vec<TextProperty>,
+    /// Value of the ORG-DIRECTORY property
+    /// ([RFC6715](https://www.rfc-editor.org/rfc/rfc6715)).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub org_directory: Vec<UriProperty>,
 
     // Geographic
     /// Value of the TZ property.
@@ -204,6 +317,14 @@ pub struct Vcard {
         serde(default, skip_serializing_if = "Option::is_none")
     )]
     pub rev: Option<DateTimeProperty>,
+    /// Value of the CREATED property
+    /// ([RFC9554](https://www.rfc-editor.org/rfc/rfc9554)).
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub created: Option<DateTimeProperty>,
     /// Value of the SOUND property.
     #[cfg_attr(
         feature = "serde",
@@ -252,12 +373,23 @@ pub struct Vcard {
     pub cal_uri: Vec<UriProperty>,
 
     // Extensions
-    /// Private property extensions (`X-`).
+    /// Private property extensions (`X-`) and IANA-registered
+    /// properties that are not known to this library.
     #[cfg_attr(
         feature = "serde",
         serde(default, skip_serializing_if = "Vec::is_empty")
     )]
     pub extensions: Vec<ExtensionProperty>,
+
+    /// Order of the properties in the source.
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) order: SourceOrder,
+
+    /// Content lines in the source when parsed with
+    /// [ParseOptions::preserve_source](crate::ParseOptions::preserve_source).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) source_lines: SourceLines,
 }
 
 impl Vcard {
@@ -268,28 +400,672 @@ impl Vcard {
         card
     }
 
+    /// Set the REV property to the current time.
+    pub fn with_rev_now(mut self) -> Self {
+        self.rev = Some(SystemClock.now().into());
+        self
+    }
+
+    /// Modify this vCard and update the REV property if
+    /// the vCard was changed.
+    ///
+    /// Returns whether the vCard was changed.
+    pub fn modify(&mut self, f: impl FnOnce(&mut Vcard)) -> bool {
+        self.modify_with_clock(&SystemClock, f)
+    }
+
+    /// Modify this vCard and update the REV property using the
+    /// given clock if the vCard was changed.
+    ///
+    /// Changes to the REV property alone are not considered a
+    /// modification.
+    ///
+    /// Returns whether the vCard was changed.
+    pub fn modify_with_clock(
+        &mut self,
+        clock: &impl Clock,
+        f: impl FnOnce(&mut Vcard),
+    ) -> bool {
+        let mut before = self.clone();
+        f(self);
+        before.rev.clone_from(&self.rev);
+        let changed = &before != self;
+        if changed {
+            self.rev = Some(clock.now().into());
+        }
+        changed
+    }
+
+    /// Iterate all the properties of this vCard.
+    ///
+    /// Properties are yielded in the order they are encoded
+    /// which groups them by kind.
+    pub fn properties(&self) -> impl Iterator<Item = PropertyRef<'_>> {
+        PropertyKind::ALL.into_iter().flat_map(move |kind| {
+            (0..self.property_count(kind))
+                .filter_map(move |index| self.property_at(kind, index))
+        })
+    }
+
+    /// Iterate all the properties of this vCard in the order
+    /// they appeared when the vCard was parsed.
+    ///
+    /// Properties without a source position, for example those
+    /// added after parsing, follow in the order they are encoded.
+    /// The order is best effort once a parsed vCard is modified
+    /// as removing a property shifts the positions of any later
+    /// properties of the same kind.
+    pub fn properties_in_source_order(
+        &self,
+    ) -> impl Iterator<Item = PropertyRef<'_>> {
+        let mut next = [0usize; PropertyKind::ALL.len()];
+        let mut properties = Vec::new();
+        for kind in self.order.0.iter().copied() {
+            let index = &mut next[kind as usize];
+            if let Some(prop) = self.property_at(kind, *index) {
+                properties.push(prop);
+                *index += 1;
+            }
+        }
+        for kind in PropertyKind::ALL {
+            properties.extend(
+                (next[kind as usize]..self.property_count(kind))
+                    .filter_map(|index| self.property_at(kind, index)),
+            );
+        }
+        properties.into_iter()
+    }
+
+    /// Group the properties of this vCard by their group name.
+    ///
+    /// Group names are case-insensitive so the keys are the
+    /// lowercase group names; properties without a group are
+    /// not included.
+    pub fn property_groups(&self) -> HashMap<String, Vec<PropertyRef<'_>>> {
+        let mut groups: HashMap<String, Vec<PropertyRef<'_>>> =
+            HashMap::new();
+        for prop in self.properties() {
+            if let Some(group) = prop.property().group() {
+                groups.entry(group.to_lowercase()).or_default().push(prop);
+            }
+        }
+        groups
+    }
+
+    /// Group the properties of a kind by their ALTID parameter.
+    ///
+    /// Properties that share an ALTID are alternative representations
+    /// of the same value, for example a formatted name in different
+    /// languages. A property without an ALTID is a group of its own;
+    /// groups are in the order of their first property.
+    ///
+    /// Use [preferred_in_group](crate::preferred_in_group) to
+    /// select a property from a group.
+    pub fn altid_groups(
+        &self,
+        kind: PropertyKind,
+    ) -> Vec<Vec<PropertyRef<'_>>> {
+        let mut groups: Vec<Vec<PropertyRef<'_>>> = Vec::new();
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        for index in 0..self.property_count(kind) {
+            let Some(prop) = self.property_at(kind, index) else {
+                continue;
+            };
+            let alt_id = prop
+                .property()
+                .parameters()
+                .and_then(|params| params.alt_id.as_deref());
+            match alt_id {
+                Some(alt_id) => {
+                    if let Some(position) = positions.get(alt_id) {
+                        groups[*position].push(prop);
+                    } else {
+                        positions.insert(alt_id, groups.len());
+                        groups.push(vec![prop]);
+                    }
+                }
+                None => groups.push(vec![prop]),
+            }
+        }
+        groups
+    }
+
+    /// Find the custom label for a property.
+    ///
+    /// Labels are stored by Apple Contacts in an `X-ABLabel`
+    /// extension with the same group as the property, for
+    /// example `item1.TEL` and `item1.X-ABLabel`.
+    pub fn label_for(&self, prop: &(impl Property + ?Sized)) -> Option<&str> {
+        let group = prop.group()?;
+        self.extensions.iter().find_map(|ext| {
+            let matches =
+                ext.name.eq_ignore_ascii_case(crate::name::X_ABLABEL)
+                    && ext
+                        .group
+                        .as_ref()
+                        .map(|g| g.eq_ignore_ascii_case(group))
+                        .unwrap_or(false);
+            match &ext.value {
+                AnyProperty::Text(label) if matches => Some(&label[..]),
+                _ => None,
+            }
+        })
+    }
+
+    /// Remove a property.
+    ///
+    /// Returns whether a property was removed; removing a
+    /// property shifts the index of any later properties of
+    /// the same kind so handles obtained earlier should not
+    /// be reused.
+    pub fn remove_property(&mut self, handle: PropertyHandle) -> bool {
+        crate::edit::remove(self, handle)
+    }
+
+    /// Replace a property in place with a property parsed
+    /// from a content line.
+    ///
+    /// The content line must contain a single property of the
+    /// same kind as the handle, for example
+    /// `EMAIL;TYPE=work:jane@example.com`.
+    pub fn set_property(
+        &mut self,
+        handle: PropertyHandle,
+        line: &str,
+    ) -> Result<()> {
+        crate::edit::set(self, handle, line)
+    }
+
+    /// Move the extension properties with a name into the
+    /// typed properties of a kind.
+    ///
+    /// The name is matched ignoring case. Values are converted
+    /// using the `TryFrom<ExtensionProperty>` implementations
+    /// of the property types and the group and parameters are
+    /// kept; when a value can not be converted no extensions
+    /// are moved. It is an error to promote more than one
+    /// extension to a property that may only appear once, when
+    /// the property already exists or when an extension has a
+    /// TYPE parameter that the property does not support.
+    ///
+    /// Properties with structured values (such as N, ADR, ORG
+    /// and CLIENTPIDMAP) are not supported.
+    ///
+    /// Returns the number of extensions that were moved.
+    ///
+    /// ```
+    /// use vcard4::{parse, PropertyKind};
+    /// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nX-ANNIVERSARY:19960415\nEND:VCARD";
+    /// let mut card = parse(input).unwrap().remove(0);
+    /// let moved = card
+    ///     .promote_extension("X-ANNIVERSARY", PropertyKind::Anniversary)
+    ///     .unwrap();
+    /// assert_eq!(1, moved);
+    /// assert!(card.extensions.is_empty());
+    /// assert_eq!("19960415", card.anniversary.as_ref().unwrap().to_string());
+    /// ```
+    pub fn promote_extension(
+        &mut self,
+        name: &str,
+        target: PropertyKind,
+    ) -> Result<usize> {
+        crate::edit::promote(self, name, target)
+    }
+
+    /// Make a property the preferred property of its kind.
+    ///
+    /// The property is assigned a PREF of 1; other properties
+    /// of the same kind with a PREF are renumbered from 2 in
+    /// their current order (ties keep their input order) so
+    /// that each rank is held by only one property. Properties
+    /// without a PREF are left unranked.
+    ///
+    /// Returns whether the property exists.
+    pub fn set_preferred(&mut self, handle: PropertyHandle) -> bool {
+        crate::edit::set_preferred(self, handle)
+    }
+
+    /// Set or clear the group of a property.
+    ///
+    /// Returns whether the property exists.
+    pub fn set_group(
+        &mut self,
+        handle: PropertyHandle,
+        group: Option<PropertyGroup>,
+    ) -> bool {
+        crate::edit::set_group(self, handle, group)
+    }
+
+    /// Remove the TEL property at an index.
+    pub fn remove_tel_at(&mut self, index: usize) -> bool {
+        self.remove_property(PropertyHandle::new(PropertyKind::Tel, index))
+    }
+
+    /// Remove the EMAIL property at an index.
+    pub fn remove_email_at(&mut self, index: usize) -> bool {
+        self.remove_property(PropertyHandle::new(PropertyKind::Email, index))
+    }
+
+    /// Make the TEL property at an index the preferred number.
+    pub fn set_preferred_tel(&mut self, index: usize) -> bool {
+        self.set_preferred(PropertyHandle::new(PropertyKind::Tel, index))
+    }
+
+    /// Make the EMAIL property at an index the preferred address.
+    pub fn set_preferred_email(&mut self, index: usize) -> bool {
+        self.set_preferred(PropertyHandle::new(PropertyKind::Email, index))
+    }
+
+    /// Find the preferred EMAIL property.
+    ///
+    /// The property with the lowest PREF is preferred and a
+    /// property without a PREF is the least preferred; when
+    /// preferences are equal the first property is preferred.
+    pub fn preferred_email(&self) -> Option<&TextProperty> {
+        by_pref(self.email.iter()).next()
+    }
+
+    /// Find the preferred EMAIL property with a TYPE.
+    ///
+    /// Combine with [preferred_email](Vcard::preferred_email)
+    /// to fall back to any address:
+    ///
+    /// ```
+    /// use vcard4::{parameter::TypeParameter, parse};
+    /// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nEMAIL;PREF=1:jane@example.com\nEMAIL;TYPE=work:jdoe@example.com\nEND:VCARD";
+    /// let card = parse(input).unwrap().remove(0);
+    /// let email = card
+    ///     .preferred_email_of_type(TypeParameter::Work)
+    ///     .or_else(|| card.preferred_email())
+    ///     .unwrap();
+    /// assert_eq!("jdoe@example.com", email.value);
+    /// ```
+    pub fn preferred_email_of_type(
+        &self,
+        kind: TypeParameter,
+    ) -> Option<&TextProperty> {
+        self.emails_of_type(kind).next()
+    }
+
+    /// Iterate the EMAIL properties with a TYPE in order of
+    /// preference.
+    ///
+    /// TYPE values are matched ignoring case and a property
+    /// matches when any of its TYPE values is equal.
+    pub fn emails_of_type(
+        &self,
+        kind: TypeParameter,
+    ) -> impl Iterator<Item = &TextProperty> + '_ {
+        by_pref(self.email.iter().filter(|prop| {
+            prop.parameters()
+                .is_some_and(|params| params.has_type(&kind))
+        }))
+    }
+
+    /// Find the preferred TEL property.
+    ///
+    /// When a telephone type is given only properties with
+    /// the type are considered; see
+    /// [preferred_email](Vcard::preferred_email) for the
+    /// order of preference.
+    pub fn preferred_tel(
+        &self,
+        kind: Option<TelephoneType>,
+    ) -> Option<&TextOrUriProperty> {
+        match kind {
+            Some(kind) => {
+                self.tels_of_type(TypeParameter::Telephone(kind)).next()
+            }
+            None => by_pref(self.tel.iter()).next(),
+        }
+    }
+
+    /// Iterate the TEL properties with a TYPE in order of
+    /// preference.
+    pub fn tels_of_type(
+        &self,
+        kind: TypeParameter,
+    ) -> impl Iterator<Item = &TextOrUriProperty> + '_ {
+        by_pref(self.tel.iter().filter(|prop| {
+            prop.parameters()
+                .is_some_and(|params| params.has_type(&kind))
+        }))
+    }
+
+    /// Find the preferred IMPP property.
+    pub fn preferred_impp(&self) -> Option<&UriProperty> {
+        by_pref(self.impp.iter()).next()
+    }
+
+    /// Iterate the IMPP properties with a TYPE in order of
+    /// preference.
+    pub fn impps_of_type(
+        &self,
+        kind: TypeParameter,
+    ) -> impl Iterator<Item = &UriProperty> + '_ {
+        by_pref(self.impp.iter().filter(|prop| {
+            prop.parameters()
+                .is_some_and(|params| params.has_type(&kind))
+        }))
+    }
+
+    /// Number of properties of a kind.
+    pub(crate) fn property_count(&self, kind: PropertyKind) -> usize {
+        match kind {
+            PropertyKind::Source => self.source.len(),
+            PropertyKind::Kind => self.kind.iter().count(),
+            PropertyKind::Xml => self.xml.len(),
+            PropertyKind::FormattedName => self.formatted_name.len(),
+            PropertyKind::Name => self.name.iter().count(),
+            PropertyKind::Nickname => self.nickname.len(),
+            PropertyKind::Photo => self.photo.len(),
+            PropertyKind::Bday => self.bday.iter().count(),
+            PropertyKind::Anniversary => self.anniversary.iter().count(),
+            PropertyKind::Birthplace => self.birthplace.iter().count(),
+            PropertyKind::Deathplace => self.deathplace.iter().count(),
+            PropertyKind::Deathdate => self.deathdate.iter().count(),
+            PropertyKind::Gender => self.gender.len(),
+            PropertyKind::Pronouns => self.pronouns.len(),
+            PropertyKind::Url => self.url.len(),
+            PropertyKind::Address => self.address.len(),
+            PropertyKind::Title => self.title.len(),
+            PropertyKind::Role => self.role.len(),
+            PropertyKind::Logo => self.logo.len(),
+            PropertyKind::Org => self.org.len(),
+            PropertyKind::Member => self.member.len(),
+            PropertyKind::Related => self.related.len(),
+            PropertyKind::Expertise => self.expertise.len(),
+            PropertyKind::Hobby => self.hobby.len(),
+            PropertyKind::Interest => self.interest.len(),
+            PropertyKind::OrgDirectory => self.org_directory.len(),
+            PropertyKind::Tel => self.tel.len(),
+            PropertyKind::Email => self.email.len(),
+            PropertyKind::Impp => self.impp.len(),
+            PropertyKind::SocialProfile => self.social_profile.len(),
+            PropertyKind::Lang => self.lang.len(),
+            PropertyKind::Language => self.language.iter().count(),
+            PropertyKind::TimeZone => self.timezone.len(),
+            PropertyKind::Geo => self.geo.len(),
+            PropertyKind::Categories => self.categories.len(),
+            PropertyKind::Note => self.note.len(),
+            PropertyKind::ProdId => self.prod_id.iter().count(),
+            PropertyKind::Rev => self.rev.iter().count(),
+            PropertyKind::Created => self.created.iter().count(),
+            PropertyKind::Sound => self.sound.len(),
+            PropertyKind::Uid => self.uid.iter().count(),
+            PropertyKind::ClientPidMap => self.client_pid_map.len(),
+            PropertyKind::Key => self.key.len(),
+            PropertyKind::FbUrl => self.fburl.len(),
+            PropertyKind::CalAdrUri => self.cal_adr_uri.len(),
+            PropertyKind::CalUri => self.cal_uri.len(),
+            PropertyKind::Extension => self.extensions.len(),
+        }
+    }
+
+    /// Get a reference to a property by kind and index.
+    pub(crate) fn property_at(
+        &self,
+        kind: PropertyKind,
+        index: usize,
+    ) -> Option<PropertyRef<'_>> {
+        fn get<T: Property>(
+            props: &[T],
+            index: usize,
+        ) -> Option<&dyn Property> {
+            props.get(index).map(|prop| prop as &dyn Property)
+        }
+
+        let property = match kind {
+            PropertyKind::Source => get(&self.source, index),
+            PropertyKind::Kind => get(self.kind.as_slice(), index),
+            PropertyKind::Xml => get(&self.xml, index),
+            PropertyKind::FormattedName => get(&self.formatted_name, index),
+            PropertyKind::Name => get(self.name.as_slice(), index),
+            PropertyKind::Nickname => get(&self.nickname, index),
+            PropertyKind::Photo => get(&self.photo, index),
+            PropertyKind::Bday => get(self.bday.as_slice(), index),
+            PropertyKind::Anniversary => {
+                get(self.anniversary.as_slice(), index)
+            }
+            PropertyKind::Birthplace => {
+                get(self.birthplace.as_slice(), index)
+            }
+            PropertyKind::Deathplace => {
+                get(self.deathplace.as_slice(), index)
+            }
+            PropertyKind::Deathdate => get(self.deathdate.as_slice(), index),
+            PropertyKind::Gender => get(&self.gender, index),
+            PropertyKind::Pronouns => get(&self.pronouns, index),
+            PropertyKind::Url => get(&self.url, index),
+            PropertyKind::Address => get(&self.address, index),
+            PropertyKind::Title => get(&self.title, index),
+            PropertyKind::Role => get(&self.role, index),
+            PropertyKind::Logo => get(&self.logo, index),
+            PropertyKind::Org => get(&self.org, index),
+            PropertyKind::Member => get(&self.member, index),
+            PropertyKind::Related => get(&self.related, index),
+            PropertyKind::Expertise => get(&self.expertise, index),
+            PropertyKind::Hobby => get(&self.hobby, index),
+            PropertyKind::Interest => get(&self.interest, index),
+            PropertyKind::OrgDirectory => get(&self.org_directory, index),
+            PropertyKind::Tel => get(&self.tel, index),
+            PropertyKind::Email => get(&self.email, index),
+            PropertyKind::Impp => get(&self.impp, index),
+            PropertyKind::SocialProfile => get(&self.social_profile, index),
+            PropertyKind::Lang => get(&self.lang, index),
+            PropertyKind::Language => get(self.language.as_slice(), index),
+            PropertyKind::TimeZone => get(&self.timezone, index),
+            PropertyKind::Geo => get(&self.geo, index),
+            PropertyKind::Categories => get(&self.categories, index),
+            PropertyKind::Note => get(&self.note, index),
+            PropertyKind::ProdId => get(self.prod_id.as_slice(), index),
+            PropertyKind::Rev => get(self.rev.as_slice(), index),
+            PropertyKind::Created => get(self.created.as_slice(), index),
+            PropertyKind::Sound => get(&self.sound, index),
+            PropertyKind::Uid => get(self.uid.as_slice(), index),
+            PropertyKind::ClientPidMap => get(&self.client_pid_map, index),
+            PropertyKind::Key => get(&self.key, index),
+            PropertyKind::FbUrl => get(&self.fburl, index),
+            PropertyKind::CalAdrUri => get(&self.cal_adr_uri, index),
+            PropertyKind::CalUri => get(&self.cal_uri, index),
+            PropertyKind::Extension => {
+                let ext = self.extensions.get(index)?;
+                return Some(PropertyRef {
+                    kind,
+                    index,
+                    name: &ext.name,
+                    property: ext,
+                });
+            }
+        }?;
+        Some(PropertyRef {
+            kind,
+            index,
+            // Only extensions do not have a name
+            name: kind.name().unwrap_or_default(),
+            property,
+        })
+    }
+
     /// Validate this vCard.
+    ///
+    /// Returns the first issue with an error severity; use
+    /// [validation_issues](Vcard::validation_issues) to find
+    /// all the issues.
     pub fn validate(&self) -> Result<()> {
-        if self.formatted_name.is_empty() {
-            return Err(Error::NoFormattedName);
+        match crate::validation::validate(self)
+            .into_iter()
+            .find(|issue| issue.severity == Severity::Error)
+        {
+            Some(issue) => Err(issue.error),
+            None => Ok(()),
         }
-        if !self.member.is_empty() {
-            if let Some(kind) = &self.kind {
-                if kind.value != Kind::Group {
-                    return Err(Error::MemberRequiresGroup);
+    }
+
+    /// Find all the validation issues for this vCard.
+    ///
+    /// Checks that FN exists, that MEMBER is only used when
+    /// KIND is group, that group names are valid, that LABEL is
+    /// only used on ADR, that PREF is between 1 and 100, that
+    /// GENDER appears once (unless the values share an ALTID) and
+    /// warns when a PID source identifier does not have a
+    /// CLIENTPIDMAP. With the `xml` feature it also warns when an
+    /// XML value is not well-formed or is in the vCard namespace.
+    pub fn validation_issues(&self) -> Vec<ValidationIssue> {
+        crate::validation::validate(self)
+    }
+
+    /// Find the URI declared by CLIENTPIDMAP for the source
+    /// of a PID parameter value.
+    ///
+    /// Returns `None` when the PID does not have a source or
+    /// there is no CLIENTPIDMAP for the source.
+    pub fn client_pid_source(&self, pid: &Pid) -> Option<&Uri> {
+        let source = pid.source?;
+        self.client_pid_map
+            .iter()
+            .find(|map| map.value.source == source)
+            .map(|map| &map.value.uri)
+    }
+
+    /// Find the PID parameter values with a source that does not
+    /// have a CLIENTPIDMAP.
+    ///
+    /// Each dangling reference is returned with a handle for
+    /// the property.
+    pub fn check_pid_integrity(&self) -> Vec<(PropertyHandle, Pid)> {
+        let mut dangling = Vec::new();
+        for prop in self.properties() {
+            let pids = prop
+                .property()
+                .parameters()
+                .and_then(|params| params.pid.as_ref());
+            for pid in pids.into_iter().flatten() {
+                if pid.source.is_some()
+                    && self.client_pid_source(pid).is_none()
+                {
+                    dangling.push((prop.handle(), pid.clone()));
                 }
-            } else {
-                return Err(Error::MemberRequiresGroup);
             }
         }
+        dangling
+    }
+
+    /// Determine if this vCard has no validation errors.
+    ///
+    /// Warnings do not make a vCard invalid.
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Find problems that do not make the vCard invalid.
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = Vec::new();
+
+        let mut prefs: Vec<u8> = Vec::new();
+        for lang in &self.lang {
+            if let Some(pref) = lang.parameters.as_ref().and_then(|p| p.pref)
+            {
+                let lint = Lint::DuplicateLanguagePref(pref);
+                if prefs.contains(&pref) && !lints.contains(&lint) {
+                    lints.push(lint);
+                }
+                prefs.push(pref);
+            }
+        }
+
+        lints
+    }
+
+    /// Add a LANG property with a preference.
+    ///
+    /// If another LANG already has the preference it and any
+    /// following consecutive preferences are shifted down
+    /// so that each preference remains unique.
+    #[cfg(feature = "language-tags")]
+    pub fn add_language(&mut self, tag: LanguageTag, pref: u8) -> Result<()> {
+        self.insert_language(tag.into(), pref)
+    }
+
+    /// Add a LANG property with a preference.
+    ///
+    /// If another LANG already has the preference it and any
+    /// following consecutive preferences are shifted down
+    /// so that each preference remains unique.
+    #[cfg(not(feature = "language-tags"))]
+    pub fn add_language(&mut self, tag: String, pref: u8) -> Result<()> {
+        self.insert_language(tag.into(), pref)
+    }
+
+    fn insert_language(
+        &mut self,
+        mut prop: LanguageProperty,
+        pref: u8,
+    ) -> Result<()> {
+        if !(1..=100).contains(&pref) {
+            return Err(Error::PrefOutOfRange(pref));
+        }
+
+        let lang_pref = |lang: &LanguageProperty| {
+            lang.parameters.as_ref().and_then(|p| p.pref)
+        };
+
+        // Find the end of the run of occupied preferences
+        let mut end = pref;
+        while self.lang.iter().any(|lang| lang_pref(lang) == Some(end)) {
+            if end == 100 {
+                return Err(Error::PrefOutOfRange(end));
+            }
+            end += 1;
+        }
+
+        for lang in self.lang.iter_mut() {
+            if let Some(params) = lang.parameters.as_mut() {
+                if let Some(value) = params.pref.as_mut() {
+                    if *value >= pref && *value < end {
+                        *value += 1;
+                    }
+                }
+            }
+        }
+
+        let mut params = prop.parameters.take().unwrap_or_default();
+        params.pref = Some(pref);
+        prop.parameters = Some(params);
+        self.lang.push(prop);
+        Ok(())
+    }
+
+    /// Add a TEL property with a `tel:` URI value.
+    ///
+    /// The VALUE parameter is set to `uri`.
+    pub fn add_tel_uri(&mut self, tel: TelUri) -> Result<()> {
+        tel.validate()?;
+        let mut prop = UriProperty::from(Uri::try_from(tel)?);
+        prop.parameters.get_or_insert_with(Default::default).value =
+            Some(ValueType::Uri);
+        self.tel.push(TextOrUriProperty::Uri(prop));
         Ok(())
     }
 
+    /// Add a TEL property with a free-form text value.
+    ///
+    /// The VALUE parameter is set to `text` so that the value
+    /// is not parsed as a URI when the vCard is parsed.
+    pub fn add_tel_text(&mut self, text: String) {
+        let mut prop = TextProperty::from(text);
+        prop.parameters.get_or_insert_with(Default::default).value =
+            Some(ValueType::Text);
+        self.tel.push(TextOrUriProperty::Text(prop));
+    }
+
     /// Parse any embedded JPEG photos from the vCard photo property.
     ///
-    /// This function looks for photo entries with an ENCODING
-    /// parameter set to `b` denoting base64 encoding and
-    /// with a TYPE parameter set to a value of `JPEG`.
+    /// This function looks for photo entries with a `data:` URI
+    /// with the `image/jpeg` media type and entries kept with
+    /// [keep_inline_binary](crate::ParseOptions::keep_inline_binary)
+    /// that have an ENCODING parameter set to `b` denoting base64
+    /// encoding and a TYPE parameter set to a value of `JPEG`.
     ///
     /// Compatible with the format used by the MacOS Contacts app; it
     /// may not be suitable for embedded JPEGs exported from other apps.
@@ -297,7 +1073,13 @@ impl Vcard {
         use crate::parameter::TypeParameter;
         let mut jpegs = Vec::new();
         for photo in self.photo.iter() {
-            if let TextOrUriProperty::Text(prop) = photo {
+            if let TextOrUriProperty::Uri(prop) = photo {
+                if let Some(data) = prop.data_uri()? {
+                    if data.media_type.eq_ignore_ascii_case("image/jpeg") {
+                        jpegs.push(data.data);
+                    }
+                }
+            } else if let TextOrUriProperty::Text(prop) = photo {
                 if let Some(params) = &prop.parameters {
                     if let (Some(types), Some(extensions)) =
                         (&params.types, &params.extensions)
@@ -323,6 +1105,357 @@ impl Vcard {
         }
         Ok(jpegs)
     }
+
+    /// Decode the data URI for the PHOTO property at an index.
+    ///
+    /// Returns `None` when there is no PHOTO at the index or
+    /// the value does not use the `data:` scheme; values that
+    /// could not be parsed as a URI are also decoded.
+    pub fn photo_data(&self, index: usize) -> Result<Option<DataUri>> {
+        match self.photo.get(index) {
+            Some(TextOrUriProperty::Uri(prop)) => prop.data_uri(),
+            Some(TextOrUriProperty::Text(prop))
+                if is_data_uri(&prop.value) =>
+            {
+                Ok(Some(prop.value.parse()?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// View of this vCard that hides the property values.
+    ///
+    /// Use the [Debug](fmt::Debug) implementation of the view
+    /// instead of the vCard when logging.
+    pub fn redacted(&self) -> RedactedVcard<'_> {
+        RedactedVcard::new(self)
+    }
+
+    /// Resolve the GENDER property for a language.
+    ///
+    /// When multiple alternative representations of the GENDER
+    /// are available the representation with a LANGUAGE parameter
+    /// matching `lang` is preferred, followed by a representation
+    /// whose primary language subtag matches; otherwise the first
+    /// GENDER property is used.
+    pub fn gender_resolved(
+        &self,
+        lang: Option<&str>,
+    ) -> Option<ResolvedGender<'_>> {
+        let language = |prop: &GenderProperty| {
+            prop.parameters
+                .as_ref()
+                .and_then(|p| p.language.as_ref())
+                .map(|l| l.to_string())
+        };
+
+        if let Some(lang) = lang {
+            let exact = self.gender.iter().find(|prop| {
+                language(prop)
+                    .map(|l| l.eq_ignore_ascii_case(lang))
+                    .unwrap_or(false)
+            });
+            if let Some(prop) = exact {
+                return Some(prop.into());
+            }
+
+            let primary = lang.split('-').next().unwrap_or(lang);
+            let partial = self.gender.iter().find(|prop| {
+                language(prop)
+                    .map(|l| {
+                        l.split('-')
+                            .next()
+                            .unwrap_or_default()
+                            .eq_ignore_ascii_case(primary)
+                    })
+                    .unwrap_or(false)
+            });
+            if let Some(prop) = partial {
+                return Some(prop.into());
+            }
+        }
+
+        self.gender.first().map(ResolvedGender::from)
+    }
+
+    /// Remove duplicate FN properties.
+    ///
+    /// Properties are duplicates when the values are equal after
+    /// [normalization](crate::normalize::normalize_text) and case
+    /// folding; properties with a different ALTID or LANGUAGE
+    /// parameter are never considered duplicates.
+    ///
+    /// Returns the properties that were removed.
+    pub fn normalize_formatted_names(
+        &mut self,
+        options: NormalizeOptions,
+    ) -> Vec<TextProperty> {
+        let key = |prop: &TextProperty| {
+            let params = prop.parameters.as_ref();
+            (
+                fold_text(&prop.value),
+                params.and_then(|p| p.alt_id.clone()),
+                params
+                    .and_then(|p| p.language.as_ref())
+                    .map(|l| l.to_string().to_lowercase()),
+            )
+        };
+
+        let mut kept: Vec<(_, TextProperty)> = Vec::new();
+        let mut removed = Vec::new();
+        for prop in std::mem::take(&mut self.formatted_name) {
+            let prop_key = key(&prop);
+            if let Some((_, existing)) =
+                kept.iter_mut().find(|(k, _)| k == &prop_key)
+            {
+                if options.keep == KeepDuplicate::RicherCasing
+                    && casing_score(&prop.value)
+                        > casing_score(&existing.value)
+                {
+                    removed.push(std::mem::replace(existing, prop));
+                } else {
+                    removed.push(prop);
+                }
+            } else {
+                kept.push((prop_key, prop));
+            }
+        }
+
+        self.formatted_name =
+            kept.into_iter().map(|(_, prop)| prop).collect();
+        removed
+    }
+
+    /// Merge this vCard with another version of the same vCard.
+    ///
+    /// Multi-valued properties are combined and identical values
+    /// are removed. Properties with a PID parameter that refer to
+    /// the same CLIENTPIDMAP URI are the same logical value and
+    /// only the version from the vCard with the later REV is kept.
+    ///
+    /// Single-valued properties (such as N, BDAY, REV and UID) are
+    /// taken from the vCard with the later REV. A vCard without
+    /// a REV is considered older and when neither vCard has a REV
+    /// this vCard is preferred.
+    ///
+    /// It is an error if the vCards have different UIDs or if
+    /// a client from the other vCard needs a new CLIENTPIDMAP
+    /// source identifier and all of 1 to 99 are in use.
+    pub fn merge(&self, other: &Vcard) -> Result<Vcard> {
+        crate::merge::merge(self, other)
+    }
+
+    /// Determine if this vCard has the same properties as another
+    /// vCard ignoring the order of the properties.
+    ///
+    /// Parameters are compared in a canonical form so the order of
+    /// TYPE values and extension parameters does not matter, TYPE
+    /// values are compared case-insensitively and an empty list
+    /// of parameters is the same as no parameters. Other values
+    /// are compared case-sensitively.
+    pub fn semantically_equals(&self, other: &Vcard) -> bool {
+        self.semantically_equals_with(other, &Default::default())
+    }
+
+    /// Determine if this vCard has the same properties as another
+    /// vCard using the given options.
+    pub fn semantically_equals_with(
+        &self,
+        other: &Vcard,
+        options: &CompareOptions,
+    ) -> bool {
+        crate::compare::property_keys(self, options)
+            == crate::compare::property_keys(other, options)
+    }
+
+    /// Compute a hash of the properties of this vCard.
+    ///
+    /// The hash is stable between releases and vCards that are
+    /// [semantically equal](Vcard::semantically_equals) have
+    /// the same hash.
+    pub fn content_hash(&self) -> u64 {
+        self.content_hash_with(&Default::default())
+    }
+
+    /// Compute a hash of the properties of this vCard using the
+    /// given options.
+    pub fn content_hash_with(&self, options: &CompareOptions) -> u64 {
+        crate::compare::content_hash(&crate::compare::property_keys(
+            self, options,
+        ))
+    }
+
+    /// Encode this vCard using the given options.
+    ///
+    /// The `Display` implementation uses the default options.
+    pub fn to_string_with(&self, options: &WriteOptions) -> String {
+        let mut encoded = String::new();
+        // Writing to a string is infallible
+        let _ = self.write_with(&mut encoded, options);
+        encoded
+    }
+
+    /// Key for sorting this vCard by name.
+    ///
+    /// The components of the key are the SORT-AS values of the
+    /// N property when present, otherwise the family, given and
+    /// additional names of the N property. When there are no
+    /// names the first formatted name (FN) is used. Components
+    /// are normalized and case folded so letter case is ignored.
+    pub fn sort_key(&self) -> SortKey {
+        SortKey::from(self)
+    }
+
+    /// Write this vCard to a writer.
+    ///
+    /// The encoding is the same as the `Display` implementation
+    /// but is streamed to the writer rather than collected
+    /// into a string.
+    pub fn to_writer(&self, mut writer: impl io::Write) -> io::Result<()> {
+        write!(writer, "{}", self)
+    }
+
+    /// Convert this vCard to a canonical form.
+    ///
+    /// Vcards that differ only in how they were encoded by a
+    /// producer have the same canonical form:
+    ///
+    /// * Extension property names and extension parameter names
+    ///   are converted to uppercase; other property and parameter
+    ///   names are always encoded in uppercase.
+    /// * Parameters are encoded in a fixed order and extension
+    ///   parameters are sorted by name.
+    /// * TYPE values are converted to lowercase, sorted and
+    ///   duplicates are removed.
+    /// * PREF values of each kind of property are renumbered
+    ///   from 1 keeping their relative order; equal preferences
+    ///   are ordered by the group, other parameters and value.
+    /// * Empty parameter lists are removed.
+    /// * Properties of the same kind are sorted by their encoded
+    ///   group, parameters and value; extensions are first
+    ///   sorted by name.
+    /// * Percent-encoded octets in `data:` URIs for PHOTO, LOGO,
+    ///   SOUND and KEY use uppercase hex digits.
+    ///
+    /// Groups and other values, including text, are unchanged
+    /// and the source order of the properties is discarded.
+    ///
+    /// Use [canonical_string](Vcard::canonical_string) to encode
+    /// the canonical form.
+    pub fn canonicalize(&mut self) {
+        crate::canonical::canonicalize(self)
+    }
+
+    /// Encode this vCard without folding lines.
+    ///
+    /// After [canonicalize](Vcard::canonicalize) the encoding is
+    /// stable so it can be hashed to compare vCards from
+    /// different producers.
+    ///
+    /// ```
+    /// use vcard4::parse;
+    /// let mut a = parse(
+    ///     "BEGIN:VCARD\nVERSION:4.0\nFN:Jane\nEMAIL;type=WORK,home:a@example.com\nEND:VCARD",
+    /// ).unwrap().remove(0);
+    /// let mut b = parse(
+    ///     "BEGIN:VCARD\nVERSION:4.0\nfn:Jane\nemail;TYPE=home;TYPE=work:a@example.com\nEND:VCARD",
+    /// ).unwrap().remove(0);
+    /// a.canonicalize();
+    /// b.canonicalize();
+    /// assert_eq!(a.canonical_string(), b.canonical_string());
+    /// ```
+    pub fn canonical_string(&self) -> String {
+        self.to_string_with(&WriteOptions::new().fold_width(None))
+    }
+
+    /// Encode this vCard keeping the content lines of the
+    /// source for properties that were not modified.
+    ///
+    /// When the vCard was parsed with
+    /// [ParseOptions::preserve_source](crate::ParseOptions::preserve_source)
+    /// the BEGIN, VERSION and END lines and the content line of
+    /// each property that still encodes the same way are written
+    /// exactly as they appeared in the source, including folds,
+    /// letter case and line endings. Modified and new properties
+    /// are encoded with the default options using the line ending
+    /// of the source.
+    ///
+    /// Properties are written in source order (see
+    /// [Vcard::properties_in_source_order]).
+    ///
+    /// ```
+    /// use vcard4::{parse_with_options, ParseOptions};
+    /// let input = "BEGIN:VCARD\nVERSION:4.0\nfn:Jane Doe\nNOTE:Original\nEND:VCARD\n";
+    /// let options = ParseOptions::new().preserve_source(true);
+    /// let mut card = parse_with_options(input, options).unwrap().remove(0);
+    /// card.note[0].value = "Changed".to_owned();
+    /// assert_eq!(
+    ///     "BEGIN:VCARD\nVERSION:4.0\nfn:Jane Doe\nNOTE:Changed\nEND:VCARD\n",
+    ///     card.to_string_preserving(),
+    /// );
+    /// ```
+    pub fn to_string_preserving(&self) -> String {
+        use crate::name::*;
+        let default;
+        let source = match &self.source_lines.0 {
+            Some(source) => source,
+            None => {
+                default = SourceText {
+                    head: format!("{}\r\n{}\r\n", BEGIN, VERSION_4),
+                    tail: END.to_owned(),
+                    line_ending: "\r\n".to_owned(),
+                    lines: Vec::new(),
+                };
+                &default
+            }
+        };
+
+        let eol = &source.line_ending;
+        let line_ending = if eol == "\n" {
+            LineEnding::Lf
+        } else {
+            LineEnding::CrLf
+        };
+        let options = WriteOptions::new().line_ending(line_ending);
+        let unfolded = WriteOptions::new().fold_width(None);
+
+        let mut used = vec![false; source.lines.len()];
+        let mut encoded = source.head.clone();
+        for prop in self.properties_in_source_order() {
+            let line =
+                content_line_with(prop.property(), prop.name(), &unfolded);
+            if let Some(index) = source.find(prop.kind(), &line, &used) {
+                used[index] = true;
+                encoded.push_str(&source.lines[index].raw);
+            } else {
+                encoded.push_str(&content_line_with(
+                    prop.property(),
+                    prop.name(),
+                    &options,
+                ));
+                encoded.push_str(eol);
+            }
+        }
+        encoded.push_str(&source.tail);
+        encoded.push_str(eol);
+        encoded
+    }
+
+    /// Encode this vCard as a payload for a QR code.
+    ///
+    /// Use [QrPayload::new] to also determine which properties
+    /// were dropped to fit the byte budget.
+    pub fn to_qr_payload(&self, format: QrFormat) -> Result<String> {
+        Ok(QrPayload::new(self, format)?.payload)
+    }
+
+    /// Encode this vCard as version 3.0.
+    ///
+    /// The conversion is lossy; use [V3Output::new] to also
+    /// determine which properties were dropped.
+    pub fn to_v3_string(&self) -> String {
+        V3Output::new(self, &Default::default()).encoded
+    }
 }
 
 impl TryFrom<&str> for Vcard {
@@ -335,185 +1468,356 @@ impl TryFrom<&str> for Vcard {
 
 impl fmt::Display for Vcard {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_with(f, &WriteOptions::default())
+    }
+}
+
+impl Vcard {
+    /// Properties in the order they are encoded with the
+    /// properties of each group moved next to the first
+    /// property of the group.
+    fn properties_by_group(&self) -> Vec<PropertyRef<'_>> {
+        let properties: Vec<PropertyRef<'_>> = self.properties().collect();
+        let group_of = |prop: &PropertyRef<'_>| {
+            prop.property()
+                .group()
+                .filter(|group| !group.is_empty())
+                .map(|group| group.to_lowercase())
+        };
+        let mut written = vec![false; properties.len()];
+        let mut ordered = Vec::with_capacity(properties.len());
+        for index in 0..properties.len() {
+            if written[index] {
+                continue;
+            }
+            let Some(group) = group_of(&properties[index]) else {
+                written[index] = true;
+                ordered.push(properties[index]);
+                continue;
+            };
+            // Extensions are last so the properties of the
+            // group are already in the expected order
+            for (other, prop) in properties.iter().enumerate().skip(index) {
+                if !written[other] && group_of(prop).as_ref() == Some(&group)
+                {
+                    written[other] = true;
+                    ordered.push(*prop);
+                }
+            }
+        }
+        ordered
+    }
+
+    /// Write this vCard using the given options.
+    fn write_with(
+        &self,
+        f: &mut impl fmt::Write,
+        options: &WriteOptions,
+    ) -> fmt::Result {
         use crate::name::*;
-        write!(f, "{}\r\n{}\r\n", BEGIN, VERSION_4)?;
+        let eol = options.line_ending.as_str();
+        let line = |prop: &dyn Property, name: &str| {
+            content_line_with(prop, name, options)
+        };
+        write!(f, "{}{}{}{}", BEGIN, eol, VERSION_4, eol)?;
+
+        if options.group_properties {
+            for prop in self.properties_by_group() {
+                write!(f, "{}{}", line(prop.property(), prop.name()), eol)?;
+            }
+            return write!(f, "{}{}", END, eol);
+        }
 
         // General
         for val in &self.source {
-            write!(f, "{}\r\n", content_line(val, SOURCE))?;
+            write!(f, "{}{}", line(val, SOURCE), eol)?;
         }
         if let Some(val) = &self.kind {
-            write!(f, "{}\r\n", content_line(val, KIND))?;
+            write!(f, "{}{}", line(val, KIND), eol)?;
         }
         for val in &self.xml {
-            write!(f, "{}\r\n", content_line(val, XML))?;
+            write!(f, "{}{}", line(val, XML), eol)?;
         }
 
         // Identification
         for val in &self.formatted_name {
-            write!(f, "{}\r\n", content_line(val, FN))?;
+            write!(f, "{}{}", line(val, FN), eol)?;
         }
         if let Some(val) = &self.name {
-            write!(f, "{}\r\n", content_line(val, N))?;
+            write!(f, "{}{}", line(val, N), eol)?;
         }
         for val in &self.nickname {
-            write!(f, "{}\r\n", content_line(val, NICKNAME))?;
+            write!(f, "{}{}", line(val, NICKNAME), eol)?;
         }
         for val in &self.photo {
-            write!(f, "{}\r\n", content_line(val, PHOTO))?;
+            write!(f, "{}{}", line(val, PHOTO), eol)?;
         }
         if let Some(val) = &self.bday {
-            write!(f, "{}\r\n", content_line(val, BDAY))?;
+            write!(f, "{}{}", line(val, BDAY), eol)?;
         }
         if let Some(val) = &self.anniversary {
-            write!(f, "{}\r\n", content_line(val, ANNIVERSARY))?;
+            write!(f, "{}{}", line(val, ANNIVERSARY), eol)?;
+        }
+        if let Some(val) = &self.birthplace {
+            write!(f, "{}{}", line(val, BIRTHPLACE), eol)?;
+        }
+        if let Some(val) = &self.deathplace {
+            write!(f, "{}{}", line(val, DEATHPLACE), eol)?;
+        }
+        if let Some(val) = &self.deathdate {
+            write!(f, "{}{}", line(val, DEATHDATE), eol)?;
+        }
+        for val in &self.gender {
+            write!(f, "{}{}", line(val, GENDER), eol)?;
         }
-        if let Some(val) = &self.gender {
-            write!(f, "{}\r\n", content_line(val, GENDER))?;
+        for val in &self.pronouns {
+            write!(f, "{}{}", line(val, PRONOUNS), eol)?;
         }
         for val in &self.url {
-            write!(f, "{}\r\n", content_line(val, URL))?;
+            write!(f, "{}{}", line(val, URL), eol)?;
         }
 
         // Delivery Addressing
         for val in &self.address {
-            write!(f, "{}\r\n", content_line(val, ADR))?;
+            write!(f, "{}{}", line(val, ADR), eol)?;
         }
 
         // Organizational
         for val in &self.title {
-            write!(f, "{}\r\n", content_line(val, TITLE))?;
+            write!(f, "{}{}", line(val, TITLE), eol)?;
         }
         for val in &self.role {
-            write!(f, "{}\r\n", content_line(val, ROLE))?;
+            write!(f, "{}{}", line(val, ROLE), eol)?;
         }
         for val in &self.logo {
-            write!(f, "{}\r\n", content_line(val, LOGO))?;
+            write!(f, "{}{}", line(val, LOGO), eol)?;
         }
         for val in &self.org {
-            write!(f, "{}\r\n", content_line(val, ORG))?;
+            write!(f, "{}{}", line(val, ORG), eol)?;
         }
         for val in &self.member {
-            write!(f, "{}\r\n", content_line(val, MEMBER))?;
+            write!(f, "{}{}", line(val, MEMBER), eol)?;
         }
         for val in &self.related {
-            write!(f, "{}\r\n", content_line(val, RELATED))?;
+            write!(f, "{}{}", line(val, RELATED), eol)?;
+        }
+        for val in &self.expertise {
+            write!(f, "{}{}", line(val, EXPERTISE), eol)?;
+        }
+        for val in &self.hobby {
+            write!(f, "{}{}", line(val, HOBBY), eol)?;
+        }
+        for val in &self.interest {
+            write!(f, "{}{}", line(val, INTEREST), eol)?;
+        }
+        for val in &self.org_directory {
+            write!(f, "{}{}", line(val, ORG_DIRECTORY), eol)?;
         }
 
         // Communications
         for val in &self.tel {
-            write!(f, "{}\r\n", content_line(val, TEL))?;
+            write!(f, "{}{}", line(val, TEL), eol)?;
         }
         for val in &self.email {
-            write!(f, "{}\r\n", content_line(val, EMAIL))?;
+            write!(f, "{}{}", line(val, EMAIL), eol)?;
         }
         for val in &self.impp {
-            write!(f, "{}\r\n", content_line(val, IMPP))?;
+            write!(f, "{}{}", line(val, IMPP), eol)?;
+        }
+        for val in &self.social_profile {
+            write!(f, "{}{}", line(val, SOCIALPROFILE), eol)?;
         }
         for val in &self.lang {
-            write!(f, "{}\r\n", content_line(val, LANG))?;
+            write!(f, "{}{}", line(val, LANG), eol)?;
+        }
+        if let Some(val) = &self.language {
+            write!(f, "{}{}", line(val, LANGUAGE), eol)?;
         }
 
         // Geographic
         for val in &self.timezone {
-            write!(f, "{}\r\n", content_line(val, TZ))?;
+            write!(f, "{}{}", line(val, TZ), eol)?;
         }
         for val in &self.geo {
-            write!(f, "{}\r\n", content_line(val, GEO))?;
+            write!(f, "{}{}", line(val, GEO), eol)?;
         }
 
         // Explanatory
         for val in &self.categories {
-            write!(f, "{}\r\n", content_line(val, CATEGORIES))?;
+            write!(f, "{}{}", line(val, CATEGORIES), eol)?;
         }
         for val in &self.note {
-            write!(f, "{}\r\n", content_line(val, NOTE))?;
+            write!(f, "{}{}", line(val, NOTE), eol)?;
         }
         if let Some(val) = &self.prod_id {
-            write!(f, "{}\r\n", content_line(val, PRODID))?;
+            write!(f, "{}{}", line(val, PRODID), eol)?;
         }
         if let Some(val) = &self.rev {
-            write!(f, "{}\r\n", content_line(val, REV))?;
+            write!(f, "{}{}", line(val, REV), eol)?;
+        }
+        if let Some(val) = &self.created {
+            write!(f, "{}{}", line(val, CREATED), eol)?;
         }
         for val in &self.sound {
-            write!(f, "{}\r\n", content_line(val, SOUND))?;
+            write!(f, "{}{}", line(val, SOUND), eol)?;
         }
         if let Some(val) = &self.uid {
-            write!(f, "{}\r\n", content_line(val, UID))?;
+            write!(f, "{}{}", line(val, UID), eol)?;
         }
         for val in &self.client_pid_map {
-            write!(f, "{}\r\n", content_line(val, CLIENTPIDMAP))?;
+            write!(f, "{}{}", line(val, CLIENTPIDMAP), eol)?;
         }
 
         // Security
         for val in &self.key {
-            write!(f, "{}\r\n", content_line(val, KEY))?;
+            write!(f, "{}{}", line(val, KEY), eol)?;
         }
 
         // Calendar
         for val in &self.fburl {
-            write!(f, "{}\r\n", content_line(val, FBURL))?;
+            write!(f, "{}{}", line(val, FBURL), eol)?;
         }
         for val in &self.cal_adr_uri {
-            write!(f, "{}\r\n", content_line(val, CALADRURI))?;
+            write!(f, "{}{}", line(val, CALADRURI), eol)?;
         }
         for val in &self.cal_uri {
-            write!(f, "{}\r\n", content_line(val, CALURI))?;
+            write!(f, "{}{}", line(val, CALURI), eol)?;
         }
 
         // Private property extensions
         for val in &self.extensions {
-            write!(f, "{}\r\n", content_line(val, &val.name))?;
+            write!(f, "{}{}", line(val, &val.name), eol)?;
         }
 
-        write!(f, "{}\r\n", END)
+        write!(f, "{}{}", END, eol)
     }
 }
 
+/// Sort properties by PREF; properties without a PREF are
+/// last and the sort is stable so ties keep their order.
+fn by_pref<'a, T: Property + 'a>(
+    props: impl Iterator<Item = &'a T>,
+) -> std::vec::IntoIter<&'a T> {
+    let mut props: Vec<&'a T> = props.collect();
+    props.sort_by_key(|prop| {
+        let pref = prop.parameters().and_then(|params| params.pref);
+        (pref.is_none(), pref)
+    });
+    props.into_iter()
+}
+
 /// Get a content line.
-fn content_line(prop: &impl Property, prop_name: &str) -> String {
+pub(crate) fn content_line(
+    prop: &(impl Property + ?Sized),
+    prop_name: &str,
+) -> String {
+    content_line_with(prop, prop_name, &WriteOptions::default())
+}
+
+/// Get a content line using the given options.
+pub(crate) fn content_line_with(
+    prop: &(impl Property + ?Sized),
+    prop_name: &str,
+    options: &WriteOptions,
+) -> String {
     let name = qualified_name(prop, prop_name);
 
-    let params = if let Some(params) = prop.parameters() {
+    let params = if let Some(params) = prop.output_parameters() {
         params.to_string()
     } else {
         String::new()
     };
 
-    // Handle escape sequences
+    // Values are escaped by the property implementations
     let value = prop.to_string();
-    /*
-    let value = value
-        .replace('\\', "\\\\")
-        .replace('\n', "\\n");
-    */
 
     let line = format!("{}{}:{}", name, params, value);
-    fold_line(line, 75)
+    if let Some(wrap_at) = options.fold_width {
+        fold_line(line, wrap_at, options.line_ending.as_str())
+    } else {
+        line
+    }
 }
 
-fn fold_line(line: String, wrap_at: usize) -> String {
+#[cfg(feature = "unicode-segmentation")]
+pub(crate) fn fold_line(line: String, wrap_at: usize, eol: &str) -> String {
     use unicode_segmentation::UnicodeSegmentation;
+    fold_segments(
+        UnicodeSegmentation::graphemes(&line[..], true),
+        wrap_at,
+        eol,
+    )
+}
+
+// Without grapheme segmentation fold on character boundaries
+// so that a multi-byte UTF-8 sequence is never split.
+#[cfg(not(feature = "unicode-segmentation"))]
+pub(crate) fn fold_line(line: String, wrap_at: usize, eol: &str) -> String {
+    fold_segments(
+        line.char_indices()
+            .map(|(index, c)| &line[index..index + c.len_utf8()]),
+        wrap_at,
+        eol,
+    )
+}
+
+/// Fold a line so that no physical line exceeds `wrap_at` octets.
+///
+/// Folds are only inserted between segments; the leading space
+/// of a continuation line counts towards the line length. A
+/// segment that is too long for a line on its own is folded
+/// on character boundaries.
+fn fold_segments<'a>(
+    segments: impl Iterator<Item = &'a str>,
+    wrap_at: usize,
+    eol: &str,
+) -> String {
+    // Continuation lines must fit a space and any character
+    let wrap_at = wrap_at.max(5);
     let mut length = 0;
     let mut folded_line = String::new();
-    for grapheme in UnicodeSegmentation::graphemes(&line[..], true) {
-        length += grapheme.len();
-        if length % wrap_at == 0 {
-            folded_line.push_str("\r\n ");
+    for segment in segments {
+        if length + segment.len() > wrap_at {
+            if segment.len() < wrap_at {
+                folded_line.push_str(eol);
+                folded_line.push(' ');
+                length = 1;
+            } else {
+                for c in segment.chars() {
+                    if length + c.len_utf8() > wrap_at {
+                        folded_line.push_str(eol);
+                        folded_line.push(' ');
+                        length = 1;
+                    }
+                    folded_line.push(c);
+                    length += c.len_utf8();
+                }
+                continue;
+            }
         }
-        folded_line.push_str(grapheme);
+        folded_line.push_str(segment);
+        length += segment.len();
     }
     folded_line
 }
 
 /// Get the fully qualified name including any group.
-fn qualified_name<'a>(
-    prop: &impl Property,
+///
+/// A group with characters that are not allowed in a group
+/// name is omitted so the content line can be parsed; an empty
+/// group is omitted.
+pub(crate) fn qualified_name<'a>(
+    prop: &(impl Property + ?Sized),
     prop_name: &'a str,
 ) -> Cow<'a, str> {
-    if let Some(group) = prop.group() {
-        Cow::Owned(format!("{}.{}", group, prop_name))
-    } else {
-        Cow::Borrowed(prop_name)
+    match prop.group() {
+        Some(group) if is_group_name(group) => {
+            Cow::Owned(format!("{}.{}", group, prop_name))
+        }
+        Some(group) if !group.is_empty() => {
+            warn_event!(group, "invalid property group omitted");
+            Cow::Borrowed(prop_name)
+        }
+        _ => Cow::Borrowed(prop_name),
     }
 }
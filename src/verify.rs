@@ -0,0 +1,239 @@
+//! Check the structure of vCards without building them.
+use logos::{Lexer, Logos};
+use std::ops::Range;
+
+use crate::{
+    error::snippet,
+    escape_control,
+    name::FN,
+    parser::{assert_token, iana_name_len, skip_line, LexResult, Token},
+    unfold::Unfolded,
+    Error, Result,
+};
+
+/// Report for a single vCard found by [verify()].
+#[derive(Debug)]
+pub struct CardReport<'s> {
+    /// Byte range of the vCard in the source.
+    pub span: Range<usize>,
+    /// The first formatted name (FN) as written in the source.
+    ///
+    /// Escape sequences are not removed and a folded value
+    /// includes the folds.
+    pub formatted_name: Option<&'s str>,
+    /// The error for the vCard, if any.
+    pub error: Option<Error>,
+}
+
+/// Check the structure of the vCards in a source without
+/// building them.
+///
+/// The content lines of each vCard are scanned to verify the
+/// BEGIN, VERSION and END delimiters, property and parameter
+/// names, quoting, control characters and that a formatted name
+/// is present. Property values such as dates and URIs are not
+/// parsed so a vCard without an error may still fail to parse.
+///
+/// In strict mode an invalid property is an error for the vCard;
+/// otherwise it is skipped as when parsing in loose mode.
+///
+/// After an error the remainder of the vCard up to the next
+/// END delimiter is skipped so later vCards are still checked.
+///
+/// ```
+/// use vcard4::verify;
+/// let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nEND:VCARD\n\
+///     BEGIN:VCARD\nVERSION:4.0\nNOTE:Unnamed\nEND:VCARD\n";
+/// let reports = verify(input, true);
+/// assert_eq!(2, reports.len());
+/// assert_eq!(Some("Jane Doe"), reports[0].formatted_name);
+/// assert!(reports[0].error.is_none());
+/// assert!(reports[1].error.is_some());
+/// ```
+pub fn verify(source: &str, strict: bool) -> Vec<CardReport<'_>> {
+    let unfolded = Unfolded::new(source);
+    // A trailing carriage return terminates the last line
+    let mut lex = Token::lexer(unfolded.text.trim_end_matches('\r'));
+    let mut reports = Vec::new();
+
+    while let Some(first) = lex.next() {
+        // Allow leading newlines and newlines between
+        // vCard definitions
+        if first == Ok(Token::NewLine) {
+            continue;
+        }
+
+        let start = lex.span().start;
+        let begin = first == Ok(Token::Begin);
+        let mut formatted_name = None;
+        let error =
+            verify_card(&mut lex, first, strict, &mut formatted_name).err();
+        if error.is_some() {
+            if begin {
+                skip_card(&mut lex);
+            } else {
+                skip_line(&mut lex);
+            }
+        }
+        reports.push(CardReport {
+            span: unfolded.source_range(start..lex.span().end),
+            formatted_name: formatted_name
+                .map(|range| &source[unfolded.source_range(range)]),
+            error,
+        });
+    }
+
+    reports
+}
+
+/// Check a single vCard.
+///
+/// The range of the first formatted name is assigned
+/// even when the vCard has an error.
+fn verify_card(
+    lex: &mut Lexer<'_, Token>,
+    first: LexResult<Token>,
+    strict: bool,
+    formatted_name: &mut Option<Range<usize>>,
+) -> Result<()> {
+    assert_token(Some(&first), &[Token::Begin])?;
+    assert_token(lex.next().as_ref(), &[Token::NewLine])?;
+    assert_token(lex.next().as_ref(), &[Token::Version])?;
+    assert_token(lex.next().as_ref(), &[Token::NewLine])?;
+
+    while let Some(first) = lex.next() {
+        if first == Ok(Token::End) {
+            break;
+        }
+        if let Ok(Token::Version) = first {
+            return Err(Error::VersionMisplaced);
+        }
+
+        match verify_property(lex, first) {
+            Ok((name, value)) => {
+                let name = name.rsplit('.').next().unwrap_or(name);
+                if formatted_name.is_none() && name.eq_ignore_ascii_case(FN) {
+                    *formatted_name = Some(value);
+                }
+            }
+            Err(e) => {
+                if strict {
+                    return Err(e);
+                }
+                skip_line(lex);
+            }
+        }
+    }
+
+    if formatted_name.is_none() {
+        return Err(Error::NoFormattedName);
+    }
+    Ok(())
+}
+
+/// Check a content line returning the property name and
+/// the range of the value.
+fn verify_property<'s>(
+    lex: &mut Lexer<'s, Token>,
+    first: LexResult<Token>,
+) -> Result<(&'s str, Range<usize>)> {
+    // Names not known to the lexer may be IANA tokens
+    if let Some(len) = iana_name_len(lex, &first) {
+        lex.bump(len - lex.slice().len());
+    } else {
+        assert_token(
+            Some(&first),
+            &[
+                Token::PropertyName,
+                Token::ExtensionName,
+                Token::TimeZone,
+                Token::Geo,
+            ],
+        )?;
+    }
+    let name = lex.slice();
+
+    let delimiter = lex.next();
+    assert_token(
+        delimiter.as_ref(),
+        &[Token::ParameterDelimiter, Token::PropertyDelimiter],
+    )?;
+    if delimiter == Some(Ok(Token::ParameterDelimiter)) {
+        verify_parameters(lex)?;
+    }
+
+    let start = lex.span().end;
+    let mut end = lex.source().len();
+    while let Some(token) = lex.next() {
+        if token == Ok(Token::NewLine) {
+            end = lex.span().start;
+            break;
+        }
+        verify_control(lex, &token)?;
+    }
+    Ok((name, start..end))
+}
+
+/// Check the parameters up to the property delimiter.
+fn verify_parameters(lex: &mut Lexer<'_, Token>) -> Result<()> {
+    loop {
+        let token = lex.next();
+        // Empty parameters are ignored
+        if token == Some(Ok(Token::ParameterDelimiter)) {
+            continue;
+        } else if token == Some(Ok(Token::PropertyDelimiter)) {
+            return Ok(());
+        }
+        assert_token(
+            token.as_ref(),
+            &[
+                Token::ParameterKey,
+                Token::ExtensionName,
+                Token::TimeZone,
+                Token::Geo,
+            ],
+        )?;
+        assert_token(lex.next().as_ref(), &[Token::ValueDelimiter])?;
+
+        let mut quoted = false;
+        loop {
+            let token = lex.next();
+            match token {
+                None | Some(Ok(Token::NewLine)) => {
+                    return Err(Error::TokenExpected);
+                }
+                Some(Ok(Token::DoubleQuote)) => quoted = !quoted,
+                Some(Ok(Token::ParameterDelimiter)) if !quoted => break,
+                Some(Ok(Token::PropertyDelimiter)) if !quoted => {
+                    return Ok(());
+                }
+                Some(token) => verify_control(lex, &token)?,
+            }
+        }
+    }
+}
+
+/// Error when the current token is a control character.
+fn verify_control(
+    lex: &Lexer<'_, Token>,
+    token: &LexResult<Token>,
+) -> Result<()> {
+    if token == &Ok(Token::Control) {
+        return Err(Error::ControlCharacter(snippet(escape_control(
+            lex.slice(),
+        ))));
+    }
+    Ok(())
+}
+
+/// Skip the remainder of a vCard after an error.
+fn skip_card(lex: &mut Lexer<'_, Token>) {
+    if lex.slice().eq_ignore_ascii_case("END:VCARD") {
+        return;
+    }
+    for token in lex.by_ref() {
+        if token == Ok(Token::End) {
+            break;
+        }
+    }
+}
@@ -0,0 +1,421 @@
+//! Convert to and from xCard.
+//!
+//! xCard is the XML format for vCard data defined by
+//! [RFC6351](https://www.rfc-editor.org/rfc/rfc6351); property
+//! values are wrapped in an element named for the value type and
+//! grouped properties are nested in a `<group>` element.
+//!
+//! The conversion shares the value mapping used for
+//! [jCard](crate::jcard) as both formats are based on the same
+//! data model.
+//!
+//! Elements in other namespaces are stored in the XML property
+//! and the XML property is embedded verbatim when writing; use
+//! [TextProperty::xml_root] to check a value before embedding it.
+//!
+//! ```
+//! use vcard4::{xcard, Vcard};
+//! let card = Vcard::new("Jane Doe".to_owned());
+//! let xml = xcard::to_xml(&card);
+//! assert!(xml.contains("<fn><text>Jane Doe</text></fn>"));
+//! let decoded = xcard::from_xml(&xml).unwrap();
+//! assert_eq!(vec![card], decoded);
+//! ```
+use roxmltree::{Document, Node};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::snippet, jcard, property::TextProperty, Error, Result, Vcard,
+};
+
+/// Namespace for xCard elements.
+pub const NAMESPACE: &str = "urn:ietf:params:xml:ns:vcard-4.0";
+
+const VCARDS: &str = "vcards";
+const VCARD: &str = "vcard";
+const GROUP: &str = "group";
+const NAME: &str = "name";
+const PARAMETERS: &str = "parameters";
+const XML: &str = "xml";
+const VERSION: &str = "version";
+
+const N_COMPONENTS: [&str; 5] =
+    ["surname", "given", "additional", "prefix", "suffix"];
+const ADR_COMPONENTS: [&str; 7] = [
+    "pobox", "ext", "street", "locality", "region", "code", "country",
+];
+const GENDER_COMPONENTS: [&str; 2] = ["sex", "identity"];
+const CLIENTPIDMAP_COMPONENTS: [&str; 2] = ["sourceid", "uri"];
+
+/// Root element of the value of an XML property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlRootInfo {
+    /// Local name of the root element.
+    pub name: String,
+    /// Namespace of the root element, if any.
+    pub namespace: Option<String>,
+}
+
+impl TextProperty {
+    /// Check the value of an XML property and find the
+    /// root element.
+    ///
+    /// The value must be well-formed XML with a single root
+    /// element, must not contain a DTD and the root element
+    /// must not be in the vCard namespace
+    /// ([RFC6350 6.1.5](https://www.rfc-editor.org/rfc/rfc6350#section-6.1.5)).
+    ///
+    /// ```
+    /// use vcard4::property::TextProperty;
+    /// let value = r#"<a xmlns="http://www.w3.org/1999/xhtml">Jane</a>"#;
+    /// let prop = TextProperty::from(value.to_owned());
+    /// let root = prop.xml_root().unwrap();
+    /// assert_eq!("a", root.name);
+    /// assert_eq!(
+    ///     Some("http://www.w3.org/1999/xhtml"),
+    ///     root.namespace.as_deref(),
+    /// );
+    /// ```
+    pub fn xml_root(&self) -> Result<XmlRootInfo> {
+        let document = Document::parse(&self.value)?;
+        let root = document.root_element();
+        let namespace = root.tag_name().namespace();
+        if namespace == Some(NAMESPACE) {
+            let pos = document.text_pos_at(root.range().start);
+            return Err(Error::XmlVcardNamespace {
+                row: pos.row,
+                col: pos.col,
+            });
+        }
+        Ok(XmlRootInfo {
+            name: root.tag_name().name().to_owned(),
+            namespace: namespace.map(str::to_owned),
+        })
+    }
+}
+
+/// Convert a vCard to an xCard document.
+pub fn to_xml(card: &Vcard) -> String {
+    let jcard = jcard::to_jcard(card);
+    let properties = jcard[1].as_array().map(Vec::as_slice).unwrap_or(&[]);
+
+    let mut xml =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!("<{} xmlns=\"{}\">\n", VCARDS, NAMESPACE));
+    xml.push_str(&format!("  <{}>\n", VCARD));
+
+    let mut groups: Vec<(&str, Vec<&Value>)> = Vec::new();
+    let mut ungrouped = Vec::new();
+    for prop in properties {
+        match prop[1][GROUP].as_str() {
+            Some(group) => {
+                if let Some((_, props)) =
+                    groups.iter_mut().find(|(name, _)| *name == group)
+                {
+                    props.push(prop);
+                } else {
+                    groups.push((group, vec![prop]));
+                }
+            }
+            None => ungrouped.push(prop),
+        }
+    }
+
+    for prop in ungrouped {
+        write_property(&mut xml, prop, "    ");
+    }
+
+    for (group, props) in groups {
+        xml.push_str(&format!(
+            "    <{} {}=\"{}\">\n",
+            GROUP,
+            NAME,
+            escape_xml(group)
+        ));
+        for prop in props {
+            write_property(&mut xml, prop, "      ");
+        }
+        xml.push_str(&format!("    </{}>\n", GROUP));
+    }
+
+    xml.push_str(&format!("  </{}>\n", VCARD));
+    xml.push_str(&format!("</{}>\n", VCARDS));
+    xml
+}
+
+/// Parse an xCard document into vCards.
+pub fn from_xml(input: &str) -> Result<Vec<Vcard>> {
+    let document = Document::parse(input)?;
+    let root = document.root_element();
+    if !is_vcard_element(&root, VCARDS) {
+        return Err(invalid("expected a vcards element"));
+    }
+
+    let mut cards = Vec::new();
+    for vcard in root.children().filter(Node::is_element) {
+        if !is_vcard_element(&vcard, VCARD) {
+            return Err(invalid("expected a vcard element"));
+        }
+
+        let mut properties = Vec::new();
+        for node in vcard.children().filter(Node::is_element) {
+            if is_vcard_element(&node, GROUP) {
+                let group = node
+                    .attribute(NAME)
+                    .ok_or_else(|| invalid("group must have a name"))?;
+                for node in node.children().filter(Node::is_element) {
+                    properties.push(property(input, &node, Some(group))?);
+                }
+            } else {
+                properties.push(property(input, &node, None)?);
+            }
+        }
+
+        let jcard =
+            Value::Array(vec![VCARD.into(), Value::Array(properties)]);
+        cards.push(jcard::from_value(&jcard)?);
+    }
+    Ok(cards)
+}
+
+/// Write a jCard property as an xCard property element.
+fn write_property(xml: &mut String, prop: &Value, indent: &str) {
+    let (Some(name), Some(value_type), Some(parameters)) =
+        (prop[0].as_str(), prop[2].as_str(), prop[1].as_object())
+    else {
+        return;
+    };
+    let values = prop.as_array().map(|v| &v[3..]).unwrap_or(&[]);
+
+    if name == VERSION {
+        return;
+    }
+
+    xml.push_str(indent);
+    if name == XML {
+        // Embedded verbatim
+        if let Some(value) = values.first().and_then(Value::as_str) {
+            xml.push_str(value);
+        }
+        xml.push('\n');
+        return;
+    }
+
+    xml.push_str(&format!("<{}>", name));
+
+    if parameters.keys().any(|key| key != GROUP) {
+        xml.push_str(&format!("<{}>", PARAMETERS));
+        for (key, value) in parameters {
+            if key == GROUP {
+                continue;
+            }
+            let value_type = match &key[..] {
+                "language" => "language-tag",
+                "pref" => "integer",
+                "geo" => "uri",
+                "tz" if value.as_str().is_some_and(|v| v.contains(':')) => {
+                    "uri"
+                }
+                _ => "text",
+            };
+            xml.push_str(&format!("<{}>", key));
+            let values = match value {
+                Value::Array(values) => values.iter().collect(),
+                value => vec![value],
+            };
+            for value in values {
+                write_value(xml, value_type, &scalar(value));
+            }
+            xml.push_str(&format!("</{}>", key));
+        }
+        xml.push_str(&format!("</{}>", PARAMETERS));
+    }
+
+    match (components(name), values) {
+        (Some(names), [Value::Array(parts)]) => {
+            for (name, part) in names.iter().zip(parts) {
+                match part {
+                    Value::Array(values) => {
+                        for value in values {
+                            write_value(xml, name, &scalar(value));
+                        }
+                    }
+                    value => write_value(xml, name, &scalar(value)),
+                }
+            }
+        }
+        (Some(names), [value]) if name == "gender" => {
+            write_value(xml, names[0], &scalar(value));
+        }
+        _ if name == "org" => {
+            for value in values {
+                match value {
+                    Value::Array(values) => {
+                        for value in values {
+                            write_value(xml, "text", &scalar(value));
+                        }
+                    }
+                    value => write_value(xml, "text", &scalar(value)),
+                }
+            }
+        }
+        _ => {
+            for value in values {
+                let value = jcard::basic_value(value_type, &scalar(value));
+                write_value(xml, value_type, &value);
+            }
+        }
+    }
+
+    xml.push_str(&format!("</{}>\n", name));
+}
+
+fn write_value(xml: &mut String, element: &str, value: &str) {
+    if value.is_empty() {
+        xml.push_str(&format!("<{}/>", element));
+    } else {
+        xml.push_str(&format!(
+            "<{}>{}</{}>",
+            element,
+            escape_xml(value),
+            element
+        ));
+    }
+}
+
+/// Convert an xCard property element to a jCard property.
+fn property(
+    input: &str,
+    node: &Node<'_, '_>,
+    group: Option<&str>,
+) -> Result<Value> {
+    let mut parameters = Map::new();
+    if let Some(group) = group {
+        parameters.insert(GROUP.to_owned(), group.into());
+    }
+
+    // Elements in other namespaces are stored in the XML property
+    if node.tag_name().namespace() != Some(NAMESPACE) {
+        return Ok(Value::Array(vec![
+            XML.into(),
+            Value::Object(parameters),
+            "text".into(),
+            input[node.range()].into(),
+        ]));
+    }
+
+    let name = node.tag_name().name();
+    let mut value_type = None;
+    let mut values = Vec::new();
+    for child in node.children().filter(Node::is_element) {
+        let child_name = child.tag_name().name();
+        if child_name == PARAMETERS {
+            for param in child.children().filter(Node::is_element) {
+                let param_values: Vec<Value> = param
+                    .children()
+                    .filter(Node::is_element)
+                    .map(|value| text(&value).into())
+                    .collect();
+                parameters.insert(
+                    param.tag_name().name().to_owned(),
+                    single_or_array(param_values),
+                );
+            }
+        } else {
+            value_type.get_or_insert(child_name);
+            values.push((child_name, text(&child)));
+        }
+    }
+
+    let value_type = value_type.ok_or_else(|| {
+        invalid(format!("property '{}' has no value", snippet(name)))
+    })?;
+
+    let values = if let Some(names) = components(name) {
+        let mut parts: Vec<Value> = names
+            .iter()
+            .map(|name| {
+                single_or_array(
+                    values
+                        .iter()
+                        .filter(|(child, _)| child == name)
+                        .map(|(_, value)| value.clone().into())
+                        .collect(),
+                )
+            })
+            .collect();
+        let identity = GENDER_COMPONENTS[1];
+        if name == "gender" && !values.iter().any(|(c, _)| *c == identity) {
+            vec![parts.remove(0)]
+        } else {
+            vec![Value::Array(parts)]
+        }
+    } else if name == "org" {
+        let parts = values.into_iter().map(|(_, v)| v.into()).collect();
+        vec![Value::Array(parts)]
+    } else {
+        values.into_iter().map(|(_, v)| v.into()).collect()
+    };
+
+    let value_type = if components(name).is_some() || name == "org" {
+        "text"
+    } else {
+        value_type
+    };
+
+    let mut property =
+        vec![name.into(), Value::Object(parameters), value_type.into()];
+    property.extend(values);
+    Ok(Value::Array(property))
+}
+
+/// Names of the elements for a structured property.
+fn components(name: &str) -> Option<&'static [&'static str]> {
+    match name {
+        "n" => Some(&N_COMPONENTS),
+        "adr" => Some(&ADR_COMPONENTS),
+        "gender" => Some(&GENDER_COMPONENTS),
+        "clientpidmap" => Some(&CLIENTPIDMAP_COMPONENTS),
+        _ => None,
+    }
+}
+
+/// Single values are a string; missing values are an empty string.
+fn single_or_array(mut values: Vec<Value>) -> Value {
+    match values.len() {
+        0 => "".into(),
+        1 => values.remove(0),
+        _ => Value::Array(values),
+    }
+}
+
+fn is_vcard_element(node: &Node<'_, '_>, name: &str) -> bool {
+    node.tag_name().namespace() == Some(NAMESPACE)
+        && node.tag_name().name() == name
+}
+
+fn text(node: &Node<'_, '_>) -> String {
+    node.descendants()
+        .filter(Node::is_text)
+        .filter_map(|node| node.text())
+        .collect()
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(val) => val.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidXcard(message.into())
+}
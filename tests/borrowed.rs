@@ -0,0 +1,83 @@
+use anyhow::Result;
+use std::borrow::Cow;
+use vcard4::{parse, parse_borrowed, Error};
+
+#[test]
+fn borrowed_values() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;LANGUAGE=en:Jane Doe
+N:Doe;Jane;Mary,Ann;Dr.;
+work.EMAIL;TYPE=work:jane@example.com
+ORG:ABC\, Inc.;North American Division
+CATEGORIES:travel,work
+NOTE:First line\nSecond line
+TEL:+1-555-555-5555
+END:VCARD"#;
+    let card = parse_borrowed(input)?.remove(0);
+
+    assert!(matches!(card.formatted_name[0].value, Cow::Borrowed(_)));
+    assert!(card.formatted_name[0].parameters.is_some());
+    let name = card.name.as_ref().unwrap();
+    assert_eq!(vec!["Mary", "Ann"], name.value[2]);
+    assert!(name.value[4].is_empty());
+    assert_eq!(Some("work"), card.email[0].group.as_deref());
+    assert!(matches!(card.email[0].value, Cow::Borrowed(_)));
+    assert_eq!(
+        vec!["ABC, Inc.", "North American Division"],
+        card.org[0].value
+    );
+    assert!(matches!(card.categories[0].value[0], Cow::Borrowed(_)));
+    assert_eq!("First line\nSecond line", card.note[0].value);
+
+    let card = card.into_owned();
+    let expected = parse(input)?.remove(0);
+    assert_eq!(expected.formatted_name, card.formatted_name);
+    assert_eq!(expected.name, card.name);
+    assert_eq!(expected.email, card.email);
+    assert_eq!(expected.org, card.org);
+    assert_eq!(expected.categories, card.categories);
+    assert_eq!(expected.note, card.note);
+    assert!(card.tel.is_empty());
+    Ok(())
+}
+
+#[test]
+fn borrowed_folded() -> Result<()> {
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n  Doe\r\n\
+        CATEGORIES:a,b\r\nEND:VCARD\r\n";
+    let card = parse_borrowed(input)?.remove(0);
+    assert_eq!("Jane Doe", card.formatted_name[0].value);
+    assert_eq!(vec!["a", "b"], card.categories[0].value);
+    assert!(matches!(card.formatted_name[0].value, Cow::Owned(_)));
+    assert!(card.categories[0]
+        .value
+        .iter()
+        .all(|value| matches!(value, Cow::Borrowed(_))));
+
+    // Lines without folds borrow when another line is folded
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\n\
+        PHOTO:data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAAB\r\n \
+        CAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==\r\n\
+        group.NOTE:Unfolded note\r\nEND:VCARD\r\n";
+    let card = parse_borrowed(input)?.remove(0);
+    assert!(matches!(card.note[0].value, Cow::Borrowed("Unfolded note")));
+    assert!(matches!(card.note[0].group, Some(Cow::Borrowed("group"))));
+    assert!(matches!(
+        card.formatted_name[0].value,
+        Cow::Borrowed("Jane Doe")
+    ));
+    Ok(())
+}
+
+#[test]
+fn borrowed_errors() -> Result<()> {
+    let input = "BEGIN:VCARD\nVERSION:4.0\nNOTE:Unnamed\nEND:VCARD";
+    assert!(matches!(parse_borrowed(input), Err(Error::NoFormattedName)));
+
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN:A\nN:A\nN:B\nEND:VCARD";
+    assert!(matches!(parse_borrowed(input), Err(Error::OnlyOnce(_))));
+
+    assert!(matches!(parse_borrowed(""), Err(Error::TokenExpected)));
+    Ok(())
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::clone_on_copy, clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
@@ -25,7 +27,7 @@ END:VCARD"#;
     );
 
     let prop = card.fburl.get(1).unwrap();
-    assert_media_type(prop.parameters.as_ref(), "text/calendar")?;
+    assert_media_type(prop.parameters.as_deref(), "text/calendar")?;
     assert_eq!(
         "ftp://example.com/busy/project-a.ifb",
         &prop.value.to_string()
@@ -77,7 +79,7 @@ END:VCARD"#;
     assert_eq!("http://cal.example.com/calA", &prop.value.to_string());
 
     let prop = card.cal_uri.get(1).unwrap();
-    assert_media_type(prop.parameters.as_ref(), "text/calendar")?;
+    assert_media_type(prop.parameters.as_deref(), "text/calendar")?;
     assert_eq!("ftp://ftp.example.com/calA.ics", &prop.value.to_string());
 
     assert_round_trip(&card)?;
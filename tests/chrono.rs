@@ -0,0 +1,104 @@
+#![cfg(feature = "chrono")]
+
+use anyhow::Result;
+use chrono::{FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use vcard4::{
+    parse,
+    property::{DateAndOrTime, DateTimeOrTextProperty, DateTimeProperty},
+    time::{OffsetDateTime, UtcOffset},
+    Date, DateTime, PartialDate, VcardBuilder,
+};
+
+#[test]
+fn chrono_date_time_offset() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+REV:19951031T222710-0530
+END:VCARD"#;
+    let mut vcards = parse(input)?;
+    let card = vcards.remove(0);
+    let rev = card.rev.as_ref().unwrap();
+
+    let value = rev.to_chrono()?;
+    let offset = FixedOffset::west_opt(5 * 3600 + 30 * 60).unwrap();
+    assert_eq!(&offset, value.offset());
+    assert_eq!(
+        offset.with_ymd_and_hms(1995, 10, 31, 22, 27, 10).unwrap(),
+        value
+    );
+
+    let rev = DateTimeProperty::try_from(value)?;
+    assert_eq!(card.rev.as_ref().unwrap().value, rev.value);
+    Ok(())
+}
+
+#[test]
+fn chrono_date_time_out_of_range() -> Result<()> {
+    let offset = UtcOffset::from_hms(25, 30, 0)?;
+    let value = DateTime::from(OffsetDateTime::now_utc().to_offset(offset));
+    assert!(value.to_chrono().is_err());
+
+    let value = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(10000, 1, 1, 0, 0, 0)
+        .unwrap();
+    assert!(DateTime::try_from(value).is_err());
+    Ok(())
+}
+
+#[test]
+fn chrono_date_and_or_time() -> Result<()> {
+    let date = NaiveDate::from_ymd_opt(1985, 4, 12).unwrap();
+    let value: DateAndOrTime = "19850412".parse()?;
+    assert_eq!(Some(date), value.to_chrono_naive_date());
+    assert_eq!(None, value.to_chrono_naive_time());
+    assert_eq!(
+        Some(date.and_hms_opt(0, 0, 0).unwrap()),
+        value.to_chrono_naive_date_time()
+    );
+
+    let value: DateAndOrTime = "19850412T093000+0200".parse()?;
+    assert_eq!(
+        Some(date.and_hms_opt(9, 30, 0).unwrap()),
+        value.to_chrono_naive_date_time()
+    );
+
+    let value: DateAndOrTime = "T093000Z".parse()?;
+    assert_eq!(
+        Some(NaiveTime::from_hms_opt(9, 30, 0).unwrap()),
+        value.to_chrono_naive_time()
+    );
+    assert_eq!(None, value.to_chrono_naive_date_time());
+
+    let value: DateAndOrTime = "--0412".parse()?;
+    assert_eq!(None, value.to_chrono_naive_date());
+
+    let value: PartialDate = "1985-04".parse()?;
+    assert_eq!(
+        Some(NaiveDate::from_ymd_opt(1985, 4, 1).unwrap()),
+        value.to_chrono_naive()
+    );
+
+    let value = DateAndOrTime::try_from(date)?;
+    assert_eq!("19850412", value.to_string());
+    Ok(())
+}
+
+#[test]
+fn chrono_builder() -> Result<()> {
+    let date = NaiveDate::from_ymd_opt(1985, 4, 12).unwrap();
+    let card = VcardBuilder::new("Jane Doe".to_owned())
+        .birthday_chrono(date)
+        .anniversary_chrono(NaiveDate::from_ymd_opt(10000, 1, 1).unwrap())
+        .finish();
+    let Some(DateTimeOrTextProperty::DateTime(bday)) = &card.bday else {
+        panic!("expecting date birthday");
+    };
+    assert_eq!(Some(date), bday.value[0].to_chrono_naive_date());
+    assert!(card.anniversary.is_none());
+
+    let value: Date = date.try_into()?;
+    assert_eq!(date, NaiveDate::try_from(value)?);
+    Ok(())
+}
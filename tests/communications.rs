@@ -1,11 +1,15 @@
+#![allow(clippy::clone_on_copy, clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
 use test_helpers::{assert_language, assert_round_trip};
 use vcard4::{
     parameter::{TelephoneType, TypeParameter},
-    parse,
-    property::TextOrUriProperty,
+    parse, parse_loose,
+    property::{Property, TextOrUriProperty},
+    tel::TelUri,
+    Error, Lint, Vcard, VcardBuilder,
 };
 
 #[test]
@@ -81,6 +85,86 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn communications_tel_uri() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;VALUE=uri:tel:+1-555-555-5555;ext=5555
+TEL;VALUE=uri:tel:7042;phone-context=example.com
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    let TextOrUriProperty::Uri(prop) = &card.tel[0] else {
+        panic!("expecting URI for TEL property");
+    };
+    let tel = TelUri::try_from(&prop.value)?;
+    assert!(tel.is_global());
+    assert_eq!("+1-555-555-5555", tel.number);
+    assert_eq!(Some("5555"), tel.extension.as_deref());
+    assert_eq!("tel:+1-555-555-5555;ext=5555", tel.to_string());
+
+    let TextOrUriProperty::Uri(prop) = &card.tel[1] else {
+        panic!("expecting URI for TEL property");
+    };
+    let tel = TelUri::try_from(&prop.value)?;
+    assert!(!tel.is_global());
+    assert_eq!("7042", tel.number);
+    assert_eq!(Some("example.com"), tel.phone_context.as_deref());
+    Ok(())
+}
+
+#[test]
+fn communications_tel_uri_invalid() -> Result<()> {
+    for value in [
+        "tel:+1-555-CALL-NOW",
+        "tel:7042",
+        "tel:+1-555-555-5555;ext=",
+        "tel:+1-555-555-5555;ext=12;ext=34",
+        "tel:7042;phone-context=-example.com",
+    ] {
+        let input = format!(
+            "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nTEL:{}\nEND:VCARD",
+            value
+        );
+        assert!(matches!(parse(&input), Err(Error::InvalidTelUri(_))));
+        let card = parse_loose(&input)?.remove(0);
+        assert!(card.tel.is_empty());
+    }
+
+    // Text values are not validated
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nTEL;VALUE=text:tel:7042\nEND:VCARD";
+    let card = parse(input)?.remove(0);
+    assert!(matches!(&card.tel[0], TextOrUriProperty::Text(_)));
+    Ok(())
+}
+
+#[test]
+fn communications_tel_helpers() -> Result<()> {
+    let mut card = Vcard::new("Jane Doe".to_owned());
+    card.add_tel_uri("tel:+1-555-555-5555;ext=5555".parse()?)?;
+    card.add_tel_text("tel:+1-555-555-0000".to_owned());
+    card.add_tel_text("555-1234".to_owned());
+
+    let encoded = card.to_string();
+    assert!(
+        encoded.contains("TEL;VALUE=uri:tel:+1-555-555-5555;ext=5555\r\n")
+    );
+    assert!(encoded.contains("TEL;VALUE=text:tel:+1-555-555-0000\r\n"));
+    assert!(encoded.contains("TEL;VALUE=text:555-1234\r\n"));
+
+    let decoded = parse(&encoded)?.remove(0);
+    assert_eq!(card, decoded);
+    assert!(matches!(&decoded.tel[0], TextOrUriProperty::Uri(_)));
+    assert!(matches!(&decoded.tel[1], TextOrUriProperty::Text(_)));
+
+    let mut tel = TelUri::new("+1-555-555-5555".to_owned())?;
+    tel.phone_context = Some("not a domain".to_owned());
+    assert!(card.add_tel_uri(tel).is_err());
+    assert!(TelUri::new("7042".to_owned()).is_err());
+    Ok(())
+}
+
 #[test]
 fn communications_email() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -156,3 +240,243 @@ END:VCARD"#;
     assert_round_trip(&card)?;
     Ok(())
 }
+
+#[test]
+fn communications_language() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+item1.LANGUAGE:de-AT
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let prop = card.language.as_ref().unwrap();
+    assert_eq!(Some("item1"), prop.group.as_deref());
+    assert_language(&prop.value, "de-AT")?;
+    assert!(card.extensions.is_empty());
+    assert!(card.to_string().contains("\r\nitem1.LANGUAGE:de-AT\r\n"));
+    assert_round_trip(&card)?;
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+LANGUAGE:de-AT
+LANGUAGE:en
+END:VCARD"#;
+    assert!(matches!(parse(input), Err(Error::OnlyOnce(_))));
+    Ok(())
+}
+
+#[test]
+fn communications_social_profile() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+SOCIALPROFILE;SERVICE-TYPE=twitter:https://twitter.com/x
+SOCIALPROFILE;SERVICE-TYPE="Example, Inc.";VALUE=text:@jane
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert_eq!(2, card.social_profile.len());
+
+    let prop = &card.social_profile[0];
+    assert!(matches!(prop, TextOrUriProperty::Uri(_)));
+    assert_eq!("https://twitter.com/x", prop.to_string());
+    assert_eq!(
+        Some("twitter"),
+        prop.parameters()
+            .and_then(|params| params.service_type.as_deref())
+    );
+
+    let prop = &card.social_profile[1];
+    assert!(matches!(prop, TextOrUriProperty::Text(_)));
+    assert_eq!(
+        Some("Example, Inc."),
+        prop.parameters()
+            .and_then(|params| params.service_type.as_deref())
+    );
+
+    let encoded = card.to_string();
+    assert!(encoded.contains(
+        "\r\nSOCIALPROFILE;SERVICE-TYPE=twitter:https://twitter.com/x\r\n"
+    ));
+    assert_round_trip(&card)?;
+
+    let card = VcardBuilder::new("Jane Doe".to_owned())
+        .social_profile_with_service(
+            "https://example.com/@jane".parse()?,
+            "Mastodon".to_owned(),
+        )
+        .finish();
+    assert_eq!(1, card.social_profile.len());
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn communications_lang_multiple() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+LANG;PREF=1:en,fr
+END:VCARD"#;
+    assert!(matches!(parse(input), Err(Error::MultipleLanguages(_))));
+
+    let card = parse_loose(input)?.remove(0);
+    assert_eq!(2, card.lang.len());
+    assert_language(&card.lang.first().unwrap().value, "en")?;
+    assert_language(&card.lang.get(1).unwrap().value, "fr")?;
+    for prop in &card.lang {
+        assert_eq!(Some(1), prop.parameters.as_ref().unwrap().pref);
+    }
+    assert_eq!(vec![Lint::DuplicateLanguagePref(1)], card.lint());
+    Ok(())
+}
+
+#[test]
+fn communications_add_language() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+LANG;PREF=1:en
+LANG;PREF=2:fr
+LANG;PREF=4:es
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+    assert!(card.lint().is_empty());
+
+    card.add_language("de".parse()?, 1)?;
+    let prefs = card
+        .lang
+        .iter()
+        .map(|p| p.parameters.as_ref().unwrap().pref.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(vec![2, 3, 4, 1], prefs);
+    assert_language(&card.lang.get(3).unwrap().value, "de")?;
+
+    // Shifting stops at the first unoccupied preference
+    card.add_language("it".parse()?, 4)?;
+    let prefs = card
+        .lang
+        .iter()
+        .map(|p| p.parameters.as_ref().unwrap().pref.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(vec![2, 3, 5, 1, 4], prefs);
+    assert!(card.lint().is_empty());
+
+    assert!(matches!(
+        card.add_language("nl".parse()?, 0),
+        Err(Error::PrefOutOfRange(0))
+    ));
+
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn communications_preferred_email() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+EMAIL:none@example.com
+EMAIL;TYPE=home;PREF=2:home@example.com
+EMAIL;TYPE=WORK;PREF=3:work@example.com
+EMAIL;TYPE=home;TYPE=Work;PREF=2:both@example.com
+EMAIL;TYPE=x-school:school@example.com
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    // Ties keep their order
+    assert_eq!("home@example.com", card.preferred_email().unwrap().value);
+
+    // TYPE is matched ignoring case across multiple TYPE parameters
+    let work: Vec<&str> = card
+        .emails_of_type(TypeParameter::Work)
+        .map(|prop| prop.value.as_str())
+        .collect();
+    assert_eq!(vec!["both@example.com", "work@example.com"], work);
+    assert_eq!(
+        "home@example.com",
+        card.preferred_email_of_type(TypeParameter::Home)
+            .unwrap()
+            .value
+    );
+    assert_eq!(
+        "school@example.com",
+        card.preferred_email_of_type(TypeParameter::Extension(
+            "SCHOOL".to_owned()
+        ))
+        .unwrap()
+        .value
+    );
+    assert!(card
+        .preferred_email_of_type(TypeParameter::Telephone(
+            TelephoneType::Cell
+        ))
+        .is_none());
+
+    // Missing PREF sorts last
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+EMAIL:first@example.com
+EMAIL:second@example.com
+EMAIL;PREF=100:last@example.com
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert_eq!("last@example.com", card.preferred_email().unwrap().value);
+    assert!(card.emails_of_type(TypeParameter::Work).next().is_none());
+
+    let card = Vcard::new("Jane Doe".to_owned());
+    assert!(card.preferred_email().is_none());
+    Ok(())
+}
+
+#[test]
+fn communications_preferred_tel() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;VALUE=uri;TYPE=voice:tel:+1-555-555-0001
+TEL;VALUE=uri;TYPE=CELL;PREF=2:tel:+1-555-555-0002
+TEL;VALUE=uri;TYPE=voice;TYPE=cell;PREF=1:tel:+1-555-555-0003
+TEL;VALUE=uri;TYPE=work,voice;PREF=5:tel:+1-555-555-0004
+IMPP:xmpp:jane@example.com
+IMPP;TYPE=work;PREF=1:sip:jane@example.com
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    let number = |prop: Option<&TextOrUriProperty>| {
+        prop.map(|prop| prop.to_string()).unwrap_or_default()
+    };
+    assert_eq!("tel:+1-555-555-0003", number(card.preferred_tel(None)));
+    assert_eq!(
+        "tel:+1-555-555-0003",
+        number(card.preferred_tel(Some(TelephoneType::Cell)))
+    );
+    assert_eq!(
+        "tel:+1-555-555-0003",
+        number(card.preferred_tel(Some(TelephoneType::Voice)))
+    );
+    assert_eq!("", number(card.preferred_tel(Some(TelephoneType::Fax))));
+
+    let voice: Vec<String> = card
+        .tels_of_type(TypeParameter::Telephone(TelephoneType::Voice))
+        .map(|prop| prop.to_string())
+        .collect();
+    assert_eq!(
+        vec![
+            "tel:+1-555-555-0003",
+            "tel:+1-555-555-0004",
+            "tel:+1-555-555-0001"
+        ],
+        voice
+    );
+    assert_eq!(1, card.tels_of_type(TypeParameter::Work).count());
+
+    assert_eq!(
+        "sip:jane@example.com",
+        card.preferred_impp().unwrap().value.to_string()
+    );
+    assert_eq!(1, card.impps_of_type(TypeParameter::Work).count());
+    assert_eq!(0, card.impps_of_type(TypeParameter::Home).count());
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use vcard4::{parse, CompareOptions};
+
+#[test]
+fn compare_property_order() -> Result<()> {
+    let a = parse(
+        r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;TYPE=home,VOICE;VALUE=uri:tel:+1-555-555-5555
+TEL;TYPE=work:tel:+1-555-555-1234
+EMAIL;X-B=2;X-A=1:jane@example.com
+END:VCARD"#,
+    )?
+    .remove(0);
+    let b = parse(
+        r#"BEGIN:VCARD
+VERSION:4.0
+EMAIL;X-A=1;X-B=2:jane@example.com
+TEL;TYPE=WORK:tel:+1-555-555-1234
+TEL;VALUE=uri;TYPE=voice,home:tel:+1-555-555-5555
+FN:Jane Doe
+END:VCARD"#,
+    )?
+    .remove(0);
+
+    assert_ne!(a, b);
+    assert!(a.semantically_equals(&b));
+    assert!(b.semantically_equals(&a));
+    assert_eq!(a.content_hash(), b.content_hash());
+    Ok(())
+}
+
+#[test]
+fn compare_values() -> Result<()> {
+    let a =
+        parse("BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nEND:VCARD")?.remove(0);
+
+    // The hash does not change between releases
+    assert_eq!(16118475842222947115, a.content_hash());
+
+    // Text is compared case-sensitively
+    let b =
+        parse("BEGIN:VCARD\nVERSION:4.0\nFN:jane doe\nEND:VCARD")?.remove(0);
+    assert!(!a.semantically_equals(&b));
+    assert_ne!(a.content_hash(), b.content_hash());
+
+    // Multiple values are compared as a multiset
+    let b = parse(
+        "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nFN:Jane Doe\nEND:VCARD",
+    )?
+    .remove(0);
+    assert!(!a.semantically_equals(&b));
+
+    // Empty parameters are the same as no parameters
+    let mut b = a.clone();
+    b.formatted_name[0].parameters = Some(Default::default());
+    assert!(a.semantically_equals(&b));
+    assert_eq!(a.content_hash(), b.content_hash());
+    Ok(())
+}
+
+#[test]
+fn compare_options() -> Result<()> {
+    let a = parse(
+        r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+PRODID:-//ONLINE DIRECTORY//NONSGML Version 1//EN
+REV:19951031T222710Z
+END:VCARD"#,
+    )?
+    .remove(0);
+    let b = parse(
+        r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+PRODID:-//Other//EN
+REV:20240101T000000Z
+END:VCARD"#,
+    )?
+    .remove(0);
+
+    assert!(!a.semantically_equals(&b));
+    let options = CompareOptions::new().ignore_rev(true);
+    assert!(!a.semantically_equals_with(&b, &options));
+
+    let options = options.ignore_prod_id(true);
+    assert!(a.semantically_equals_with(&b, &options));
+    assert_eq!(a.content_hash_with(&options), b.content_hash_with(&options));
+    assert_ne!(a.content_hash(), b.content_hash());
+    Ok(())
+}
@@ -1,8 +1,15 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
 use test_helpers::assert_round_trip;
-use vcard4::parse;
+use vcard4::{
+    parameter::Parameters,
+    parse, parse_loose,
+    property::{AddressProperty, DeliveryAddress},
+    Error, VcardBuilder,
+};
 
 #[test]
 fn delivery_adr() -> Result<()> {
@@ -32,14 +39,278 @@ U.S.A."#,
     );
 
     let address = &prop.value;
-    assert!(address.po_box.is_none());
-    assert!(address.extended_address.is_none());
-    assert_eq!("123 Main Street", address.street_address.as_ref().unwrap());
-    assert_eq!("Any Town", address.locality.as_ref().unwrap());
-    assert_eq!("CA", address.region.as_ref().unwrap());
-    assert_eq!("91921-1234", address.postal_code.as_ref().unwrap());
-    assert_eq!("U.S.A.", address.country_name.as_ref().unwrap());
+    assert!(address.po_box.is_empty());
+    assert!(address.extended_address.is_empty());
+    assert_eq!(vec!["123 Main Street"], address.street_address);
+    assert_eq!(vec!["Any Town"], address.locality);
+    assert_eq!(vec!["CA"], address.region);
+    assert_eq!(vec!["91921-1234"], address.postal_code);
+    assert_eq!(vec!["U.S.A."], address.country_name);
+
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn delivery_adr_components() -> Result<()> {
+    let components = [
+        "PO Box 1",
+        "Apt 2",
+        "Hauptstr. 5",
+        "Berlin",
+        "Berlin",
+        "10115",
+        "Germany",
+        "Extra",
+    ];
+
+    for count in 1..=8 {
+        let value = components[0..count].join(";");
+        let input = format!(
+            "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nADR;TYPE=home:{}\nEND:VCARD",
+            value
+        );
+
+        if count > 7 {
+            assert!(parse(&input).is_err());
+            let mut vcards = parse_loose(&input)?;
+            let card = vcards.remove(0);
+            let address = &card.address.first().unwrap().value;
+            assert_eq!(vec!["Germany;Extra"], address.country_name);
+            continue;
+        }
+
+        for mut vcards in [parse(&input)?, parse_loose(&input)?] {
+            let card = vcards.remove(0);
+            let address = &card.address.first().unwrap().value;
+            let fields = [
+                &address.po_box,
+                &address.extended_address,
+                &address.street_address,
+                &address.locality,
+                &address.region,
+                &address.postal_code,
+                &address.country_name,
+            ];
+            for (index, field) in fields.iter().enumerate() {
+                if index < count {
+                    assert_eq!(&vec![components[index]], *field);
+                } else {
+                    assert!(field.is_empty());
+                }
+            }
+
+            // Always encoded with all the components
+            let encoded = address.to_string();
+            assert_eq!(6, encoded.matches(';').count());
+            assert_round_trip(&card)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn delivery_adr_missing_components() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+N:Doe;Jane
+ADR;TYPE=home:;;Hauptstr. 5;Berlin;;10115
+END:VCARD"#;
+    let mut vcards = parse(input)?;
+    let card = vcards.remove(0);
+
+    let address = &card.address.first().unwrap().value;
+    assert_eq!(vec!["10115"], address.postal_code);
+    assert!(address.country_name.is_empty());
+
+    let name = card.name.as_ref().unwrap();
+    assert_eq!(vec!["Doe"], name.value.family_names);
+    assert_eq!(vec!["Jane"], name.value.given_names);
+    assert!(card.to_string().contains("N:Doe;Jane;;;\r\n"));
+
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn delivery_adr_escaped() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+ADR:;;123 Main St\, Apt 4\; Rear\\Side\nBuilding B;Springfield;;12345;USA
+END:VCARD"#;
+    let mut vcards = parse(input)?;
+    let card = vcards.remove(0);
+
+    let address = &card.address.first().unwrap().value;
+    assert_eq!(
+        vec!["123 Main St, Apt 4; Rear\\Side\nBuilding B"],
+        address.street_address
+    );
+    assert_eq!(vec!["Springfield"], address.locality);
+    assert!(address.region.is_empty());
+    assert_eq!(vec!["12345"], address.postal_code);
+    assert_eq!(vec!["USA"], address.country_name);
+
+    assert!(card.to_string().contains(
+        "ADR:;;123 Main St\\, Apt 4\\; Rear\\\\Side\\nBuilding B;\
+         Springfield;;12345;USA\r\n"
+    ));
+
+    let reparsed = parse(card.to_string())?.remove(0);
+    assert_eq!(card, reparsed);
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn delivery_adr_multiple_values() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+ADR:;;123 Main Street,Suite 100;Springfield;;12345;USA
+ADR:;;123 Main Street\, Suite 100;Springfield;;12345;USA
+END:VCARD"#;
+    let mut vcards = parse(input)?;
+    let card = vcards.remove(0);
+
+    let address = &card.address[0].value;
+    assert_eq!(vec!["123 Main Street", "Suite 100"], address.street_address);
+    let address = &card.address[1].value;
+    assert_eq!(vec!["123 Main Street, Suite 100"], address.street_address);
+
+    let encoded = card.to_string();
+    assert!(encoded.contains(
+        "ADR:;;123 Main Street,Suite 100;Springfield;;12345;USA\r\n"
+    ));
+    assert!(encoded.contains(
+        "ADR:;;123 Main Street\\, Suite 100;Springfield;;12345;USA\r\n"
+    ));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn delivery_adr_display_label() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+ADR;GEO="geo:12.3457,78.910";LABEL="Mr. John Q. Public, Esq.\n
+ Mail Drop: TNE QB\n123 Main Street\nAny Town, CA  91921-1234\n
+ U.S.A.":;;123 Main Street;Any Town;CA;91921-1234;U.S.A.
+ADR:;;Heidestrasse 17;Koeln;;51147;Germany
+END:VCARD"#;
+    let mut vcards = parse(input)?;
+    let card = vcards.remove(0);
+
+    let expected = "Mr. John Q. Public, Esq.
+Mail Drop: TNE QB
+123 Main Street
+Any Town, CA  91921-1234
+U.S.A.";
+    assert_eq!(expected, card.address[0].display_label());
+    assert_eq!(
+        "Heidestrasse 17\nKoeln\n51147\nGermany",
+        card.address[1].display_label()
+    );
+
+    let mut prop = AddressProperty::from(DeliveryAddress::default());
+    assert_eq!("", prop.display_label());
+    prop.parameters = Some(Box::new(
+        Parameters::default()
+            .with_label(r"Heidestrasse 17\n51147 Koeln".to_owned()),
+    ));
+    assert_eq!("Heidestrasse 17\n51147 Koeln", prop.display_label());
+    Ok(())
+}
+
+#[test]
+fn delivery_adr_from_label() -> Result<()> {
+    let address = DeliveryAddress::from_label(
+        "Mr. John Q. Public, Esq.\nMail Drop: TNE QB\n123 Main Street\n\
+         Any Town, CA  91921-1234\nU.S.A.",
+    );
+    assert_eq!(
+        vec![
+            "Mr. John Q. Public, Esq.",
+            "Mail Drop: TNE QB",
+            "123 Main Street"
+        ],
+        address.street_address
+    );
+    assert_eq!(vec!["Any Town"], address.locality);
+    assert_eq!(vec!["CA"], address.region);
+    assert_eq!(vec!["91921-1234"], address.postal_code);
+    assert_eq!(vec!["U.S.A."], address.country_name);
+
+    let address = DeliveryAddress::from_label(
+        r"Heidestrasse 17\n51147 Koeln\nDeutschland",
+    );
+    assert_eq!(vec!["Heidestrasse 17"], address.street_address);
+    assert_eq!(vec!["Koeln"], address.locality);
+    assert!(address.region.is_empty());
+    assert_eq!(vec!["51147"], address.postal_code);
+    assert_eq!(vec!["Deutschland"], address.country_name);
+
+    let address = DeliveryAddress::from_label("  123 Main Street \n\n");
+    assert_eq!(vec!["123 Main Street"], address.street_address);
+    assert!(address.locality.is_empty());
+    assert!(address.country_name.is_empty());
+
+    assert_eq!(DeliveryAddress::default(), DeliveryAddress::from_label(""));
+    Ok(())
+}
 
+#[test]
+fn delivery_adr_country_code() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+ADR;cc=us:;;123 Main St;Anytown;CA;91921;
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let params = card.address[0].parameters.as_ref().unwrap();
+    assert_eq!(Some("US"), params.country_code.as_deref());
+    assert!(card
+        .to_string()
+        .contains("\r\nADR;CC=US:;;123 Main St;Anytown;CA;91921;\r\n"));
     assert_round_trip(&card)?;
+
+    let built = VcardBuilder::new("Jane Doe".to_owned())
+        .address_with(
+            card.address[0].value.clone(),
+            Parameters::new().with_country_code("US".to_owned()),
+        )
+        .finish();
+    assert_eq!(card, built);
+
+    // Only two letters are accepted in strict mode
+    let invalid = input.replace("cc=us", "CC=USA");
+    assert!(matches!(
+        parse(&invalid),
+        Err(Error::UnknownCountryCode(value)) if value == "USA"
+    ));
+
+    // Loose mode keeps the raw value as an extension
+    let card = parse_loose(&invalid)?.remove(0);
+    let params = card.address[0].parameters.as_ref().unwrap();
+    assert!(params.country_code.is_none());
+    assert_eq!(
+        &vec![("CC".to_owned(), vec!["USA".to_owned()])],
+        params.extensions.as_ref().unwrap(),
+    );
+    assert!(card.to_string().contains("\r\nADR;CC=USA:"));
+
+    // Only allowed on ADR
+    let input = input.replace(
+        "ADR;cc=us:;;123 Main St;Anytown;CA;91921;",
+        "NOTE;CC=US:Note",
+    );
+    assert!(matches!(
+        parse(&input),
+        Err(Error::InvalidCountryCode(name)) if name == "NOTE"
+    ));
     Ok(())
 }
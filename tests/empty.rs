@@ -0,0 +1,84 @@
+mod test_helpers;
+
+use anyhow::Result;
+use test_helpers::assert_round_trip;
+use vcard4::{
+    parse, parse_loose,
+    property::{DeliveryAddress, Property},
+    Vcard,
+};
+
+fn parse_both(input: &str) -> Result<Vcard> {
+    let card = parse(input)?.remove(0);
+    assert_eq!(card, parse_loose(input)?.remove(0));
+    assert_round_trip(&card)?;
+    Ok(card)
+}
+
+#[test]
+fn empty_property_values() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+NICKNAME:
+TEL:
+ADR:
+ADR;;;:;;;;;;
+ORG:
+NOTE:
+END:VCARD"#;
+    let card = parse_both(input)?;
+    assert_eq!("", card.nickname[0].value);
+    assert_eq!(2, card.address.len());
+    for adr in &card.address {
+        assert_eq!(DeliveryAddress::default(), adr.value);
+        assert!(adr.parameters.is_none());
+    }
+    assert_eq!(vec![String::new()], card.org[0].value);
+    assert_eq!("", card.note[0].value);
+    Ok(())
+}
+
+#[test]
+fn empty_property_value_last() -> Result<()> {
+    let input =
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE:\r\nEND:VCARD";
+    let card = parse_both(input)?;
+    assert_eq!("", card.note[0].value);
+
+    // Value at the end of the source without END:VCARD
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE:";
+    let card = parse_both(input)?;
+    assert_eq!("", card.note[0].value);
+
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE:Text";
+    let card = parse_both(input)?;
+    assert_eq!("Text", card.note[0].value);
+    Ok(())
+}
+
+#[test]
+fn empty_parameter_values() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;LANGUAGE=;ALTID="":Jane Doe
+TEL;TYPE=:123
+TEL;TYPE="";PREF=1:456
+NOTE;PREF=;X-EMPTY=:Text
+END:VCARD"#;
+    let card = parse_both(input)?;
+    assert!(card.formatted_name[0].parameters.is_none());
+    assert!(card.tel[0].parameters().is_none());
+    let params = card.tel[1].parameters().unwrap();
+    assert!(params.types.is_none());
+    assert_eq!(Some(1), params.pref);
+
+    // Extension parameters keep the empty value
+    let params = card.note[0].parameters.as_ref().unwrap();
+    assert!(params.pref.is_none());
+    assert_eq!(
+        &vec![("X-EMPTY".to_owned(), vec![String::new()])],
+        params.extensions.as_ref().unwrap()
+    );
+    Ok(())
+}
@@ -1,7 +1,10 @@
 mod test_helpers;
 
 use anyhow::Result;
-use vcard4::{helper::*, parameter::*, parse, property::ClientPidMap, Error};
+use vcard4::{
+    helper::*, parameter::*, parse, parse_with_options,
+    property::ClientPidMap, Error, ParseOptions,
+};
 
 #[test]
 fn error_empty() -> Result<()> {
@@ -41,7 +44,8 @@ fn error_parse_from_str() -> Result<()> {
 
     assert!("foo".parse::<TelephoneType>().is_err());
     assert!("foo".parse::<RelatedType>().is_err());
-    assert!("foo".parse::<ValueType>().is_err());
+    assert!("".parse::<ValueType>().is_err());
+    assert!("foo bar".parse::<ValueType>().is_err());
 
     assert!("0;urn:uid:".parse::<ClientPidMap>().is_err());
 
@@ -78,6 +82,22 @@ FN;PREF=0:Jane Doe"#;
     Ok(())
 }
 
+#[test]
+fn error_parse_loose_skips_line() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+NOTE;PID=x;LANGUAGE=en:Note;X-NOTE:Extra
+TITLE:Boss
+END:VCARD"#;
+    let options = ParseOptions::new().strict(false);
+    let card = parse_with_options(input, options)?.remove(0);
+    assert!(card.note.is_empty());
+    assert!(card.extensions.is_empty());
+    assert_eq!("Boss", card.title[0].value);
+    Ok(())
+}
+
 #[test]
 fn error_parse_type_on_invalid_prop() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -97,7 +117,7 @@ VERSION:4.0
 FN;GEO=geo:1\,2:Jane Doe
 END:VCARD"#;
     let result = parse(input);
-    assert!(matches!(result, Err(Error::NotQuoted(_))));
+    assert!(matches!(result, Err(Error::NotQuoted(name)) if name == "GEO"));
     Ok(())
 }
 
@@ -306,6 +326,15 @@ fn error_control_character_value() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn error_control_character_carriage_return() -> Result<()> {
+    // Only a carriage return before a line feed is a line ending
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\rDoe\r\nEND:VCARD";
+    let result = parse(input);
+    assert!(matches!(result, Err(Error::ControlCharacter(_))));
+    Ok(())
+}
+
 #[test]
 fn error_control_character_param() -> Result<()> {
     let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nADR;LABEL=label\x7F:;;;;;;\r\nEND:VCARD";
@@ -313,3 +342,93 @@ fn error_control_character_param() -> Result<()> {
     assert!(matches!(result, Err(Error::ControlCharacter(_))));
     Ok(())
 }
+
+#[test]
+fn error_locations() -> Result<()> {
+    let options = ParseOptions::new().locations(true);
+
+    // Folded lines are included in the line count
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE:ö\r\n continued\r\nNOTE;LABEL=work:bar\r\nEND:VCARD\r\n";
+    let result = parse_with_options(input, options);
+    let Err(Error::Parse {
+        line,
+        column,
+        source,
+    }) = result
+    else {
+        panic!("expected error with location, got {:?}", result);
+    };
+    assert_eq!((6, 1), (line, column));
+    assert!(matches!(*source, Error::InvalidLabel(_)));
+
+    // Structural errors use the location of the token
+    let input =
+        "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nVERSION:4.0\nEND:VCARD";
+    let result = parse_with_options(input, options);
+    assert!(matches!(
+        result,
+        Err(Error::Parse { line: 4, column: 1, source }) if matches!(*source, Error::VersionMisplaced)
+    ));
+
+    // Validation errors use the location of the vCard
+    let input = "\nBEGIN:VCARD\nVERSION:4.0\nEND:VCARD";
+    let result = parse_with_options(input, options);
+    assert!(matches!(
+        result,
+        Err(Error::Parse {
+            line: 2,
+            column: 1,
+            ..
+        })
+    ));
+
+    // Errors are not wrapped by default
+    let result = parse(input);
+    assert!(!matches!(result, Err(Error::Parse { .. })));
+    Ok(())
+}
+
+#[test]
+fn error_source_text_bounded() -> Result<()> {
+    let large = "é".repeat(1024 * 1024);
+    let card = |line: String| {
+        format!("BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\n{}\nEND:VCARD", line)
+    };
+    let inputs = [
+        card(format!("BDAY:{}", large)),
+        card(format!("KIND:{}", large)),
+        card(format!("GENDER:{}", large)),
+        card(format!("CLIENTPIDMAP:{}", large)),
+        card(format!("NOTE;X-{}=1;VALUE=bad:Note", large)),
+        card(format!("NOTE;VALUE={}:Note", large)),
+        card(format!("EXPERTISE;LEVEL={}:Rust", large)),
+        card(format!("EMAIL;PID={}:jane@example.com", large)),
+        card(format!("NOTE:{}", "\u{7}".repeat(1024 * 1024))),
+    ];
+    for input in &inputs {
+        let Err(error) = parse(input) else {
+            panic!("expected error for {}", &input[..64]);
+        };
+        let message = error.to_string();
+        assert!(message.chars().count() < 200, "{}", message);
+        assert!(format!("{:?}", error).chars().count() < 200);
+    }
+
+    let error = vcard4::qr::parse_mecard(&large).unwrap_err();
+    assert!(matches!(&error, Error::InvalidMeCard(value)
+        if value.chars().count() == 65 && value.ends_with('…')));
+    assert!(error.to_string().chars().count() < 200);
+    Ok(())
+}
+
+#[test]
+fn error_parse_geo_quoted_empty() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+ADR;GEO="":;;123 Main Street;Any Town;CA;91921;U.S.A.
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert!(card.address[0].parameters.is_none());
+    Ok(())
+}
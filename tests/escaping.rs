@@ -1,8 +1,15 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
+use proptest::prelude::*;
 use test_helpers::assert_round_trip;
-use vcard4::parse;
+use vcard4::{
+    parse, parse_loose,
+    property::{Gender, Sex},
+    Error, VcardBuilder,
+};
 
 #[test]
 fn escape_semi_colon() -> Result<()> {
@@ -74,3 +81,161 @@ BabsCo, Inc.
     assert_round_trip(&card)?;
     Ok(())
 }
+
+#[test]
+fn escape_quoted_printable() -> Result<()> {
+    let input = "BEGIN:VCARD\r
+VERSION:4.0\r
+FN:Jane Doe\r
+NOTE;ENCODING=QUOTED-PRINTABLE;CHARSET=UTF-8:H=C3=A9llo=0D=0Aworld\r
+END:VCARD\r
+";
+    let card = parse(input)?.remove(0);
+    let note = card.note.first().unwrap();
+    assert_eq!("Héllo\nworld", note.value);
+    assert!(note.parameters.is_none());
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn escape_quoted_printable_soft_break() -> Result<()> {
+    // Soft line break continues on the next line and an
+    // escape sequence is split by folding
+    let input = "BEGIN:VCARD\r
+VERSION:4.0\r
+FN:Jane Doe\r
+NOTE;ENCODING=QUOTED-PRINTABLE;PREF=1:Caf=C3=\r
+ =A9 au lait =\r
+ et cr=C3=A8me =\r
+br=C3=BBl=C3=A9e\r
+EMAIL:jane@example.com\r
+END:VCARD\r
+";
+    let card = parse(input)?.remove(0);
+    let note = card.note.first().unwrap();
+    assert_eq!("Café au lait et crème brûlée", note.value);
+    let params = note.parameters.as_ref().unwrap();
+    assert_eq!(Some(1), params.pref);
+    assert!(params.extensions.is_none());
+    assert_eq!("jane@example.com", card.email[0].value);
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn escape_quoted_printable_soft_break_last_line() -> Result<()> {
+    // The soft line break does not continue on a content line
+    for next in ["END:VCARD", "EMAIL:jane@example.com\r\nEND:VCARD"] {
+        let input = format!(
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\n\
+            NOTE;ENCODING=QUOTED-PRINTABLE:Caf=C3=A9=\r\n{}\r\n",
+            next
+        );
+        assert!(matches!(parse(&input), Err(Error::QuotedPrintable(_))));
+        let card = parse_loose(&input)?.remove(0);
+        assert!(card.note.is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn escape_quoted_printable_invalid() -> Result<()> {
+    let input = "BEGIN:VCARD\r
+VERSION:4.0\r
+FN:Jane Doe\r
+NOTE;ENCODING=QUOTED-PRINTABLE:Invalid =ZZ escape\r
+NOTE;ENCODING=QUOTED-PRINTABLE:Valid=20escape\r
+END:VCARD\r
+";
+    assert!(matches!(parse(input), Err(Error::QuotedPrintable(_))));
+
+    let card = parse_loose(input)?.remove(0);
+    assert_eq!(1, card.note.len());
+    assert_eq!("Valid escape", card.note[0].value);
+    Ok(())
+}
+
+#[test]
+fn escape_parameter_label() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+ADR;LABEL="123 Main St.^n98765 ^'Somewhere^'":;;123 Main St.;;;98765;
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let params = card.address[0].parameters.as_ref().unwrap();
+    assert_eq!(
+        Some("123 Main St.\n98765 \"Somewhere\""),
+        params.label.as_deref()
+    );
+
+    let encoded = card.to_string();
+    assert!(encoded.contains(r#"LABEL=123 Main St.^n98765 ^'Somewhere^':"#));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn escape_parameter_extension() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;X-SHAPE=^^_^^;X-OTHER=a^b:Jane Doe
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let params = card.formatted_name[0].parameters.as_ref().unwrap();
+    let extensions = params.extensions.as_ref().unwrap();
+    assert_eq!(
+        ("X-SHAPE".to_owned(), vec!["^_^".to_owned()]),
+        extensions[0]
+    );
+    // Unknown escape sequences are preserved
+    assert_eq!(
+        ("X-OTHER".to_owned(), vec!["a^b".to_owned()]),
+        extensions[1]
+    );
+
+    assert!(card.to_string().contains("X-SHAPE=^^_^^;"));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+/// Text containing characters that must be escaped.
+fn escaped_text() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9 \\n,;\\\\]{0,24}"
+}
+
+proptest! {
+    #[test]
+    fn escape_round_trip(
+        formatted_name in escaped_text(),
+        note in escaped_text(),
+        org in proptest::collection::vec(escaped_text(), 1..4),
+        family_name in escaped_text(),
+        given_name in escaped_text(),
+        categories in proptest::collection::vec(escaped_text(), 1..4),
+        identity in escaped_text(),
+    ) {
+        let mut card = VcardBuilder::new(formatted_name)
+            .note(note)
+            .org(org)
+            .name([
+                family_name,
+                given_name,
+                String::new(),
+                String::new(),
+                String::new(),
+            ])
+            .categories(categories)
+            .finish();
+        card.gender.push(
+            Gender {
+                sex: Sex::Female,
+                identity: Some(identity),
+            }
+            .into(),
+        );
+        let decoded = parse(card.to_string()).unwrap().remove(0);
+        prop_assert_eq!(card, decoded);
+    }
+}
@@ -1,7 +1,12 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
-use vcard4::{parse, property::TextOrUriProperty};
+use vcard4::{
+    parameter::Pid, parse, property::TextOrUriProperty, PropertyHandle,
+    PropertyKind, Severity,
+};
 
 use test_helpers::assert_round_trip;
 
@@ -30,6 +35,21 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn explanatory_categories_semi_colon() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+CATEGORIES:work\;travel,friends\, family
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let prop = card.categories.first().unwrap();
+    assert_eq!(vec!["work;travel", "friends, family"], prop.value);
+    assert_eq!("work\\;travel,friends\\, family", prop.to_string());
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
 #[test]
 fn explanatory_prod_id() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -65,6 +85,77 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn explanatory_rev_extended() -> Result<()> {
+    let rev = |value: &str| -> Result<String> {
+        let input = format!(
+            "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nREV:{}\nEND:VCARD",
+            value
+        );
+        let card = parse(input)?.remove(0);
+        Ok(card.rev.as_ref().unwrap().value.to_string())
+    };
+
+    assert_eq!("1995-10-31T22:27:10Z", rev("19951031T222710Z")?);
+    assert_eq!("1995-10-31T22:27:10Z", rev("1995-10-31T22:27:10Z")?);
+    assert_eq!(
+        "1995-10-31T22:27:10-05:30",
+        rev("1995-10-31T22:27:10-05:30")?
+    );
+    assert_eq!(
+        "1995-10-31T22:27:10+05:30",
+        rev("1995-10-31T22:27:10+05:30")?
+    );
+    assert_eq!("2023-04-01T12:00:00Z", rev("2023-04-01T12:00:00.000Z")?);
+    assert_eq!("2023-04-01T12:00:00.5Z", rev("20230401T120000.5Z")?);
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+REV:1995-10-31T22:27:10+05:30
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert!(card
+        .to_string()
+        .contains("\r\nREV:19951031T222710+0530\r\n"));
+    assert_round_trip(&card)?;
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+REV:2023-04-01T12:00:00.250Z
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert!(card.to_string().contains("\r\nREV:20230401T120000Z\r\n"));
+    Ok(())
+}
+
+#[test]
+fn explanatory_created() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+CREATED:20240101T000000Z
+REV:20240301T120000Z
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let prop = card.created.as_ref().unwrap();
+    assert_eq!("2024-01-01T00:00:00Z", &prop.value.to_string());
+    assert!(card.to_string().contains(
+        "\r\nREV:20240301T120000Z\r\nCREATED:20240101T000000Z\r\n"
+    ));
+    assert_round_trip(&card)?;
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+CREATED:20240101T000000Z
+CREATED:20240102T000000Z
+END:VCARD"#;
+    assert!(parse(input).is_err());
+    Ok(())
+}
+
 #[test]
 fn explanatory_sound() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -155,6 +246,46 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn explanatory_client_pid_source() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:J. Doe
+N:Doe;J.;;;
+EMAIL;PID=1.1:jdoe@example.com
+EMAIL;PID=2.1,2.2:ceo@example.com
+TEL;PID=1.1;VALUE=uri:tel:+1-555-555-5555
+TEL;PID=1.3;VALUE=uri:tel:+1-555-555-1234
+CLIENTPIDMAP:1;urn:uuid:3df403f4-5924-4bb7-b077-3c711d9eb34b
+CLIENTPIDMAP:2;urn:uuid:d89c9c7a-2e1b-4832-82de-7e992d95faa5
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    let pids = card.email[1].parameters.as_ref().unwrap().pid.as_ref();
+    let pids = pids.unwrap();
+    assert_eq!(
+        "urn:uuid:3df403f4-5924-4bb7-b077-3c711d9eb34b",
+        card.client_pid_source(&pids[0]).unwrap().to_string()
+    );
+    assert_eq!(
+        "urn:uuid:d89c9c7a-2e1b-4832-82de-7e992d95faa5",
+        card.client_pid_source(&pids[1]).unwrap().to_string()
+    );
+    assert!(card.client_pid_source(&Pid::new(1, None)).is_none());
+
+    let dangling = card.check_pid_integrity();
+    assert_eq!(1, dangling.len());
+    assert_eq!(PropertyHandle::new(PropertyKind::Tel, 1), dangling[0].0);
+    assert_eq!(Pid::new(1, Some(3)), dangling[0].1);
+
+    // Dangling references are validation warnings
+    let issues = card.validation_issues();
+    assert_eq!(1, issues.len());
+    assert_eq!(Severity::Warning, issues[0].severity);
+    assert!(card.is_valid());
+    Ok(())
+}
+
 #[test]
 fn explanatory_url() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -1,3 +1,5 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
@@ -8,8 +10,12 @@ use vcard4::{
         parse_timestamp, parse_utc_offset,
     },
     parameter::{TypeParameter, ValueType},
-    parse,
-    property::{AnyProperty, DateAndOrTime},
+    parse, parse_loose,
+    property::{
+        AnyProperty, DateAndOrTime, DateTimeOrTextProperty, Property, Sex,
+        TextOrUriProperty, TextProperty,
+    },
+    Error, PropertyKind, VcardBuilder,
 };
 
 #[test]
@@ -60,6 +66,40 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn extension_unknown_value_type() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+X-FOO;VALUE=x-custom:data;more
+X-BAR;VALUE=futuretype:data
+END:VCARD"#;
+    let mut vcards = parse(input)?;
+    assert_eq!(1, vcards.len());
+    let card = vcards.remove(0);
+
+    let prop = card.extensions.first().unwrap();
+    assert_eq!(
+        &ValueType::Extension("x-custom".to_string()),
+        prop.parameters.as_ref().unwrap().value.as_ref().unwrap()
+    );
+    assert_eq!(&AnyProperty::Text("data;more".to_string()), &prop.value);
+
+    let prop = card.extensions.get(1).unwrap();
+    assert_eq!(
+        &ValueType::IanaToken("futuretype".to_string()),
+        prop.parameters.as_ref().unwrap().value.as_ref().unwrap()
+    );
+    assert_eq!(&AnyProperty::Text("data".to_string()), &prop.value);
+
+    let encoded = card.to_string();
+    assert!(encoded.contains("X-FOO;VALUE=x-custom:data;more\r\n"));
+    assert!(encoded.contains("X-BAR;VALUE=futuretype:data\r\n"));
+
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
 #[test]
 fn extension_uri() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -108,10 +148,7 @@ END:VCARD"#;
         prop.parameters.as_ref().unwrap().value.as_ref().unwrap()
     );
 
-    let expected = parse_date_list("20221107")?
-        .into_iter()
-        .map(vcard4::Date::from)
-        .collect();
+    let expected = parse_date_list("20221107")?;
     assert_eq!(&AnyProperty::Date(expected), &prop.value);
 
     assert_round_trip(&card)?;
@@ -281,6 +318,7 @@ END:VCARD"#;
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn extension_float() -> Result<()> {
     let input = r#"BEGIN:VCARD
 VERSION:4.0
@@ -422,3 +460,274 @@ END:VCARD"#;
     assert_round_trip(&card)?;
     Ok(())
 }
+
+#[test]
+fn extension_date_partial() -> Result<()> {
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nX-FOO;VALUE=date:--0203,20221107\r\nEND:VCARD\r\n";
+    let card = parse(input)?.remove(0);
+    let prop = card.extensions.first().unwrap();
+    let AnyProperty::DateAndOrTime(values) = &prop.value else {
+        panic!("expected date and or time values");
+    };
+    assert_eq!(2, values.len());
+    assert!(matches!(values[0], DateAndOrTime::PartialDate(_)));
+    assert!(matches!(values[1], DateAndOrTime::Date(_)));
+    assert_eq!(input, card.to_string());
+    Ok(())
+}
+
+#[test]
+fn extension_iana_token() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+GRAMGENDER;LANGUAGE=en:feminine
+X-DATE;VALUE=date:19960415
+item1.CONTACT-URI;PREF=1:mailto:contact@example.com
+HOMEPAGE;TYPE=work:https://example.com/jane
+NOTEBOOK:Not a NOTE
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert_eq!(5, card.extensions.len());
+    assert!(card.note.is_empty());
+
+    let prop = &card.extensions[0];
+    assert_eq!("GRAMGENDER", &prop.name);
+    assert_eq!(&AnyProperty::Text("feminine".to_owned()), &prop.value);
+
+    let prop = &card.extensions[1];
+    assert_eq!("X-DATE", &prop.name);
+    assert!(matches!(prop.value, AnyProperty::Date(_)));
+
+    let prop = &card.extensions[2];
+    assert_eq!("CONTACT-URI", &prop.name);
+    assert_eq!(Some("item1"), prop.group.as_deref());
+    assert_eq!(Some(1), prop.parameters.as_ref().unwrap().pref);
+
+    let prop = &card.extensions[3];
+    assert_eq!("HOMEPAGE", &prop.name);
+    assert_eq!(
+        &vec![TypeParameter::Work],
+        prop.parameters.as_ref().unwrap().types.as_ref().unwrap()
+    );
+
+    assert_eq!("NOTEBOOK", &card.extensions[4].name);
+
+    let encoded = card.to_string();
+    assert!(encoded.contains("\r\nGRAMGENDER;LANGUAGE=en:"));
+    assert!(encoded.contains("\r\nitem1.CONTACT-URI;PREF=1:"));
+    assert_eq!(card, parse(encoded)?.remove(0));
+
+    // The lexer does not allow names that are not tokens
+    assert!(parse(input.replace("NOTEBOOK", "NOTE BOOK")).is_err());
+    let card = parse_loose(input.replace("NOTEBOOK", "NOTE_BOOK"))?.remove(0);
+    assert_eq!(4, card.extensions.len());
+    Ok(())
+}
+
+#[test]
+fn extensions_promote_anniversary() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+item1.x-anniversary;ALTID=1:19960415
+X-FOO:bar
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+    assert_eq!(
+        1,
+        card.promote_extension("X-ANNIVERSARY", PropertyKind::Anniversary)?
+    );
+    assert_eq!(1, card.extensions.len());
+    let anniversary = card.anniversary.as_ref().unwrap();
+    assert_eq!(Some("item1"), anniversary.group().map(String::as_str));
+    assert_eq!(
+        Some("1"),
+        anniversary.parameters().and_then(|p| p.alt_id.as_deref())
+    );
+    assert!(matches!(
+        anniversary,
+        DateTimeOrTextProperty::DateTime(prop)
+            if matches!(prop.value[..], [DateAndOrTime::Date(_)])
+    ));
+    assert!(card
+        .to_string()
+        .contains("item1.ANNIVERSARY;ALTID=1:19960415\r\n"));
+    assert_round_trip(&card)?;
+
+    // Nothing to promote
+    assert_eq!(
+        0,
+        card.promote_extension("X-ANNIVERSARY", PropertyKind::Anniversary)?
+    );
+
+    // Text values are kept when VALUE is text
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+X-ANNIVERSARY;VALUE=text:Spring 1996
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+    card.promote_extension("x-anniversary", PropertyKind::Anniversary)?;
+    assert!(matches!(
+        card.anniversary,
+        Some(DateTimeOrTextProperty::Text(ref prop)) if prop.value == "Spring 1996"
+    ));
+    Ok(())
+}
+
+#[test]
+fn extensions_promote_gender_social_profile() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+X-GENDER:F;woman
+X-SOCIALPROFILE;SERVICE-TYPE=Example:https://example.com/jane
+X-SOCIALPROFILE;VALUE=text:janedoe
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+    assert_eq!(1, card.promote_extension("X-GENDER", PropertyKind::Gender)?);
+    assert_eq!(
+        2,
+        card.promote_extension(
+            "X-SOCIALPROFILE",
+            PropertyKind::SocialProfile
+        )?
+    );
+    assert!(card.extensions.is_empty());
+
+    let gender = &card.gender[0].value;
+    assert_eq!(Sex::Female, gender.sex);
+    assert_eq!(Some("woman"), gender.identity.as_deref());
+
+    assert!(matches!(
+        &card.social_profile[0],
+        TextOrUriProperty::Uri(prop)
+            if prop.value.to_string() == "https://example.com/jane"
+    ));
+    assert_eq!(
+        Some("Example"),
+        card.social_profile[0]
+            .parameters()
+            .and_then(|p| p.service_type.as_deref())
+    );
+    assert!(matches!(
+        &card.social_profile[1],
+        TextOrUriProperty::Text(prop) if prop.value == "janedoe"
+    ));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn extensions_promote_errors() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+BDAY:19531015
+X-BDAY:19531016
+X-GENDER:F
+X-GENDER:Unknown
+X-DATE;VALUE=integer:1
+X-PROFILE;TYPE=work:https://example.com
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+
+    // Extensions are kept when a value does not convert
+    assert!(card
+        .promote_extension("X-GENDER", PropertyKind::Gender)
+        .is_err());
+    assert!(card.gender.is_empty());
+    assert_eq!(5, card.extensions.len());
+
+    assert!(matches!(
+        card.promote_extension("X-BDAY", PropertyKind::Bday),
+        Err(Error::OnlyOnce(_))
+    ));
+    assert!(matches!(
+        card.promote_extension("X-DATE", PropertyKind::Anniversary),
+        Err(Error::UnsupportedValueType(_, _))
+    ));
+    assert!(matches!(
+        card.promote_extension("X-BDAY", PropertyKind::Address),
+        Err(Error::PromoteNotSupported(_))
+    ));
+    assert!(matches!(
+        card.promote_extension("X-PROFILE", PropertyKind::SocialProfile),
+        Err(Error::TypeParameter(_))
+    ));
+    assert_eq!(1, card.promote_extension("X-PROFILE", PropertyKind::Url)?);
+    assert_eq!(4, card.extensions.len());
+
+    let prop = card.extensions[0].clone();
+    let text = TextProperty::try_from(prop)?;
+    assert_eq!("19531016", text.value);
+    Ok(())
+}
+
+#[test]
+fn extension_value_type_synthesized() -> Result<()> {
+    let values = [
+        AnyProperty::Integer(vec![1, -2]),
+        AnyProperty::Float(vec![1.5]),
+        AnyProperty::Boolean(true),
+        AnyProperty::Date(parse_date_list("19850412")?),
+        AnyProperty::DateTime(parse_date_time_list("19961022T140000Z")?),
+        AnyProperty::Time(parse_time_list("102200Z")?),
+        AnyProperty::Timestamp(vec![parse_timestamp("19961022T140000Z")?]),
+        AnyProperty::UtcOffset(parse_utc_offset("-0500")?),
+        AnyProperty::Uri("https://example.com".parse()?),
+        AnyProperty::Text("text".to_owned()),
+    ];
+
+    for value in values {
+        let value_type = value.value_type();
+        let card = VcardBuilder::new("Jane Doe".to_owned())
+            .extension("X-VALUE", value)
+            .finish();
+        assert!(card.extensions[0].parameters.is_none());
+
+        let encoded = card.to_string();
+        if value_type == ValueType::Text {
+            assert!(encoded.contains("\r\nX-VALUE:text\r\n"));
+        } else {
+            let expected = format!("\r\nX-VALUE;VALUE={}:", value_type);
+            assert!(encoded.contains(&expected));
+        }
+
+        let decoded = parse(&encoded)?.remove(0);
+        assert_eq!(card.extensions[0].value, decoded.extensions[0].value);
+    }
+    Ok(())
+}
+
+#[test]
+fn extension_value_type_mismatch() -> Result<()> {
+    // The VALUE parameter does not match the value
+    let mut card = VcardBuilder::new("Jane Doe".to_owned())
+        .extension("X-COUNT", AnyProperty::Integer(vec![3]))
+        .extension_text("X-NOTE", "note".to_owned())
+        .finish();
+    card.extensions[0].parameters =
+        Some(Box::new(";TYPE=home;VALUE=text".parse()?));
+    card.extensions[1].parameters = Some(Box::new(";VALUE=integer".parse()?));
+    let encoded = card.to_string();
+    assert!(encoded.contains("\r\nX-COUNT;VALUE=integer;TYPE=home:3\r\n"));
+    assert!(encoded.contains("\r\nX-NOTE:note\r\n"));
+
+    let decoded = parse(&encoded)?.remove(0);
+    assert_eq!(AnyProperty::Integer(vec![3]), decoded.extensions[0].value);
+    assert_eq!(
+        AnyProperty::Text("note".to_owned()),
+        decoded.extensions[1].value
+    );
+
+    // Truncated dates keep the date VALUE
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nX-DATE;VALUE=date:--0412\nX-TYPE;VALUE=x-custom:value\nEND:VCARD";
+    let card = parse(input)?.remove(0);
+    let encoded = card.to_string();
+    assert!(encoded.contains("\r\nX-DATE;VALUE=date:--0412\r\n"));
+    assert!(encoded.contains("\r\nX-TYPE;VALUE=x-custom:value\r\n"));
+    assert_round_trip(&card)?;
+    Ok(())
+}
@@ -1,8 +1,13 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
 use test_helpers::assert_round_trip;
-use vcard4::parse;
+use vcard4::{
+    iter, parse, parse_borrowed, parse_collect, parse_with_options, Error,
+    LineEnding, ParseOptions, WriteOptions,
+};
 
 #[test]
 fn parse_multi_byte() -> Result<()> {
@@ -55,3 +60,224 @@ fn parse_folded_tab() -> Result<()> {
     assert_round_trip(&card)?;
     Ok(())
 }
+
+#[test]
+fn parse_escaped_newline_space() -> Result<()> {
+    // An escaped newline followed by whitespace is not a fold
+    for (input, expected) in [
+        (
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE:first\\n second\r\nEND:VCARD\r\n",
+            "first\n second",
+        ),
+        (
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE:first\\n\tsecond\r\nEND:VCARD\r\n",
+            "first\n\tsecond",
+        ),
+        (
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE:fir\r\n st\\n\r\n  second\r\nEND:VCARD\r\n",
+            "first\n second",
+        ),
+        (
+            "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Jane Doe\r\nNOTE:first\\n second\r\nEND:VCARD\r\n",
+            "first\n second",
+        ),
+    ] {
+        let card = parse(input)?.remove(0);
+        assert_eq!(expected, card.note[0].value);
+        assert_round_trip(&card)?;
+
+        let card = iter(input, true).next().unwrap()?;
+        assert_eq!(expected, card.note[0].value);
+
+        let card = parse_borrowed(input)?.remove(0);
+        assert_eq!(expected, card.note[0].value);
+    }
+    Ok(())
+}
+
+#[test]
+fn parse_folded_names() -> Result<()> {
+    let expected = parse(
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE;LANGUAGE=en:Note\r\nEND:VCARD",
+    )?;
+
+    let inputs = [
+        // Fold before the colon
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN\r\n :Jane Doe\r\nNOTE;LANGUAGE=en:Note\r\nEND:VCARD",
+        // Fold inside a property name
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNO\r\n TE;LANGUAGE=en:Note\r\nEND:VCARD",
+        // Fold inside BEGIN
+        "BEG\r\n IN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE;LANGUAGE=en:Note\r\nEND:VCARD",
+        // Fold inside a parameter name and value
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE;LANG\r\n\tUAGE=e\r\n n:Note\r\nEND:VCARD",
+    ];
+    for input in inputs {
+        assert_eq!(expected, parse(input)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn parse_folded_locations() -> Result<()> {
+    // Locations refer to the source before unfolding
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane\n  Doe\nBDAY;VALUE=date-and-or-time:not-a-date\nNO\n TE;LABEL=work:bar\nEND:VCARD";
+    let result =
+        parse_with_options(input, ParseOptions::new().locations(true));
+    assert!(matches!(
+        result,
+        Err(Error::Parse {
+            line: 5,
+            column: 1,
+            ..
+        })
+    ));
+
+    let (_, errors) = parse_collect(input)?.remove(0);
+    assert_eq!(2, errors.len());
+    assert_eq!("BDAY;VALUE=date-and-or-time:not-a-date", errors[0].raw);
+    assert_eq!("NO\n TE;LABEL=work:bar", errors[1].raw);
+    assert_eq!(errors[1].raw, input[errors[1].span.clone()].trim_end());
+
+    let card = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane\n  Doe\nEND:VC\n ARD";
+    let input = format!("{}\n{}\n", card, card);
+    let mut it = iter(&input, true);
+    while let Some(result) = it.next_with_span() {
+        let (_, span) = result?;
+        assert_eq!(card, input[span].trim_end());
+    }
+    Ok(())
+}
+
+#[test]
+fn parse_line_endings() -> Result<()> {
+    let lines = [
+        "BEGIN:VCARD",
+        "VERSION:4.0",
+        "FN:Mr. John Q.",
+        "  Public",
+        "NOTE:Line one\\nLine\\, two",
+        "ORG:ABC\\, Inc.;North American Division",
+        "END:VCARD",
+    ];
+    let crlf = lines.join("\r\n");
+    let expected = parse(&crlf)?;
+    assert_eq!("Mr. John Q. Public", expected[0].formatted_name[0].value);
+
+    let inputs = [
+        lines.join("\n"),
+        format!("{}\r\n", crlf),
+        // Mixed line endings
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                format!(
+                    "{}{}",
+                    line,
+                    if index % 2 == 0 { "\r\n" } else { "\n" }
+                )
+            })
+            .collect(),
+        // Stray carriage returns before a line feed
+        lines.join("\r\r\n"),
+        // Trailing carriage return
+        format!("{}\r", lines.join("\n")),
+    ];
+    for input in inputs {
+        assert_eq!(expected, parse(&input)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn fold_long_lines() -> Result<()> {
+    let note =
+        "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(4);
+    let input = format!(
+        "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nNOTE:{}\nNOTE:{}\nEND:VCARD",
+        note,
+        "Grüße aus Köln 😀 ".repeat(12),
+    );
+    let card = parse(input)?.remove(0);
+    let encoded = card.to_string();
+    assert!(encoded.contains("\r\n "));
+    let line = encoded.lines().find(|l| l.starts_with("NOTE:")).unwrap();
+    assert!(line.len() <= 75);
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn fold_line_length() -> Result<()> {
+    let family = "👨‍👩‍👧‍👦";
+    for prefix in 0..8 {
+        let note = format!(
+            "{}{}",
+            "x".repeat(prefix),
+            format!("Grüße 😀 {} ", family).repeat(20)
+        );
+        let input = format!(
+            "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nNOTE:{}\nEND:VCARD",
+            note
+        );
+        let card = parse(input)?.remove(0);
+        let encoded = card.to_string();
+        assert!(encoded.contains("\r\n "));
+        for line in encoded.split("\r\n") {
+            assert!(line.len() <= 75, "line too long: {}", line);
+        }
+
+        // Folds are never inserted inside a grapheme cluster
+        if cfg!(feature = "unicode-segmentation") {
+            assert_eq!(20, encoded.matches(family).count());
+        }
+
+        assert_eq!(note, card.note.first().unwrap().value);
+        assert_round_trip(&card)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn fold_disabled() -> Result<()> {
+    let note = "0123456789".repeat(30);
+    let input = format!(
+        "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nNOTE:{}\nEND:VCARD",
+        note
+    );
+    let card = parse(input)?.remove(0);
+
+    let options = WriteOptions::new().fold_width(None);
+    let encoded = card.to_string_with(&options);
+    assert!(encoded.contains(&format!("\r\nNOTE:{}\r\n", note)));
+    assert_eq!(card, parse(&encoded)?.remove(0));
+
+    // Default options match the Display implementation
+    assert_eq!(card.to_string(), card.to_string_with(&WriteOptions::new()));
+    Ok(())
+}
+
+#[test]
+fn fold_line_ending_lf() -> Result<()> {
+    let note = "0123456789".repeat(30);
+    let input = format!(
+        "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nNOTE:{}\nEND:VCARD",
+        note
+    );
+    let card = parse(input)?.remove(0);
+
+    let options = WriteOptions::new()
+        .fold_width(Some(76))
+        .line_ending(LineEnding::Lf);
+    let encoded = card.to_string_with(&options);
+    assert!(!encoded.contains('\r'));
+    assert!(encoded.starts_with("BEGIN:VCARD\nVERSION:4.0\n"));
+    assert!(encoded.ends_with("END:VCARD\n"));
+    assert_eq!(4, encoded.matches("\n ").count());
+    for line in encoded.lines() {
+        assert!(line.len() <= 76);
+    }
+    assert!(encoded.lines().any(|line| line.len() == 76));
+    assert_eq!(card, parse(&encoded)?.remove(0));
+    Ok(())
+}
@@ -0,0 +1,214 @@
+// Parameters implements Drop with the zeroize feature so struct
+// update syntax cannot be used to build generated values
+#![allow(clippy::field_reassign_with_default)]
+
+use anyhow::Result;
+use proptest::prelude::*;
+use time::UtcOffset;
+use vcard4::{
+    parameter::{
+        Parameters, Pid, RelatedType, TelephoneType, TimeZoneParameter,
+        TypeParameter, ValueType,
+    },
+    parse, parse_property,
+    property::{ParsedProperty, TextOrUriProperty},
+    Error,
+};
+
+#[test]
+fn fragment_parse() -> Result<()> {
+    let params: Parameters = ";TYPE=work;PREF=1".parse()?;
+    assert_eq!(Some(1), params.pref);
+    assert_eq!(Some(vec![TypeParameter::Work]), params.types);
+    assert_eq!(";PREF=1;TYPE=work", params.to_string());
+
+    let params =
+        Parameters::from_fragment(r#";ALTID="a;b:c";LABEL="Home""#, None)?;
+    assert_eq!(Some("a;b:c"), params.alt_id.as_deref());
+    assert_eq!(Some("Home"), params.label.as_deref());
+
+    assert_eq!(Parameters::default(), "".parse()?);
+    Ok(())
+}
+
+#[test]
+fn fragment_property_rules() -> Result<()> {
+    assert!(Parameters::from_fragment(";TYPE=home", Some("TEL")).is_ok());
+    assert!(matches!(
+        Parameters::from_fragment(";TYPE=home", Some("PRODID")),
+        Err(Error::TypeParameter(_))
+    ));
+    assert!(Parameters::from_fragment(";LABEL=Home", Some("ADR")).is_ok());
+    assert!(matches!(
+        Parameters::from_fragment(";LABEL=Home", Some("FN")),
+        Err(Error::InvalidLabel(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn fragment_errors() -> Result<()> {
+    assert!(matches!(
+        "TYPE=work".parse::<Parameters>(),
+        Err(Error::DelimiterExpected)
+    ));
+    assert!(";FOO=bar".parse::<Parameters>().is_err());
+    assert!(";PREF=1:value".parse::<Parameters>().is_err());
+    Ok(())
+}
+
+#[test]
+fn fragment_property() -> Result<()> {
+    let (name, group, prop) =
+        parse_property("EMAIL;TYPE=work:jane@corp.example")?;
+    assert_eq!("EMAIL", name);
+    assert!(group.is_none());
+    let ParsedProperty::Text(email) = &prop else {
+        panic!("expecting a text property");
+    };
+    assert_eq!("jane@corp.example", email.value);
+    assert_eq!(
+        Some(vec![TypeParameter::Work]),
+        email.parameters.as_ref().and_then(|p| p.types.clone())
+    );
+
+    // Same values as the full parser
+    let line = "item1.TEL;VALUE=uri;TYPE=cell:tel:+1-555-\r\n 555-5555\r\n";
+    let (name, group, prop) = parse_property(line)?;
+    assert_eq!("TEL", name);
+    assert_eq!(Some("item1".to_owned()), group);
+    let card = parse(format!(
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n{}END:VCARD\r\n",
+        line
+    ))?
+    .remove(0);
+    assert_eq!(ParsedProperty::TextOrUri(card.tel[0].clone()), prop);
+    assert!(matches!(
+        prop,
+        ParsedProperty::TextOrUri(TextOrUriProperty::Uri(_))
+    ));
+    assert_eq!("tel:+1-555-555-5555", prop.property().to_string());
+
+    let (name, _, prop) = parse_property("X-FOO;X-A=b:bar")?;
+    assert_eq!("X-FOO", name);
+    assert!(matches!(prop, ParsedProperty::Extension(_)));
+
+    let (name, _, prop) = parse_property("N:Doe;Jane;;;")?;
+    assert_eq!("N", name);
+    assert!(matches!(prop, ParsedProperty::Name(_)));
+    Ok(())
+}
+
+#[test]
+fn fragment_property_errors() {
+    assert!(matches!(parse_property(""), Err(Error::TokenExpected)));
+    assert!(matches!(
+        parse_property("FN:Jane\r\nNOTE:Other"),
+        Err(Error::IncorrectToken(_))
+    ));
+    assert!(parse_property("BDAY:not-a-date").is_err());
+    assert!(parse_property("BEGIN:VCARD").is_err());
+    assert!(parse_property(":value").is_err());
+}
+
+fn text() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9][a-zA-Z0-9 -]{0,8}[a-zA-Z0-9]"
+}
+
+fn value_type() -> impl Strategy<Value = ValueType> {
+    prop_oneof![
+        Just(ValueType::Text),
+        Just(ValueType::Uri),
+        Just(ValueType::Date),
+        Just(ValueType::DateAndOrTime),
+        Just(ValueType::Integer),
+        Just(ValueType::LanguageTag),
+        "[a-z]{3,8}".prop_map(ValueType::IanaToken),
+        "x-[a-z]{3,8}".prop_map(ValueType::Extension),
+    ]
+}
+
+fn type_parameter() -> impl Strategy<Value = TypeParameter> {
+    prop_oneof![
+        Just(TypeParameter::Home),
+        Just(TypeParameter::Work),
+        Just(TypeParameter::Telephone(TelephoneType::Cell)),
+        Just(TypeParameter::Telephone(TelephoneType::Voice)),
+        Just(TypeParameter::Related(RelatedType::Friend)),
+        "custom-[a-z]{1,4}".prop_map(TypeParameter::Extension),
+    ]
+}
+
+fn timezone() -> impl Strategy<Value = TimeZoneParameter> {
+    prop_oneof![
+        (-12i8..=14, prop_oneof![Just(0i8), Just(30i8)]).prop_map(
+            |(h, m)| {
+                let m = if h < 0 { -m } else { m };
+                TimeZoneParameter::UtcOffset(
+                    UtcOffset::from_hms(h, m, 0).unwrap(),
+                )
+            }
+        ),
+        "[A-Z][a-z]{2,8}/[A-Z][a-z]{2,8}".prop_map(TimeZoneParameter::Text),
+        "[a-z]{1,8}".prop_map(|s| TimeZoneParameter::Uri(
+            format!("https://example.com/tz/{}", s).parse().unwrap()
+        )),
+    ]
+}
+
+prop_compose! {
+    fn parameters()(
+        language in proptest::option::of(
+            prop_oneof![Just("en"), Just("fr-CA"), Just("ja")]),
+        value in proptest::option::of(value_type()),
+        pref in proptest::option::of(1u8..=100),
+        alt_id in proptest::option::of(text()),
+        pid in proptest::option::of(proptest::collection::vec(
+            (0u64..100, proptest::option::of(0u64..100))
+                .prop_map(|(local, source)| Pid::new(local, source)),
+            1..3)),
+        types in proptest::option::of(
+            proptest::collection::vec(type_parameter(), 1..4)),
+        media_type in proptest::option::of(
+            prop_oneof![Just("text/plain"), Just("image/png")]),
+        calscale in proptest::option::of("[a-z]{1,10}"),
+        sort_as in proptest::option::of(
+            proptest::collection::vec(text(), 1..3)),
+        geo in proptest::option::of((-90i16..=90, -180i16..=180)),
+        timezone in proptest::option::of(timezone()),
+        label in proptest::option::of(text()),
+        extensions in proptest::option::of(proptest::collection::vec(
+            ("X-[A-Z]{1,6}", proptest::collection::vec(text(), 1..3)),
+            1..3)),
+    ) -> Parameters {
+        let mut params = Parameters::default();
+        params.language = language.map(|l| l.parse().unwrap());
+        params.value = value;
+        params.pref = pref;
+        params.alt_id = alt_id;
+        params.pid = pid;
+        params.media_type = media_type.map(|m| m.parse().unwrap());
+        params.calscale = calscale;
+        params.sort_as = sort_as;
+        params.geo = geo.map(|(lat, lon)| {
+            format!("geo:{},{}", lat, lon).parse().unwrap()
+        });
+        params.timezone = timezone;
+        params.label = label;
+        params.extensions = extensions;
+        // Duplicate TYPE values are removed when parsing
+        for value in types.unwrap_or_default() {
+            params.add_type(value);
+        }
+        params
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_fragment_round_trip(params in parameters()) {
+        let fragment = params.to_string();
+        let decoded = Parameters::from_fragment(&fragment, None).unwrap();
+        prop_assert_eq!(params, decoded);
+    }
+}
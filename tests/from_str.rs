@@ -0,0 +1,77 @@
+use proptest::prelude::*;
+use std::str::FromStr;
+use vcard4::{
+    data::DataUri,
+    geo::GeoUri,
+    helper::*,
+    parameter::{
+        Parameters, Pid, RelatedType, TelephoneType, TypeParameter, ValueType,
+    },
+    property::{
+        ClientPidMap, DateAndOrTime, DeliveryAddress, Gender, Kind, Sex,
+        StructuredName, UtcOffsetProperty,
+    },
+    tel::TelUri,
+    Date, DateTime, PartialDate, Uri,
+};
+
+/// Parse a value discarding the result; a panic fails the test.
+fn check<T: FromStr>(value: &str) {
+    let _ = value.parse::<T>();
+}
+
+/// Arbitrary text and text made of the characters used
+/// by dates, times and URIs mixed with multi-byte characters.
+fn input() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<String>(),
+        "[-+TZ:0-9.;,=±é中😀\\\\ ]{0,24}",
+        "(geo|tel|data|x-|X-|T|--|-)[-+TZ:0-9.;,=±é中😀/ ]{0,24}",
+    ]
+}
+
+proptest! {
+    #[test]
+    fn from_str_no_panic(value in input()) {
+        check::<GeoUri>(&value);
+        check::<TypeParameter>(&value);
+        check::<Pid>(&value);
+        check::<RelatedType>(&value);
+        check::<TelephoneType>(&value);
+        check::<ValueType>(&value);
+        check::<Parameters>(&value);
+        check::<DeliveryAddress>(&value);
+        check::<StructuredName>(&value);
+        check::<ClientPidMap>(&value);
+        check::<DateAndOrTime>(&value);
+        check::<UtcOffsetProperty>(&value);
+        check::<Kind>(&value);
+        check::<Gender>(&value);
+        check::<Sex>(&value);
+        check::<DataUri>(&value);
+        check::<Uri>(&value);
+        check::<TelUri>(&value);
+        check::<DateTime>(&value);
+        check::<Date>(&value);
+        check::<PartialDate>(&value);
+    }
+
+    #[test]
+    fn helper_no_panic(value in input()) {
+        let _ = parse_utc_offset(&value);
+        let _ = parse_time_list(&value);
+        let _ = parse_date_list(&value);
+        let _ = parse_date_time_list(&value);
+        let _ = parse_timestamp_list(&value);
+        let _ = parse_date_and_or_time_list(&value);
+        let _ = parse_boolean(&value);
+        let _ = parse_integer_list(&value);
+        let _ = parse_float_list(&value);
+    }
+}
+
+#[test]
+fn from_str_multi_byte_prefix() {
+    assert!("tೈl:+1".parse::<TelUri>().is_err());
+    assert!(":0".parse::<Uri>().is_err());
+}
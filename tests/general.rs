@@ -1,8 +1,10 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
 use test_helpers::assert_round_trip;
-use vcard4::{parse, property::*};
+use vcard4::{parse, property::*, Error};
 
 // General
 
@@ -78,6 +80,39 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn general_kind_extension() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+KIND:Application
+FN:Calendar Service
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert_eq!(Kind::Application, card.kind.as_ref().unwrap().value);
+    assert!(card.to_string().contains("\r\nKIND:application\r\n"));
+    assert_round_trip(&card)?;
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+KIND:x-Device
+FN:Thermostat
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert_eq!(
+        Kind::Extension("x-Device".to_owned()),
+        card.kind.as_ref().unwrap().value
+    );
+    assert!(card.to_string().contains("\r\nKIND:x-Device\r\n"));
+    assert_round_trip(&card)?;
+
+    assert!(matches!("".parse::<Kind>(), Err(Error::UnknownKind(_))));
+    assert!(matches!(
+        "a device".parse::<Kind>(),
+        Err(Error::UnknownKind(_))
+    ));
+    Ok(())
+}
+
 #[test]
 fn general_xml() -> Result<()> {
     let input = r#"BEGIN:VCARD
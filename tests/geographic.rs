@@ -1,8 +1,17 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
 use test_helpers::assert_round_trip;
-use vcard4::{parse, property::*};
+use vcard4::{
+    geo::GeoUri,
+    parameter::{Parameters, ValueType},
+    parse,
+    property::*,
+    time::UtcOffset,
+    Vcard, VcardBuilder,
+};
 
 // Geographic Properties
 
@@ -72,6 +81,69 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn geographic_tz_utc_offset() -> Result<()> {
+    let tz = |value: &str| -> Result<TimeZoneProperty> {
+        let input = format!(
+            "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nTZ;VALUE=utc-offset:{}\nEND:VCARD",
+            value
+        );
+        let card = parse(input)?.remove(0);
+        assert_round_trip(&card)?;
+        Ok(card.timezone[0].clone())
+    };
+    let offset = |value: &str| -> Result<((i8, i8, i8), String)> {
+        let TimeZoneProperty::UtcOffset(prop) = &tz(value)? else {
+            panic!("expecting utc-offset value for TZ");
+        };
+        Ok((prop.value.as_hms(), prop.to_string()))
+    };
+
+    assert_eq!(((0, 0, 0), "+0000".to_owned()), offset("Z")?);
+    assert_eq!(((5, 0, 0), "+0500".to_owned()), offset("+05")?);
+    assert_eq!(((-5, 0, 0), "-0500".to_owned()), offset("-05")?);
+    assert_eq!(((-5, -30, 0), "-0530".to_owned()), offset("-0530")?);
+    assert_eq!(((0, -30, 0), "-0030".to_owned()), offset("-0030")?);
+
+    // Minutes without hours are not allowed
+    assert!(tz("30").is_err());
+    assert!(tz(":30").is_err());
+    assert!("+5".parse::<UtcOffsetProperty>().is_err());
+
+    let prop: UtcOffsetProperty = "-05".parse()?;
+    assert_eq!("-0500", prop.to_string());
+    Ok(())
+}
+
+#[test]
+fn geographic_tz_utc_offset_all() -> Result<()> {
+    // Every quarter hour from -12:00 to +14:00
+    for quarters in -48i32..=56 {
+        let minutes = quarters * 15;
+        let value = UtcOffset::from_whole_seconds(minutes * 60)?;
+        let sign = if minutes < 0 { '-' } else { '+' };
+        let expected = format!(
+            "{}{:02}{:02}",
+            sign,
+            minutes.abs() / 60,
+            minutes.abs() % 60
+        );
+
+        let prop = UtcOffsetProperty::from(value).with_parameters(
+            Parameters::new().with_value(ValueType::UtcOffset),
+        );
+        assert_eq!(expected, prop.to_string());
+        let parsed: UtcOffsetProperty = expected.parse()?;
+        assert_eq!(value, parsed.value);
+
+        let mut card = Vcard::new("Jane Doe".to_owned());
+        card.timezone.push(TimeZoneProperty::UtcOffset(prop));
+        assert!(card.to_string().contains(&format!(":{}\r\n", expected)));
+        assert_round_trip(&card)?;
+    }
+    Ok(())
+}
+
 #[test]
 fn geographic_geo() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -86,6 +158,49 @@ END:VCARD"#;
     let geo = card.geo.get(0).unwrap();
 
     assert_eq!("geo:37.386013,-122.082932", &geo.value.to_string());
+
+    let coords = geo.as_geo().unwrap();
+    assert_eq!(37.386013, coords.latitude);
+    assert_eq!(-122.082932, coords.longitude);
+    assert!(coords.altitude.is_none());
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn geographic_geo_uri() -> Result<()> {
+    let geo: GeoUri = "geo:48.2010,16.3695,183;crs=wgs84;u=66.5".parse()?;
+    assert_eq!(48.201, geo.latitude);
+    assert_eq!(16.3695, geo.longitude);
+    assert_eq!(Some(183.0), geo.altitude);
+    assert_eq!(Some("wgs84"), geo.crs.as_deref());
+    assert_eq!(Some(66.5), geo.uncertainty);
+    assert_eq!("geo:48.201,16.3695,183;crs=wgs84;u=66.5", geo.to_string());
+
+    let geo: GeoUri = "geo:37.386013\\,-122.082932".parse()?;
+    assert_eq!(-122.082932, geo.longitude);
+
+    assert!("geo:90.5,0".parse::<GeoUri>().is_err());
+    assert!("geo:0,-180.1".parse::<GeoUri>().is_err());
+    assert!("geo:inf,0".parse::<GeoUri>().is_err());
+    assert!("geo:1e2,0".parse::<GeoUri>().is_err());
+    assert!("geo:0".parse::<GeoUri>().is_err());
+    assert!("geo:0,0;u=-1".parse::<GeoUri>().is_err());
+    assert!("https://example.com".parse::<GeoUri>().is_err());
+    Ok(())
+}
+
+#[test]
+fn geographic_geo_coords() -> Result<()> {
+    let card = VcardBuilder::new("Jane Doe".to_owned())
+        .geo_coords(37.386013, -122.082932)
+        .geo_coords(91.0, 0.0)
+        .finish();
+    assert_eq!(1, card.geo.len());
+    assert_eq!(
+        "geo:37.386013,-122.082932",
+        card.geo.first().unwrap().value.to_string()
+    );
     assert_round_trip(&card)?;
     Ok(())
 }
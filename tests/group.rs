@@ -1,8 +1,14 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
 use test_helpers::assert_round_trip;
-use vcard4::parse;
+use vcard4::{
+    parse,
+    property::{Property, PropertyGroup, TextOrUriProperty},
+    Error, PropertyHandle, PropertyKind, Uri, VcardBuilder, WriteOptions,
+};
 
 #[test]
 fn group() -> Result<()> {
@@ -26,3 +32,255 @@ END:VCARD"#;
     assert_round_trip(&card)?;
     Ok(())
 }
+
+#[test]
+fn group_label() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+item1.TEL:(123) 456-7890
+item1.X-ABLabel:my-custom-phone
+ITEM2.EMAIL:jane@example.com
+item2.x-ablabel:_$!<Other>!$_
+TEL:+1-555-555-0000
+item3.X-ABLabel:orphan
+END:VCARD"#;
+
+    let card = parse(input)?.remove(0);
+
+    let tel = &card.tel[0];
+    assert!(matches!(tel, TextOrUriProperty::Text(_)));
+    assert_eq!(Some("my-custom-phone"), card.label_for(tel));
+    assert_eq!(Some("_$!<Other>!$_"), card.label_for(&card.email[0]));
+    assert_eq!(None, card.label_for(&card.tel[1]));
+
+    let groups = card.property_groups();
+    assert_eq!(3, groups.len());
+
+    let item1 = &groups["item1"];
+    assert_eq!(2, item1.len());
+    assert_eq!(PropertyKind::Tel, item1[0].kind());
+    assert_eq!("X-ABLabel", item1[1].name());
+
+    let item2 = &groups["item2"];
+    assert_eq!(PropertyKind::Email, item2[0].kind());
+    assert_eq!(PropertyKind::Extension, item2[1].kind());
+
+    // Groups that only contain extensions
+    let item3 = &groups["item3"];
+    assert_eq!(1, item3.len());
+    assert_eq!("orphan", item3[0].property().to_string());
+
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn group_label_builder() -> Result<()> {
+    let url: Uri = "https://example.com".parse()?;
+    let card = VcardBuilder::new("Jane Doe".to_owned())
+        .telephone("+1-555-555-0000".to_owned())
+        .telephone_with_label("(123) 456-7890".into(), "my-custom-phone")
+        .email_with_label("jane@example.com".into(), "_$!<Other>!$_")
+        .url_with_label(url, "blog")
+        .finish();
+
+    assert_eq!(Some("item1"), card.tel[1].group().map(|g| &g[..]));
+    assert_eq!(Some("my-custom-phone"), card.label_for(&card.tel[1]));
+    assert_eq!(Some("_$!<Other>!$_"), card.label_for(&card.email[0]));
+    assert_eq!(Some("blog"), card.label_for(&card.url[0]));
+    assert_eq!(None, card.label_for(&card.tel[0]));
+    assert_eq!(3, card.property_groups().len());
+
+    let names: Vec<String> = card
+        .properties_in_source_order()
+        .map(|prop| match prop.property().group() {
+            Some(group) => format!("{}.{}", group, prop.name()),
+            None => prop.name().to_owned(),
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            "FN",
+            "TEL",
+            "item1.TEL",
+            "item1.X-ABLabel",
+            "item2.EMAIL",
+            "item2.X-ABLabel",
+            "item3.URL",
+            "item3.X-ABLabel",
+        ],
+        names
+    );
+
+    // Labels are written next to the labeled property by default
+    let encoded = card.to_string();
+    assert!(encoded.contains(
+        "item1.TEL:(123) 456-7890\r\nitem1.X-ABLabel:my-custom-phone\r\n"
+    ));
+    assert!(encoded.contains(
+        "item2.EMAIL:jane@example.com\r\nitem2.X-ABLabel:_$!<Other>!$_\r\n"
+    ));
+    assert!(encoded.contains(
+        "item3.URL:https://example.com/\r\nitem3.X-ABLabel:blog\r\n"
+    ));
+
+    // Labels are written after the other properties by kind
+    let options = WriteOptions::new().group_properties(false);
+    assert!(card.to_string_with(&options).ends_with(
+        "item2.EMAIL:jane@example.com\r\n\
+        item1.X-ABLabel:my-custom-phone\r\n\
+        item2.X-ABLabel:_$!<Other>!$_\r\n\
+        item3.X-ABLabel:blog\r\nEND:VCARD\r\n"
+    ));
+
+    let parsed = parse(encoded)?.remove(0);
+    assert_eq!(Some("my-custom-phone"), parsed.label_for(&parsed.tel[1]));
+    Ok(())
+}
+
+#[test]
+fn group_keyword_names() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+tz.TEL;TZ=-0500:+1-555-555-5555
+geo.GEO:geo:37.386013,-122.082932
+type.NOTE;TYPE=work:Note
+item1.EMAIL:jane@example.com
+TZ.TZ:-0500
+GEO.X-FOO:bar
+tz.TEL;tz=-0500;geo="geo:1,2":+1-555-555-5556
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let names: Vec<String> = card
+        .properties_in_source_order()
+        .map(|prop| match prop.property().group() {
+            Some(group) => format!("{}.{}", group, prop.name()),
+            None => prop.name().to_owned(),
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            "FN",
+            "tz.TEL",
+            "geo.GEO",
+            "type.NOTE",
+            "item1.EMAIL",
+            "TZ.TZ",
+            "GEO.X-FOO",
+            "tz.TEL",
+        ],
+        names
+    );
+
+    let params = card.tel[1].parameters().unwrap();
+    assert!(params.timezone.is_some());
+    assert!(params.geo.is_some());
+    assert!(card.note[0].parameters().unwrap().types.is_some());
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn group_name_invalid() -> Result<()> {
+    for name in ["", "my group!", "item.1", "ä", "a;b"] {
+        assert!(matches!(
+            PropertyGroup::new(name),
+            Err(Error::InvalidGroup(_))
+        ));
+    }
+    let group: PropertyGroup = "Item-1".parse()?;
+    assert_eq!("Item-1", group.as_str());
+    assert_eq!("Item-1", String::from(group));
+
+    // Invalid names set directly are omitted when written
+    let mut card = VcardBuilder::new("Jane Doe".to_owned())
+        .telephone("+1-555-555-5555".to_owned())
+        .finish();
+    if let TextOrUriProperty::Text(prop) = &mut card.tel[0] {
+        prop.group = Some("my group!".to_owned());
+    }
+    let handle = PropertyHandle::new(PropertyKind::Tel, 0);
+    assert!(matches!(
+        &card.validation_issues()[0].error,
+        Error::InvalidGroup(group) if group == "my group!"
+    ));
+
+    let encoded = card.to_string();
+    assert!(encoded.contains("\r\nTEL:+1-555-555-5555\r\n"));
+    let decoded = parse(&encoded)?.remove(0);
+    assert!(decoded.tel[0].group().is_none());
+
+    // Clear the invalid group
+    assert!(card.set_group(handle, None));
+    assert!(card.validate().is_ok());
+    Ok(())
+}
+
+#[test]
+fn group_set_round_trip() -> Result<()> {
+    let mut card = VcardBuilder::new("Jane Doe".to_owned())
+        .telephone("+1-555-555-5555".to_owned())
+        .email("jane@example.com".to_owned())
+        .finish();
+    let group = PropertyGroup::new("home-contact-1")?;
+    let tel = PropertyHandle::new(PropertyKind::Tel, 0);
+    let email = PropertyHandle::new(PropertyKind::Email, 0);
+    assert!(card.set_group(tel, Some(group.clone())));
+    assert!(card.set_group(email, Some(group)));
+    assert!(!card.set_group(PropertyHandle::new(PropertyKind::Tel, 1), None));
+
+    let encoded = card.to_string();
+    eprintln!("{encoded}");
+    assert!(encoded.contains("\r\nhome-contact-1.TEL:"));
+    assert!(encoded.contains("\r\nhome-contact-1.EMAIL:"));
+    assert_eq!(2, card.property_groups()["home-contact-1"].len());
+    assert!(card.validate().is_ok());
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn group_properties_adjacent() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+item1.TEL:+1-555-555-0001
+item1.X-ABLabel:_$!<Mobile>!$_
+item2.TEL:+1-555-555-0002
+item2.X-ABLabel:Pager
+ITEM1.EMAIL:jane@example.com
+NOTE:Note
+X-SOCIAL:jane
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    // Extensions are written last when grouping is disabled
+    let options = WriteOptions::new().group_properties(false);
+    let encoded = card.to_string_with(&options);
+    eprintln!("{encoded}");
+    let lines: Vec<&str> = encoded.lines().collect();
+    assert_eq!("item1.X-ABLabel:_$!<Mobile>!$_", lines[7]);
+
+    let encoded = card.to_string();
+    let lines: Vec<&str> = encoded.lines().collect();
+    assert_eq!(
+        vec![
+            "BEGIN:VCARD",
+            "VERSION:4.0",
+            "FN:Jane Doe",
+            "item1.TEL:+1-555-555-0001",
+            "ITEM1.EMAIL:jane@example.com",
+            "item1.X-ABLabel:_$!<Mobile>!$_",
+            "item2.TEL:+1-555-555-0002",
+            "item2.X-ABLabel:Pager",
+            "NOTE:Note",
+            "X-SOCIAL:jane",
+            "END:VCARD",
+        ],
+        lines
+    );
+    assert_eq!(card, parse(&encoded)?.remove(0));
+    Ok(())
+}
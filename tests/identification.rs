@@ -1,8 +1,13 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
 use test_helpers::assert_round_trip;
-use vcard4::{parse, property::*};
+use vcard4::{
+    parameter::ValueType, parse, parse_loose, parse_with_options,
+    property::*, Error, ParseOptions, PartialDate,
+};
 
 #[test]
 fn identification_fn() -> Result<()> {
@@ -32,11 +37,94 @@ END:VCARD"#;
 
     let card = vcards.remove(0);
     let name = card.name.as_ref().unwrap();
-    assert_eq!(vec!["Public", "John", "Quinlan", "Mr.", "Esq."], name.value);
+    assert_eq!(vec!["Public"], name.value.family_names);
+    assert_eq!(vec!["John"], name.value.given_names);
+    assert_eq!(vec!["Quinlan"], name.value.additional_names);
+    assert_eq!(vec!["Mr."], name.value.honorific_prefixes);
+    assert_eq!(vec!["Esq."], name.value.honorific_suffixes);
     assert_round_trip(&card)?;
     Ok(())
 }
 
+#[test]
+fn identification_n_multiple_values() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Mr. John Q. Public\, Esq.
+N:Public;John;Quinlan,Jake;Mr.;Esq.
+END:VCARD"#;
+    let mut vcards = parse(input)?;
+    let card = vcards.remove(0);
+    let name = card.name.as_ref().unwrap();
+    assert_eq!(vec!["Quinlan", "Jake"], name.value.additional_names);
+    assert!(card
+        .to_string()
+        .contains("N:Public;John;Quinlan,Jake;Mr.;Esq.\r\n"));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn identification_n_escaped() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+N:Doe\, Smith;Jane\;Mary;;;
+END:VCARD"#;
+    let mut vcards = parse(input)?;
+    let card = vcards.remove(0);
+    let name = card.name.as_ref().unwrap();
+    assert_eq!(vec!["Doe, Smith"], name.value.family_names);
+    assert_eq!(vec!["Jane;Mary"], name.value.given_names);
+    assert!(name.value.additional_names.is_empty());
+    assert!(card
+        .to_string()
+        .contains("N:Doe\\, Smith;Jane\\;Mary;;;\r\n"));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn identification_n_escaped_comma_list() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+N:Doe;Jane;Mary\, Ann,Lou;;
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let name = card.name.as_ref().unwrap();
+    assert_eq!(vec!["Mary, Ann", "Lou"], name.value.additional_names);
+    assert!(card
+        .to_string()
+        .contains("N:Doe;Jane;Mary\\, Ann,Lou;;\r\n"));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn identification_n_too_many_components() {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+N:Doe;Jane;;;;Extra
+END:VCARD"#;
+    assert!(matches!(parse(input), Err(Error::InvalidName(_))));
+}
+
+#[test]
+fn identification_n_too_many_components_loose() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+N:Doe;Jane;;;Esq.;Extra
+END:VCARD"#;
+    let card = parse_loose(input)?.remove(0);
+    let name = card.name.as_ref().unwrap();
+    assert_eq!(vec!["Doe"], name.value.family_names);
+    assert_eq!(vec!["Esq.;Extra"], name.value.honorific_suffixes);
+    Ok(())
+}
+
 #[test]
 fn identification_nickname() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -49,7 +137,11 @@ END:VCARD"#;
 
     let card = vcards.remove(0);
     let name = card.name.as_ref().unwrap();
-    assert_eq!(vec!["Public", "John", "Quinlan", "Mr.", "Esq."], name.value);
+    assert_eq!(vec!["Public"], name.value.family_names);
+    assert_eq!(vec!["John"], name.value.given_names);
+    assert_eq!(vec!["Quinlan"], name.value.additional_names);
+    assert_eq!(vec!["Mr."], name.value.honorific_prefixes);
+    assert_eq!(vec!["Esq."], name.value.honorific_suffixes);
     assert_round_trip(&card)?;
     Ok(())
 }
@@ -93,6 +185,37 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn identification_photo_data() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+PHOTO:data:image/png;base64\,iVBORw0K
+ Ggo=
+PHOTO:http://www.example.com/pub/photos/jqpublic.gif
+PHOTO:data:,Hello%2C%20World
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    let photo = card.photo_data(0)?.unwrap();
+    assert_eq!("image/png", photo.media_type);
+    assert!(photo.is_base64);
+    assert_eq!(b"\x89PNG\r\n\x1a\n", &photo.data[..]);
+
+    assert!(card.photo_data(1)?.is_none());
+    assert!(card.photo_data(3)?.is_none());
+
+    let text = card.photo_data(2)?.unwrap();
+    assert_eq!("text/plain;charset=US-ASCII", text.media_type);
+    assert!(!text.is_base64);
+    assert_eq!(b"Hello, World", &text.data[..]);
+
+    let prop = UriProperty::from_image_bytes("image/png", &photo.data)?;
+    assert_eq!("data:image/png;base64,iVBORw0KGgo=", prop.value.to_string());
+    assert_eq!(Some(photo), prop.data_uri()?);
+    Ok(())
+}
+
 #[test]
 fn identification_bday() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -135,6 +258,80 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn identification_bday_text_fallback() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+BDAY;LANGUAGE=en:circa 1800
+ANNIVERSARY:19960415
+END:VCARD"#;
+    assert!(parse(input).is_err());
+
+    for strict in [true, false] {
+        let options =
+            ParseOptions::new().strict(strict).date_text_fallback(true);
+        let card = parse_with_options(input, options)?.remove(0);
+        let Some(DateTimeOrTextProperty::Text(bday)) = &card.bday else {
+            panic!("expecting text for BDAY");
+        };
+        assert_eq!("circa 1800", bday.value);
+        let params = bday.parameters.as_ref().unwrap();
+        assert_eq!(Some(ValueType::Text), params.value);
+        let language = params.language.as_ref().map(|l| l.to_string());
+        assert_eq!(Some("en".to_owned()), language);
+        assert!(matches!(
+            card.anniversary,
+            Some(DateTimeOrTextProperty::DateTime(_))
+        ));
+        assert!(card
+            .to_string()
+            .contains("BDAY;LANGUAGE=en;VALUE=text:circa 1800\r\n"));
+        assert_round_trip(&card)?;
+    }
+
+    let card = parse_loose(input)?.remove(0);
+    assert!(matches!(
+        &card.bday,
+        Some(DateTimeOrTextProperty::Text(bday)) if bday.value == "circa 1800"
+    ));
+    Ok(())
+}
+
+#[test]
+fn identification_bday_partial() -> Result<()> {
+    let forms = [
+        ("--0203", PartialDate::new(None, Some(2), Some(3))?),
+        ("--0229", PartialDate::new(None, Some(2), Some(29))?),
+        ("---03", PartialDate::new(None, None, Some(3))?),
+        ("--02", PartialDate::new(None, Some(2), None)?),
+        ("1985", PartialDate::new(Some(1985), None, None)?),
+        ("1985-04", PartialDate::new(Some(1985), Some(4), None)?),
+    ];
+    for (value, expected) in forms {
+        let input = format!(
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nBDAY:{}\r\nANNIVERSARY:{}\r\nEND:VCARD\r\n",
+            value, value
+        );
+        let card = parse(&input)?.remove(0);
+        let Some(DateTimeOrTextProperty::DateTime(bday)) = &card.bday else {
+            panic!("expected date value for {}", value);
+        };
+        assert_eq!(vec![DateAndOrTime::PartialDate(expected)], bday.value);
+        assert_eq!(input, card.to_string());
+        assert_eq!(card, parse(card.to_string())?.remove(0));
+    }
+
+    for value in ["--1302", "--0230", "---32", "--02031"] {
+        let input = format!(
+            "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nBDAY:{}\nEND:VCARD",
+            value
+        );
+        assert!(parse(input).is_err(), "{} should be invalid", value);
+    }
+    Ok(())
+}
+
 #[test]
 fn identification_anniversary() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -166,6 +363,61 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn identification_birth_and_death() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+BIRTHPLACE:Babies'R'Us Hospital
+DEATHPLACE;VALUE=uri:geo:46.772673,-71.282945
+DEATHDATE:19960415
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert!(matches!(
+        card.birthplace.as_ref().unwrap(),
+        TextOrUriProperty::Text(prop) if prop.value == "Babies'R'Us Hospital"
+    ));
+    assert!(matches!(
+        card.deathplace.as_ref().unwrap(),
+        TextOrUriProperty::Uri(_)
+    ));
+    let deathdate = card.deathdate.as_ref().unwrap();
+    assert!(matches!(deathdate, DateTimeOrTextProperty::DateTime(_)));
+    assert_eq!("19960415", &deathdate.to_string());
+
+    let encoded = card.to_string();
+    assert!(encoded.contains("\r\nBIRTHPLACE:Babies'R'Us Hospital\r\n"));
+    assert!(encoded.contains("\r\nDEATHDATE:19960415\r\n"));
+    assert_eq!(card, parse(encoded)?.remove(0));
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+BIRTHPLACE:http://example.com/hospital
+DEATHDATE;VALUE=text:circa 1800
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    // Text is the default value type for places
+    assert!(matches!(
+        card.birthplace.as_ref().unwrap(),
+        TextOrUriProperty::Text(_)
+    ));
+    assert!(matches!(
+        card.deathdate.as_ref().unwrap(),
+        DateTimeOrTextProperty::Text(prop) if prop.value == "circa 1800"
+    ));
+    assert_eq!(card, parse(card.to_string())?.remove(0));
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+DEATHPLACE:Paris
+DEATHPLACE:London
+END:VCARD"#;
+    assert!(matches!(parse(input), Err(Error::OnlyOnce(_))));
+    Ok(())
+}
+
 #[test]
 fn identification_gender() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -177,8 +429,8 @@ END:VCARD"#;
     assert_eq!(1, vcards.len());
 
     let card = vcards.remove(0);
-    assert_eq!(Sex::Male, card.gender.as_ref().unwrap().value.sex);
-    assert_eq!(None, card.gender.as_ref().unwrap().value.identity);
+    assert_eq!(Sex::Male, card.gender.first().unwrap().value.sex);
+    assert_eq!(None, card.gender.first().unwrap().value.identity);
     assert_round_trip(&card)?;
 
     let input = r#"BEGIN:VCARD
@@ -190,8 +442,8 @@ END:VCARD"#;
     assert_eq!(1, vcards.len());
 
     let card = vcards.remove(0);
-    assert_eq!(Sex::Female, card.gender.as_ref().unwrap().value.sex);
-    assert_eq!(None, card.gender.as_ref().unwrap().value.identity);
+    assert_eq!(Sex::Female, card.gender.first().unwrap().value.sex);
+    assert_eq!(None, card.gender.first().unwrap().value.identity);
     assert_round_trip(&card)?;
 
     let input = r#"BEGIN:VCARD
@@ -203,11 +455,11 @@ END:VCARD"#;
     assert_eq!(1, vcards.len());
 
     let card = vcards.remove(0);
-    assert_eq!(Sex::Male, card.gender.as_ref().unwrap().value.sex);
+    assert_eq!(Sex::Male, card.gender.first().unwrap().value.sex);
     assert_eq!(
         "Fellow",
         card.gender
-            .as_ref()
+            .first()
             .unwrap()
             .value
             .identity
@@ -225,11 +477,11 @@ END:VCARD"#;
     assert_eq!(1, vcards.len());
 
     let card = vcards.remove(0);
-    assert_eq!(Sex::Female, card.gender.as_ref().unwrap().value.sex);
+    assert_eq!(Sex::Female, card.gender.first().unwrap().value.sex);
     assert_eq!(
         "grrrl",
         card.gender
-            .as_ref()
+            .first()
             .unwrap()
             .value
             .identity
@@ -247,11 +499,11 @@ END:VCARD"#;
     assert_eq!(1, vcards.len());
 
     let card = vcards.remove(0);
-    assert_eq!(Sex::Other, card.gender.as_ref().unwrap().value.sex);
+    assert_eq!(Sex::Other, card.gender.first().unwrap().value.sex);
     assert_eq!(
         "intersex",
         card.gender
-            .as_ref()
+            .first()
             .unwrap()
             .value
             .identity
@@ -269,11 +521,11 @@ END:VCARD"#;
     assert_eq!(1, vcards.len());
 
     let card = vcards.remove(0);
-    assert_eq!(Sex::None, card.gender.as_ref().unwrap().value.sex);
+    assert_eq!(Sex::None, card.gender.first().unwrap().value.sex);
     assert_eq!(
         "it's complicated",
         card.gender
-            .as_ref()
+            .first()
             .unwrap()
             .value
             .identity
@@ -284,3 +536,136 @@ END:VCARD"#;
 
     Ok(())
 }
+
+#[test]
+fn identification_gender_alternatives() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+GENDER;ALTID=1;LANGUAGE=en:F;woman
+GENDER;ALTID=1;LANGUAGE=fr:F;femme
+END:VCARD"#;
+    let mut vcards = parse(input)?;
+    assert_eq!(1, vcards.len());
+
+    let card = vcards.remove(0);
+    assert_eq!(2, card.gender.len());
+
+    let gender = card.gender_resolved(Some("fr")).unwrap();
+    assert_eq!(&Sex::Female, gender.sex());
+    assert_eq!(Some("femme"), gender.identity());
+
+    let gender = card.gender_resolved(Some("en-GB")).unwrap();
+    assert_eq!(Some("woman"), gender.identity());
+
+    let gender = card.gender_resolved(Some("de")).unwrap();
+    assert_eq!(Some("woman"), gender.identity());
+
+    let gender = card.gender_resolved(None).unwrap();
+    assert!(gender.sex().is_specified());
+    assert_eq!(Some("woman"), gender.identity());
+
+    assert_round_trip(&card)?;
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+GENDER;ALTID=1;LANGUAGE=en:F;woman
+GENDER;ALTID=2;LANGUAGE=fr:F;femme
+END:VCARD"#;
+    assert!(parse(input).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn identification_gender_new() -> Result<()> {
+    let gender = Gender::new(Sex::Other).with_identity("intersex");
+    assert_eq!("O;intersex", &gender.to_string());
+    assert!(!Gender::from(Sex::None).sex.is_specified());
+    Ok(())
+}
+
+#[test]
+fn identification_gender_case_insensitive() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+GENDER:f;something
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let gender = &card.gender.first().unwrap().value;
+    assert_eq!(Sex::Female, gender.sex);
+    assert_eq!(Some("something"), gender.identity.as_deref());
+    assert_eq!("F;something", gender.to_string());
+    assert_round_trip(&card)?;
+
+    for (value, sex) in [
+        ("m", Sex::Male),
+        ("o", Sex::Other),
+        ("n", Sex::NotApplicable),
+        ("u", Sex::Unknown),
+    ] {
+        assert_eq!(sex, value.parse::<Sex>()?);
+    }
+    Ok(())
+}
+
+#[test]
+fn identification_gender_unrecognized() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+GENDER:x;other
+END:VCARD"#;
+    assert!(matches!(parse(input), Err(Error::UnknownSex(_))));
+
+    let card = parse_loose(input)?.remove(0);
+    let gender = &card.gender.first().unwrap().value;
+    assert_eq!(Sex::Unrecognized('x'), gender.sex);
+    assert!(gender.sex.is_specified());
+    assert_eq!("x;other", gender.to_string());
+
+    let reparsed = parse_loose(card.to_string())?.remove(0);
+    assert_eq!(card, reparsed);
+
+    assert!("MF".parse::<Sex>().is_err());
+    assert!("?".parse::<Sex>().is_err());
+    Ok(())
+}
+
+#[test]
+fn identification_gender_escaped() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+GENDER:O;semi\;colon\, comma\\slash
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let gender = &card.gender.first().unwrap().value;
+    assert_eq!(Sex::Other, gender.sex);
+    assert_eq!(Some("semi;colon, comma\\slash"), gender.identity.as_deref());
+    assert_round_trip(&card)?;
+
+    let gender = Gender::new(Sex::Other).with_identity("a;b");
+    assert_eq!("O;a\\;b", gender.to_string());
+    assert_eq!(gender, gender.to_string().parse::<Gender>()?);
+    Ok(())
+}
+
+#[test]
+fn identification_pronouns() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+PRONOUNS;PREF=1:they/them
+PRONOUNS;LANGUAGE=de;PREF=2:sie/ihr
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert_eq!(2, card.pronouns.len());
+    assert_eq!("they/them", card.pronouns[0].value);
+    assert_eq!("sie/ihr", card.pronouns[1].value);
+    assert!(card.extensions.is_empty());
+    assert_round_trip(&card)?;
+    Ok(())
+}
@@ -1,5 +1,10 @@
+#![allow(clippy::redundant_pattern_matching)]
+
 use anyhow::Result;
-use vcard4::iter;
+use vcard4::{
+    iter, parse, parse_loose, parse_with_options, Error, ParseOptions,
+    Recovery, VcardIterator,
+};
 
 #[test]
 fn iter_one() -> Result<()> {
@@ -46,3 +51,213 @@ fn iter_error_expected() -> Result<()> {
     assert!(matches!(it.next(), None));
     Ok(())
 }
+
+#[test]
+fn iter_span() -> Result<()> {
+    let first = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nEND:VCARD";
+    let second = "BEGIN:VCARD\nVERSION:4.0\nFN:John Doe\nEND:VCARD";
+    let input = format!("{}\n\n{}\n", first, second);
+    let mut it = iter(&input, true);
+
+    let (card, span) = it.next_with_span().unwrap()?;
+    assert_eq!("Jane Doe", card.formatted_name[0].value);
+    assert_eq!(first, input[span].trim_end());
+
+    let (card, span) = it.next_with_span().unwrap()?;
+    assert_eq!("John Doe", card.formatted_name[0].value);
+    assert_eq!(second, input[span].trim_end());
+    assert!(it.next_with_span().is_none());
+    Ok(())
+}
+
+#[test]
+fn iter_span_reparse() -> Result<()> {
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nEND:VCARD\r\n\
+        BEGIN:VCARD\r\nVERSION:4.0\r\nFN:John\r\n  Doe\r\n\
+        NOTE:Folded\r\n note\r\nEND:VCARD\r\n\r\n\
+        BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Mary Doe\r\nEND:VCARD\r\n";
+    let mut it = iter(input, true);
+    let mut spans = Vec::new();
+    while let Some(result) = it.next_with_span() {
+        spans.push(result?);
+    }
+    assert_eq!(3, spans.len());
+
+    for (card, span) in spans {
+        let mut cards = parse(&input[span])?;
+        assert_eq!(1, cards.len());
+        assert_eq!(card, cards.remove(0));
+    }
+    Ok(())
+}
+
+#[test]
+fn iter_fused() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:John Doe
+UNKNOWN
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:Mary Doe
+END:VCARD"#;
+
+    // Errors stop the iteration in strict and loose mode
+    for strict in [true, false] {
+        let mut it = iter(input, strict);
+        assert!(matches!(it.next(), Some(Ok(_))));
+        assert!(matches!(it.next(), Some(Err(_))));
+        assert!(it.next().is_none());
+        assert!(it.next().is_none());
+        assert!(it.next_with_span().is_none());
+    }
+
+    let options = ParseOptions::new().recovery(Recovery::Report);
+    let mut it = VcardIterator::with_options(input, options);
+    assert!(matches!(it.next(), Some(Ok(_))));
+    assert!(matches!(it.next(), Some(Err(_))));
+    assert!(matches!(it.next(), Some(Ok(_))));
+    assert!(it.next().is_none());
+    assert!(it.next().is_none());
+    Ok(())
+}
+
+const BOUNDARY: &str = "--boundary\r\n\
+Content-Type: text/vcard\r\n\
+\r\n\
+BEGIN:VCARD\r\n\
+VERSION:4.0\r\n\
+FN:Jane Doe\r\n\
+END:VCARD\r\n\
+--boundary\r\n\
+  \r\n\
+BEGIN:VCARD\r\n\
+VERSION:4.0\r\n\
+FN:John Doe\r\n\
+END:VCARD\r\n\
+--boundary--\r\n";
+
+const TRUNCATED: &str = "BEGIN:VCARD\r\n\
+VERSION:4.0\r\n\
+FN:Jane Doe\r\n\
+NOTE:Truncated\r\n\
+BEGIN:VCARD\r\n\
+VERSION:4.0\r\n\
+FN:John Doe\r\n\
+END:VCARD\r\n";
+
+fn formatted_names(
+    it: VcardIterator<'_>,
+) -> Vec<std::result::Result<String, Error>> {
+    it.map(|card| card.map(|card| card.formatted_name[0].value.clone()))
+        .collect()
+}
+
+#[test]
+fn iter_recovery_boundary() -> Result<()> {
+    assert!(parse_loose(BOUNDARY).is_err());
+
+    let options = ParseOptions::new().recovery(Recovery::Skip);
+    let names: Vec<String> = parse_with_options(BOUNDARY, options)?
+        .into_iter()
+        .map(|card| card.formatted_name[0].value.clone())
+        .collect();
+    assert_eq!(vec!["Jane Doe", "John Doe"], names);
+
+    let names =
+        formatted_names(VcardIterator::with_options(BOUNDARY, options));
+    assert!(
+        matches!(&names[..], [Ok(a), Ok(b)] if a == "Jane Doe" && b == "John Doe")
+    );
+
+    let options = ParseOptions::new().recovery(Recovery::Report);
+    let names =
+        formatted_names(VcardIterator::with_options(BOUNDARY, options));
+    assert_eq!(5, names.len());
+    assert!(names[0].is_err());
+    assert_eq!("Jane Doe", names[1].as_ref().unwrap());
+    assert!(names[2].is_err());
+    assert_eq!("John Doe", names[3].as_ref().unwrap());
+    assert!(names[4].is_err());
+
+    // The skipped content is reported with its range
+    let skipped: Vec<&str> = names
+        .iter()
+        .filter_map(|name| match name {
+            Err(Error::SkippedContent(range)) => {
+                Some(&BOUNDARY[range.clone()])
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            "--boundary\r\nContent-Type: text/vcard\r\n\r\n",
+            "--boundary\r\n\r\n",
+            "--boundary--\r\n",
+        ],
+        skipped
+    );
+    Ok(())
+}
+
+#[test]
+fn iter_recovery_begin_line_start() -> Result<()> {
+    let input = "Subject: BEGIN:VCARD\r\n\
+BEGIN:VCARD\r\n\
+VERSION:4.0\r\n\
+FN:Jane Doe\r\n\
+END:VCARD\r\n";
+    let options = ParseOptions::new().recovery(Recovery::Report);
+    let names = formatted_names(VcardIterator::with_options(input, options));
+    assert_eq!(2, names.len());
+    assert!(matches!(
+        &names[0],
+        Err(Error::SkippedContent(range)) if &input[range.clone()] == "Subject: BEGIN:VCARD\r\n"
+    ));
+    assert_eq!("Jane Doe", names[1].as_ref().unwrap());
+    Ok(())
+}
+
+#[test]
+fn iter_recovery_limit() -> Result<()> {
+    let options = ParseOptions::new()
+        .recovery(Recovery::Skip)
+        .max_cards(Some(1));
+    let names =
+        formatted_names(VcardIterator::with_options(BOUNDARY, options));
+    assert_eq!(2, names.len());
+    assert_eq!("Jane Doe", names[0].as_ref().unwrap());
+    assert!(matches!(names[1], Err(Error::LimitExceeded { .. })));
+    Ok(())
+}
+
+#[test]
+fn iter_recovery_truncated() -> Result<()> {
+    let options = ParseOptions::new().recovery(Recovery::Report);
+    let names =
+        formatted_names(VcardIterator::with_options(TRUNCATED, options));
+    assert_eq!(2, names.len());
+    assert!(matches!(names[0], Err(Error::IncorrectToken(_))));
+    assert_eq!("John Doe", names[1].as_ref().unwrap());
+
+    let options = ParseOptions::new().strict(false).recovery(Recovery::Skip);
+    let cards = parse_with_options(TRUNCATED, options)?;
+    assert_eq!(1, cards.len());
+    assert_eq!("John Doe", cards[0].formatted_name[0].value);
+
+    // Limits are not recovered
+    let options = ParseOptions::new()
+        .recovery(Recovery::Skip)
+        .max_properties_per_card(Some(1));
+    assert!(matches!(
+        parse_with_options(TRUNCATED, options),
+        Err(Error::LimitExceeded { .. })
+    ));
+    Ok(())
+}
@@ -0,0 +1,168 @@
+#![cfg(feature = "jcard")]
+
+use anyhow::Result;
+use vcard4::{
+    jcard::{from_jcard, to_jcard},
+    parse,
+    property::Property,
+    Error,
+};
+
+// https://www.rfc-editor.org/rfc/rfc7095#appendix-B.1
+const EXAMPLE: &str = r#"["vcard",
+  [
+    ["version", {}, "text", "4.0"],
+    ["fn", {}, "text", "Simon Perreault"],
+    ["n",
+      {},
+      "text",
+      ["Perreault", "Simon", "", "", ["ing. jr", "M.Sc."]]
+    ],
+    ["bday", {}, "date-and-or-time", "--02-03"],
+    ["anniversary",
+      {},
+      "date-and-or-time",
+      "2009-08-08T14:30:00-05:00"
+    ],
+    ["gender", {}, "text", "M"],
+    ["lang", { "pref": "1" }, "language-tag", "fr"],
+    ["lang", { "pref": "2" }, "language-tag", "en"],
+    ["org", { "type": "work" }, "text", "Viagenie"],
+    ["adr",
+       { "type": "work" },
+       "text",
+       [
+        "",
+        "Suite D2-630",
+        "2875 Laurier",
+        "Quebec",
+        "QC",
+        "G1V 2M2",
+        "Canada"
+       ]
+    ],
+    ["tel",
+      { "type": ["work", "voice"], "pref": "1" },
+      "uri",
+      "tel:+1-418-656-9254;ext=102"
+    ],
+    ["tel",
+      { "type": ["work", "cell", "voice", "video", "text"] },
+      "uri",
+      "tel:+1-418-262-6501"
+    ],
+    ["email",
+      { "type": "work" },
+      "text",
+      "simon.perreault@viagenie.ca"
+    ],
+    ["geo", { "type": "work" }, "uri", "geo:46.772673,-71.282945"],
+    ["key",
+      { "type": "work" },
+      "uri",
+      "http://www.viagenie.ca/simon.perreault/simon.asc"
+    ],
+    ["tz", {}, "utc-offset", "-05:00"],
+    ["url", { "type": "home" }, "uri", "http://nomis80.org"]
+  ]
+]"#;
+
+#[test]
+fn jcard_rfc_example() -> Result<()> {
+    let card = from_jcard(EXAMPLE)?;
+    assert_eq!("Simon Perreault", card.formatted_name[0].value);
+    let name = &card.name.as_ref().unwrap().value;
+    assert_eq!(vec!["Perreault"], name.family_names);
+    assert_eq!(vec!["ing. jr", "M.Sc."], name.honorific_suffixes);
+    assert_eq!(vec!["Canada"], card.address[0].value.country_name);
+    assert_eq!(2, card.tel.len());
+    assert_eq!("tel:+1-418-656-9254;ext=102", card.tel[0].to_string());
+    assert_eq!(Some(1), card.tel[0].parameters().and_then(|p| p.pref));
+
+    let value = to_jcard(&card);
+    assert_eq!(Some("date-and-or-time"), value[1][3][2].as_str());
+    assert_eq!(Some("utc-offset"), value[1][14][2].as_str());
+    assert_eq!(Some("-05:00"), value[1][14][3].as_str());
+    assert_eq!(Some("M.Sc."), value[1][2][3][4][1].as_str());
+
+    let decoded = from_jcard(&value.to_string())?;
+    assert_eq!(card, decoded);
+    Ok(())
+}
+
+#[test]
+fn jcard_group_and_extensions() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+item1.EMAIL;X-SERVICE=work:jane@example.com
+CATEGORIES:friend,family
+ORG:Example;Research
+X-SCORE;VALUE=integer:1,2
+NOTE:semi\;colon\, comma
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let value = to_jcard(&card);
+    let email = &value[1][3];
+    assert_eq!(Some("item1"), email[1]["group"].as_str());
+    assert_eq!(Some("work"), email[1]["x-service"].as_str());
+
+    let decoded = from_jcard(&value.to_string())?;
+    assert_eq!(card, decoded);
+    Ok(())
+}
+
+#[test]
+fn jcard_invalid() -> Result<()> {
+    assert!(matches!(
+        from_jcard(r#"["vcalendar", []]"#),
+        Err(Error::InvalidJcard(_))
+    ));
+    assert!(matches!(
+        from_jcard(r#"["vcard", [["version", {}, "text", "3.0"]]]"#),
+        Err(Error::InvalidJcard(_))
+    ));
+    let card = from_jcard(r#"["vcard", [["fn", {}, "text", "a\nb"]]]"#)?;
+    assert_eq!("a\nb", card.formatted_name[0].value);
+    assert!(matches!(
+        from_jcard(r#"["vcard", [["url", {}, "uri", "http://a\nb"]]]"#),
+        Err(Error::InvalidJcard(_))
+    ));
+    assert!(matches!(from_jcard("{"), Err(Error::Json(_))));
+    Ok(())
+}
+
+#[test]
+fn jcard_parameter_values() -> Result<()> {
+    let card = from_jcard(
+        r#"["vcard", [["fn", {
+            "x-list": ["a,b", "say \"hi\"", "c:d"],
+            "x-single": "1,2 ^ \"3\""
+        }, "text", "Jane Doe"],
+        ["adr", { "label": "1 Main St, Springfield" }, "text",
+            ["", "", "1 Main St", "Springfield", "", "", ""]]]]"#,
+    )?;
+    let params = card.formatted_name[0].parameters().unwrap();
+    let extensions = params.extensions.as_ref().unwrap();
+    let values = |name: &str| {
+        extensions
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, values)| values.clone())
+    };
+    assert_eq!(
+        Some(vec![
+            "a,b".to_owned(),
+            "say \"hi\"".to_owned(),
+            "c:d".to_owned()
+        ]),
+        values("X-LIST")
+    );
+    assert_eq!(Some(vec!["1,2 ^ \"3\"".to_owned()]), values("X-SINGLE"));
+    let label = card.address[0].parameters().and_then(|p| p.label.clone());
+    assert_eq!(Some("1 Main St, Springfield".to_owned()), label);
+
+    let decoded = from_jcard(&to_jcard(&card).to_string())?;
+    assert_eq!(card, decoded);
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use vcard4::{parse_with_options, Error, Limit, ParseOptions, VcardIterator};
+
+const INPUT: &str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\n\
+    NOTE;LANGUAGE=en;PREF=1:A note that is\r\n  folded\r\n\
+    END:VCARD\r\n\
+    BEGIN:VCARD\r\nVERSION:4.0\r\nFN:John Doe\r\nEND:VCARD\r\n";
+
+fn exceeded(options: ParseOptions) -> Option<(Limit, usize)> {
+    match parse_with_options(INPUT, options) {
+        Err(Error::LimitExceeded { which, limit }) => Some((which, limit)),
+        _ => None,
+    }
+}
+
+#[test]
+fn limits_not_exceeded() -> Result<()> {
+    let options = ParseOptions::new()
+        .max_cards(Some(2))
+        .max_properties_per_card(Some(2))
+        .max_property_size(Some(45))
+        .max_parameters_per_property(Some(2));
+    assert_eq!(2, parse_with_options(INPUT, options)?.len());
+    Ok(())
+}
+
+#[test]
+fn limits_exceeded() -> Result<()> {
+    let options = ParseOptions::new();
+    assert_eq!(
+        Some((Limit::Cards, 1)),
+        exceeded(options.max_cards(Some(1)))
+    );
+    assert_eq!(
+        Some((Limit::PropertiesPerCard, 1)),
+        exceeded(options.max_properties_per_card(Some(1)))
+    );
+    // Size of the unfolded NOTE content line
+    assert_eq!(
+        Some((Limit::PropertySize, 44)),
+        exceeded(options.max_property_size(Some(44)))
+    );
+    assert_eq!(
+        Some((Limit::ParametersPerProperty, 1)),
+        exceeded(options.max_parameters_per_property(Some(1)))
+    );
+    Ok(())
+}
+
+#[test]
+fn limits_loose() -> Result<()> {
+    let options = ParseOptions::new()
+        .strict(false)
+        .max_properties_per_card(Some(1));
+    assert_eq!(Some((Limit::PropertiesPerCard, 1)), exceeded(options));
+    Ok(())
+}
+
+#[test]
+fn limits_iter() -> Result<()> {
+    let options = ParseOptions::new().max_cards(Some(1));
+    let mut it = VcardIterator::with_options(INPUT, options);
+    assert!(it.next().unwrap().is_ok());
+    assert!(matches!(
+        it.next(),
+        Some(Err(Error::LimitExceeded {
+            which: Limit::Cards,
+            limit: 1
+        }))
+    ));
+    Ok(())
+}
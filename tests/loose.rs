@@ -2,7 +2,10 @@ mod test_helpers;
 
 use anyhow::Result;
 use test_helpers::assert_round_trip;
-use vcard4::{parse_loose, property::Kind};
+use vcard4::{
+    parameter::TimeZoneParameter, parse_collect, parse_loose, property::Kind,
+    Error,
+};
 
 #[test]
 fn loose() -> Result<()> {
@@ -22,3 +25,101 @@ END:VCARD"#;
     assert_round_trip(&card)?;
     Ok(())
 }
+
+#[test]
+fn loose_collect_errors() -> Result<()> {
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nTEL;VALUE=uri:tel:abc\r\nTEL;VALUE=uri:tel:+1-555-555-5555\r\nwork.TEL;VALUE=uri:tel:\r\nEND:VCARD\r\nBEGIN:VCARD\r\nVERSION:4.0\r\nFN:John Doe\r\nEND:VCARD\r\n";
+
+    let mut cards = parse_collect(input)?;
+    assert_eq!(2, cards.len());
+
+    let (card, errors) = cards.remove(0);
+    assert_eq!(1, card.tel.len());
+    assert_eq!(2, errors.len());
+
+    assert_eq!("TEL", errors[0].name);
+    assert_eq!("TEL;VALUE=uri:tel:abc", errors[0].raw);
+    assert_eq!("TEL;VALUE=uri:tel:abc\r\n", &input[errors[0].span.clone()]);
+    assert!(matches!(errors[0].error, Error::InvalidTelUri(_)));
+
+    assert_eq!("TEL", errors[1].name);
+    assert_eq!("work.TEL;VALUE=uri:tel:", errors[1].raw);
+
+    let (_, errors) = cards.remove(0);
+    assert!(errors.is_empty());
+    Ok(())
+}
+
+#[test]
+fn loose_unquoted_geo() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+ADR;TYPE=home;GEO=geo:52.52\,13.40;LABEL="Heidestrasse 17":;;Heidestrasse 17;Berlin;;10115;Germany
+ADR;GEO=geo:12.3,45.6:;;123 Main Street;Any Town;CA;91921;U.S.A.
+END:VCARD"#;
+
+    assert!(matches!(
+        vcard4::parse(input),
+        Err(Error::NotQuoted(name)) if name == "GEO"
+    ));
+
+    let card = parse_loose(input)?.remove(0);
+    assert_eq!(2, card.address.len());
+
+    let prop = &card.address[0];
+    let params = prop.parameters.as_ref().unwrap();
+    assert_eq!("geo:52.52,13.40", params.geo.as_ref().unwrap().to_string());
+    assert_eq!("Heidestrasse 17", params.label.as_ref().unwrap());
+    assert_eq!(vec!["Heidestrasse 17"], prop.value.street_address);
+    assert_eq!(vec!["Berlin"], prop.value.locality);
+    assert_eq!(vec!["Germany"], prop.value.country_name);
+
+    let prop = &card.address[1];
+    let params = prop.parameters.as_ref().unwrap();
+    assert_eq!("geo:12.3,45.6", params.geo.as_ref().unwrap().to_string());
+    assert_eq!(vec!["123 Main Street"], prop.value.street_address);
+    assert_eq!(vec!["U.S.A."], prop.value.country_name);
+
+    // Written with quotes
+    assert!(card.to_string().contains(r#"GEO="geo:12.3,45.6""#));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn loose_unquoted_tz_uri() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+ADR;TZ=https://example.com/tz-database/america-new_york:;;123 Main Street;Any Town;NY;10001;U.S.A.
+ADR;TZ=America/New_York:;;1 Broadway;New York;NY;10004;U.S.A.
+END:VCARD"#;
+
+    assert!(matches!(
+        vcard4::parse(input),
+        Err(Error::NotQuoted(name)) if name == "TZ"
+    ));
+
+    let card = parse_loose(input)?.remove(0);
+    let prop = &card.address[0];
+    let params = prop.parameters.as_ref().unwrap();
+    let Some(TimeZoneParameter::Uri(uri)) = &params.timezone else {
+        panic!("expecting TZ URI");
+    };
+    assert_eq!(
+        "https://example.com/tz-database/america-new_york",
+        uri.to_string()
+    );
+    assert_eq!(vec!["123 Main Street"], prop.value.street_address);
+
+    let prop = &card.address[1];
+    let params = prop.parameters.as_ref().unwrap();
+    assert!(matches!(
+        &params.timezone,
+        Some(TimeZoneParameter::Text(text)) if text == "America/New_York"
+    ));
+    assert_eq!(vec!["1 Broadway"], prop.value.street_address);
+    assert_round_trip(&card)?;
+    Ok(())
+}
@@ -0,0 +1,180 @@
+mod test_helpers;
+
+use anyhow::Result;
+use test_helpers::assert_round_trip;
+use vcard4::{parse, Error};
+
+#[test]
+fn merge_client_pid_map() -> Result<()> {
+    let local = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;PID=1.1:tel:+1-555-555-0100
+TEL;PID=2.1:tel:+1-555-555-0200
+REV:20240102T000000Z
+UID:urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6
+CLIENTPIDMAP:1;urn:uuid:3df403f4-5924-4bb7-b077-3c711d9eb34b
+END:VCARD"#;
+
+    let remote = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;PID=1.2:tel:+1-555-555-0101
+TEL;PID=2.2:tel:+1-555-555-0200
+TEL;PID=1.1:tel:+1-555-555-0300
+REV:20240101T000000Z
+UID:urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6
+CLIENTPIDMAP:1;urn:uuid:d89c9c7a-2e1b-4832-82de-7e992d95faa5
+CLIENTPIDMAP:2;urn:uuid:3df403f4-5924-4bb7-b077-3c711d9eb34b
+END:VCARD"#;
+
+    let local = parse(local)?.remove(0);
+    let remote = parse(remote)?.remove(0);
+    let merged = local.merge(&remote)?;
+
+    // Remote source 2 is the same client as local source 1
+    // and the other client is assigned a new source
+    let maps = merged
+        .client_pid_map
+        .iter()
+        .map(|p| p.value.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "1;urn:uuid:3df403f4-5924-4bb7-b077-3c711d9eb34b",
+            "2;urn:uuid:d89c9c7a-2e1b-4832-82de-7e992d95faa5",
+        ],
+        maps
+    );
+
+    // The local card is newer so the edited value is kept
+    let tels = merged.tel.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "tel:+1-555-555-0100",
+            "tel:+1-555-555-0200",
+            "tel:+1-555-555-0300"
+        ],
+        tels
+    );
+    let encoded = merged.to_string();
+    assert!(encoded.contains("TEL;PID=1.1:tel:+1-555-555-0100\r\n"));
+    assert!(encoded.contains("TEL;PID=2.1:tel:+1-555-555-0200\r\n"));
+    assert!(encoded.contains("TEL;PID=1.2:tel:+1-555-555-0300\r\n"));
+    assert!(encoded.contains("REV:20240102T000000Z\r\n"));
+
+    // Merging in the other direction keeps the newer value
+    // using the source identifiers of the remote card
+    let merged = remote.merge(&local)?;
+    let encoded = merged.to_string();
+    assert!(encoded.contains("TEL;PID=1.2:tel:+1-555-555-0100\r\n"));
+    assert!(!encoded.contains("+1-555-555-0101"));
+    assert_eq!(3, merged.tel.len());
+
+    assert_eq!(merged, parse(encoded)?.remove(0));
+    Ok(())
+}
+
+#[test]
+fn merge_single_values() -> Result<()> {
+    let local = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+N:Doe;Jane;;;
+EMAIL:jane@example.com
+NOTE:first
+END:VCARD"#;
+
+    let remote = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+N:Smith;Jane;;;
+EMAIL:jane@example.com
+EMAIL:jane.smith@example.com
+REV:20240101T000000Z
+END:VCARD"#;
+
+    let local = parse(local)?.remove(0);
+    let remote = parse(remote)?.remove(0);
+    let merged = local.merge(&remote)?;
+
+    assert_eq!(1, merged.formatted_name.len());
+    assert_eq!(2, merged.email.len());
+    assert_eq!(1, merged.note.len());
+    let name = merged.name.as_ref().unwrap();
+    assert_eq!(vec!["Smith"], name.value.family_names);
+    assert!(merged.rev.is_some());
+    assert_round_trip(&merged)?;
+    Ok(())
+}
+
+#[test]
+fn merge_uid_conflict() -> Result<()> {
+    let local = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+UID:urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6
+END:VCARD"#;
+
+    let remote = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+UID:urn:uuid:3df403f4-5924-4bb7-b077-3c711d9eb34b
+END:VCARD"#;
+
+    let local = parse(local)?.remove(0);
+    let remote = parse(remote)?.remove(0);
+    assert!(matches!(
+        local.merge(&remote),
+        Err(Error::UidConflict(_, _))
+    ));
+    Ok(())
+}
+
+#[test]
+fn merge_client_pid_map_sources() -> Result<()> {
+    let local = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;PID=99.1:tel:+1-555-555-0100
+CLIENTPIDMAP:99;urn:uuid:3df403f4-5924-4bb7-b077-3c711d9eb34b
+END:VCARD"#;
+
+    let remote = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;PID=1.1:tel:+1-555-555-0200
+CLIENTPIDMAP:1;urn:uuid:d89c9c7a-2e1b-4832-82de-7e992d95faa5
+END:VCARD"#;
+
+    // The lowest free source is used rather than 100
+    let local = parse(local)?.remove(0);
+    let remote = parse(remote)?.remove(0);
+    let merged = local.merge(&remote)?;
+    let sources = merged
+        .client_pid_map
+        .iter()
+        .map(|p| p.value.source)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![99, 1], sources);
+    assert!(merged
+        .to_string()
+        .contains("TEL;PID=1.1:tel:+1-555-555-0200\r\n"));
+    assert_round_trip(&merged)?;
+
+    // No source is free when all of 1 to 99 are in use
+    let mut full = String::from("BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\n");
+    for source in 1..=99 {
+        full.push_str(&format!(
+            "CLIENTPIDMAP:{};urn:uuid:00000000-0000-0000-0000-{:012}\n",
+            source, source
+        ));
+    }
+    full.push_str("END:VCARD");
+    let full = parse(full)?.remove(0);
+    assert!(matches!(
+        full.merge(&remote),
+        Err(Error::ClientPidMapExhausted)
+    ));
+    Ok(())
+}
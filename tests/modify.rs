@@ -0,0 +1,190 @@
+mod test_helpers;
+
+use anyhow::Result;
+use test_helpers::assert_round_trip;
+use time::{Date, Month, OffsetDateTime, Time};
+use vcard4::{
+    parse, property::Property, Clock, DateTime, Error, PropertyHandle,
+    PropertyKind, Vcard,
+};
+
+struct MockClock;
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime {
+        OffsetDateTime::now_utc()
+            .replace_date(
+                Date::from_calendar_date(2020, Month::June, 1).unwrap(),
+            )
+            .replace_time(Time::MIDNIGHT)
+            .into()
+    }
+}
+
+#[test]
+fn modify_unchanged() -> Result<()> {
+    let mut card = Vcard::new("Jane Doe".to_owned());
+    let changed = card.modify_with_clock(&MockClock, |card| {
+        card.formatted_name[0].value = "Jane Doe".to_owned();
+    });
+    assert!(!changed);
+    assert!(card.rev.is_none());
+    Ok(())
+}
+
+#[test]
+fn modify_changed() -> Result<()> {
+    let mut card = Vcard::new("Jane Doe".to_owned());
+    let changed = card.modify_with_clock(&MockClock, |card| {
+        card.tel.push("+10987654321".to_owned().into());
+    });
+    assert!(changed);
+    assert_eq!("20200601T000000Z", card.rev.as_ref().unwrap().to_string());
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn modify_rev_now() -> Result<()> {
+    let card = Vcard::new("Jane Doe".to_owned()).with_rev_now();
+    assert!(card.rev.is_some());
+    assert_round_trip(&card)?;
+
+    let mut card = Vcard::new("Jane Doe".to_owned());
+    assert!(card.modify(|card| card.note.push("Note".to_owned().into())));
+    assert!(card.rev.is_some());
+    Ok(())
+}
+
+fn prefs(props: &[impl Property]) -> Vec<Option<u8>> {
+    props
+        .iter()
+        .map(|prop| prop.parameters().and_then(|p| p.pref))
+        .collect()
+}
+
+#[test]
+fn modify_remove_property() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+TEL:+1-555-555-0001
+FN:Jane Doe
+TEL:+1-555-555-0002
+BDAY:19700101
+TEL:+1-555-555-0003
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+
+    let handle = card
+        .properties()
+        .find(|prop| prop.property().to_string() == "+1-555-555-0002")
+        .map(|prop| prop.handle())
+        .unwrap();
+    assert_eq!(PropertyHandle::new(PropertyKind::Tel, 1), handle);
+    assert!(card.remove_property(handle));
+    assert!(card.remove_property(PropertyHandle::new(PropertyKind::Bday, 0)));
+    assert!(!card.remove_property(PropertyHandle::new(PropertyKind::Bday, 0)));
+    assert!(!card.remove_tel_at(2));
+    assert!(card.bday.is_none());
+
+    let order: Vec<String> = card
+        .properties_in_source_order()
+        .map(|prop| prop.property().to_string())
+        .collect();
+    assert_eq!(
+        vec!["+1-555-555-0001", "Jane Doe", "+1-555-555-0003"],
+        order
+    );
+
+    assert!(card.remove_tel_at(0));
+    assert_eq!(1, card.tel.len());
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn modify_set_property() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+EMAIL:jane@example.com
+EMAIL:jane@example.org
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+
+    let handle = PropertyHandle::new(PropertyKind::Email, 1);
+    card.set_property(handle, "EMAIL;TYPE=work:doe@example.org")?;
+    assert_eq!("jane@example.com", card.email[0].value);
+    assert_eq!("doe@example.org", card.email[1].value);
+    assert!(card.email[1].parameters.is_some());
+
+    assert!(matches!(
+        card.set_property(handle, "NOTE:Not an email"),
+        Err(Error::PropertyKindMismatch(_, _))
+    ));
+    assert!(matches!(
+        card.set_property(
+            PropertyHandle::new(PropertyKind::Email, 2),
+            "EMAIL:a@example.com"
+        ),
+        Err(Error::PropertyNotFound(_, 2))
+    ));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn modify_set_preferred() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+EMAIL;PREF=1:a@example.com
+EMAIL;PREF=2:b@example.com
+EMAIL:c@example.com
+EMAIL;PREF=3:d@example.com
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+
+    // Property without a preference ranks below all others
+    assert!(card.set_preferred_email(2));
+    assert_eq!(vec![Some(2), Some(3), Some(1), Some(4)], prefs(&card.email));
+
+    // Other ranked properties keep their relative order
+    assert!(card.set_preferred_email(1));
+    assert_eq!(vec![Some(3), Some(1), Some(2), Some(4)], prefs(&card.email));
+
+    assert!(card.set_preferred_email(1));
+    assert_eq!(vec![Some(3), Some(1), Some(2), Some(4)], prefs(&card.email));
+
+    assert!(!card.set_preferred_email(4));
+
+    // Tied preferences are separated
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+EMAIL;PREF=1:a@example.com
+EMAIL;PREF=1:b@example.com
+EMAIL:c@example.com
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+    assert!(card.set_preferred_email(1));
+    assert_eq!(vec![Some(2), Some(1), None], prefs(&card.email));
+    assert!(card.set_preferred_email(0));
+    assert_eq!(vec![Some(1), Some(2), None], prefs(&card.email));
+
+    let mut card = Vcard::new("Jane Doe".to_owned());
+    card.add_tel_text("+1-555-555-0001".to_owned());
+    card.add_tel_text("+1-555-555-0002".to_owned());
+    assert!(card.set_preferred_tel(1));
+    assert!(card.set_preferred_tel(0));
+    assert_eq!(vec![Some(1), Some(2)], prefs(&card.tel));
+    assert_eq!(
+        1,
+        card.tel
+            .iter()
+            .filter(|tel| tel.parameters().and_then(|p| p.pref) == Some(1))
+            .count()
+    );
+    assert_round_trip(&card)?;
+    Ok(())
+}
@@ -0,0 +1,174 @@
+mod test_helpers;
+
+use anyhow::Result;
+use test_helpers::assert_round_trip;
+use vcard4::{
+    normalize::{normalize_text, KeepDuplicate, NormalizeOptions},
+    parse,
+};
+
+#[test]
+fn normalize_text_whitespace() -> Result<()> {
+    assert_eq!("Jane Doe", normalize_text(" Jane  Doe "));
+    assert_eq!("Jane Doe", normalize_text("Jane\t\u{3000}Doe"));
+    // Decomposed characters are composed (NFC)
+    assert_eq!("Ren\u{e9}e", normalize_text("Rene\u{301}e"));
+    Ok(())
+}
+
+#[test]
+fn normalize_formatted_names_duplicates() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:jane doe
+FN:Jane Doe
+FN:Jane  Doe
+FN:John Doe
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+    let removed = card.normalize_formatted_names(Default::default());
+    assert_eq!(2, removed.len());
+    assert_eq!(2, card.formatted_name.len());
+    assert_eq!("jane doe", card.formatted_name.first().unwrap().value);
+    assert_eq!("John Doe", card.formatted_name.get(1).unwrap().value);
+    assert_round_trip(&card)?;
+
+    let mut card = parse(input)?.remove(0);
+    let removed = card.normalize_formatted_names(NormalizeOptions {
+        keep: KeepDuplicate::RicherCasing,
+    });
+    assert_eq!(
+        vec!["jane doe", "Jane  Doe"],
+        removed.iter().map(|p| p.value.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!("Jane Doe", card.formatted_name.first().unwrap().value);
+    assert_eq!("John Doe", card.formatted_name.get(1).unwrap().value);
+    Ok(())
+}
+
+#[test]
+fn normalize_formatted_names_alt_id() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;ALTID=1;LANGUAGE=en:Jane Doe
+FN;ALTID=1;LANGUAGE=fr:Jane Doe
+FN;ALTID=2:Jane Doe
+FN:JANE DOE
+END:VCARD"#;
+    let mut card = parse(input)?.remove(0);
+    let removed = card.normalize_formatted_names(Default::default());
+    assert!(removed.is_empty());
+    assert_eq!(4, card.formatted_name.len());
+    Ok(())
+}
+
+#[test]
+fn normalize_formatted_names_case_folding() -> Result<()> {
+    // Lowercasing alone does not match these values,
+    // full case folding is required
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Stra\u{df}e
+FN:STRASSE
+FN:ὈΔΥΣΣΕΎΣ
+FN:ὀδυσσεύς
+FN:Rene\u{301}e
+FN:Renée
+END:VCARD"#
+        .replace("\\u{df}", "\u{df}")
+        .replace("\\u{301}", "\u{301}");
+    let mut card = parse(input)?.remove(0);
+    assert_ne!(
+        card.formatted_name[0].value.to_lowercase(),
+        card.formatted_name[1].value.to_lowercase()
+    );
+    let removed = card.normalize_formatted_names(Default::default());
+    assert_eq!(3, removed.len());
+    assert_eq!(
+        vec!["Stra\u{df}e", "ὈΔΥΣΣΕΎΣ", "Rene\u{301}e"],
+        card.formatted_name
+            .iter()
+            .map(|p| p.value.as_str())
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn normalize_canonical_string() -> Result<()> {
+    let first = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\n\
+        EMAIL;TYPE=WORK,home;PREF=3:jane@example.com\r\n\
+        EMAIL;PREF=7:jdoe@example.com\r\n\
+        TEL;VALUE=uri;TYPE=cell:tel:+1-555-555-5555\r\n\
+        X-FOO;x-b=2;X-A=1:Custom\r\n\
+        NOTE:A note that is long enough to be folded when it is \
+        encoded by the producer\r\n\
+        END:VCARD\r\n";
+    let second = "BEGIN:VCARD\nVERSION:4.0\nfn:Jane Doe\n\
+        note:A note that is long enough to be folded when it is\n  \
+        encoded by the producer\n\
+        x-foo;X-A=1;X-B=2:Custom\n\
+        tel;type=CELL;value=uri:tel:+1-555-555-5555\n\
+        email;pref=2:jdoe@example.com\n\
+        email;type=home;type=work;type=HOME;pref=1:jane@example.com\n\
+        END:VCARD\n";
+
+    let mut a = parse(first)?.remove(0);
+    let mut b = parse(second)?.remove(0);
+    assert_ne!(a.canonical_string(), b.canonical_string());
+    a.canonicalize();
+    b.canonicalize();
+    assert_eq!(a.canonical_string(), b.canonical_string());
+    assert_eq!(a.content_hash(), b.content_hash());
+
+    let expected = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\n\
+        TEL;VALUE=uri;TYPE=cell:tel:+1-555-555-5555\r\n\
+        EMAIL;PREF=1;TYPE=home,work:jane@example.com\r\n\
+        EMAIL;PREF=2:jdoe@example.com\r\n\
+        NOTE:A note that is long enough to be folded when it is \
+        encoded by the producer\r\n\
+        X-FOO;X-A=1;X-B=2:Custom\r\n\
+        END:VCARD\r\n";
+    assert_eq!(expected, a.canonical_string());
+    assert_round_trip(&a)?;
+    Ok(())
+}
+
+#[test]
+fn normalize_canonical_pref_ties() -> Result<()> {
+    let first = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\n\
+        TEL;PREF=1:+1\nTEL;PREF=1:+2\nEND:VCARD";
+    let second = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\n\
+        TEL;PREF=1:+2\nTEL;PREF=1:+1\nEND:VCARD";
+
+    let mut a = parse(first)?.remove(0);
+    let mut b = parse(second)?.remove(0);
+    a.canonicalize();
+    b.canonicalize();
+    assert_eq!(a.canonical_string(), b.canonical_string());
+    assert!(a
+        .canonical_string()
+        .contains("\r\nTEL;PREF=1:+1\r\nTEL;PREF=2:+2\r\n"));
+    Ok(())
+}
+
+#[test]
+fn normalize_canonical_data_uri() -> Result<()> {
+    let card = |photo: &str| {
+        format!(
+            "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\n\
+            PHOTO:{}\nEND:VCARD",
+            photo
+        )
+    };
+    let mut a = parse(card("data:text/plain,a%2fb%c3%a9"))?.remove(0);
+    let mut b = parse(card("data:text/plain,a%2Fb%C3%A9"))?.remove(0);
+    assert_ne!(a.canonical_string(), b.canonical_string());
+    a.canonicalize();
+    b.canonicalize();
+    assert_eq!(a.canonical_string(), b.canonical_string());
+    assert!(a
+        .canonical_string()
+        .contains("PHOTO:data:text/plain,a%2Fb%C3%A9"));
+    Ok(())
+}
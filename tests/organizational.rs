@@ -1,8 +1,12 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
 use test_helpers::assert_round_trip;
-use vcard4::{parameter::TypeParameter, parse, property::*, Uri};
+use vcard4::{
+    parameter::TypeParameter, parse, parse_loose, property::*, Error, Uri,
+};
 
 #[test]
 fn organizational_title() -> Result<()> {
@@ -90,6 +94,21 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn organizational_org_delimiter() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+ORG:ABC;Div\, East;Marketing\;Sales
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let org = card.org.first().unwrap();
+    assert_eq!(vec!["ABC", "Div, East", "Marketing;Sales"], org.value);
+    assert_eq!("ABC;Div\\, East;Marketing\\;Sales", org.to_string());
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
 #[test]
 fn organizational_member() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -268,3 +287,65 @@ END:VCARD"#;
 
     Ok(())
 }
+
+#[test]
+fn organizational_open_social() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+EXPERTISE;LEVEL=Expert;INDEX=1:chemistry
+EXPERTISE;LEVEL=beginner;INDEX=2:chinese literature
+HOBBY;LEVEL=high:reading
+INTEREST;LEVEL=medium;INDEX=1:r&b music
+ORG-DIRECTORY;INDEX=1:http://directory.mycompany.example.com
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert_eq!(2, card.expertise.len());
+    assert_eq!("chemistry", card.expertise[0].value);
+    let params = card.expertise[0].parameters.as_ref().unwrap();
+    assert_eq!(Some("expert"), params.level.as_deref());
+    assert_eq!(Some(1), params.index);
+    assert_eq!("reading", card.hobby[0].value);
+    assert_eq!("r&b music", card.interest[0].value);
+    assert_eq!(
+        "http://directory.mycompany.example.com/",
+        card.org_directory[0].value.to_string()
+    );
+    assert_eq!(
+        Some(1),
+        card.org_directory[0].parameters.as_ref().unwrap().index
+    );
+
+    let encoded = card.to_string();
+    assert!(encoded.contains("\r\nEXPERTISE;LEVEL=expert;INDEX=1:chemistry"));
+    assert!(encoded.contains("\r\nHOBBY;LEVEL=high:reading"));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn organizational_open_social_level() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+HOBBY;LEVEL=expert:reading
+END:VCARD"#;
+    assert!(matches!(parse(input), Err(Error::UnknownLevel(_, _))));
+    let card = parse_loose(input)?.remove(0);
+    let params = card.hobby[0].parameters.as_ref().unwrap();
+    assert_eq!(Some("expert"), params.level.as_deref());
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;LEVEL=high:Jane Doe
+END:VCARD"#;
+    assert!(matches!(parse(input), Err(Error::InvalidLevel(_))));
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+EXPERTISE;INDEX=0:chemistry
+END:VCARD"#;
+    assert!(matches!(parse(input), Err(Error::InvalidIndex(_))));
+    Ok(())
+}
@@ -1,14 +1,22 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
 
 use vcard4::{
+    geo::GeoUri,
     helper::parse_utc_offset,
     parameter::{
-        Pid, RelatedType, TelephoneType, TimeZoneParameter, TypeParameter,
-        ValueType,
+        Parameters, Pid, RelatedType, TelephoneType, TimeZoneParameter,
+        TypeList, TypeParameter, ValueType,
+    },
+    parse, parse_loose, parse_with_options,
+    property::{
+        DateAndOrTimeProperty, DateTimeOrTextProperty, Property,
+        TextListProperty, TextOrUriProperty, TextProperty, UriProperty,
     },
-    parse, Error,
+    time, Date, Error, ParseOptions, Vcard,
 };
 
 use test_helpers::{assert_language, assert_media_type, assert_round_trip};
@@ -136,6 +144,95 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn param_pid_multiple() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+EMAIL;PID=1.1,99.2:jane@example.com
+CLIENTPIDMAP:1;urn:uuid:1
+CLIENTPIDMAP:2;urn:uuid:2
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert_eq!(
+        &vec![Pid::new(1, Some(1)), Pid::new(99, Some(2))],
+        card.email[0]
+            .parameters
+            .as_ref()
+            .unwrap()
+            .pid
+            .as_ref()
+            .unwrap()
+    );
+    assert!(card.to_string().contains("EMAIL;PID=1.1,99.2:jane"));
+    assert_round_trip(&card)?;
+
+    let mut params = Parameters::new();
+    params.add_pid(Pid::new(1, None));
+    params.add_pid(Pid::new(2, Some(1)));
+    params.add_pid(Pid::new(1, None));
+    assert_eq!(";PID=1,2.1", params.to_string());
+    Ok(())
+}
+
+#[test]
+fn param_pid_invalid() {
+    for value in ["123.4", "1.4567", "100"] {
+        assert!(
+            matches!(value.parse::<Pid>(), Err(Error::PidOutOfRange(_))),
+            "{value}"
+        );
+    }
+    for value in ["", ".1", "1.", "a.1", "+1", "1.2.3"] {
+        assert!(
+            matches!(value.parse::<Pid>(), Err(Error::InvalidPid(_))),
+            "{value}"
+        );
+    }
+
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;PID=123.4567:Jane Doe
+END:VCARD"#;
+    assert!(matches!(parse(input), Err(Error::PidOutOfRange(_))));
+}
+
+#[test]
+fn param_unicode_digits() -> Result<()> {
+    let input = "BEGIN:VCARD\nVERSION:4.0\n\
+FN;PREF=\u{FF11}:Jane Doe\n\
+FN;PID=\u{0662}.\u{0663}:Jane Doe Smith\n\
+FN;PREF=\u{200F}\u{FF15}\u{200F}:J. Doe\n\
+FN:Jane\n\
+END:VCARD";
+
+    // Strict mode rejects regardless of the option
+    assert!(parse(input).is_err());
+    let options = ParseOptions::new().normalize_unicode_digits(true);
+    assert!(parse_with_options(input, options).is_err());
+
+    // Loose mode without the option skips the properties
+    let card = parse_loose(input)?.remove(0);
+    assert_eq!(1, card.formatted_name.len());
+
+    let options = options.strict(false);
+    let card = parse_with_options(input, options)?.remove(0);
+    assert_eq!(4, card.formatted_name.len());
+
+    let prop = card.formatted_name.get(0).unwrap();
+    assert_eq!(Some(1), prop.parameters.as_ref().unwrap().pref);
+    let prop = card.formatted_name.get(1).unwrap();
+    assert_eq!(
+        &vec![Pid::new(2, Some(3))],
+        prop.parameters.as_ref().unwrap().pid.as_ref().unwrap()
+    );
+    let prop = card.formatted_name.get(2).unwrap();
+    assert_eq!(Some(5), prop.parameters.as_ref().unwrap().pref);
+
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
 fn assert_param_type(value: TypeParameter) -> Result<()> {
     let input = format!(
         r#"BEGIN:VCARD
@@ -197,6 +294,80 @@ fn param_type() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn param_type_case_insensitive() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;type=CELL;type=VOICE;type=pref;TYPE=Work:+1-555-555-5555
+RELATED;TYPE=Co-Worker,HOME,X-Custom,Other:urn:uuid:1
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    let params = card.tel[0].parameters().unwrap();
+    assert_eq!(Some(1), params.pref);
+    assert_eq!(
+        &vec![
+            TypeParameter::Telephone(TelephoneType::Cell),
+            TypeParameter::Telephone(TelephoneType::Voice),
+            TypeParameter::Work,
+        ],
+        params.types.as_ref().unwrap()
+    );
+
+    let params = card.related[0].parameters().unwrap();
+    assert_eq!(
+        &vec![
+            TypeParameter::Related(RelatedType::CoWorker),
+            TypeParameter::Home,
+            TypeParameter::Extension("Custom".to_owned()),
+            TypeParameter::Extension("Other".to_owned()),
+        ],
+        params.types.as_ref().unwrap()
+    );
+
+    let encoded = card.to_string();
+    assert!(encoded.contains("TEL;PREF=1;TYPE=cell,voice,work:"));
+    assert!(encoded.contains("RELATED;TYPE=co-worker,home,X-Custom,X-Other:"));
+    assert_eq!(card, parse(encoded)?.remove(0));
+    Ok(())
+}
+
+#[test]
+fn param_type_duplicates() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;TYPE=voice,cell,voice;TYPE=CELL,cell:+1-555-555-5555
+END:VCARD"#;
+    let mut vcards = parse(input)?;
+    assert_eq!(1, vcards.len());
+    let card = vcards.remove(0);
+    let prop = card.tel.first().unwrap();
+    let params = prop.parameters().unwrap();
+    assert_eq!(
+        &vec![
+            TypeParameter::Telephone(TelephoneType::Voice),
+            TypeParameter::Telephone(TelephoneType::Cell),
+        ],
+        params.types.as_ref().unwrap()
+    );
+    assert!(card.to_string().contains("TEL;TYPE=voice,cell:"));
+    assert_round_trip(&card)?;
+
+    let mut params: Parameters = Default::default();
+    params.add_type(TypeParameter::Extension("Foo".to_string()));
+    params.add_type(TypeParameter::Extension("foo".to_string()));
+    params.add_type(TypeParameter::Home);
+    params.add_type(TypeParameter::Home);
+    assert_eq!(2, params.types.as_ref().unwrap().len());
+    assert!(params.has_type(&TypeParameter::Extension("FOO".to_string())));
+    assert!(params.has_type(&TypeParameter::Home));
+    assert!(!params.has_type(&TypeParameter::Work));
+
+    Ok(())
+}
+
 #[test]
 fn param_mediatype() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -207,7 +378,7 @@ END:VCARD"#;
     assert_eq!(1, vcards.len());
     let card = vcards.remove(0);
     let prop = card.formatted_name.get(0).unwrap();
-    assert_media_type(prop.parameters.as_ref(), "text/plain")?;
+    assert_media_type(prop.parameters.as_deref(), "text/plain")?;
     assert_eq!(
         &ValueType::Text,
         prop.parameters.as_ref().unwrap().value.as_ref().unwrap()
@@ -252,6 +423,47 @@ END:VCARD"#;
     Ok(())
 }
 
+#[test]
+fn param_sort_as_escaped() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;SORT-AS="Harten\, Rene van,Rene":Rene van Harten
+N;SORT-AS="say ^'hi^'","a;b",c:Harten;Rene;van;;
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let sort_as = |params: &Option<Box<Parameters>>| {
+        params.as_ref().unwrap().sort_as.clone().unwrap()
+    };
+    assert_eq!(
+        vec!["Harten, Rene van", "Rene"],
+        sort_as(&card.formatted_name[0].parameters)
+    );
+    assert_eq!(
+        vec!["say \"hi\"", "a;b", "c"],
+        sort_as(&card.name.as_ref().unwrap().parameters)
+    );
+
+    // Quoted only when required
+    let encoded = card.to_string();
+    assert!(encoded.contains(r#"FN;SORT-AS="Harten\, Rene van",Rene:"#));
+    assert!(encoded.contains(r#"N;SORT-AS=say ^'hi^',"a;b",c:"#));
+    assert_round_trip(&card)?;
+
+    // Extension values use the same list encoding
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;X-LIST="a\,b",c:Jane Doe
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let params = card.formatted_name[0].parameters.as_ref().unwrap();
+    assert_eq!(
+        vec![("X-LIST".to_owned(), vec!["a,b".to_owned(), "c".to_owned()])],
+        params.extensions.clone().unwrap()
+    );
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
 #[test]
 fn param_geo() -> Result<()> {
     let input = r#"BEGIN:VCARD
@@ -273,6 +485,11 @@ END:VCARD"#;
             .unwrap()
             .to_string()
     );
+    let geo = GeoUri::try_from(
+        prop.parameters.as_ref().unwrap().geo.as_ref().unwrap(),
+    )?;
+    assert_eq!(37.386013, geo.latitude);
+    assert_eq!(-122.082932, geo.longitude);
     assert_round_trip(&card)?;
     Ok(())
 }
@@ -368,3 +585,294 @@ END:VCARD"#;
 
     Ok(())
 }
+
+#[test]
+fn param_unknown() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;CREATED=20240101T000000Z:Jane Doe
+IMPP;DERIVED=TRUE;PREF=1;X-ID="a,b":skype:jane
+END:VCARD"#;
+    let err = parse(input).expect_err("unknown parameter should fail");
+    assert!(matches!(err, Error::UnknownParameter(_)));
+
+    let options = ParseOptions::new().keep_unknown_parameters(true);
+    let mut vcards = parse_with_options(input, options)?;
+    assert_eq!(1, vcards.len());
+    let card = vcards.remove(0);
+
+    let params = card.formatted_name[0].parameters.as_ref().unwrap();
+    assert_eq!(
+        &vec![("CREATED".to_owned(), vec!["20240101T000000Z".to_owned()])],
+        params.extensions.as_ref().unwrap()
+    );
+
+    let params = card.impp[0].parameters.as_ref().unwrap();
+    assert_eq!(Some(1), params.pref);
+    assert_eq!(
+        &vec![
+            ("DERIVED".to_owned(), vec!["TRUE".to_owned()]),
+            ("X-ID".to_owned(), vec!["a".to_owned(), "b".to_owned()]),
+        ],
+        params.extensions.as_ref().unwrap()
+    );
+
+    let encoded = card.to_string();
+    assert!(encoded.contains("IMPP;PREF=1;DERIVED=TRUE;X-ID=a,b:"));
+    let decoded = parse_with_options(&encoded, options)?.remove(0);
+    assert_eq!(card, decoded);
+
+    // Malformed parameters are still rejected
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;DERIVED:Jane Doe
+END:VCARD"#;
+    assert!(parse_with_options(input, options).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn param_with_parameters() -> Result<()> {
+    let mut card = Vcard::new("Jane Doe".to_owned());
+    card.nickname.push(
+        TextProperty::from("Janey".to_owned()).with_parameters(
+            Parameters::new()
+                .with_alt_id("1".to_owned())
+                .with_pref(2)
+                .with_type(TypeParameter::Home)
+                .with_type(TypeParameter::Home),
+        ),
+    );
+    card.url.push(
+        UriProperty::from("https://example.com".parse::<vcard4::Uri>()?)
+            .with_parameters(
+                Parameters::new()
+                    .with_index(1)
+                    .with_pid(vec!["1.1".parse::<Pid>()?]),
+            ),
+    );
+    card.categories.push(
+        TextListProperty::new_comma(vec!["a".to_owned(), "b".to_owned()])
+            .with_parameters(
+                Parameters::new().with_sort_as(vec!["b".to_owned()]),
+            ),
+    );
+    let date: Date =
+        time::Date::from_calendar_date(1985, time::Month::April, 12)?.into();
+    card.bday = Some(DateTimeOrTextProperty::DateTime(
+        DateAndOrTimeProperty::from(date).with_parameters(
+            Parameters::new().with_calscale("gregorian".to_owned()),
+        ),
+    ));
+    card.tel.push(
+        TextOrUriProperty::from("+1-555-555-5555".to_owned())
+            .with_parameters(Parameters::new().with_extension(
+                "X-LABEL".to_owned(),
+                vec!["mobile".to_owned()],
+            )),
+    );
+
+    let params = card.nickname[0].parameters().unwrap();
+    assert_eq!(Some(&vec![TypeParameter::Home]), params.types.as_ref());
+
+    let encoded = card.to_string();
+    assert!(encoded.contains("NICKNAME;PREF=2;ALTID=1;TYPE=home:Janey\r\n"));
+    assert!(encoded.contains("URL;PID=1.1;INDEX=1:https://example.com"));
+    assert!(encoded.contains("CATEGORIES;SORT-AS=b:a,b\r\n"));
+    assert!(encoded.contains("BDAY;CALSCALE=gregorian:19850412\r\n"));
+    assert!(encoded.contains("TEL;X-LABEL=mobile:+1-555-555-5555\r\n"));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn param_quoting() -> Result<()> {
+    let params = Parameters::new()
+        .with_alt_id("1".to_owned())
+        .with_type(TypeParameter::Home)
+        .with_type(TypeParameter::Work)
+        .with_sort_as(vec!["Doe, Jane".to_owned(), "Jane".to_owned()])
+        .with_label("1 Main St\nTown; \"North\"".to_owned())
+        .with_service_type("Example".to_owned())
+        .with_extension(
+            "X-SERVICE".to_owned(),
+            vec!["https://example.com".to_owned(), "chat".to_owned()],
+        );
+    let encoded = params.to_string();
+    assert_eq!(
+        ";ALTID=1;TYPE=home,work;SORT-AS=\"Doe\\, Jane\",Jane\
+        ;LABEL=\"1 Main St^nTown; ^'North^'\";SERVICE-TYPE=Example\
+        ;X-SERVICE=\"https://example.com\",chat",
+        encoded
+    );
+    let reparsed = Parameters::from_fragment(&encoded, None)?;
+    assert_eq!(params, reparsed);
+    Ok(())
+}
+
+#[test]
+fn param_quoting_third_party() -> Result<()> {
+    // Apple Contacts
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+item1.EMAIL;TYPE=INTERNET,HOME;PREF=1:jane@example.com
+item1.X-ABLabel:_$!<Home>!$_
+TEL;TYPE="CELL,VOICE";PREF=1:+1 555 0100
+ADR;TYPE=HOME;LABEL="1 Main St\nTown":;;1 Main St;Town;;;
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let encoded = card.to_string();
+    assert!(encoded
+        .contains("\r\nADR;TYPE=home;LABEL=1 Main St^nTown:;;1 Main St;"));
+    assert!(
+        encoded.contains("\r\nTEL;PREF=1;TYPE=cell,voice:+1 555 0100\r\n")
+    );
+    assert!(encoded.contains(
+        "\r\nitem1.EMAIL;PREF=1;TYPE=X-INTERNET,home:jane@example.com\r\n"
+    ));
+    assert_round_trip(&card)?;
+
+    // Google Contacts
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:John Doe
+EMAIL;TYPE=INTERNET;TYPE=WORK:john@example.com
+TEL;TYPE=CELL:+1 555 0101
+X-SOCIALPROFILE;TYPE=twitter:https://twitter.com/john
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let encoded = card.to_string();
+    assert!(encoded
+        .contains("\r\nEMAIL;TYPE=X-INTERNET,work:john@example.com\r\n"));
+    assert!(encoded.contains(
+        "\r\nX-SOCIALPROFILE;TYPE=X-twitter:https://twitter.com/john\r\n"
+    ));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn param_tz_colon() -> Result<()> {
+    for input in [
+        "BEGIN:VCARD\nVERSION:4.0\nFN;TZ=UTC+05:30:Jane\nEND:VCARD",
+        "BEGIN:VCARD\nVERSION:4.0\nFN;TZ=\"UTC+05:30\":Jane\nEND:VCARD",
+        "BEGIN:VCARD\nVERSION:4.0\nFN;TZ=UTC+05:30;LANGUAGE=en:Jane\nEND:VCARD",
+    ] {
+        let card = parse(input)?.remove(0);
+        let prop = card.formatted_name.get(0).unwrap();
+        assert_eq!("Jane", prop.value);
+        assert_eq!(
+            &TimeZoneParameter::Text(String::from("UTC+05:30")),
+            prop.parameters.as_ref().unwrap().timezone.as_ref().unwrap()
+        );
+        assert!(card.to_string().contains("FN;"));
+        assert!(card.to_string().contains("TZ=\"UTC+05:30\""));
+        assert_round_trip(&card)?;
+    }
+
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN;TZ=-05:00:Jane\nEND:VCARD";
+    let card = parse(input)?.remove(0);
+    let prop = card.formatted_name.get(0).unwrap();
+    assert_eq!("Jane", prop.value);
+    assert_eq!(
+        &TimeZoneParameter::Text(String::from("-05:00")),
+        prop.parameters.as_ref().unwrap().timezone.as_ref().unwrap()
+    );
+    assert_round_trip(&card)?;
+
+    // Digits after a value that is not an offset are
+    // part of the property value
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane\n\
+        ADR;TZ=Europe/Paris:12;;1 Main St;City;;;\nEND:VCARD";
+    let card = parse(input)?.remove(0);
+    let prop = card.address.first().unwrap();
+    assert_eq!(vec!["12"], prop.value.po_box);
+    assert_eq!(vec!["1 Main St"], prop.value.street_address);
+    assert_eq!(
+        &TimeZoneParameter::Text(String::from("Europe/Paris")),
+        prop.parameters.as_ref().unwrap().timezone.as_ref().unwrap()
+    );
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn param_tz_text_value() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TZ;VALUE=text:UTC+01:00
+TZ:GMT+05:30
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let values: Vec<String> =
+        card.timezone.iter().map(|tz| tz.to_string()).collect();
+    assert_eq!(vec!["UTC+01:00", "GMT+05:30"], values);
+    assert!(card.to_string().contains("\r\nTZ;VALUE=text:UTC+01:00\r\n"));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn param_type_helpers() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;TYPE=home,cell,x-Private;VALUE=uri:tel:+1-555-555-5555
+TEL;TYPE=work,voice:+1-555-555-5556
+RELATED;TYPE=friend,co-worker:urn:uuid:03a0e51f-d1aa-4385-8a53-e29025acd8af
+EMAIL:jane@example.com
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    let tel = &card.tel[0];
+    assert!(tel.is_home());
+    assert!(!tel.is_work());
+    assert_eq!(vec![&TelephoneType::Cell], tel.telephone_types());
+    let params = tel.parameters().unwrap();
+    assert!(params.has_type(&TypeParameter::Extension("PRIVATE".into())));
+    assert_eq!(
+        vec!["home", "cell", "X-Private"],
+        params.type_strings().collect::<Vec<_>>()
+    );
+
+    let tel = &card.tel[1];
+    assert!(tel.is_work());
+    assert_eq!(vec![&TelephoneType::Voice], tel.telephone_types());
+
+    let related = &card.related[0];
+    assert_eq!(
+        &[
+            TypeParameter::Related(RelatedType::Friend),
+            TypeParameter::Related(RelatedType::CoWorker),
+        ],
+        related.types()
+    );
+    assert!(related.telephone_types().is_empty());
+
+    let email = &card.email[0];
+    assert!(email.types().is_empty());
+    assert!(!email.is_home());
+
+    let list: TypeList = "home, cell,,x-private,HOME".parse()?;
+    assert_eq!(
+        &[
+            TypeParameter::Home,
+            TypeParameter::Telephone(TelephoneType::Cell),
+            TypeParameter::Extension("private".into()),
+        ],
+        list.types()
+    );
+    assert_eq!("home,cell,X-private", list.to_string());
+    assert!(list.matches(card.tel[0].parameters()));
+    assert!(!list.matches(card.tel[1].parameters()));
+    assert!(!list.matches(None));
+
+    let list: TypeList = "friend,co-worker".parse()?;
+    assert!(list.matches(related.parameters()));
+    assert!(TypeList::default().matches(None));
+    assert_eq!("", TypeList::default().to_string());
+    Ok(())
+}
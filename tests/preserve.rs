@@ -0,0 +1,78 @@
+use anyhow::Result;
+use vcard4::{
+    parse_with_options, ParseOptions, PropertyHandle, PropertyKind,
+    VcardBuilder,
+};
+
+const INPUT: &str = "begin:vcard\nVERSION:4.0\nfn:Jane Doe\n\
+    EMAIL;type=WORK:jane@example.com\n\
+    NOTE:A long note that was folded\n  by the client\n\
+    X-CUSTOM;X-A=\"b\":value\n\
+    EMAIL:jane@home.example.com\n\
+    end:vcard\n";
+
+fn options() -> ParseOptions {
+    ParseOptions::new().preserve_source(true)
+}
+
+#[test]
+fn preserve_unmodified() -> Result<()> {
+    let card = parse_with_options(INPUT, options())?.remove(0);
+    assert_eq!(INPUT, card.to_string_preserving());
+    assert_ne!(INPUT, card.to_string());
+    Ok(())
+}
+
+#[test]
+fn preserve_modified() -> Result<()> {
+    let mut card = parse_with_options(INPUT, options())?.remove(0);
+    card.note[0].value = "Changed".to_owned();
+    card.remove_property(PropertyHandle {
+        kind: PropertyKind::Email,
+        index: 0,
+    });
+    card.title.push("Engineer".to_owned().into());
+    let expected = "begin:vcard\nVERSION:4.0\nfn:Jane Doe\n\
+        NOTE:Changed\n\
+        X-CUSTOM;X-A=\"b\":value\n\
+        EMAIL:jane@home.example.com\n\
+        TITLE:Engineer\n\
+        end:vcard\n";
+    assert_eq!(expected, card.to_string_preserving());
+    Ok(())
+}
+
+#[test]
+fn preserve_crlf() -> Result<()> {
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n  Doe\r\n\
+        END:VCARD\r\n";
+    let mut card = parse_with_options(input, options())?.remove(0);
+    assert_eq!(input, card.to_string_preserving());
+    card.nickname.push("Jay".to_owned().into());
+    assert_eq!(
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n  Doe\r\n\
+        NICKNAME:Jay\r\nEND:VCARD\r\n",
+        card.to_string_preserving()
+    );
+    Ok(())
+}
+
+#[test]
+fn preserve_without_source() -> Result<()> {
+    let card = VcardBuilder::new("Jane Doe".to_owned())
+        .nickname("Jay".to_owned())
+        .finish();
+    assert_eq!(card.to_string(), card.to_string_preserving());
+
+    let card = parse_with_options(INPUT, ParseOptions::new())?.remove(0);
+    assert!(card.to_string_preserving().starts_with("BEGIN:VCARD\r\n"));
+    Ok(())
+}
+
+#[test]
+fn preserve_equality() -> Result<()> {
+    let preserved = parse_with_options(INPUT, options())?.remove(0);
+    let parsed = parse_with_options(INPUT, ParseOptions::new())?.remove(0);
+    assert_eq!(parsed, preserved);
+    Ok(())
+}
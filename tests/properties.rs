@@ -0,0 +1,159 @@
+use anyhow::Result;
+use vcard4::{parse, preferred_in_group, PropertyKind, PropertyRef, Vcard};
+
+const INPUT: &str = r#"BEGIN:VCARD
+VERSION:4.0
+UID:urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6
+X-FOO:bar
+TEL:+1-555-555-5555
+FN:Jane Doe
+item1.EMAIL;PREF=1:jane@example.com
+LANG:en
+NOTE:first
+TEL:+1-555-555-0000
+LANG:fr
+END:VCARD"#;
+
+#[test]
+fn properties_encoded_order() -> Result<()> {
+    let card = parse(INPUT)?.remove(0);
+    let props = card.properties().collect::<Vec<_>>();
+    assert_eq!(9, props.len());
+
+    let names = props.iter().map(|p| p.name()).collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "FN", "TEL", "TEL", "EMAIL", "LANG", "LANG", "NOTE", "UID",
+            "X-FOO"
+        ],
+        names
+    );
+
+    let email = &props[3];
+    assert_eq!(PropertyKind::Email, email.kind());
+    assert_eq!(0, email.index());
+    assert_eq!(Some(&"item1".to_string()), email.property().group());
+    assert_eq!(Some(1), email.property().parameters().and_then(|p| p.pref));
+    assert_eq!("item1.EMAIL;PREF=1:jane@example.com", email.to_string());
+
+    let tel = &props[2];
+    assert_eq!(1, tel.index());
+    assert_eq!("+1-555-555-0000", tel.property().to_string());
+
+    // Encoding the properties matches the encoded vCard
+    let encoded = card.to_string();
+    let lines = card
+        .properties()
+        .map(|p| format!("{}\r\n", p))
+        .collect::<String>();
+    assert!(encoded.contains(&lines));
+    Ok(())
+}
+
+#[test]
+fn properties_source_order() -> Result<()> {
+    let card = parse(INPUT)?.remove(0);
+    let names = card
+        .properties_in_source_order()
+        .map(|p| p.name().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "UID", "X-FOO", "TEL", "FN", "EMAIL", "LANG", "NOTE", "TEL",
+            "LANG"
+        ],
+        names
+    );
+
+    let indices = card
+        .properties_in_source_order()
+        .filter(|p| p.kind() == PropertyKind::Tel)
+        .map(|p| p.index())
+        .collect::<Vec<_>>();
+    assert_eq!(vec![0, 1], indices);
+
+    // Source order is ignored when comparing vCards
+    let reparsed = parse(card.to_string())?.remove(0);
+    assert_eq!(card, reparsed);
+    Ok(())
+}
+
+#[test]
+fn properties_source_order_modified() -> Result<()> {
+    let mut card = parse(INPUT)?.remove(0);
+    card.note.clear();
+    card.title.push("Engineer".to_owned().into());
+    let names = card
+        .properties_in_source_order()
+        .map(|p| p.name().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "UID", "X-FOO", "TEL", "FN", "EMAIL", "LANG", "TEL", "LANG",
+            "TITLE"
+        ],
+        names
+    );
+    Ok(())
+}
+
+#[test]
+fn properties_source_order_unparsed() {
+    let card = Vcard::new("Jane Doe".to_owned());
+    let props = card.properties_in_source_order().collect::<Vec<_>>();
+    assert_eq!(1, props.len());
+    assert_eq!(PropertyKind::FormattedName, props[0].kind());
+}
+
+#[test]
+fn properties_altid_groups() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN;ALTID=1;LANGUAGE=en:Taro Yamada
+FN;ALTID=1;LANGUAGE=ja:山田太郎
+FN:Yamada
+TITLE;ALTID=2;LANGUAGE=en-GB;PREF=2:Engineer
+TITLE;ALTID=2;LANGUAGE=en-US;PREF=1:Engineer
+TITLE;ALTID=2;LANGUAGE=fr:Ingénieur
+ORG;ALTID=a:Example
+ADR;ALTID=1;LANGUAGE=en:;;1 Main St;Town;;;
+ADR;ALTID=1;LANGUAGE=ja:;;本町1;町;;;
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    let groups = card.altid_groups(PropertyKind::FormattedName);
+    assert_eq!(2, groups.len());
+    assert_eq!(vec![0, 1], indices(&groups[0]));
+    assert_eq!(vec![2], indices(&groups[1]));
+
+    let preferred = |group: &[PropertyRef<'_>], language| {
+        preferred_in_group(group, language).map(|prop| prop.index())
+    };
+    assert_eq!(Some(1), preferred(&groups[0], Some("ja")));
+    assert_eq!(Some(0), preferred(&groups[0], Some("EN")));
+    // Falls back to the first property without a match
+    assert_eq!(Some(0), preferred(&groups[0], Some("de")));
+    assert_eq!(Some(0), preferred(&groups[0], None));
+    assert_eq!(Some(2), preferred(&groups[1], Some("ja")));
+
+    // Language ranges match subtags then the lowest PREF wins
+    let groups = card.altid_groups(PropertyKind::Title);
+    assert_eq!(1, groups.len());
+    assert_eq!(Some(1), preferred(&groups[0], Some("en")));
+    assert_eq!(Some(0), preferred(&groups[0], Some("en-gb")));
+    assert_eq!(Some(2), preferred(&groups[0], Some("fr")));
+    assert_eq!(Some(1), preferred(&groups[0], None));
+
+    let groups = card.altid_groups(PropertyKind::Address);
+    assert_eq!(1, groups.len());
+    assert_eq!(Some(1), preferred(&groups[0], Some("ja")));
+
+    assert_eq!(1, card.altid_groups(PropertyKind::Org).len());
+    assert!(card.altid_groups(PropertyKind::Note).is_empty());
+    assert!(preferred_in_group(&[], Some("en")).is_none());
+    Ok(())
+}
+
+fn indices(group: &[PropertyRef<'_>]) -> Vec<usize> {
+    group.iter().map(|prop| prop.index()).collect()
+}
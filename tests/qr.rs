@@ -0,0 +1,172 @@
+mod test_helpers;
+
+use anyhow::Result;
+use vcard4::{
+    parse,
+    property::DateTimeOrTextProperty,
+    qr::{parse_mecard, QrFormat, QrPayload},
+    Error,
+};
+
+const INPUT: &str = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+N:Doe;Jane;;;
+TEL;VALUE=uri:tel:+1-555-555-5555
+EMAIL:jane@example.com
+PHOTO:http://www.example.com/pub/photos/jane.gif
+SOUND:http://www.example.com/pub/sounds/jane.ogg
+NOTE:A short note.
+NOTE:A very long note that goes on and on describing the contact in far more detail than anybody scanning a QR code would ever need to know about.
+X-FOO:bar
+X-ABC:xyz
+END:VCARD"#;
+
+#[test]
+fn qr_vcard_no_budget() -> Result<()> {
+    let card = parse(INPUT)?.remove(0);
+    let payload = card.to_qr_payload(QrFormat::Vcard { max_bytes: None })?;
+    assert_eq!(card.to_string(), payload);
+    Ok(())
+}
+
+#[test]
+fn qr_vcard_budget() -> Result<()> {
+    let card = parse(INPUT)?.remove(0);
+    let full = card.to_string().len();
+
+    // Fits so nothing is dropped
+    let qr = QrPayload::new(
+        &card,
+        QrFormat::Vcard {
+            max_bytes: Some(full),
+        },
+    )?;
+    assert!(qr.dropped.is_empty());
+
+    // Dropping the PHOTO is enough
+    let qr = QrPayload::new(
+        &card,
+        QrFormat::Vcard {
+            max_bytes: Some(full - 1),
+        },
+    )?;
+    assert_eq!(vec!["PHOTO"], qr.dropped);
+    assert!(!qr.payload.contains("PHOTO"));
+    assert!(qr.payload.contains("SOUND"));
+
+    let qr = QrPayload::new(
+        &card,
+        QrFormat::Vcard {
+            max_bytes: Some(200),
+        },
+    )?;
+    assert_eq!(vec!["PHOTO", "SOUND", "NOTE"], qr.dropped);
+    assert!(qr.payload.contains("X-FOO"));
+
+    let qr = QrPayload::new(
+        &card,
+        QrFormat::Vcard {
+            max_bytes: Some(160),
+        },
+    )?;
+    assert_eq!(vec!["PHOTO", "SOUND", "NOTE", "X-FOO", "X-ABC"], qr.dropped);
+    assert!(qr.payload.len() <= 160);
+
+    // Short notes are kept while possible
+    let trimmed = parse(&qr.payload)?.remove(0);
+    assert_eq!(1, trimmed.note.len());
+    assert_eq!("A short note.", trimmed.note.first().unwrap().value);
+
+    // Trimming is deterministic
+    for budget in [130, 160, 200, 320] {
+        let format = QrFormat::Vcard {
+            max_bytes: Some(budget),
+        };
+        assert_eq!(
+            QrPayload::new(&card, format)?,
+            QrPayload::new(&card, format)?
+        );
+    }
+
+    let result = card.to_qr_payload(QrFormat::Vcard {
+        max_bytes: Some(10),
+    });
+    assert!(matches!(result, Err(Error::QrPayloadTooLarge(_, 10))));
+    Ok(())
+}
+
+#[test]
+fn qr_mecard() -> Result<()> {
+    let card = parse(INPUT)?.remove(0);
+    let payload = card.to_qr_payload(QrFormat::MeCard)?;
+    assert!(payload.starts_with("MECARD:N:Doe,Jane;TEL:+1-555-555-5555;"));
+    assert!(payload.contains("EMAIL:jane@example.com;"));
+    assert!(payload.ends_with(";;"));
+
+    let decoded = parse_mecard(&payload)?;
+    assert_eq!("Jane Doe", decoded.formatted_name.first().unwrap().value);
+    assert_eq!(card.name, decoded.name);
+    assert_eq!(card.note, decoded.note);
+    assert_eq!(card.email, decoded.email);
+    Ok(())
+}
+
+#[test]
+fn qr_mecard_escape() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Doe\, Jane
+N:Doe\, Smith;Jane: J;;;
+ADR:;;1 Main St\, Apt 2;Springfield;;12345;USA
+URL:http://example.com/a:b
+NOTE:Escapes \\ \; \, :
+BDAY:19800102
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let payload = card.to_qr_payload(QrFormat::MeCard)?;
+    assert!(payload.contains(r"N:Doe\, Smith,Jane\: J;"));
+    assert!(payload.contains(r"NOTE:Escapes \\ \; \, \:;"));
+    assert!(
+        payload.contains(r"ADR:,,1 Main St\, Apt 2,Springfield,,12345,USA;")
+    );
+    assert!(payload.contains("BDAY:19800102;"));
+
+    let decoded = parse_mecard(&payload)?;
+    assert_eq!(card.name, decoded.name);
+    assert_eq!(card.note, decoded.note);
+    assert_eq!(card.url, decoded.url);
+    assert_eq!(card.address, decoded.address);
+    assert!(matches!(
+        decoded.bday,
+        Some(DateTimeOrTextProperty::DateTime(_))
+    ));
+    assert_eq!(card.bday, decoded.bday);
+
+    // Encoding the decoded card is stable
+    assert_eq!(payload, decoded.to_qr_payload(QrFormat::MeCard)?);
+
+    // Year zero is a full date but dates without a year are skipped
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane\nBDAY:00000102\nEND:VCARD";
+    let card = parse(input)?.remove(0);
+    let payload = card.to_qr_payload(QrFormat::MeCard)?;
+    assert!(payload.contains("BDAY:00000102;"));
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane\nBDAY:--0102\nEND:VCARD";
+    let card = parse(input)?.remove(0);
+    let payload = card.to_qr_payload(QrFormat::MeCard)?;
+    assert!(!payload.contains("BDAY"));
+    Ok(())
+}
+
+#[test]
+fn qr_mecard_invalid() -> Result<()> {
+    assert!(matches!(
+        parse_mecard("BEGIN:VCARD"),
+        Err(Error::InvalidMeCard(_))
+    ));
+    assert!(matches!(
+        parse_mecard("MECARD:TEL:123;;"),
+        Err(Error::NoFormattedName)
+    ));
+    Ok(())
+}
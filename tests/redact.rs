@@ -0,0 +1,42 @@
+use anyhow::Result;
+use vcard4::parse;
+
+#[test]
+fn redact_debug() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+TEL;TYPE=cell;VALUE=uri:tel:+1-555-555-5555
+item1.EMAIL;X-SECRET=hidden:jane@example.com
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    // Debug output of the vCard includes the values
+    assert!(format!("{:?}", card).contains("+1-555-555-5555"));
+
+    for redacted in [
+        format!("{:?}", card.redacted()),
+        format!("{:#?}", card.redacted()),
+    ] {
+        assert!(!redacted.contains("555"));
+        assert!(!redacted.contains("Jane Doe"));
+        assert!(!redacted.contains("jane@example.com"));
+        assert!(!redacted.contains("hidden"));
+
+        assert!(redacted.contains("TEL"));
+        assert!(redacted.contains("TYPE"));
+        assert!(redacted.contains("item1"));
+        assert!(redacted.contains("X-SECRET"));
+    }
+
+    assert_eq!(
+        concat!(
+            r#"Vcard { properties: [FN { length: 8 }, "#,
+            r#"TEL { parameters: ["VALUE", "TYPE"], length: 19 }, "#,
+            r#"EMAIL { group: "item1", parameters: ["X-SECRET"], "#,
+            r#"length: 16 }] }"#,
+        ),
+        format!("{:?}", card.redacted())
+    );
+    Ok(())
+}
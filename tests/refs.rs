@@ -0,0 +1,136 @@
+mod test_helpers;
+
+use anyhow::Result;
+use std::collections::HashMap;
+use test_helpers::assert_round_trip;
+use vcard4::{
+    parse,
+    property::TextOrUriProperty,
+    refs::{find_uid_references, rewrite_uids, ReferenceKind},
+};
+
+const GROUP: &str = r#"BEGIN:VCARD
+VERSION:4.0
+KIND:group
+FN:The Doe family
+MEMBER:urn:uuid:03a0e51f-d1aa-4385-8a53-e29025acd8af
+MEMBER:urn:uuid:B8767877-B4A1-4C70-9ACC-505D3819E519
+MEMBER:mailto:someone@example.com
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:John Doe
+UID:urn:uuid:03a0e51f-d1aa-4385-8a53-e29025acd8af
+RELATED;TYPE=spouse:urn:uuid:b8767877-b4a1-4c70-9acc-505d3819e519
+RELATED;VALUE=text:urn:uuid:b8767877-b4a1-4c70-9acc-505d3819e519
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+UID:urn:uuid:b8767877-b4a1-4c70-9acc-505d3819e519
+END:VCARD"#;
+
+#[test]
+fn refs_find() -> Result<()> {
+    let cards = parse(GROUP)?;
+    let references = find_uid_references(&cards);
+    assert_eq!(4, references.len());
+
+    let members: Vec<_> = references
+        .iter()
+        .filter(|r| r.location.kind == ReferenceKind::Member)
+        .collect();
+    assert_eq!(3, members.len());
+    assert_eq!(Some(1), members[0].target);
+    assert_eq!(Some(2), members[1].target);
+    assert_eq!(None, members[2].target);
+
+    let related = references
+        .iter()
+        .find(|r| r.location.kind == ReferenceKind::Related)
+        .unwrap();
+    assert_eq!(1, related.location.card);
+    assert_eq!(0, related.location.index);
+    assert_eq!(Some(2), related.target);
+    Ok(())
+}
+
+#[test]
+fn refs_rewrite() -> Result<()> {
+    let mut cards = parse(GROUP)?;
+
+    let mut mapping = HashMap::new();
+    mapping.insert(
+        "urn:uuid:03a0e51f-d1aa-4385-8a53-e29025acd8af".to_owned(),
+        "urn:uuid:11111111-1111-4111-8111-111111111111".to_owned(),
+    );
+    mapping.insert(
+        "URN:UUID:b8767877-b4a1-4c70-9acc-505d3819e519".to_owned(),
+        "urn:uuid:22222222-2222-4222-8222-222222222222".to_owned(),
+    );
+    mapping.insert(
+        "mailto:nobody@example.com".to_owned(),
+        "not a uri".to_owned(),
+    );
+
+    let report = rewrite_uids(&mut cards, &mapping);
+    assert_eq!(3, report.rewritten.len());
+    assert!(report.invalid.is_empty());
+
+    let group = cards.first().unwrap();
+    assert_eq!(
+        "urn:uuid:11111111-1111-4111-8111-111111111111",
+        group.member.first().unwrap().value.to_string()
+    );
+    assert_eq!(
+        "urn:uuid:22222222-2222-4222-8222-222222222222",
+        group.member.get(1).unwrap().value.to_string()
+    );
+    assert_eq!(
+        "mailto:someone@example.com",
+        group.member.get(2).unwrap().value.to_string()
+    );
+
+    let john = cards.get(1).unwrap();
+    if let TextOrUriProperty::Uri(related) = john.related.first().unwrap() {
+        assert_eq!(
+            "urn:uuid:22222222-2222-4222-8222-222222222222",
+            related.value.to_string()
+        );
+    } else {
+        panic!("expecting URI property");
+    }
+
+    // Text values are never rewritten
+    if let TextOrUriProperty::Text(related) = john.related.get(1).unwrap() {
+        assert_eq!(
+            "urn:uuid:b8767877-b4a1-4c70-9acc-505d3819e519",
+            related.value
+        );
+    } else {
+        panic!("expecting text property");
+    }
+
+    for card in &cards {
+        assert_round_trip(card)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn refs_rewrite_invalid() -> Result<()> {
+    let mut cards = parse(GROUP)?;
+    let mut mapping = HashMap::new();
+    mapping.insert(
+        "mailto:someone@example.com".to_owned(),
+        "not a uri".to_owned(),
+    );
+    let report = rewrite_uids(&mut cards, &mapping);
+    assert!(report.is_empty());
+    assert_eq!(1, report.invalid.len());
+    assert_eq!(
+        "mailto:someone@example.com",
+        cards[0].member.get(2).unwrap().value.to_string()
+    );
+    Ok(())
+}
@@ -0,0 +1,134 @@
+#![cfg(feature = "serde")]
+
+use anyhow::Result;
+use serde_json::json;
+use vcard4::{parse, Error, ValidatedVcard, Vcard};
+
+// The serialized form must not depend on the enabled features
+#[test]
+fn schema_stable() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+KIND:group
+FN:Jane Doe
+MEMBER:urn:uuid:03a0e51f-d1aa-4385-8a53-e29025acd8af
+BDAY:--0412
+PHOTO;MEDIATYPE=image/png:https://example.com/a.png
+TZ;VALUE=utc-offset:-0500
+LANG;PREF=1:en-US
+EMAIL;TYPE=work:jane@example.com
+X-TIME;VALUE=time:102200Z
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+
+    let expected = json!({
+        "kind": { "value": { "kind": "group" } },
+        "formattedName": [{ "value": "Jane Doe" }],
+        "photo": [{
+            "kind": "uri",
+            "value": {
+                "value": "https://example.com/a.png",
+                "parameters": { "media_type": "image/png" }
+            }
+        }],
+        "bday": {
+            "kind": "dateTime",
+            "value": {
+                "value": [{ "kind": "partialDate", "value": "--0412" }]
+            }
+        },
+        "email": [{
+            "value": "jane@example.com",
+            "parameters": { "types": [{ "kind": "work" }] }
+        }],
+        "lang": [{ "value": "en-US", "parameters": { "pref": 1 } }],
+        "member": [{
+            "value": "urn:uuid:03a0e51f-d1aa-4385-8a53-e29025acd8af"
+        }],
+        "timezone": [{
+            "kind": "utcOffset",
+            "value": {
+                "value": "-05:00:00",
+                "parameters": { "value": { "kind": "utc-offset" } }
+            }
+        }],
+        "extensions": [{
+            "name": "X-TIME",
+            "value": {
+                "kind": "time",
+                "value": [["10:22:00.0", "+00:00:00"]]
+            },
+            "parameters": { "value": { "kind": "time" } }
+        }]
+    });
+
+    assert_eq!(expected, serde_json::to_value(&card)?);
+
+    let decoded: Vcard = serde_json::from_value(expected)?;
+    assert_eq!(card, decoded);
+    Ok(())
+}
+
+#[test]
+fn schema_tagged_values() -> Result<()> {
+    // Text that looks like a URI or date keeps its kind
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+BDAY;VALUE=text:2000-01-01
+KEY;VALUE=text:https://example.com
+X-URI;VALUE=uri:https://example.com
+X-DATE;VALUE=date:20221107
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let data = serde_json::to_string(&card)?;
+    let decoded: Vcard = serde_json::from_str(&data)?;
+    assert_eq!(card, decoded);
+    Ok(())
+}
+
+#[test]
+fn schema_validated() -> Result<()> {
+    // Missing formatted name
+    let value = json!({ "nickname": [{ "value": "Johnny" }] });
+    let card: Vcard = serde_json::from_value(value.clone())?;
+    assert!(card.formatted_name.is_empty());
+    let result = serde_json::from_value::<ValidatedVcard>(value);
+    assert!(result.unwrap_err().to_string().contains("formatted name"));
+
+    // Out of range PREF and PID
+    let value = json!({
+        "formattedName": [{ "value": "Jane Doe" }],
+        "email": [{
+            "value": "jane@example.com",
+            "parameters": { "pref": 200 }
+        }]
+    });
+    assert!(serde_json::from_value::<Vcard>(value.clone()).is_ok());
+    assert!(serde_json::from_value::<ValidatedVcard>(value).is_err());
+
+    let value = json!({
+        "formattedName": [{ "value": "Jane Doe" }],
+        "email": [{
+            "value": "jane@example.com",
+            "parameters": { "pid": [{ "local": 12345 }] }
+        }]
+    });
+    assert!(serde_json::from_value::<Vcard>(value.clone()).is_ok());
+    assert!(serde_json::from_value::<ValidatedVcard>(value).is_err());
+
+    // Valid vCards serialize the same as a vCard
+    let card =
+        parse("BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nEND:VCARD")?.remove(0);
+    let value = serde_json::to_value(&card)?;
+    let validated: ValidatedVcard = serde_json::from_value(value.clone())?;
+    assert_eq!(&card, validated.as_ref());
+    assert_eq!(value, serde_json::to_value(&validated)?);
+    assert_eq!(card, validated.into_inner());
+
+    assert!(matches!(
+        ValidatedVcard::try_from(Vcard::default()),
+        Err(Error::NoFormattedName)
+    ));
+    Ok(())
+}
@@ -1,8 +1,15 @@
+#![allow(clippy::get_first)]
+
 mod test_helpers;
 
 use anyhow::Result;
 use test_helpers::{assert_media_type, assert_round_trip};
-use vcard4::{parse, property::*};
+use vcard4::{
+    parameter::{Parameters, ValueType},
+    parse,
+    property::*,
+    LineEnding, Vcard, WriteOptions,
+};
 
 #[test]
 fn security_key() -> Result<()> {
@@ -41,7 +48,10 @@ END:VCARD"#;
 
     let prop = card.key.get(0).unwrap();
     if let TextOrUriProperty::Uri(prop) = prop {
-        assert_media_type(prop.parameters.as_ref(), "application/pgp-keys")?;
+        assert_media_type(
+            prop.parameters.as_deref(),
+            "application/pgp-keys",
+        )?;
         assert_eq!("ftp://example.com/keys/jdoe", &prop.value.to_string());
         assert_round_trip(&card)?;
     } else {
@@ -49,3 +59,87 @@ END:VCARD"#;
     }
     Ok(())
 }
+
+#[test]
+fn security_key_data() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+KEY:http://www.example.com/keys/jdoe.cer
+KEY:data:application/pgp-keys;base64,a2V5
+KEY;VALUE=text:ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIO+/k jane@example.com
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    assert_eq!(
+        KeyData::Uri("http://www.example.com/keys/jdoe.cer".parse()?),
+        KeyData::try_from(&card.key[0])?
+    );
+    assert_eq!(
+        KeyData::Binary {
+            media_type: "application/pgp-keys".to_owned(),
+            bytes: b"key".to_vec(),
+        },
+        KeyData::try_from(&card.key[1])?
+    );
+    assert_eq!(
+        KeyData::Text(
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIO+/k jane@example.com"
+                .to_owned()
+        ),
+        KeyData::try_from(&card.key[2])?
+    );
+
+    let prop = TextOrUriProperty::from_key_bytes(
+        "application/pkix-cert",
+        b"\x30\x82",
+    )?;
+    assert_eq!("data:application/pkix-cert;base64,MII=", prop.to_string());
+    Ok(())
+}
+
+#[test]
+fn security_key_text_folded() -> Result<()> {
+    // Armored key of several kilobytes with base64 lines
+    // that include '+' and '/'
+    let lines: Vec<String> = (0..64)
+        .map(|line| {
+            (0..64)
+                .map(|index| {
+                    let alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+                        abcdefghijklmnopqrstuvwxyz0123456789+/";
+                    alphabet[(line * 7 + index * 13) % 64] as char
+                })
+                .collect()
+        })
+        .collect();
+    let armored = format!(
+        "-----BEGIN PGP PUBLIC KEY BLOCK-----\n\n{}\n=twTO\n\
+        -----END PGP PUBLIC KEY BLOCK-----\n",
+        lines.join("\n")
+    );
+
+    let mut card = Vcard::new("Jane Doe".to_owned());
+    card.key.push(TextOrUriProperty::Text(TextProperty {
+        value: armored.clone(),
+        group: None,
+        parameters: Some(Box::new(
+            Parameters::new().with_value(ValueType::Text),
+        )),
+    }));
+    for width in [Some(75), Some(2), None] {
+        for line_ending in [LineEnding::CrLf, LineEnding::Lf] {
+            let options = WriteOptions::new()
+                .fold_width(width)
+                .line_ending(line_ending);
+            let encoded = card.to_string_with(&options);
+            let parsed = parse(&encoded)?.remove(0);
+            assert_eq!(
+                Some(KeyData::Text(armored.clone())),
+                parsed.key.first().map(KeyData::try_from).transpose()?
+            );
+            assert_eq!(card, parsed);
+        }
+    }
+    assert_round_trip(&card)?;
+    Ok(())
+}
@@ -0,0 +1,18 @@
+// Budgets for the size of the core types, if a change pushes
+// these over the limit consider boxing the offending data.
+//
+// Only checked for 64-bit targets with the default features as
+// the optional types change the layout.
+#[cfg(all(
+    target_pointer_width = "64",
+    not(feature = "mime"),
+    not(feature = "language-tags")
+))]
+#[test]
+fn size_budget() {
+    use std::mem::size_of;
+    use vcard4::{Error, Result, Vcard};
+
+    const { assert!(size_of::<Error>() <= 56) };
+    const { assert!(size_of::<Result<Vcard>>() <= 3200) };
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use vcard4::{parse, sort, Vcard};
+
+fn formatted_names(cards: &[Vcard]) -> Vec<&str> {
+    cards
+        .iter()
+        .map(|card| card.formatted_name[0].value.as_str())
+        .collect()
+}
+
+#[test]
+fn sort_as_overrides_name() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Rene van der Harten
+N;SORT-AS="Harten,Rene":van der Harten;Rene;J.;Sir;R.D.O.N.
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:Robert Pau Shou Chang
+N;SORT-AS="Pau Shou Chang,Robert":Shou Chang;Robert;Pau;;
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+N:Doe;Jane;;;
+END:VCARD"#;
+    let mut cards = parse(input)?;
+    assert_eq!(
+        &["harten".to_owned(), "rene".to_owned()],
+        cards[0].sort_key().components()
+    );
+    assert_eq!(
+        &["doe".to_owned(), "jane".to_owned()],
+        cards[2].sort_key().components()
+    );
+    sort(&mut cards);
+    assert_eq!(
+        vec!["Jane Doe", "Rene van der Harten", "Robert Pau Shou Chang"],
+        formatted_names(&cards)
+    );
+    Ok(())
+}
+
+#[test]
+fn sort_without_name() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Zoe
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:Mock Company
+N:;;;;
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:Jane Smith
+N:Smith;Jane;;;
+END:VCARD"#;
+    let mut cards = parse(input)?;
+    assert_eq!(&["zoe".to_owned()], cards[0].sort_key().components());
+    assert_eq!(
+        &["mock company".to_owned()],
+        cards[1].sort_key().components()
+    );
+    sort(&mut cards);
+    assert_eq!(
+        vec!["Mock Company", "Jane Smith", "Zoe"],
+        formatted_names(&cards)
+    );
+    Ok(())
+}
+
+#[test]
+fn sort_folded_case() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Straße
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:ÉMILE
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:émile
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:STRASSE
+END:VCARD
+BEGIN:VCARD
+VERSION:4.0
+FN:Émile
+END:VCARD"#;
+    let mut cards = parse(input)?;
+    assert_eq!(cards[0].sort_key(), cards[3].sort_key());
+    assert_eq!(cards[1].sort_key(), cards[2].sort_key());
+
+    // Equal keys keep their order
+    sort(&mut cards);
+    assert_eq!(
+        vec!["Straße", "STRASSE", "ÉMILE", "émile", "Émile"],
+        formatted_names(&cards)
+    );
+    Ok(())
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "tracing")]
+
+use anyhow::Result;
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+use tracing_subscriber::fmt::MakeWriter;
+use vcard4::{parse_loose, property::Property};
+
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Buffer {
+    type Writer = Buffer;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Run a function and collect the events it emits.
+fn traced<T>(f: impl FnOnce() -> T) -> Result<(T, String)> {
+    let buffer = Buffer::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_ansi(false)
+        .with_writer(buffer.clone())
+        .finish();
+
+    let value = tracing::subscriber::with_default(subscriber, f);
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone())?;
+    Ok((value, output))
+}
+
+#[test]
+fn tracing_loose_warnings() -> Result<()> {
+    let input = include_str!("../fixtures/loose.vcf");
+    let (cards, output) = traced(|| parse_loose(input))?;
+    let cards = cards?;
+    assert_eq!(1, cards.len());
+
+    let warnings = output
+        .lines()
+        .filter(|line| line.contains("WARN"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(2, warnings.len());
+    assert!(warnings[0].contains("property=\"KIND\""));
+    assert!(warnings[1].contains("property=\"BDAY\""));
+    assert!(warnings
+        .iter()
+        .all(|line| line.contains("property skipped")));
+    assert!(output.contains("duplicate TYPE removed"));
+    Ok(())
+}
+
+#[test]
+fn tracing_loose_parameters() -> Result<()> {
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN;PREF=0:Jane Doe\n\
+        IMPP;DERIVED=TRUE:xmpp:jane@example.com\nEND:VCARD";
+    let (cards, output) = traced(|| parse_loose(input))?;
+    let card = cards?.remove(0);
+    assert_eq!(Some(1), card.formatted_name[0].parameters().unwrap().pref);
+    assert_eq!(1, card.impp.len());
+    assert!(card.impp[0].parameters().is_none());
+
+    let warnings = output
+        .lines()
+        .filter(|line| line.contains("WARN"))
+        .collect::<Vec<_>>();
+    assert_eq!(2, warnings.len());
+    assert!(warnings[0].contains("pref clamped"));
+    assert!(warnings[1].contains("unknown parameter skipped"));
+    assert!(warnings[1].contains("parameter_name=\"DERIVED\""));
+    Ok(())
+}
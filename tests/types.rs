@@ -1,3 +1,5 @@
+#![allow(clippy::unnecessary_fallible_conversions)]
+
 use anyhow::Result;
 use proptest::prelude::*;
 use time::UtcOffset;
@@ -27,12 +29,32 @@ fn types_time_only() -> Result<()> {
     assert_eq!("10:22:00.0", &time.to_string());
     assert_eq!("-08:00:00", &offset.to_string());
 
+    let (time, offset) = parse_time("1022+05")?;
+    assert_eq!("10:22:00.0", &time.to_string());
+    assert_eq!("+05:00:00", &offset.to_string());
+
     // Trigger some branches
     assert!(parse_time("-").is_err());
 
     Ok(())
 }
 
+#[test]
+fn types_time_invalid_offset() {
+    // Only a trailing zone designator is an offset
+    assert!(parse_time("10-22-00").is_err());
+    assert!(parse_time("102200--0800").is_err());
+    assert!(parse_time("102200+-0800").is_err());
+    assert!(parse_time("102200-08-00").is_err());
+    assert!(parse_time("102200-080").is_err());
+    assert!(parse_time("102200-08000").is_err());
+    assert!(parse_time("102200.5+1-0800").is_err());
+    assert!(parse_time("102200Z-0800").is_err());
+    assert!(parse_time("102200-0800Z").is_err());
+    assert!(parse_time("102200-08:00").is_err());
+    assert!(parse_date_time("19961022T10-22-00").is_err());
+}
+
 #[test]
 fn types_date_only() -> Result<()> {
     let date = parse_date("19850412")?;
@@ -114,35 +136,31 @@ fn types_date_and_or_time() -> Result<()> {
     }
 
     let value: DateAndOrTime = "1985-04".parse()?;
-    if let DateAndOrTime::Date(value) = value {
-        //let value = value.get(0).unwrap();
-        assert_eq!("1985-04-01", &value.to_string());
+    if let DateAndOrTime::PartialDate(value) = value {
+        assert_eq!("1985-04", &value.to_string());
     } else {
-        panic!("expecting Date variant");
+        panic!("expecting PartialDate variant");
     }
 
     let value: DateAndOrTime = "1985".parse()?;
-    if let DateAndOrTime::Date(value) = value {
-        //let value = value.get(0).unwrap();
-        assert_eq!("1985-01-01", &value.to_string());
+    if let DateAndOrTime::PartialDate(value) = value {
+        assert_eq!("1985", &value.to_string());
     } else {
-        panic!("expecting Date variant");
+        panic!("expecting PartialDate variant");
     }
 
     let value: DateAndOrTime = "--0412".parse()?;
-    if let DateAndOrTime::Date(value) = value {
-        //let value = value.get(0).unwrap();
-        assert_eq!("0000-04-12", &value.to_string());
+    if let DateAndOrTime::PartialDate(value) = value {
+        assert_eq!("--0412", &value.to_string());
     } else {
-        panic!("expecting Date variant");
+        panic!("expecting PartialDate variant");
     }
 
     let value: DateAndOrTime = "---12".parse()?;
-    if let DateAndOrTime::Date(value) = value {
-        //let value = value.get(0).unwrap();
-        assert_eq!("0000-01-12", &value.to_string());
+    if let DateAndOrTime::PartialDate(value) = value {
+        assert_eq!("---12", &value.to_string());
     } else {
-        panic!("expecting Date variant");
+        panic!("expecting PartialDate variant");
     }
 
     let value: DateAndOrTime = "T102200".parse()?;
@@ -307,6 +325,7 @@ proptest! {
         let value = format!("{:04}{:02}{:02}", y, m, d);
         //println!("{}", value);
         let date = parse_date(&value).unwrap();
+        let date = date.as_ref();
 
         let m2: u8 = date.month().try_into().unwrap();
         let (y2, d2) = (date.year(), date.day());
@@ -0,0 +1,120 @@
+use anyhow::Result;
+use vcard4::{parse, parse_bytes, parse_loose, Error};
+
+const MULTI_BYTE: [&str; 4] = ["ö", "😀", "中", "\u{301}"];
+
+/// Insert multi-byte characters at every offset of the input;
+/// parsing may fail but must never panic.
+fn assert_no_panic(input: &str) {
+    for insert in MULTI_BYTE {
+        for (offset, _) in input.char_indices() {
+            let mut value = input.to_owned();
+            value.insert_str(offset, insert);
+            let _ = parse(&value);
+            let _ = parse_loose(&value);
+        }
+    }
+}
+
+#[test]
+fn utf8_photo_data_uri() {
+    assert_no_panic(
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nitem1.PHOTO;MEDIATYPE=image/png;ALTID=\"1\":data:image/png;base64,iVBORw0KGgo=\r\nEND:VCARD\r\n",
+    );
+}
+
+#[test]
+fn utf8_note() {
+    assert_no_panic(
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE;LANGUAGE=en;X-TEST=\"a,b\":Line one\\nline\\, two\\; three\r\n  folded\r\nGENDER:M;identity\r\nBDAY:T102200Z\r\nANNIVERSARY:--0412\r\nTZ:-0500\r\nN:Doe;Jane\\,J;;;\r\nADR;LABEL=\"1 Main St\":;;1 Main St;Town;;;\r\nCLIENTPIDMAP:1;urn:uuid:1\r\nX-DATE;VALUE=date-time:20200101T101010-0500\r\nREV:20200101T101010Z\r\nEND:VCARD\r\n",
+    );
+}
+
+#[test]
+fn utf8_version_3() {
+    assert_no_panic(
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Jane Doe\r\nNOTE;ENCODING=QUOTED-PRINTABLE;CHARSET=UTF-8:caf=C3=A9=\r\n=20\r\nTEL;HOME:+1 555\r\nEND:VCARD\r\n",
+    );
+}
+
+#[test]
+fn utf8_text_values() -> Result<()> {
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Юрий Гагарин\r\nNICKNAME;ALTID=中:中文😀\r\nNICKNAME;LANGUAGE=ja:\"うさぎ\"\r\nitem1.ADR;LABEL=\"Улица 1\\n北京 🏠\":;;Улица 1;北京;;;\r\nitem1.X-ABLABEL:дом 🏡\r\nNOTE;X-TAG=ü,ß:😀\\, 😃\r\nEND:VCARD\r\n";
+    let card = parse(input)?.remove(0);
+
+    assert_eq!("Юрий Гагарин", card.formatted_name[0].value);
+    assert_eq!("中文😀", card.nickname[0].value);
+    let params = card.nickname[0].parameters.as_ref().unwrap();
+    assert_eq!(Some("中"), params.alt_id.as_deref());
+    assert_eq!("\"うさぎ\"", card.nickname[1].value);
+
+    let adr = &card.address[0];
+    let params = adr.parameters.as_ref().unwrap();
+    assert_eq!(Some("Улица 1\n北京 🏠"), params.label.as_deref());
+    assert_eq!(Some("дом 🏡"), card.label_for(adr));
+
+    let params = card.note[0].parameters.as_ref().unwrap();
+    assert_eq!(
+        &vec![("X-TAG".to_owned(), vec!["ü".to_owned(), "ß".to_owned()])],
+        params.extensions.as_ref().unwrap()
+    );
+    assert_eq!("😀, 😃", card.note[0].value);
+
+    assert_eq!(card, parse(card.to_string())?.remove(0));
+    Ok(())
+}
+
+#[test]
+fn utf8_group_names() -> Result<()> {
+    // Group names are limited to ASCII letters, digits and hyphens
+    for group in ["группа", "组", "😀"] {
+        let input = format!(
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\n{}.NICKNAME:Ж\r\nEND:VCARD\r\n",
+            group
+        );
+        let err = parse(&input).unwrap_err();
+        assert!(matches!(err, Error::IncorrectToken(_)), "{err:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn utf8_bytes_bom() -> Result<()> {
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE:中文\r\nEND:VCARD\r\n";
+    let mut bytes = vec![0xEF, 0xBB, 0xBF];
+    bytes.extend_from_slice(input.as_bytes());
+    let card = parse_bytes(&bytes)?.remove(0);
+    assert_eq!("Jane Doe", card.formatted_name[0].value);
+    assert_eq!("中文", card.note[0].value);
+    assert_eq!(card, parse_bytes(input.as_bytes())?.remove(0));
+
+    let err = parse_bytes(&[0x42, 0xFF, 0x3A]).unwrap_err();
+    assert!(matches!(err, Error::Utf8(_)), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn utf8_bytes_utf16() {
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nEND:VCARD\r\n";
+    let mut little_endian = vec![0xFF, 0xFE];
+    little_endian.extend(input.encode_utf16().flat_map(u16::to_le_bytes));
+    let err = parse_bytes(&little_endian).unwrap_err();
+    assert!(matches!(err, Error::Encoding("UTF-16LE")), "{err:?}");
+    assert_eq!("input is UTF-16LE encoded, expected UTF-8", err.to_string());
+
+    // Without a byte order mark
+    let big_endian: Vec<u8> =
+        input.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let err = parse_bytes(&big_endian).unwrap_err();
+    assert!(matches!(err, Error::Encoding("UTF-16BE")), "{err:?}");
+}
+
+#[test]
+fn utf8_to_writer() -> Result<()> {
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nNOTE:😀 a long note that is folded across more than one content line\r\nEND:VCARD\r\n";
+    let card = parse(input)?.remove(0);
+    let mut output = Vec::new();
+    card.to_writer(&mut output)?;
+    assert_eq!(card.to_string().as_bytes(), &output[..]);
+    Ok(())
+}
@@ -1,9 +1,87 @@
 use anyhow::Result;
-use vcard4::Vcard;
+use vcard4::{
+    parameter::{Parameters, Pid},
+    property::Kind,
+    Error, PropertyHandle, PropertyKind, Severity, Vcard, VcardBuilder,
+};
 
 #[test]
 fn validate() -> Result<()> {
     let card: Vcard = Default::default();
     assert!(card.validate().is_err());
+    assert!(!card.is_valid());
+    Ok(())
+}
+
+#[test]
+fn validate_issues() -> Result<()> {
+    let label: Parameters = ";LABEL=Home".parse()?;
+
+    // Out of range values are rejected by the parser
+    let mut pref: Parameters = ";TYPE=home".parse()?;
+    pref.pref = Some(101);
+
+    let card = VcardBuilder::new("Jane Doe".to_owned())
+        .member("urn:uuid:03a0e51f-d1aa-4385-8a53-e29025acd8af".parse()?)
+        .telephone_with("+1-555-555-5555".to_owned(), label)
+        .email_with("jane@example.com".to_owned(), pref)
+        .finish();
+
+    let issues = card.validation_issues();
+    assert_eq!(3, issues.len());
+    assert!(issues.iter().all(|i| i.severity == Severity::Error));
+
+    assert_eq!(
+        Some(PropertyHandle::new(PropertyKind::Member, 0)),
+        issues[0].property
+    );
+    assert!(matches!(issues[0].error, Error::MemberRequiresGroup));
+    assert!(
+        matches!(&issues[1].error, Error::InvalidLabel(name) if name == "TEL")
+    );
+    assert!(matches!(issues[2].error, Error::PrefOutOfRange(101)));
+
+    assert!(!card.is_valid());
+    assert!(matches!(card.validate(), Err(Error::MemberRequiresGroup)));
+    Ok(())
+}
+
+#[test]
+fn validate_pid_out_of_range() -> Result<()> {
+    let mut params = Parameters::default();
+    params.add_pid(Pid::new(12345, None));
+
+    let card = VcardBuilder::new("Jane Doe".to_owned())
+        .email_with("jane@example.com".to_owned(), params)
+        .finish();
+
+    let issues = card.validation_issues();
+    assert_eq!(1, issues.len());
+    assert_eq!(Severity::Error, issues[0].severity);
+    assert!(matches!(
+        &issues[0].error,
+        Error::PidOutOfRange(pid) if pid == "12345"
+    ));
+    assert!(!card.is_valid());
+    Ok(())
+}
+
+#[test]
+fn validate_pid_warning() -> Result<()> {
+    let params: Parameters = ";PID=1.2".parse()?;
+
+    let card = VcardBuilder::new("Jane Doe".to_owned())
+        .kind(Kind::Individual)
+        .email_with("jane@example.com".to_owned(), params)
+        .finish();
+
+    let issues = card.validation_issues();
+    assert_eq!(1, issues.len());
+    assert_eq!(Severity::Warning, issues[0].severity);
+    assert!(matches!(issues[0].error, Error::UnknownPidSource(2)));
+
+    // Warnings do not make the vCard invalid
+    assert!(card.is_valid());
+    assert!(card.validate().is_ok());
     Ok(())
 }
@@ -0,0 +1,68 @@
+use anyhow::Result;
+use vcard4::{iter, verify, Error};
+
+#[test]
+fn verify_spans_match_iter() -> Result<()> {
+    let input = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nEND:VCARD\r\n\
+        \r\n\
+        BEGIN:VCARD\r\nVERSION:4.0\r\nFN;LANGUAGE=en:John\r\n  Doe\r\n\
+        TEL;TYPE=\"voice,home\":+1-555-555-5555\r\nEND:VCARD\r\n";
+    let reports = verify(input, true);
+    assert_eq!(2, reports.len());
+    let mut it = iter(input, true);
+    for report in &reports {
+        assert!(report.error.is_none());
+        let (_, span) = it.next_with_span().unwrap()?;
+        assert_eq!(span, report.span);
+    }
+    assert_eq!(Some("Jane Doe"), reports[0].formatted_name);
+    assert_eq!(Some("John\r\n  Doe"), reports[1].formatted_name);
+    Ok(())
+}
+
+#[test]
+fn verify_formatted_name_escaped() -> Result<()> {
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Doe\\, Jane\nEND:VCARD";
+    let reports = verify(input, true);
+    assert_eq!(1, reports.len());
+    assert_eq!(Some("Doe\\, Jane"), reports[0].formatted_name);
+    assert_eq!(0..input.len(), reports[0].span);
+    Ok(())
+}
+
+#[test]
+fn verify_strict_and_loose() -> Result<()> {
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\n\
+        NOTE;X-A=\"open:value\n\
+        END:VCARD\n\
+        BEGIN:VCARD\nVERSION:4.0\nFN:John Doe\nEND:VCARD\n";
+
+    let reports = verify(input, true);
+    assert_eq!(2, reports.len());
+    assert!(matches!(reports[0].error, Some(Error::TokenExpected)));
+    assert_eq!(Some("Jane Doe"), reports[0].formatted_name);
+    assert!(reports[1].error.is_none());
+    assert_eq!(Some("John Doe"), reports[1].formatted_name);
+
+    let reports = verify(input, false);
+    assert_eq!(2, reports.len());
+    assert!(reports.iter().all(|report| report.error.is_none()));
+    Ok(())
+}
+
+#[test]
+fn verify_errors() -> Result<()> {
+    let input = "BEGIN:VCARD\nVERSION:4.0\nNOTE:Unnamed\nEND:VCARD\n\
+        garbage\n\
+        BEGIN:VCARD\nVERSION:4.0\nFN:A\u{7}\nEND:VCARD\n\
+        BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\nVERSION:4.0\nEND:VCARD\n";
+    let reports = verify(input, true);
+    assert_eq!(4, reports.len());
+    assert!(matches!(reports[0].error, Some(Error::NoFormattedName)));
+    assert!(reports[1].error.is_some());
+    assert_eq!("garbage\n", &input[reports[1].span.clone()]);
+    assert!(matches!(reports[2].error, Some(Error::ControlCharacter(_))));
+    assert!(matches!(reports[3].error, Some(Error::VersionMisplaced)));
+    assert!(verify("", true).is_empty());
+    Ok(())
+}
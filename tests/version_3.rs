@@ -1,7 +1,12 @@
 mod test_helpers;
 
 use anyhow::Result;
-use vcard4::parse;
+use vcard4::{
+    parameter::{TelephoneType, TypeParameter},
+    parse, parse_with_options,
+    property::TextOrUriProperty,
+    ParseOptions, PropertyKind, V3Options, V3Output, WriteOptions,
+};
 
 use test_helpers::assert_round_trip;
 
@@ -22,3 +27,352 @@ END:VCARD"#;
     assert_round_trip(&card)?;
     Ok(())
 }
+
+fn v3() -> ParseOptions {
+    ParseOptions::new().allow_v3(true)
+}
+
+const GOOGLE: &str = r#"BEGIN:VCARD
+VERSION:3.0
+FN:Jane Doe
+N:Doe;Jane;;;
+EMAIL;TYPE=INTERNET;TYPE=HOME:jane@example.com
+EMAIL;TYPE=INTERNET:jane.doe@example.org
+TEL;TYPE=CELL:+1 555-555-5555
+TEL;TYPE=HOME,VOICE:+1 555-555-0000
+ADR;TYPE=HOME:;;123 Main St;Springfield;IL;62701;US
+ORG:Acme
+TITLE:Engineer
+item1.URL:http\://www.example.com/jane
+item1.X-ABLabel:
+NOTE:Met at the conference\, table 5
+CATEGORIES:myContacts,Friends
+END:VCARD
+"#;
+
+#[test]
+fn v3_google_contacts() -> Result<()> {
+    let mut vcards = parse_with_options(GOOGLE, v3())?;
+    assert_eq!(1, vcards.len());
+    let card = vcards.remove(0);
+
+    assert_eq!("Jane Doe", card.formatted_name[0].value);
+
+    // INTERNET is removed, type values are case-insensitive
+    let params = card.email[0].parameters.as_ref().unwrap();
+    assert_eq!(Some(&vec![TypeParameter::Home]), params.types.as_ref());
+    assert!(card.email[1].parameters.is_none());
+
+    let tel = card.tel.get(1).unwrap();
+    let TextOrUriProperty::Text(tel) = tel else {
+        panic!("expecting text TEL");
+    };
+    let params = tel.parameters.as_ref().unwrap();
+    assert_eq!(
+        Some(&vec![
+            TypeParameter::Home,
+            TypeParameter::Telephone(TelephoneType::Voice),
+        ]),
+        params.types.as_ref()
+    );
+
+    // Escaped colon
+    assert_eq!("http://www.example.com/jane", card.url[0].value.to_string());
+    assert_eq!(vec!["Springfield"], card.address[0].value.locality);
+
+    let encoded = card.to_string();
+    assert!(encoded.contains("VERSION:4.0"));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn v3_outlook() -> Result<()> {
+    let input = "BEGIN:VCARD\r
+VERSION:3.0\r
+N;CHARSET=utf-8:Doe;John;;;\r
+FN;CHARSET=utf-8:John Doe\r
+TEL;WORK;VOICE;PREF:(111) 555-1212\r
+EMAIL;PREF;INTERNET:john@example.com\r
+ADR;WORK;PREF:;;100 Waters Edge;Baytown;LA;30314;United States of America\r
+LABEL;WORK;PREF;ENCODING=QUOTED-PRINTABLE:100 Waters Edge=0D=0A=\r
+Baytown\\, LA 30314=0D=0AUnited States of America\r
+NOTE;CHARSET=ISO-8859-1;ENCODING=QUOTED-PRINTABLE:Caf=E9\r
+REV:20080424T195243Z\r
+END:VCARD\r
+";
+    let card = parse_with_options(input, v3())?.remove(0);
+
+    let TextOrUriProperty::Text(tel) = &card.tel[0] else {
+        panic!("expecting text TEL");
+    };
+    let params = tel.parameters.as_ref().unwrap();
+    assert_eq!(Some(1), params.pref);
+    assert_eq!(
+        Some(&vec![
+            TypeParameter::Work,
+            TypeParameter::Telephone(TelephoneType::Voice),
+        ]),
+        params.types.as_ref()
+    );
+
+    let params = card.email[0].parameters.as_ref().unwrap();
+    assert_eq!(Some(1), params.pref);
+    assert!(params.types.is_none());
+
+    // CHARSET is removed
+    assert!(card.formatted_name[0].parameters.is_none());
+
+    // LABEL is attached to the ADR
+    assert_eq!(1, card.address.len());
+    assert!(card.extensions.is_empty());
+    let params = card.address[0].parameters.as_ref().unwrap();
+    assert_eq!(
+        Some("100 Waters Edge\nBaytown, LA 30314\nUnited States of America"),
+        params.label.as_deref()
+    );
+
+    assert_eq!("Café", card.note[0].value);
+    assert!(card.note[0].parameters.is_none());
+
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn v3_label_group() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:3.0
+FN:Jane Doe
+ADR;TYPE=HOME:;;1 Home St;Town;;;
+item1.ADR;TYPE=WORK:;;2 Work St;City;;;
+LABEL;TYPE=WORK:Work label
+LABEL;TYPE=POSTAL:Postal label
+END:VCARD"#;
+    let card = parse_with_options(input, v3())?.remove(0);
+    assert_eq!(2, card.address.len());
+    let label = |index: usize| {
+        card.address[index]
+            .parameters
+            .as_ref()
+            .and_then(|p| p.label.clone())
+    };
+    assert_eq!(Some("Postal label".to_owned()), label(0));
+    assert_eq!(Some("Work label".to_owned()), label(1));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn v3_label_without_adr() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:3.0
+FN:Jane Doe
+LABEL;TYPE=HOME:1 Home St
+END:VCARD"#;
+    let card = parse_with_options(input, v3())?.remove(0);
+    assert_eq!(1, card.address.len());
+    let params = card.address[0].parameters.as_ref().unwrap();
+    assert_eq!(Some("1 Home St"), params.label.as_deref());
+    assert_eq!(Some(&vec![TypeParameter::Home]), params.types.as_ref());
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn v3_not_allowed() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:3.0
+FN:Jane Doe
+TEL;WORK;VOICE:(111) 555-1212
+END:VCARD"#;
+    assert!(parse(input).is_err());
+    assert!(parse_with_options(input, v3()).is_ok());
+
+    // Only applies to version 3.0
+    let input = input.replace("VERSION:3.0", "VERSION:4.0");
+    assert!(parse_with_options(input, v3()).is_err());
+    Ok(())
+}
+
+#[test]
+fn v3_unsupported_charset() {
+    let input = r#"BEGIN:VCARD
+VERSION:3.0
+FN;CHARSET=SHIFT_JIS;ENCODING=QUOTED-PRINTABLE:=82=A0
+END:VCARD"#;
+    assert!(parse_with_options(input, v3()).is_err());
+}
+
+#[test]
+fn v3_write() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+N:Doe;Jane;;;
+KIND:individual
+BDAY:19850412
+GENDER:F
+item1.ADR;TYPE=home;PREF=1;LABEL="1 Main St\nTown":;;1 Main St;Town;;;
+TEL;VALUE=uri;TYPE=cell:tel:+1-555-555-5555
+EMAIL;TYPE=work,home;PID=1.1:jane@example.com
+TZ;VALUE=utc-offset:-0500
+GEO:geo:37.386013,-122.082932
+PHOTO:data:image/png;base64,iVBORw0K
+LOGO:https://example.com/logo.png
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let output = V3Output::new(
+        &card,
+        &V3Options::new().write_options(WriteOptions::new().fold_width(None)),
+    );
+    let lines: Vec<&str> = output.encoded.lines().collect();
+    assert_eq!(
+        vec![
+            "BEGIN:VCARD",
+            "VERSION:3.0",
+            "FN:Jane Doe",
+            "N:Doe;Jane;;;",
+            "PHOTO;ENCODING=b;TYPE=PNG:iVBORw0K",
+            "BDAY:1985-04-12",
+            "item1.ADR;TYPE=home,pref:;;1 Main St;Town;;;",
+            "item1.LABEL;TYPE=home,pref:1 Main St\\nTown",
+            "LOGO;VALUE=uri:https://example.com/logo.png",
+            "TEL;TYPE=cell:+1-555-555-5555",
+            "EMAIL;TYPE=work,home:jane@example.com",
+            "TZ;VALUE=utc-offset:-05:00",
+            "GEO:37.386013;-122.082932",
+            "END:VCARD",
+        ],
+        lines
+    );
+    let kinds: Vec<PropertyKind> =
+        output.dropped.iter().map(|handle| handle.kind).collect();
+    assert_eq!(vec![PropertyKind::Kind, PropertyKind::Gender], kinds);
+
+    // Read back with version 3.0 support; the reader does not
+    // upgrade the version 3.0 forms of TZ and GEO
+    let encoded: String = output
+        .encoded
+        .lines()
+        .filter(|line| !line.starts_with("TZ") && !line.starts_with("GEO"))
+        .map(|line| format!("{}\r\n", line))
+        .collect();
+    let card = parse_with_options(&encoded, v3())?.remove(0);
+    assert_eq!("Jane Doe", card.formatted_name[0].value);
+    let params = card.address[0].parameters.as_ref().unwrap();
+    assert_eq!(Some(1), params.pref);
+    assert_eq!(Some("1 Main St\nTown"), params.label.as_deref());
+    assert_eq!(1, card.tel.len());
+    Ok(())
+}
+
+#[test]
+fn v3_write_extensions() -> Result<()> {
+    let input = r#"BEGIN:VCARD
+VERSION:4.0
+FN:Jane Doe
+GENDER:F
+ANNIVERSARY:19960415
+BDAY:--0412
+END:VCARD"#;
+    let card = parse(input)?.remove(0);
+    let encoded = card.to_v3_string();
+    assert!(encoded.starts_with("BEGIN:VCARD\r\nVERSION:3.0\r\nN:;;;;\r\n"));
+    assert!(!encoded.contains("GENDER"));
+
+    let output = V3Output::new(&card, &V3Options::new().extensions(true));
+    assert!(output.encoded.contains("\r\nX-GENDER:F\r\n"));
+    assert!(output.encoded.contains("\r\nX-ANNIVERSARY:19960415\r\n"));
+    // Partial dates cannot be represented
+    assert!(!output.encoded.contains("BDAY"));
+    assert_eq!(1, output.dropped.len());
+    assert_eq!(PropertyKind::Bday, output.dropped[0].kind);
+    Ok(())
+}
+
+#[test]
+fn v3_inline_binary() -> Result<()> {
+    let input = "BEGIN:VCARD\r
+VERSION:3.0\r
+FN:Jane Doe\r
+PHOTO;ENCODING=b;TYPE=JPEG:/9j/4AAQ\r
+ SkZJRg==\r
+LOGO;ENCODING=BASE64;TYPE=PNG:iVBORw0KGgo=\r
+SOUND;ENCODING=b;MEDIATYPE=audio/ogg:T2dnUw==\r
+KEY;ENCODING=b:AQID\r
+END:VCARD\r\n";
+    let card = parse_with_options(input, v3())?.remove(0);
+    let TextOrUriProperty::Uri(photo) = &card.photo[0] else {
+        panic!("expected a URI");
+    };
+    assert_eq!(
+        "data:image/jpeg;base64,/9j/4AAQSkZJRg==",
+        photo.value.to_string()
+    );
+    assert!(photo.parameters.is_none());
+    assert_eq!(1, card.parse_photo_jpeg()?.len());
+
+    let logo = card.logo[0].data_uri()?.unwrap();
+    assert_eq!("image/png", logo.media_type);
+    assert_eq!(b"\x89PNG\r\n\x1a\n", &logo.data[..]);
+
+    let sound = card.sound[0].data_uri()?.unwrap();
+    assert_eq!("audio/ogg", sound.media_type);
+    assert!(card.sound[0].parameters.is_some());
+
+    let TextOrUriProperty::Uri(key) = &card.key[0] else {
+        panic!("expected a URI");
+    };
+    let key = key.data_uri()?.unwrap();
+    assert_eq!("application/octet-stream", key.media_type);
+    assert_eq!(vec![1, 2, 3], key.data);
+
+    assert_round_trip(&card)?;
+
+    // Invalid base64 drops the property in loose mode
+    let input = input.replace("AQID", "A!ID");
+    assert!(parse_with_options(&input, v3()).is_err());
+    let card = parse_with_options(&input, v3().strict(false))?.remove(0);
+    assert!(card.key.is_empty());
+    Ok(())
+}
+
+#[test]
+fn v3_inline_binary_type_hint() -> Result<()> {
+    let input = "BEGIN:VCARD\r
+VERSION:3.0\r
+FN:Jane Doe\r
+PHOTO;ENCODING=BASE64;TYPE=WORK,PNG:iVBORw0KGgo=\r
+END:VCARD\r\n";
+    let card = parse_with_options(input, v3())?.remove(0);
+    let TextOrUriProperty::Uri(photo) = &card.photo[0] else {
+        panic!("expected a URI");
+    };
+    assert_eq!("image/png", photo.data_uri()?.unwrap().media_type);
+    assert_eq!(
+        &vec![TypeParameter::Work],
+        photo.parameters.as_ref().unwrap().types.as_ref().unwrap()
+    );
+    assert!(card.to_string().contains(
+        "\r\nPHOTO;TYPE=work:data:image/png;base64,iVBORw0KGgo=\r\n"
+    ));
+    assert_round_trip(&card)?;
+    Ok(())
+}
+
+#[test]
+fn v3_inline_binary_keep() -> Result<()> {
+    let input = include_str!("../fixtures/photo.vcf");
+    let options = ParseOptions::new().keep_inline_binary(true);
+    let card = parse_with_options(input, options)?.remove(0);
+    assert!(matches!(card.photo[0], TextOrUriProperty::Text(_)));
+    assert!(card.to_string().contains("PHOTO;TYPE=X-JPEG;ENCODING=b:"));
+    assert_eq!(1, card.parse_photo_jpeg()?.len());
+
+    // Converted by default
+    let converted = parse(input)?.remove(0);
+    assert!(matches!(converted.photo[0], TextOrUriProperty::Uri(_)));
+    assert_eq!(card.parse_photo_jpeg()?, converted.parse_photo_jpeg()?);
+    Ok(())
+}
@@ -0,0 +1,209 @@
+#![cfg(feature = "xml")]
+
+use anyhow::Result;
+use vcard4::{
+    parse,
+    xcard::{from_xml, to_xml},
+    Error, PropertyKind, Severity,
+};
+
+// https://www.rfc-editor.org/rfc/rfc6351#appendix-B.1
+const EXAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<vcards xmlns="urn:ietf:params:xml:ns:vcard-4.0">
+  <vcard>
+    <fn><text>Simon Perreault</text></fn>
+    <n>
+      <surname>Perreault</surname>
+      <given>Simon</given>
+      <additional/>
+      <prefix/>
+      <suffix>ing. jr</suffix>
+      <suffix>M.Sc.</suffix>
+    </n>
+    <bday><date>--0203</date></bday>
+    <anniversary>
+      <date-time>20090808T1430-0500</date-time>
+    </anniversary>
+    <gender><sex>M</sex></gender>
+    <lang>
+      <parameters><pref><integer>1</integer></pref></parameters>
+      <language-tag>fr</language-tag>
+    </lang>
+    <lang>
+      <parameters><pref><integer>2</integer></pref></parameters>
+      <language-tag>en</language-tag>
+    </lang>
+    <org>
+      <parameters><type><text>work</text></type></parameters>
+      <text>Viagenie</text>
+    </org>
+    <adr>
+      <parameters>
+        <type><text>work</text></type>
+        <label><text>Simon Perreault
+2875 boul. Laurier, suite D2-630
+Quebec, Quebec, Canada
+G1V 2M2</text></label>
+      </parameters>
+      <pobox/>
+      <ext/>
+      <street>2875 boul. Laurier, suite D2-630</street>
+      <locality>Quebec</locality>
+      <region>QC</region>
+      <code>G1V 2M2</code>
+      <country>Canada</country>
+    </adr>
+    <tel>
+      <parameters>
+        <type>
+          <text>work</text>
+          <text>voice</text>
+        </type>
+      </parameters>
+      <uri>tel:+1-418-656-9254;ext=102</uri>
+    </tel>
+    <tel>
+      <parameters>
+        <type>
+          <text>work</text>
+          <text>text</text>
+          <text>voice</text>
+          <text>cell</text>
+          <text>video</text>
+        </type>
+      </parameters>
+      <uri>tel:+1-418-262-6501</uri>
+    </tel>
+    <email>
+      <parameters><type><text>work</text></type></parameters>
+      <text>simon.perreault@viagenie.ca</text>
+    </email>
+    <geo>
+      <parameters><type><text>work</text></type></parameters>
+      <uri>geo:46.766336,-71.28955</uri>
+    </geo>
+    <key>
+      <parameters><type><text>work</text></type></parameters>
+      <uri>http://www.viagenie.ca/simon.perreault/simon.asc</uri>
+    </key>
+    <tz><text>America/Montreal</text></tz>
+    <url>
+      <parameters><type><text>home</text></type></parameters>
+      <uri>http://nomis80.org</uri>
+    </url>
+  </vcard>
+</vcards>"#;
+
+#[test]
+fn xcard_rfc_example() -> Result<()> {
+    let mut cards = from_xml(EXAMPLE)?;
+    assert_eq!(1, cards.len());
+    let card = cards.remove(0);
+
+    assert_eq!("Simon Perreault", card.formatted_name[0].value);
+    let name = &card.name.as_ref().unwrap().value;
+    assert_eq!(vec!["ing. jr", "M.Sc."], name.honorific_suffixes);
+    let address = &card.address[0];
+    assert_eq!(
+        vec!["2875 boul. Laurier, suite D2-630"],
+        address.value.street_address
+    );
+    assert!(address
+        .parameters
+        .as_ref()
+        .and_then(|p| p.label.as_ref())
+        .unwrap()
+        .contains("\nQuebec, Quebec, Canada\n"));
+    assert_eq!(2, card.tel.len());
+    assert_eq!(2, card.lang.len());
+
+    let xml = to_xml(&card);
+    assert!(xml.contains("<bday><date-and-or-time>"));
+    assert!(xml.contains("<suffix>ing. jr</suffix><suffix>M.Sc.</suffix>"));
+    assert!(xml.contains("<pobox/><ext/>"));
+
+    let decoded = from_xml(&xml)?.remove(0);
+    assert_eq!(card, decoded);
+    Ok(())
+}
+
+#[test]
+fn xcard_group_and_xml() -> Result<()> {
+    let input = r#"<vcards xmlns="urn:ietf:params:xml:ns:vcard-4.0">
+  <vcard>
+    <fn><text>Jane Doe</text></fn>
+    <group name="contact">
+      <email><text>jane@example.com</text></email>
+      <x-ablabel><unknown>Work &amp; Play</unknown></x-ablabel>
+    </group>
+    <a:note xmlns:a="http://example.com/ns">Embedded</a:note>
+  </vcard>
+  <vcard>
+    <fn><text>John Doe</text></fn>
+  </vcard>
+</vcards>"#;
+
+    let cards = from_xml(input)?;
+    assert_eq!(2, cards.len());
+    let card = &cards[0];
+    assert_eq!(Some("contact"), card.email[0].group.as_deref());
+    assert_eq!("Work & Play", card.extensions[0].value.to_string());
+    assert_eq!(
+        r#"<a:note xmlns:a="http://example.com/ns">Embedded</a:note>"#,
+        card.xml[0].value
+    );
+
+    let xml = to_xml(card);
+    assert!(xml.contains(r#"<group name="contact">"#));
+    assert!(xml.contains(
+        r#"<a:note xmlns:a="http://example.com/ns">Embedded</a:note>"#
+    ));
+    let decoded = from_xml(&xml)?.remove(0);
+    assert_eq!(card, &decoded);
+    Ok(())
+}
+
+#[test]
+fn xcard_invalid() {
+    assert!(matches!(
+        from_xml("<vcards><vcard/></vcards>"),
+        Err(Error::InvalidXcard(_))
+    ));
+    assert!(matches!(from_xml("<vcards"), Err(Error::Xml(_))));
+}
+
+#[test]
+fn xcard_xml_root() -> Result<()> {
+    let input = "BEGIN:VCARD\nVERSION:4.0\nFN:Jane Doe\n\
+        XML:<a:note xmlns:a=\"http://example.com/ns\">Note</a:note>\n\
+        XML:<b>Unclosed\n\
+        XML:<!DOCTYPE b><b/>\n\
+        XML:<a/><b/>\n\
+        XML:<fn xmlns=\"urn:ietf:params:xml:ns:vcard-4.0\"/>\n\
+        END:VCARD\n";
+    // Malformed XML does not prevent parsing
+    let card = parse(input)?.remove(0);
+    assert_eq!(5, card.xml.len());
+
+    let root = card.xml[0].xml_root()?;
+    assert_eq!("note", root.name);
+    assert_eq!(Some("http://example.com/ns"), root.namespace.as_deref());
+    assert!(matches!(card.xml[1].xml_root(), Err(Error::Xml(_))));
+    assert!(matches!(card.xml[2].xml_root(), Err(Error::Xml(_))));
+    assert!(matches!(card.xml[3].xml_root(), Err(Error::Xml(_))));
+    assert!(matches!(
+        card.xml[4].xml_root(),
+        Err(Error::XmlVcardNamespace { row: 1, col: 1 })
+    ));
+
+    let issues = card.validation_issues();
+    assert_eq!(4, issues.len());
+    for (issue, index) in issues.iter().zip(1..) {
+        assert_eq!(Severity::Warning, issue.severity);
+        let handle = issue.property.unwrap();
+        assert_eq!(PropertyKind::Xml, handle.kind);
+        assert_eq!(index, handle.index);
+    }
+    assert!(card.validate().is_ok());
+    Ok(())
+}